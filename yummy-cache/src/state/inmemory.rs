@@ -2,18 +2,22 @@
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::ops::Deref;
 use std::sync::Arc;
 use std::borrow::Borrow;
 use std::sync::atomic::AtomicUsize;
 use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Serialize, Deserialize};
 use yummy_model::*;
 use yummy_model::meta::*;
+use yummy_model::meta::operation::{UserMetaOperation, RoomMetaOperation};
 use yummy_model::config::YummyConfig;
+use yummy_model::auth::RefreshTokenRotation;
 use yummy_model::state::*;
+use yummy_model::matchmaking::MatchmakingTicket;
 use yummy_model::meta::collection::{UserMetaCollection, UserMetaCollectionInformation, RoomMetaCollection, RoomMetaCollectionInformation};
 
 use crate::cache::{YummyCache, YummyCacheResource};
@@ -28,7 +32,8 @@ use super::resource::YummyCacheResourceFactory;
 #[derive(Clone)]
 pub struct ConnectionInfo {
     pub user_id: Arc<UserId>,
-    pub room_user_type: RoomUserType
+    pub room_user_type: RoomUserType,
+    pub join_order: usize
 }
 
 #[derive(Default)]
@@ -36,16 +41,26 @@ pub struct ConnectionResource;
 struct RoomState {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub password: Option<String>,
     pub access_type: CreateRoomAccessType,
     pub max_user: usize,
+    pub max_spectator: usize,
     pub tags: Vec<String>,
     pub insert_date: i32,
     pub join_request: bool,
+    pub auto_start: bool,
+    pub game_state: RoomGameState,
+    pub ready_users: HashSet<UserId>,
     pub connection_count: AtomicUsize,
+    pub spectator_count: AtomicUsize,
+    pub join_counter: usize,
     pub connections: YummyCache<SessionId, ConnectionInfo>,
-    pub banned_users: HashSet<UserId>,
+    pub banned_users: HashMap<UserId, RoomBan>,
     pub metas: HashMap<String, MetaType<RoomMetaAccess>>,
     pub join_requests: HashMap<SessionId, RoomUserType>,
+    pub messages: VecDeque<RoomMessage>,
+    pub message_counter: usize,
+    pub invites: HashMap<UserId, RoomInvite>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -60,6 +75,18 @@ pub struct UserState {
     pub joined_rooms: std::collections::HashMap<RoomId, SessionId>,
 }
 
+struct TokenFamily {
+    pub user_id: UserId,
+    pub refresh_token: TokenId,
+    pub expire_date: i32
+}
+
+/* Order is used to find the oldest sessions, insert date is not unique */
+struct SessionState {
+    pub order: usize,
+    pub info: UserSession
+}
+
 #[derive(Clone)]
 pub struct YummyState {
     #[allow(dead_code)]
@@ -68,10 +95,16 @@ pub struct YummyState {
     rooms: Arc<parking_lot::Mutex<std::collections::HashMap<RoomId, RoomState>>>,
     session_to_users: Arc<parking_lot::Mutex<std::collections::HashMap<SessionId, Arc<UserId>>>>,
     session_to_room: Arc<parking_lot::Mutex<std::collections::HashMap<SessionId, std::collections::HashSet<RoomId>>>>,
+    sessions: Arc<parking_lot::Mutex<HashMap<SessionId, SessionState>>>,
+    session_counter: Arc<AtomicUsize>,
     user_informations: Arc<YummyCache<UserId, UserInformationModel>>,
     user_types: Arc<YummyCache<UserId, UserType>>,
     user_metas: Arc<YummyCache<UserId, UserMetaCollection>>,
-    room_metas: Arc<YummyCache<RoomId, RoomMetaCollection>>
+    room_metas: Arc<YummyCache<RoomId, RoomMetaCollection>>,
+    user_friends: Arc<YummyCache<UserId, HashSet<UserId>>>,
+    matchmaking: Arc<parking_lot::Mutex<HashMap<String, HashMap<SessionId, MatchmakingTicket>>>>,
+    token_families: Arc<parking_lot::Mutex<HashMap<TokenFamilyId, TokenFamily>>>,
+    meta_locks: Arc<parking_lot::Mutex<HashMap<String, Arc<parking_lot::Mutex<()>>>>>
 }
 
 /* **************************************************************************************************************** */
@@ -80,12 +113,33 @@ pub struct YummyState {
 /* *************************************************** TRAITS ***************************************************** */
 /* ************************************************* IMPLEMENTS *************************************************** */
 /* **************************************************************************************************************** */
+impl RoomState {
+    fn get_user_length(&self) -> usize {
+        self.connection_count.load(std::sync::atomic::Ordering::Relaxed) - self.spectator_count.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /* Spectators have their own capacity, so they are not blocking the players. 0 means unlimited */
+    fn has_free_slot(&self, room_user_type: &RoomUserType) -> Result<(), YummyStateError> {
+        match room_user_type {
+            RoomUserType::Spectator => match self.max_spectator == 0 || self.max_spectator > self.spectator_count.load(std::sync::atomic::Ordering::Relaxed) {
+                true => Ok(()),
+                false => Err(YummyStateError::RoomHasMaxSpectators)
+            },
+            _ => match self.max_user == 0 || self.max_user > self.get_user_length() {
+                true => Ok(()),
+                false => Err(YummyStateError::RoomHasMaxUsers)
+            }
+        }
+    }
+}
+
 impl YummyState {
     pub fn new(config: Arc<YummyConfig>, resource_factory: Box<dyn YummyCacheResourceFactory>) -> Self {
         let user_informations = YummyCache::new(config.clone(), resource_factory.user_information());
         let user_metas = YummyCache::new(config.clone(), resource_factory.user_metas());
         let user_types = YummyCache::new(config.clone(), resource_factory.user_type());
         let room_metas = YummyCache::new(config.clone(), resource_factory.room_metas());
+        let user_friends = YummyCache::new(config.clone(), resource_factory.user_friends());
 
         Self {
             config,
@@ -94,10 +148,16 @@ impl YummyState {
             rooms: Arc::new(parking_lot::Mutex::default()),
             session_to_users: Arc::new(parking_lot::Mutex::default()),
             session_to_room: Arc::new(parking_lot::Mutex::default()),
+            sessions: Arc::new(parking_lot::Mutex::default()),
+            session_counter: Arc::new(AtomicUsize::default()),
             user_informations: Arc::new(user_informations),
             user_metas: Arc::new(user_metas),
             user_types: Arc::new(user_types),
             room_metas: Arc::new(room_metas),
+            user_friends: Arc::new(user_friends),
+            matchmaking: Arc::new(parking_lot::Mutex::default()),
+            token_families: Arc::new(parking_lot::Mutex::default()),
+            meta_locks: Arc::new(parking_lot::Mutex::default()),
         }
     }
 
@@ -105,7 +165,7 @@ impl YummyState {
         match self.user_informations.get(user_id)? {
             Some(mut result) => {
                 let access = access as i32;
-                result.metas = result.metas.map(|metas| metas.into_iter().filter(|item| item.meta.get_access_level() as i32 <= access).collect());
+                result.metas = result.metas.map(|metas| metas.into_iter().filter(|item| item.meta.get_access_level() as i32 <= access && !item.meta.is_expired()).collect());
                 Ok(Some(result))
             },
             None => Ok(None)
@@ -136,7 +196,7 @@ impl YummyState {
                 let access_type = access as i32;
                 metas = metas
                     .into_iter()
-                    .filter(|meta| meta.meta.get_access_level() as i32 <= access_type)
+                    .filter(|meta| meta.meta.get_access_level() as i32 <= access_type && !meta.meta.is_expired())
                     .collect::<UserMetaCollection>();
                 Ok(metas)
             },
@@ -150,7 +210,7 @@ impl YummyState {
                 let access_type = access as i32;
                 metas = metas
                     .into_iter()
-                    .filter(|meta| meta.meta.get_access_level() as i32 <= access_type)
+                    .filter(|meta| meta.meta.get_access_level() as i32 <= access_type && !meta.meta.is_expired())
                     .collect::<RoomMetaCollection>();
                 Ok(metas)
             },
@@ -226,23 +286,175 @@ impl YummyState {
         }
     }
     
+    /* Every user and room has own meta lock, operations on the different owners are not waiting each other. Unused locks are removed */
+    fn with_meta_lock<R>(&self, owner: String, callback: impl FnOnce() -> R) -> R {
+        let lock = self.meta_locks.lock().entry(owner.clone()).or_default().clone();
+        let result = {
+            let _guard = lock.lock();
+            callback()
+        };
+
+        let mut locks = self.meta_locks.lock();
+        if Arc::strong_count(&lock) == 2 {
+            locks.remove(&owner);
+        }
+        result
+    }
+
+    /* Meta is read, calculated and persisted under the user's meta lock, so concurrent operations will not lose any update */
+    pub fn apply_user_meta_operation<F>(&self, user_id: &UserId, key: String, access: UserMetaAccess, operation: &UserMetaOperation, persist: F) -> Result<UserMetaType, YummyStateError> where F: FnMut(&UserMetaType) -> anyhow::Result<()> {
+        self.with_meta_lock(format!("user:{}", user_id.to_string()), || self.inner_apply_user_meta_operation(user_id, key, access, operation, persist))
+    }
+
+    fn inner_apply_user_meta_operation<F>(&self, user_id: &UserId, key: String, access: UserMetaAccess, operation: &UserMetaOperation, mut persist: F) -> Result<UserMetaType, YummyStateError> where F: FnMut(&UserMetaType) -> anyhow::Result<()> {
+        let mut metas = self.user_metas.get(user_id)?.unwrap_or_default();
+        let current = metas.get_with_name(&key).map(|item| item.meta.clone()).filter(|meta| !meta.is_expired());
+
+        if let Some(current) = current.as_ref() {
+            if current.get_access_level() as i32 > access as i32 {
+                return Err(YummyStateError::MetaAccessLevelNotEnough);
+            }
+        }
+
+        let meta = operation.apply(current.as_ref())?;
+        persist(&meta)?;
+
+        match meta {
+            MetaType::Null => metas.remove_with_name(&key),
+            _ => metas.add(key.clone(), meta.clone())
+        };
+
+        // User information cache has a copy of the metas
+        if let Some(mut informations) = self.user_informations.get(user_id)? {
+            let mut information_metas = informations.metas.take().unwrap_or_default();
+
+            match meta {
+                MetaType::Null => information_metas.remove_with_name(&key),
+                _ => information_metas.add(key, meta.clone())
+            };
+
+            informations.metas = (!information_metas.is_empty()).then_some(information_metas);
+            self.user_informations.set(user_id, informations)?;
+        }
+
+        self.user_metas.set(user_id, metas)?;
+        Ok(meta)
+    }
+
+    /* Room's meta lock is kept until the meta persisted, so concurrent operations will not lose any update. Rooms are not locked during the persist */
+    pub fn apply_room_meta_operation<F>(&self, room_id: &RoomId, key: String, access: RoomMetaAccess, operation: &RoomMetaOperation, persist: F) -> Result<RoomMetaType, YummyStateError> where F: FnMut(&RoomMetaType) -> anyhow::Result<()> {
+        self.with_meta_lock(format!("room:{}", room_id.to_string()), || self.inner_apply_room_meta_operation(room_id, key, access, operation, persist))
+    }
+
+    fn inner_apply_room_meta_operation<F>(&self, room_id: &RoomId, key: String, access: RoomMetaAccess, operation: &RoomMetaOperation, mut persist: F) -> Result<RoomMetaType, YummyStateError> where F: FnMut(&RoomMetaType) -> anyhow::Result<()> {
+        let current = match self.rooms.lock().get(room_id) {
+            Some(room) => room.metas.get(&key).filter(|meta| !meta.is_expired()).cloned(),
+            None => return Err(YummyStateError::RoomNotFound)
+        };
+
+        if let Some(current) = current.as_ref() {
+            if current.get_access_level() > access {
+                return Err(YummyStateError::MetaAccessLevelNotEnough);
+            }
+        }
+
+        let meta = operation.apply(current.as_ref())?;
+        persist(&meta)?;
+
+        if let Some(room) = self.rooms.lock().get_mut(room_id) {
+            match meta {
+                MetaType::Null => room.metas.remove(&key),
+                _ => room.metas.insert(key.clone(), meta.clone())
+            };
+        }
+
+        let mut metas = self.room_metas.get(room_id)?.unwrap_or_default();
+        match meta {
+            MetaType::Null => metas.remove_with_name(&key),
+            _ => metas.add(key, meta.clone())
+        };
+
+        self.room_metas.set(room_id, metas)?;
+        Ok(meta)
+    }
+
+    #[tracing::instrument(name="get_user_friends", skip(self))]
+    pub fn get_user_friends(&self, user_id: &UserId) -> Result<HashSet<UserId>, YummyStateError> {
+        Ok(self.user_friends.get(user_id)?.unwrap_or_default())
+    }
+
+    #[tracing::instrument(name="is_friend", skip(self))]
+    pub fn is_friend(&self, user_id: &UserId, target_user_id: &UserId) -> Result<bool, YummyStateError> {
+        Ok(self.get_user_friends(user_id)?.contains(target_user_id))
+    }
+
+    #[tracing::instrument(name="add_friend", skip(self))]
+    pub fn add_friend(&self, user_id: &UserId, friend_user_id: &UserId) -> Result<(), YummyStateError> {
+        let mut friends = self.get_user_friends(user_id)?;
+        friends.insert(friend_user_id.clone());
+        self.user_friends.set(user_id, friends)?;
+
+        let mut friends = self.get_user_friends(friend_user_id)?;
+        friends.insert(user_id.clone());
+        self.user_friends.set(friend_user_id, friends)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name="remove_friend", skip(self))]
+    pub fn remove_friend(&self, user_id: &UserId, friend_user_id: &UserId) -> Result<(), YummyStateError> {
+        let mut friends = self.get_user_friends(user_id)?;
+        friends.remove(friend_user_id);
+        self.user_friends.set(user_id, friends)?;
+
+        let mut friends = self.get_user_friends(friend_user_id)?;
+        friends.remove(user_id);
+        self.user_friends.set(friend_user_id, friends)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name="invalidate_user_cache", skip(self))]
+    pub fn invalidate_user_cache(&self, user_id: &UserId) -> Result<(), YummyStateError> {
+        self.user_informations.remove(user_id);
+        self.user_types.remove(user_id);
+        self.user_metas.remove(user_id);
+        self.user_friends.remove(user_id);
+        Ok(())
+    }
+
+    #[tracing::instrument(name="invalidate_room_meta_cache", skip(self))]
+    pub fn invalidate_room_meta_cache(&self, room_id: &RoomId) -> Result<(), YummyStateError> {
+        self.room_metas.remove(room_id);
+        Ok(())
+    }
+
     #[tracing::instrument(name="ban_user_from_room", skip(self))]
-    pub fn ban_user_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<(), YummyStateError> {
+    pub fn ban_user_from_room(&self, room_id: &RoomId, user_id: &UserId, ban: RoomBan) -> Result<(), YummyStateError> {
         match self.rooms.lock().get_mut(room_id) {
             Some(room) => {
-                room.banned_users.insert(user_id.clone());
+                room.banned_users.insert(user_id.clone(), ban);
                 Ok(())
             },
             None => Err(YummyStateError::RoomNotFound)
         }
     }
+
+    #[tracing::instrument(name="unban_user_from_room", skip(self))]
+    pub fn unban_user_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<bool, YummyStateError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => Ok(room.banned_users.remove(user_id).map(|ban| ban.is_active(now)).unwrap_or_default()),
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
     
     #[tracing::instrument(name="is_empty", skip(self))]
     pub fn is_empty(&self) -> bool {
         self.rooms.lock().len() == 0 &&
             self.users.lock().len() == 0 &&
             self.session_to_room.lock().len() == 0 &&
-            self.session_to_users.lock().len() == 0
+            self.session_to_users.lock().len() == 0 &&
+            self.sessions.lock().len() == 0
     }
 
     #[tracing::instrument(name="is_user_online", skip(self))]
@@ -276,9 +488,22 @@ impl YummyState {
         if let Some(room) = self.rooms.lock().get_mut(room_id) {
             match room.connections.get(&session_id)? {
                 Some(mut user) => {
+                    /* Spectators and players have separate capacities, moved user needs a free slot on the other side */
+                    match (&user.room_user_type, &user_type) {
+                        (RoomUserType::Spectator, RoomUserType::Spectator) => (),
+                        (RoomUserType::Spectator, _) => {
+                            room.has_free_slot(&user_type)?;
+                            room.spectator_count.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+                        },
+                        (_, RoomUserType::Spectator) => {
+                            room.has_free_slot(&user_type)?;
+                            room.spectator_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        },
+                        _ => ()
+                    };
+
                     user.room_user_type = user_type;
                     room.connections.set(&session_id, user)?;
-                    room.connection_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                 },
                 None => return Err(YummyStateError::UserNotFound)
             };
@@ -292,12 +517,58 @@ impl YummyState {
         self.session_to_users.lock().contains_key(session_id.borrow())
     }
 
+    #[tracing::instrument(name="get_user_sessions", skip(self))]
+    pub fn get_user_sessions(&self, user_id: &UserId) -> Vec<SessionId> {
+        match self.users.lock().get(user_id) {
+            Some(user) => user.sessions.iter().cloned().collect(),
+            None => Vec::new()
+        }
+    }
+
+    /* Sessions are ordered by creation, oldest session is the first one */
+    #[tracing::instrument(name="get_user_session_list", skip(self))]
+    pub fn get_user_session_list(&self, user_id: &UserId) -> Vec<UserSession> {
+        let session_ids = self.get_user_sessions(user_id);
+        let sessions = self.sessions.lock();
+
+        let mut result = session_ids
+            .iter()
+            .filter_map(|session_id| sessions.get(session_id))
+            .collect::<Vec<_>>();
+
+        result.sort_by_key(|session| session.order);
+        result.into_iter().map(|session| session.info.clone()).collect()
+    }
+
+    #[tracing::instrument(name="update_session_activity", skip(self))]
+    pub fn update_session_activity(&self, session_id: &SessionId, client: Option<ClientInfo>) {
+        if let Some(session) = self.sessions.lock().get_mut(session_id) {
+            session.info.last_activity = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+            if let Some(client) = client {
+                session.info.ip = client.ip;
+                session.info.client = client.client;
+            }
+        }
+    }
+
     #[tracing::instrument(name="new_session", skip(self))]
-    pub fn new_session(&self, user_id: &UserId, name: Option<String>, user_type: UserType) -> SessionId {
+    pub fn new_session(&self, user_id: &UserId, name: Option<String>, user_type: UserType, client: ClientInfo) -> SessionId {
         use std::collections::HashSet;
 
         let session_id = SessionId::new();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
         self.session_to_users.lock().insert(session_id.clone(), Arc::new(user_id.clone()));
+        self.sessions.lock().insert(session_id.clone(), SessionState {
+            order: self.session_counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            info: UserSession {
+                session_id: session_id.clone(),
+                insert_date: now,
+                last_activity: now,
+                ip: client.ip,
+                client: client.client
+            }
+        });
 
         let mut users = self.users.lock();
 
@@ -337,6 +608,7 @@ impl YummyState {
     #[tracing::instrument(name="close_session", skip(self))]
     pub fn close_session(&self, user_id: &UserId, session_id: &SessionId) -> bool {
         let user_id = self.session_to_users.lock().remove(session_id);
+        self.sessions.lock().remove(session_id);
 
         match user_id {
             Some(user_id) => {
@@ -365,22 +637,32 @@ impl YummyState {
 
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(name="create_room", skip(self))]
-    pub fn create_room(&self, room_id: &RoomId, insert_date: i32, name: Option<String>, description: Option<String>, access_type: CreateRoomAccessType, max_user: usize, tags: Vec<String>, metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>, join_request: bool) {
+    pub fn create_room(&self, room_id: &RoomId, insert_date: i32, name: Option<String>, description: Option<String>, access_type: CreateRoomAccessType, max_user: usize, max_spectator: usize, tags: Vec<String>, metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>, join_request: bool, password: Option<String>, auto_start: bool) {
         use std::collections::HashMap;
 
         self.rooms.lock().insert(*room_id, RoomState {
             max_user,
+            max_spectator,
             insert_date,
             connection_count: AtomicUsize::new(0),
+            spectator_count: AtomicUsize::new(0),
+            join_counter: 0,
             connections: YummyCache::new(self.config.clone(), Box::new(ConnectionResource::default())),
             tags,
             name,
             description,
+            password,
             access_type,
             metas: metas.unwrap_or_default(),
             join_request,
+            auto_start,
+            game_state: RoomGameState::default(),
+            ready_users: HashSet::default(),
             join_requests: HashMap::default(),
-            banned_users: HashSet::default(),
+            banned_users: HashMap::default(),
+            messages: VecDeque::default(),
+            message_counter: 0,
+            invites: HashMap::default(),
         });
     }
 
@@ -393,25 +675,20 @@ impl YummyState {
                     return Err(YummyStateError::UserAlreadInRoom);
                 }
                 
-                let users_len = room.connection_count.load(std::sync::atomic::Ordering::Relaxed);
+                room.has_free_slot(&user_type)?;
 
-                // If the max_user 0 or lower than users count, add to room
-                if room.max_user == 0 || room.max_user > users_len {
-                    let inserted = room.join_requests.insert(session_id.clone(), user_type);
+                let inserted = room.join_requests.insert(session_id.clone(), user_type);
 
-                    if inserted.is_some() {
-                        return Err(YummyStateError::AlreadyRequested)
-                    }
+                if inserted.is_some() {
+                    return Err(YummyStateError::AlreadyRequested)
+                }
 
-                    match self.users.lock().get_mut(user_id) {
-                        Some(user) => user.join_requests.insert(*room_id, session_id.clone()),
-                        None => return Err(YummyStateError::UserNotFound)
-                    };
+                match self.users.lock().get_mut(user_id) {
+                    Some(user) => user.join_requests.insert(*room_id, session_id.clone()),
+                    None => return Err(YummyStateError::UserNotFound)
+                };
 
-                    Ok(())
-                } else {
-                    Err(YummyStateError::RoomHasMaxUsers)
-                }
+                Ok(())
             }
             None => Err(YummyStateError::RoomNotFound)
         }
@@ -448,37 +725,36 @@ impl YummyState {
         match self.rooms.lock().get_mut(room_id.borrow()) {
             Some(room) => {
 
-                let users_len = room.connection_count.load(std::sync::atomic::Ordering::Relaxed);
+                room.has_free_slot(&room_user_type)?;
 
-                // If the max_user 0 or lower than users count, add to room
-                if room.max_user == 0 || room.max_user > users_len {
-
-                    // User alread in the room
-                    if room.connections.contains(session_id) {
-                        return Err(YummyStateError::UserAlreadInRoom);
-                    }
+                // User alread in the room
+                if room.connections.contains(session_id) {
+                    return Err(YummyStateError::UserAlreadInRoom);
+                }
 
-                    room.connection_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                    room.connections.set(session_id, ConnectionInfo { user_id: Arc::new(user_id.clone()), room_user_type })?;
-                    
-                    let mut user_to_room = self.session_to_room.lock();
-                    match user_to_room.get_mut(session_id) {
-                        Some(user_to_room) => {
-                            user_to_room.insert(*room_id);
-                        },
-                        None => {
-                            user_to_room.insert(session_id.clone(), std::collections::HashSet::from([*room_id]));
-                        }
-                    };
+                if room_user_type == RoomUserType::Spectator {
+                    room.spectator_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
 
-                    if let Some(user) = self.users.lock().get_mut(user_id) {
-                        user.joined_rooms.insert(*room_id, session_id.clone());
+                room.join_counter += 1;
+                room.connection_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                room.connections.set(session_id, ConnectionInfo { user_id: Arc::new(user_id.clone()), room_user_type, join_order: room.join_counter })?;
+                
+                let mut user_to_room = self.session_to_room.lock();
+                match user_to_room.get_mut(session_id) {
+                    Some(user_to_room) => {
+                        user_to_room.insert(*room_id);
+                    },
+                    None => {
+                        user_to_room.insert(session_id.clone(), std::collections::HashSet::from([*room_id]));
                     }
+                };
 
-                    Ok(())
-                } else {
-                    Err(YummyStateError::RoomHasMaxUsers)
+                if let Some(user) = self.users.lock().get_mut(user_id) {
+                    user.joined_rooms.insert(*room_id, session_id.clone());
                 }
+
+                Ok(())
             }
             None => Err(YummyStateError::RoomNotFound)
         }
@@ -528,7 +804,12 @@ impl YummyState {
                 let user_removed = room.connections.contains(session_id);
                 match user_removed {
                     true => {
+                        if let Some(RoomUserType::Spectator) = room.connections.get(session_id)?.map(|connection| connection.room_user_type) {
+                            room.spectator_count.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+
                         room.connections.remove(session_id);
+                        room.ready_users.remove(user_id);
                         let previous_value = room.connection_count.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
                         Ok(previous_value - 1 == 0)
                     },
@@ -545,6 +826,25 @@ impl YummyState {
         Ok(room_removed)
     }
 
+    #[tracing::instrument(name="set_room_user_ready", skip(self))]
+    pub fn set_room_user_ready(&self, room_id: &RoomId, user_id: &UserId, ready: bool) -> Result<(), YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                if !room.connections.iter().any(|(_, connection)| connection.user_id.as_ref() == user_id) {
+                    return Err(YummyStateError::UserCouldNotFoundInRoom);
+                }
+
+                match ready {
+                    true => room.ready_users.insert(user_id.clone()),
+                    false => room.ready_users.remove(user_id)
+                };
+
+                Ok(())
+            }
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
     #[tracing::instrument(name="get_users_from_room", skip(self))]
     pub fn get_users_from_room(&self, room_id: &RoomId) -> Result<Vec<Arc<UserId>>, YummyStateError> {
         match self.rooms.lock().get(room_id) {
@@ -580,6 +880,11 @@ impl YummyState {
         None
     }
 
+    #[tracing::instrument(name="get_session_location", skip(self))]
+    pub fn get_session_location(&self, session_id: &SessionId) -> Option<String> {
+        None
+    }
+
     #[tracing::instrument(name="get_join_requests", skip(self))]
     pub fn get_join_requests(&self, room_id: &RoomId) -> Result<HashMap<Arc<UserId>, RoomUserType>, YummyStateError> {
         match self.rooms.lock().get(room_id) {
@@ -602,12 +907,51 @@ impl YummyState {
 
     #[tracing::instrument(name="is_user_banned_from_room", skip(self))]
     pub fn is_user_banned_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<bool, YummyStateError> {
-        match self.rooms.lock().get(room_id) {
-            Some(room) => Ok(room.banned_users.contains(user_id)),
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                // Expired bans are removed
+                room.banned_users.retain(|_, ban| ban.is_active(now));
+                Ok(room.banned_users.contains_key(user_id))
+            },
             None => Err(YummyStateError::RoomNotFound)
         }
     }
 
+    fn get_active_bans(&self, room: &RoomState) -> HashMap<UserId, RoomBan> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        room.banned_users.iter()
+            .filter(|(_, ban)| ban.is_active(now))
+            .map(|(user_id, ban)| (user_id.clone(), ban.clone()))
+            .collect()
+    }
+
+    /* Users are ordered by join time, oldest member is the first one */
+    fn get_room_users(&self, room: &RoomState, spectators: bool) -> Vec<RoomUserInformation> {
+        let mut users = Vec::new();
+        let user_cache = self.users.lock();
+
+        for (_, connection_info) in room.connections.iter() {
+            if (connection_info.room_user_type == RoomUserType::Spectator) != spectators {
+                continue;
+            }
+
+            let name = match user_cache.get(connection_info.user_id.deref()) {
+                Some(user) => user.name.clone(),
+                None => None
+            };
+            users.push((connection_info.join_order, RoomUserInformation {
+                user_id: connection_info.user_id.clone(),
+                name,
+                user_type: connection_info.room_user_type.clone()
+            }));
+        }
+
+        users.sort_by_key(|(join_order, _)| *join_order);
+        users.into_iter().map(|(_, user)| user).collect()
+    }
+
     #[tracing::instrument(name="get_room_info", skip(self))]
     pub fn get_room_info(&self, room_id: &RoomId, access_level: RoomMetaAccess, query: &[RoomInfoTypeVariant]) -> Result<RoomInfoTypeCollection, YummyStateError> {
         let mut result = RoomInfoTypeCollection::default();
@@ -619,35 +963,28 @@ impl YummyState {
                     let item = match item {
                         RoomInfoTypeVariant::InsertDate => RoomInfoType::InsertDate(room.insert_date),
                         RoomInfoTypeVariant::MaxUser => RoomInfoType::MaxUser(room.max_user),
+                        RoomInfoTypeVariant::MaxSpectator => RoomInfoType::MaxSpectator(room.max_spectator),
                         RoomInfoTypeVariant::RoomName => RoomInfoType::RoomName(room.name.clone()),
                         RoomInfoTypeVariant::Description => RoomInfoType::Description(room.description.clone()),
-                        RoomInfoTypeVariant::UserLength => RoomInfoType::UserLength(room.connection_count.load(std::sync::atomic::Ordering::Relaxed)),
+                        RoomInfoTypeVariant::UserLength => RoomInfoType::UserLength(room.get_user_length()),
+                        RoomInfoTypeVariant::SpectatorLength => RoomInfoType::SpectatorLength(room.spectator_count.load(std::sync::atomic::Ordering::Relaxed)),
                         RoomInfoTypeVariant::AccessType => RoomInfoType::AccessType(room.access_type),
                         RoomInfoTypeVariant::JoinRequest => RoomInfoType::JoinRequest(room.join_request),
-                        RoomInfoTypeVariant::Users => {
-                            let mut users = Vec::new();
-                            let user_cache = self.users.lock();
-
-                            for (_, connection_info) in room.connections.iter() {
-                                let name = match user_cache.get(connection_info.user_id.deref()) {
-                                    Some(user) => user.name.clone(),
-                                    None => None
-                                };
-                                users.push(RoomUserInformation {
-                                    user_id: connection_info.user_id.clone(),
-                                    name,
-                                    user_type: connection_info.room_user_type.clone()
-                                });
-                            }
-
-                            RoomInfoType::Users(users)
-                        },
-                        RoomInfoTypeVariant::BannedUsers => RoomInfoType::BannedUsers(room.banned_users.clone()),
+                        RoomInfoTypeVariant::Password => RoomInfoType::Password(match access_level >= RoomMetaAccess::Moderator {
+                            true => room.password.clone(),
+                            false => None
+                        }),
+                        RoomInfoTypeVariant::Users => RoomInfoType::Users(self.get_room_users(room, false)),
+                        RoomInfoTypeVariant::Spectators => RoomInfoType::Spectators(self.get_room_users(room, true)),
+                        RoomInfoTypeVariant::BannedUsers => RoomInfoType::BannedUsers(self.get_active_bans(room)),
                         RoomInfoTypeVariant::Tags => RoomInfoType::Tags(room.tags.clone()),
+                        RoomInfoTypeVariant::GameState => RoomInfoType::GameState(room.game_state),
+                        RoomInfoTypeVariant::ReadyUsers => RoomInfoType::ReadyUsers(room.ready_users.clone()),
+                        RoomInfoTypeVariant::AutoStart => RoomInfoType::AutoStart(room.auto_start),
                         RoomInfoTypeVariant::Metas => {
                             let metas: HashMap<String, MetaType<RoomMetaAccess>> = room.metas
                                 .iter()
-                                .filter(|(_, value)| value.get_access_level() <= access_level)
+                                .filter(|(_, value)| value.get_access_level() <= access_level && !value.is_expired())
                                 .map(|(key, value)| (key.clone(), value.clone()))
                                 .collect();
                             RoomInfoType::Metas(metas)
@@ -672,28 +1009,59 @@ impl YummyState {
                     RoomInfoType::Description(description) => room.description = description,
                     RoomInfoType::Users(_) => (),
                     RoomInfoType::MaxUser(max_user) => room.max_user = max_user,
+                    RoomInfoType::MaxSpectator(max_spectator) => room.max_spectator = max_spectator,
                     RoomInfoType::UserLength(_) => (),
+                    RoomInfoType::SpectatorLength(_) => (),
+                    RoomInfoType::Spectators(_) => (),
                     RoomInfoType::AccessType(access_type) => room.access_type = access_type,
                     RoomInfoType::JoinRequest(join_request) => room.join_request = join_request,
+                    RoomInfoType::Password(password) => room.password = password,
                     RoomInfoType::Tags(tags) => room.tags = tags,
                     RoomInfoType::Metas(metas) => room.metas = metas,
                     RoomInfoType::BannedUsers(banned_users) => room.banned_users = banned_users,
                     RoomInfoType::InsertDate(_) => (),
+                    RoomInfoType::GameState(game_state) => room.game_state = game_state,
+                    RoomInfoType::ReadyUsers(ready_users) => room.ready_users = ready_users,
+                    RoomInfoType::AutoStart(auto_start) => room.auto_start = auto_start,
                 };
             }
         }
     }
 
     #[tracing::instrument(name="get_rooms", skip(self))]
-    pub fn get_rooms(&self, tag: &Option<String>, query: &[RoomInfoTypeVariant]) -> Result<Vec<RoomInfoTypeCollection>, YummyStateError> {
+    pub fn get_rooms(&self, filter: &RoomListFilter, query: &[RoomInfoTypeVariant]) -> Result<(Vec<RoomInfoTypeCollection>, Option<String>), YummyStateError> {
         let mut result = Vec::default();
+        let limit = filter.get_limit(self.config.max_room_list_page_size);
+        let cursor = filter.get_cursor();
         let rooms = self.rooms.lock();
-        let rooms = match tag {
-            Some(tag) => rooms.iter().filter(|item| item.1.tags.contains(tag)).collect::<Vec<_>>(),
-            None => rooms.iter().collect::<Vec<_>>()
+
+        let mut rooms = rooms.iter()
+            .filter(|(_, room_state)| filter.is_tags_match(&room_state.tags))
+            .filter(|(_, room_state)| filter.access_type.map(|access_type| access_type == room_state.access_type).unwrap_or(true))
+            .filter(|(_, room_state)| filter.is_game_state_match(room_state.game_state))
+            .filter(|(_, room_state)| !filter.has_free_slot || room_state.has_free_slot(&RoomUserType::User).is_ok())
+            .filter(|(_, room_state)| filter.metas.iter().all(|meta| meta.is_match(room_state.metas.get(&meta.key))))
+            .map(|(room_id, room_state)| {
+                let score = match filter.sort {
+                    RoomListSort::InsertDate => room_state.insert_date as i64,
+                    RoomListSort::UserCount => room_state.get_user_length() as i64
+                };
+                (score, room_id.to_string(), room_id, room_state)
+            })
+            .filter(|(score, room_id, _, _)| cursor.as_ref().map(|cursor| cursor.is_after(*score, room_id, filter.descending)).unwrap_or(true))
+            .collect::<Vec<_>>();
+
+        rooms.sort_by(|left, right| (left.0, &left.1).cmp(&(right.0, &right.1)));
+        if filter.descending {
+            rooms.reverse();
+        }
+
+        let next_cursor = match limit > 0 && rooms.len() > limit {
+            true => rooms.get(limit - 1).map(|(score, room_id, _, _)| RoomListCursor::new(*score, room_id.clone()).to_string()),
+            false => None
         };
 
-        for (room_id, room_state) in rooms.into_iter() {
+        for (_, _, room_id, room_state) in rooms.into_iter().take(limit) {
             let mut room_info = RoomInfoTypeCollection {
                 room_id: Some(*room_id),
                 .. Default::default()
@@ -705,39 +1073,230 @@ impl YummyState {
                 match item {
                     RoomInfoTypeVariant::InsertDate => room_info.items.push(RoomInfoType::InsertDate(room_state.insert_date)),
                     RoomInfoTypeVariant::MaxUser => room_info.items.push(RoomInfoType::MaxUser(room_state.max_user)),
+                    RoomInfoTypeVariant::MaxSpectator => room_info.items.push(RoomInfoType::MaxSpectator(room_state.max_spectator)),
                     RoomInfoTypeVariant::JoinRequest => room_info.items.push(RoomInfoType::JoinRequest(room_state.join_request)),
+                    RoomInfoTypeVariant::Password => room_info.items.push(RoomInfoType::Password(None)),
                     RoomInfoTypeVariant::RoomName => room_info.items.push(RoomInfoType::RoomName(room_state.name.clone())),
                     RoomInfoTypeVariant::Description => room_info.items.push(RoomInfoType::Description(room_state.description.clone())),
-                    RoomInfoTypeVariant::UserLength => room_info.items.push(RoomInfoType::UserLength(room_state.connection_count.load(std::sync::atomic::Ordering::Relaxed))),
+                    RoomInfoTypeVariant::UserLength => room_info.items.push(RoomInfoType::UserLength(room_state.get_user_length())),
+                    RoomInfoTypeVariant::SpectatorLength => room_info.items.push(RoomInfoType::SpectatorLength(room_state.spectator_count.load(std::sync::atomic::Ordering::Relaxed))),
                     RoomInfoTypeVariant::AccessType => room_info.items.push(RoomInfoType::AccessType(room_state.access_type)),
-                    RoomInfoTypeVariant::Users => {                        
-                        let mut users = Vec::new();
-                        let user_cache = self.users.lock();
-
-                        for (_, connection_info) in room_state.connections.iter() {
-                            let name = match user_cache.get(connection_info.user_id.deref()) {
-                                Some(user) => user.name.clone(),
-                                None => None
-                            };
-                            users.push(RoomUserInformation {
-                                user_id: connection_info.user_id.clone(),
-                                name,
-                                user_type: connection_info.room_user_type.clone()
-                            });
-                        }
-
-                        room_info.items.push(RoomInfoType::Users(users))
-                    },
-                    RoomInfoTypeVariant::BannedUsers => room_info.items.push(RoomInfoType::BannedUsers(room_state.banned_users.clone())),
+                    RoomInfoTypeVariant::Users => room_info.items.push(RoomInfoType::Users(self.get_room_users(room_state, false))),
+                    RoomInfoTypeVariant::Spectators => room_info.items.push(RoomInfoType::Spectators(self.get_room_users(room_state, true))),
+                    RoomInfoTypeVariant::BannedUsers => room_info.items.push(RoomInfoType::BannedUsers(self.get_active_bans(room_state))),
                     RoomInfoTypeVariant::Tags => room_info.items.push(RoomInfoType::Tags(room_state.tags.clone())),
-                    RoomInfoTypeVariant::Metas => room_info.items.push(RoomInfoType::Metas(room_state.metas.clone()))
+                    RoomInfoTypeVariant::Metas => room_info.items.push(RoomInfoType::Metas(room_state.metas.iter().filter(|(_, value)| !value.is_expired()).map(|(key, value)| (key.clone(), value.clone())).collect())),
+                    RoomInfoTypeVariant::GameState => room_info.items.push(RoomInfoType::GameState(room_state.game_state)),
+                    RoomInfoTypeVariant::ReadyUsers => room_info.items.push(RoomInfoType::ReadyUsers(room_state.ready_users.clone())),
+                    RoomInfoTypeVariant::AutoStart => room_info.items.push(RoomInfoType::AutoStart(room_state.auto_start))
                 };
             }
 
             result.push(room_info);
         }
 
-        Ok(result)
+        Ok((result, next_cursor))
+    }
+
+    /* Only the last messages are kept, history size 0 disables the history but messages still get an id */
+    #[tracing::instrument(name="add_room_message", skip(self))]
+    pub fn add_room_message(&self, room_id: &RoomId, user_id: Option<&UserId>, message: serde_json::Value, insert_date: i32) -> Result<RoomMessage, YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                room.message_counter += 1;
+
+                let message = RoomMessage {
+                    id: room.message_counter,
+                    user_id: user_id.cloned(),
+                    message,
+                    insert_date
+                };
+
+                room.messages.push_back(message.clone());
+                while room.messages.len() > self.config.room_message_history_size {
+                    room.messages.pop_front();
+                }
+
+                Ok(message)
+            },
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    #[tracing::instrument(name="get_room_messages", skip(self))]
+    pub fn get_room_messages(&self, room_id: &RoomId, before: Option<usize>, limit: usize) -> Result<Vec<RoomMessage>, YummyStateError> {
+        match self.rooms.lock().get(room_id) {
+            Some(room) => {
+                let messages = room.messages.iter()
+                    .filter(|message| before.map(|before| message.id < before).unwrap_or(true))
+                    .collect::<Vec<_>>();
+
+                Ok(messages[messages.len().saturating_sub(limit)..].iter().map(|message| (*message).clone()).collect())
+            },
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    /* Previous invitation for the same user is replaced */
+    #[tracing::instrument(name="add_room_invite", skip(self))]
+    pub fn add_room_invite(&self, room_id: &RoomId, invite: RoomInvite) -> Result<(), YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                room.invites.insert(invite.user_id.clone(), invite);
+                Ok(())
+            },
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    #[tracing::instrument(name="get_room_invite", skip(self))]
+    pub fn get_room_invite(&self, room_id: &RoomId, user_id: &UserId, now: i32) -> Result<Option<RoomInvite>, YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                room.invites.retain(|_, invite| invite.expire_date > now);
+                Ok(room.invites.get(user_id).cloned())
+            },
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    #[tracing::instrument(name="get_room_invites", skip(self))]
+    pub fn get_room_invites(&self, room_id: &RoomId, now: i32) -> Result<Vec<RoomInvite>, YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => {
+                room.invites.retain(|_, invite| invite.expire_date > now);
+
+                let mut invites = room.invites.values().cloned().collect::<Vec<_>>();
+                invites.sort_by_key(|invite| invite.insert_date);
+                Ok(invites)
+            },
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    #[tracing::instrument(name="remove_room_invite", skip(self))]
+    pub fn remove_room_invite(&self, room_id: &RoomId, user_id: &UserId) -> Result<Option<RoomInvite>, YummyStateError> {
+        match self.rooms.lock().get_mut(room_id) {
+            Some(room) => Ok(room.invites.remove(user_id)),
+            None => Err(YummyStateError::RoomNotFound)
+        }
+    }
+
+    #[tracing::instrument(name="add_matchmaking_ticket", skip(self))]
+    pub fn add_matchmaking_ticket(&self, queue: &str, ticket: MatchmakingTicket) -> Result<(), YummyStateError> {
+        let mut queues = self.matchmaking.lock();
+        let tickets = queues.entry(queue.to_string()).or_default();
+
+        if tickets.contains_key(&ticket.session_id) {
+            return Err(YummyStateError::UserAlreadyInQueue);
+        }
+
+        tickets.insert(ticket.session_id.clone(), ticket);
+        Ok(())
+    }
+
+    #[tracing::instrument(name="remove_matchmaking_ticket", skip(self))]
+    pub fn remove_matchmaking_ticket(&self, queue: &str, session_id: &SessionId) -> Result<bool, YummyStateError> {
+        let mut queues = self.matchmaking.lock();
+        let removed = match queues.get_mut(queue) {
+            Some(tickets) => tickets.remove(session_id).is_some(),
+            None => false
+        };
+
+        if queues.get(queue).map(|tickets| tickets.is_empty()).unwrap_or_default() {
+            queues.remove(queue);
+        }
+
+        Ok(removed)
+    }
+
+    #[tracing::instrument(name="get_matchmaking_queues", skip(self))]
+    pub fn get_matchmaking_queues(&self) -> Result<Vec<String>, YummyStateError> {
+        Ok(self.matchmaking.lock().keys().cloned().collect())
+    }
+
+    #[tracing::instrument(name="get_matchmaking_tickets", skip(self))]
+    pub fn get_matchmaking_tickets(&self, queue: &str) -> Result<Vec<MatchmakingTicket>, YummyStateError> {
+        Ok(self.matchmaking.lock().get(queue).map(|tickets| tickets.values().cloned().collect()).unwrap_or_default())
+    }
+
+    /* Remove all tickets from the queue only if all of them still waiting in the queue */
+    #[tracing::instrument(name="claim_matchmaking_tickets", skip(self))]
+    pub fn claim_matchmaking_tickets(&self, queue: &str, session_ids: &[SessionId]) -> Result<bool, YummyStateError> {
+        let mut queues = self.matchmaking.lock();
+        let tickets = match queues.get_mut(queue) {
+            Some(tickets) => tickets,
+            None => return Ok(false)
+        };
+
+        if !session_ids.iter().all(|session_id| tickets.contains_key(session_id)) {
+            return Ok(false);
+        }
+
+        for session_id in session_ids.iter() {
+            tickets.remove(session_id);
+        }
+
+        if tickets.is_empty() {
+            queues.remove(queue);
+        }
+
+        Ok(true)
+    }
+
+    #[tracing::instrument(name="create_token_family", skip(self))]
+    pub fn create_token_family(&self, user_id: &UserId, family: &TokenFamilyId, refresh_token: &TokenId) -> Result<(), YummyStateError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let mut families = self.token_families.lock();
+
+        // Expired families are not valid anymore, no need to keep them
+        families.retain(|_, family| family.expire_date > now);
+        families.insert(family.clone(), TokenFamily {
+            user_id: user_id.clone(),
+            refresh_token: refresh_token.clone(),
+            expire_date: now + self.config.refresh_token_lifetime.as_secs() as i32
+        });
+        Ok(())
+    }
+
+    #[tracing::instrument(name="is_token_family_active", skip(self))]
+    pub fn is_token_family_active(&self, family: &TokenFamilyId) -> Result<bool, YummyStateError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        Ok(self.token_families.lock().get(family).map(|family| family.expire_date > now).unwrap_or_default())
+    }
+
+    /* The refresh token can be used only once. If an old one comes back, the token is stolen and the whole family is revoked */
+    #[tracing::instrument(name="rotate_refresh_token", skip(self))]
+    pub fn rotate_refresh_token(&self, family: &TokenFamilyId, refresh_token: &TokenId, new_refresh_token: &TokenId) -> Result<RefreshTokenRotation, YummyStateError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let mut families = self.token_families.lock();
+
+        match families.get_mut(family) {
+            Some(token_family) if token_family.expire_date > now => match &token_family.refresh_token == refresh_token {
+                true => {
+                    token_family.refresh_token = new_refresh_token.clone();
+                    token_family.expire_date = now + self.config.refresh_token_lifetime.as_secs() as i32;
+                    Ok(RefreshTokenRotation::Rotated)
+                },
+                false => {
+                    families.remove(family);
+                    Ok(RefreshTokenRotation::Reused)
+                }
+            },
+            _ => Ok(RefreshTokenRotation::NotFound)
+        }
+    }
+
+    #[tracing::instrument(name="revoke_token_family", skip(self))]
+    pub fn revoke_token_family(&self, family: &TokenFamilyId) -> Result<(), YummyStateError> {
+        self.token_families.lock().remove(family);
+        Ok(())
+    }
+
+    #[tracing::instrument(name="revoke_user_tokens", skip(self))]
+    pub fn revoke_user_tokens(&self, user_id: &UserId) -> Result<(), YummyStateError> {
+        self.token_families.lock().retain(|_, family| &family.user_id != user_id);
+        Ok(())
     }
 }
 
@@ -2,6 +2,8 @@
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
+use std::collections::HashSet;
+
 use yummy_model::{UserId, UserInformationModel, meta::collection::{UserMetaCollection, RoomMetaCollection}, UserType, RoomId};
 use crate::cache::YummyCacheResource;
 
@@ -18,6 +20,7 @@ pub trait YummyCacheResourceFactory {
     fn user_metas(&self) -> Box<dyn YummyCacheResource<K=UserId, V=UserMetaCollection>>;
     fn user_type(&self) -> Box<dyn YummyCacheResource<K=UserId, V=UserType>>;
     fn room_metas(&self) -> Box<dyn YummyCacheResource<K=RoomId, V=RoomMetaCollection>>;
+    fn user_friends(&self) -> Box<dyn YummyCacheResource<K=UserId, V=HashSet<UserId>>>;
 }
 
 /* **************************************************************************************************************** */
@@ -1,17 +1,27 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use redis::Commands;
 use super::*;
+use super::resource::YummyCacheResourceFactory;
+use crate::cache::YummyCacheResource;
 
 use yummy_model::config::YummyConfig;
-use yummy_model::meta::{RoomMetaAccess, MetaType};
-use yummy_model::{UserId, RoomId, SessionId};
+use yummy_model::meta::{RoomMetaAccess, UserMetaAccess, MetaType, UserMetaType, RoomMetaType};
+use yummy_model::meta::operation::{MetaOperation, UserMetaOperation, RoomMetaOperation};
+use yummy_model::{UserId, RoomId, SessionId, TokenId, TokenFamilyId};
+use yummy_model::auth::RefreshTokenRotation;
 use yummy_model::CreateRoomAccessType;
 use yummy_model::RoomUserType;
+use yummy_model::RoomGameState;
 use yummy_model::UserType;
+use yummy_model::matchmaking::MatchmakingTicket;
+use yummy_model::state::{RoomListFilter, RoomListSort, RoomListCursor, RoomMessage, RoomBan, ClientInfo, UserSession};
 
 #[allow(unused_macros)]
 macro_rules! redis_result {
@@ -27,32 +37,588 @@ macro_rules! redis_result {
     }
 }
 
+/* Empty member keeps the friend set in redis even if the user does not have any friend */
+const FRIEND_SET_MARKER: &str = "";
+
+/* Room list keys are removed after the query, lifetime is the fallback if the connection is lost in the meantime */
+const ROOM_LIST_KEY_LIFETIME: usize = 60; // in seconds
+
 #[derive(Clone)]
 pub struct YummyState {
     #[allow(dead_code)]
     config: Arc<YummyConfig>,
-    redis: r2d2::Pool<redis::Client>
+    redis: r2d2::Pool<redis::Client>,
+    user_friends: Arc<dyn YummyCacheResource<K=UserId, V=HashSet<UserId>>>
 }
 
 impl YummyState {
-    pub fn new(config: Arc<YummyConfig>, redis: r2d2::Pool<redis::Client>) -> Self {
-        Self {
+    pub fn new(config: Arc<YummyConfig>, resource_factory: Box<dyn YummyCacheResourceFactory>, redis: r2d2::Pool<redis::Client>) -> Self {
+        Self::check_redis_support(&redis);
+        let state = Self {
             config,
-            redis
+            redis,
+            user_friends: Arc::from(resource_factory.user_friends())
+        };
+        state.migrate_room_bans();
+        state
+    }
+
+    /* Bans were kept in "room-banned" sets without any ban information, they are moved into "room-bans" hashes as permanent bans */
+    fn migrate_room_bans(&self) {
+        let mut redis = match self.redis.get() {
+            Ok(redis) => redis,
+            Err(_) => return
+        };
+
+        let prefix = format!("{}room-banned:", self.config.redis_prefix);
+        let keys = match redis.scan_match::<_, String>(format!("{}*", prefix)) {
+            Ok(keys) => keys.collect::<Vec<_>>(),
+            Err(error) => {
+                log::error!("Redis error: {}", error.to_string());
+                return;
+            }
+        };
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let ban = serde_json::to_string(&RoomBan { reason: None, insert_date: now, expire_date: None }).unwrap_or_default();
+
+        for key in keys.into_iter() {
+            let room_id = key.trim_start_matches(&prefix);
+            let users = redis_result!(redis.smembers::<_, Vec<String>>(&key));
+
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+
+            for user_id in users.iter() {
+                pipe.cmd("HSETNX").arg(format!("{}room-bans:{}", self.config.redis_prefix, room_id)).arg(user_id).arg(&ban).ignore();
+            }
+
+            redis_result!(pipe.cmd("DEL").arg(&key).ignore().query::<()>(&mut redis));
+        }
+    }
+
+    /* Expiring metas are stored with the hash field expiration, older servers reject them */
+    /* Expiring metas are written with HEXPIREAT inside the transactions, unknown command aborts the whole transaction and the meta is not written.
+       Commands are checked instead of the version, compatible servers are not always reporting the Redis version */
+    fn check_redis_support(redis: &r2d2::Pool<redis::Client>) {
+        let mut redis = redis.get().unwrap_or_else(|error| panic!("Redis connection could not be created. {}", error));
+        let commands = redis::cmd("COMMAND").arg("INFO").arg("HEXPIREAT").arg("HEXPIRETIME")
+            .query::<Vec<redis::Value>>(&mut *redis)
+            .unwrap_or_else(|error| panic!("Redis commands could not be checked. {}", error));
+
+        if commands.iter().any(|command| matches!(command, redis::Value::Nil)) {
+            panic!("Redis does not support hash field expiration, Redis 7.4 or newer is required");
+        }
+    }
+
+    /* Players and spectators are counted in different fields */
+    fn get_length_field(user_type: &RoomUserType) -> &'static str {
+        match user_type {
+            RoomUserType::Spectator => "spec-len",
+            _ => "user-len"
+        }
+    }
+
+    /* Spectators are not counted for the user count sorting */
+    fn get_user_count_change(user_type: &RoomUserType) -> i32 {
+        match user_type {
+            RoomUserType::Spectator => 0,
+            _ => 1
+        }
+    }
+
+    /* room_info should have user-len, max-user, spec-len and max-spec fields. 0 means unlimited */
+    fn has_free_slot(room_info: &[usize], user_type: &RoomUserType) -> Result<(), YummyStateError> {
+        let user_len = room_info.first().copied().unwrap_or_default();
+        let max_user = room_info.get(1).copied().unwrap_or_default();
+        let spectator_len = room_info.get(2).copied().unwrap_or_default();
+        let max_spectator = room_info.get(3).copied().unwrap_or_default();
+
+        match user_type {
+            RoomUserType::Spectator => match max_spectator == 0 || max_spectator > spectator_len {
+                true => Ok(()),
+                false => Err(YummyStateError::RoomHasMaxSpectators)
+            },
+            _ => match max_user == 0 || max_user > user_len {
+                true => Ok(()),
+                false => Err(YummyStateError::RoomHasMaxUsers)
+            }
+        }
+    }
+
+    fn parse_room_meta(type_info: i32, value: &redis::Value, access: i32) -> MetaType<RoomMetaAccess> {
+        Self::parse_meta(type_info, value, access)
+    }
+
+    fn parse_meta<T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>>(type_info: i32, value: &redis::Value, access: i32) -> MetaType<T> {
+        use redis::FromRedisValue;
+
+        match type_info {
+            1 => MetaType::Number(FromRedisValue::from_redis_value(value).unwrap_or_default(), T::from(access)),
+            2 => MetaType::String(FromRedisValue::from_redis_value(value).unwrap_or_default(), T::from(access)),
+            3 => MetaType::Bool(FromRedisValue::from_redis_value(value).unwrap_or_default(), T::from(access)),
+            4 => {
+                let value: String = FromRedisValue::from_redis_value(value).unwrap_or_default();
+                MetaType::List(Box::new(serde_json::from_str(&value).unwrap_or_default()), T::from(access))
+            },
+            5 => {
+                let value: String = FromRedisValue::from_redis_value(value).unwrap_or_default();
+                MetaType::Object(Box::new(serde_json::from_str(&value).unwrap_or_default()), T::from(access))
+            },
+            _ => MetaType::Number(FromRedisValue::from_redis_value(value).unwrap_or_default(), T::from(access)),
+        }
+    }
+
+    /* Redis expires the hash fields natively, so expired metas are never returned back. Hash field expiration requires Redis 7.4,
+       so the commands are sent only for the expiring metas and the expiring keys are marked to not ask the expire date of every meta */
+    fn expire_meta_fields<'a>(pipe: &'a mut redis::Pipeline, hashes: [&str; 3], expiring_key: &str, field: &str, expire_date: Option<i32>) -> &'a mut redis::Pipeline {
+        if let Some(expire_date) = expire_date {
+            for hash in hashes.into_iter() {
+                pipe.cmd("HEXPIREAT").arg(hash).arg(expire_date).arg("FIELDS").arg(1).arg(field).ignore();
+            }
+            pipe.cmd("SADD").arg(expiring_key).arg(field).ignore();
+        }
+        pipe
+    }
+
+    /* Meta hashes are watched, so the transaction will be retried if the meta changed by someone else before the execution.
+       New value is persisted before the execution, failed persist discards the transaction and retried one persists the latest value */
+    fn apply_meta_operation<T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>, F: FnMut(&MetaType<T>) -> anyhow::Result<()>>(&self, name: &str, id: &str, key: &str, access: T, operation: &MetaOperation<T>, mut persist: F) -> Result<MetaType<T>, YummyStateError> {
+        let mut redis = match self.redis.get() {
+            Ok(redis) => redis,
+            Err(_) => return Err(YummyStateError::CacheCouldNotReaded)
+        };
+
+        let value_key = format!("{}{}-meta-val:{}", self.config.redis_prefix, name, id);
+        let type_key = format!("{}{}-meta-type:{}", self.config.redis_prefix, name, id);
+        let access_key = format!("{}{}-meta-acc:{}", self.config.redis_prefix, name, id);
+        let expiring_key = format!("{}{}-meta-exp:{}", self.config.redis_prefix, name, id);
+        let access: i32 = access.into();
+
+        let result = redis::transaction(&mut *redis, &[&value_key, &type_key, &access_key, &expiring_key], |redis, pipe| {
+            let (value, type_info, meta_access, is_expiring): (redis::Value, Option<i32>, Option<i32>, bool) = redis::pipe()
+                .hget(&value_key, key)
+                .hget(&type_key, key)
+                .hget(&access_key, key)
+                .sismember(&expiring_key, key)
+                .query(redis)?;
+
+            /* HSET clears the field expiration, current expire date needs to be kept */
+            let expire_date = match is_expiring {
+                true => redis::cmd("HEXPIRETIME").arg(&value_key).arg("FIELDS").arg(1).arg(key).query::<Vec<i64>>(redis)?
+                    .first().filter(|expire_date| **expire_date > 0).map(|expire_date| *expire_date as i32),
+                false => None
+            };
+            let current = type_info.map(|type_info| Self::parse_meta::<T>(type_info, &value, meta_access.unwrap_or_default()).with_expire_date(expire_date));
+
+            if meta_access.unwrap_or_default() > access {
+                return Ok(Some(Err(YummyStateError::MetaAccessLevelNotEnough)));
+            }
+
+            let meta = match operation.apply(current.as_ref()) {
+                Ok(meta) => meta,
+                Err(error) => return Ok(Some(Err(YummyStateError::MetaOperationFailed(error))))
+            };
+
+            if let Err(error) = persist(&meta) {
+                return Ok(Some(Err(YummyStateError::CacheError(error))));
+            }
+
+            if is_expiring {
+                pipe.srem(&expiring_key, key).ignore();
+            }
+
+            let (value, type_info, meta_access): (String, i32, i32) = match meta.get_value() {
+                MetaType::Null | MetaType::Expiring(_, _) => {
+                    pipe.hdel(&value_key, key).ignore()
+                        .hdel(&type_key, key).ignore()
+                        .hdel(&access_key, key).ignore();
+
+                    return pipe.query::<Option<()>>(redis).map(|result| result.map(|_| Ok(meta.clone())));
+                },
+                MetaType::Number(value, per) => (value.to_string(), 1, per.clone().into()),
+                MetaType::String(value, per) => (value.clone(), 2, per.clone().into()),
+                MetaType::Bool(value, per) => (value.to_string(), 3, per.clone().into()),
+                MetaType::List(value, per) => (serde_json::to_string(value.deref()).unwrap_or_default(), 4, per.clone().into()),
+                MetaType::Object(value, per) => (serde_json::to_string(value.deref()).unwrap_or_default(), 5, per.clone().into())
+            };
+
+            pipe.hset(&value_key, key, value).ignore()
+                .hset(&type_key, key, type_info).ignore()
+                .hset(&access_key, key, meta_access).ignore();
+
+            Self::expire_meta_fields(pipe, [&value_key, &type_key, &access_key], &expiring_key, key, meta.get_expire_date());
+            pipe.query::<Option<()>>(redis).map(|result| result.map(|_| Ok(meta.clone())))
+        });
+
+        match result {
+            Ok(result) => result,
+            Err(error) => {
+                log::error!("Redis error: {}", error.to_string());
+                Err(YummyStateError::CacheCouldNotReaded)
+            }
+        }
+    }
+
+    fn get_sort_key(&self, sort: RoomListSort) -> String {
+        match sort {
+            RoomListSort::InsertDate => format!("{}rooms-date", self.config.redis_prefix),
+            RoomListSort::UserCount => format!("{}rooms-users", self.config.redis_prefix)
+        }
+    }
+
+    /* Free slot and meta filters could not be indexed, candidate rooms are checked with one pipeline for the each page chunk */
+    fn get_room_list_matches(&self, redis: &mut redis::Connection, filter: &RoomListFilter, room_ids: &[&str]) -> Vec<bool> {
+        if room_ids.is_empty() || (!filter.has_free_slot && filter.metas.is_empty()) {
+            return vec![true; room_ids.len()];
+        }
+
+        let mut pipe = redis::pipe();
+        for room_id in room_ids.iter() {
+            if filter.has_free_slot {
+                pipe.cmd("HMGET").arg(format!("{}room:{}", self.config.redis_prefix, room_id)).arg("user-len").arg("max-user");
+            }
+
+            if !filter.metas.is_empty() {
+                for key in ["room-meta-val", "room-meta-type", "room-meta-acc"] {
+                    let command = pipe.cmd("HMGET").arg(format!("{}{}:{}", self.config.redis_prefix, key, room_id));
+                    for meta in filter.metas.iter() {
+                        command.arg(&meta.key);
+                    }
+                }
+            }
+        }
+
+        // Results are consumed in the same order with the commands, failed query does not match any room
+        let results = redis_result!(pipe.query::<Vec<redis::Value>>(redis));
+        let mut results = results.iter();
+
+        room_ids.iter().map(|_| {
+            let mut matched = true;
+
+            if filter.has_free_slot {
+                let room_info = results.next().and_then(|value| redis::from_redis_value::<Vec<usize>>(value).ok()).unwrap_or_default();
+                matched = Self::has_free_slot(&room_info, &RoomUserType::User).is_ok();
+            }
+
+            if !filter.metas.is_empty() {
+                let values = results.next().and_then(|value| redis::from_redis_value::<Vec<redis::Value>>(value).ok()).unwrap_or_default();
+                let types = results.next().and_then(|value| redis::from_redis_value::<Vec<Option<i32>>>(value).ok()).unwrap_or_default();
+                let access = results.next().and_then(|value| redis::from_redis_value::<Vec<Option<i32>>>(value).ok()).unwrap_or_default();
+
+                matched = matched && filter.metas.iter().enumerate().all(|(index, meta)| {
+                    let value = match (values.get(index), types.get(index).copied().flatten(), access.get(index).copied().flatten()) {
+                        (Some(value), Some(type_info), Some(access)) => Some(Self::parse_room_meta(type_info, value, access)),
+                        _ => None
+                    };
+
+                    meta.is_match(value.as_ref())
+                });
+            }
+
+            matched
+        }).collect()
+    }
+
+    /* Rooms are filtered with the index sets and ordered with the sorted sets. Returns room ids and the next page cursor */
+    fn get_room_list_page(&self, redis: &mut redis::Connection, filter: &RoomListFilter, limit: usize) -> (Vec<String>, Option<String>) {
+        let list_id = uuid::Uuid::new_v4();
+        let sort_key = self.get_sort_key(filter.sort);
+        let mut index_keys = Vec::new();
+        let mut temporary_keys = Vec::new();
+
+        if limit == 0 {
+            return (Vec::new(), None);
+        }
+
+        // Temporary keys are created in one transaction and expire even if the cleanup below could not run
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+
+        if !filter.tags.is_empty() {
+            let tags_key = format!("{}room-list-tag:{}", self.config.redis_prefix, list_id);
+            let command = pipe.cmd(if filter.match_all_tags { "SINTERSTORE" } else { "SUNIONSTORE" }).arg(&tags_key);
+
+            for tag in filter.tags.iter() {
+                command.arg(format!("{}tag:{}", self.config.redis_prefix, tag));
+            }
+
+            command.ignore();
+            index_keys.push(tags_key.clone());
+            temporary_keys.push(tags_key);
+        }
+
+        if let Some(access_type) = filter.access_type {
+            index_keys.push(format!("{}rooms-access:{}", self.config.redis_prefix, i32::from(access_type)));
+        }
+
+        if let Some(game_state) = filter.game_state {
+            index_keys.push(format!("{}rooms-state:{}", self.config.redis_prefix, i32::from(game_state)));
+        }
+
+        if filter.joinable {
+            let state_key = format!("{}room-list-state:{}", self.config.redis_prefix, list_id);
+            let command = pipe.cmd("SUNIONSTORE").arg(&state_key);
+
+            for game_state in [RoomGameState::Waiting, RoomGameState::ReadyCheck, RoomGameState::Playing, RoomGameState::Finished] {
+                if RoomListFilter::is_joinable_state(game_state) {
+                    command.arg(format!("{}rooms-state:{}", self.config.redis_prefix, i32::from(game_state)));
+                }
+            }
+
+            command.ignore();
+            index_keys.push(state_key.clone());
+            temporary_keys.push(state_key);
+        }
+
+        // Sets have no score, zero weight keeps the sort value as a score
+        let list_key = match index_keys.is_empty() {
+            true => sort_key,
+            false => {
+                let list_key = format!("{}room-list:{}", self.config.redis_prefix, list_id);
+                let command = pipe.cmd("ZINTERSTORE").arg(&list_key).arg(index_keys.len() + 1).arg(&sort_key);
+
+                for key in index_keys.iter() {
+                    command.arg(key);
+                }
+
+                command.arg("WEIGHTS").arg(1);
+                for _ in index_keys.iter() {
+                    command.arg(0);
+                }
+
+                command.ignore();
+                temporary_keys.push(list_key.clone());
+                list_key
+            }
+        };
+
+        if !temporary_keys.is_empty() {
+            for key in temporary_keys.iter() {
+                pipe.expire(key, ROOM_LIST_KEY_LIFETIME).ignore();
+            }
+
+            redis_result!(pipe.query::<()>(redis));
+        }
+
+        let cursor = filter.get_cursor();
+        let chunk_size = limit * 2;
+        let mut offset = 0;
+        let mut rooms = Vec::new();
+        let mut last_room: Option<RoomListCursor> = None;
+        let mut next_cursor = None;
+
+        'search: loop {
+            let mut command = match filter.descending {
+                true => redis::cmd("ZREVRANGEBYSCORE"),
+                false => redis::cmd("ZRANGEBYSCORE")
+            };
+
+            // Rooms that have the same score with the cursor are checked with the room id
+            match (filter.descending, &cursor) {
+                (true, Some(cursor)) => command.arg(&list_key).arg(cursor.score).arg("-inf"),
+                (true, None) => command.arg(&list_key).arg("+inf").arg("-inf"),
+                (false, Some(cursor)) => command.arg(&list_key).arg(cursor.score).arg("+inf"),
+                (false, None) => command.arg(&list_key).arg("-inf").arg("+inf")
+            };
+
+            let items = redis_result!(command.arg("WITHSCORES").arg("LIMIT").arg(offset).arg(chunk_size).query::<Vec<(String, f64)>>(redis));
+            let fetched = items.len();
+
+            let candidates: Vec<(String, i64)> = items.into_iter()
+                .map(|(room_id, score)| (room_id, score as i64))
+                .filter(|(room_id, score)| cursor.as_ref().map(|cursor| cursor.is_after(*score, room_id, filter.descending)).unwrap_or(true))
+                .collect();
+
+            let room_ids: Vec<&str> = candidates.iter().map(|(room_id, _)| &room_id[..]).collect();
+            let matches = self.get_room_list_matches(redis, filter, &room_ids);
+
+            for ((room_id, score), matched) in candidates.into_iter().zip(matches.into_iter()) {
+                if !matched {
+                    continue;
+                }
+
+                if rooms.len() == limit {
+                    next_cursor = last_room.take().map(|cursor| cursor.to_string());
+                    break 'search;
+                }
+
+                last_room = Some(RoomListCursor::new(score, room_id.clone()));
+                rooms.push(room_id);
+            }
+
+            if fetched < chunk_size {
+                break;
+            }
+
+            offset += fetched;
+        }
+
+        if !temporary_keys.is_empty() {
+            redis_result!(redis.del::<_, ()>(temporary_keys));
+        }
+
+        (rooms, next_cursor)
+    }
+
+    fn get_room_users(&self, redis: &mut redis::Connection, room_id: &str, spectators: bool) -> Vec<RoomUserInformation> {
+
+        // This request is slow compare to other. We should change it to lua script to increase performance
+        let mut user_infos = Vec::new();
+        let mut sessions = redis_result!(redis.hgetall::<_, HashMap<SessionId, RoomUserType>>(format!("{}room-sessions:{}", self.config.redis_prefix, room_id)));
+
+        // Users are ordered by join time, oldest member is the first one
+        let join_order = redis_result!(redis.zrange::<_, Vec<SessionId>>(format!("{}room-join:{}", self.config.redis_prefix, room_id), 0, -1));
+
+        for session_id in join_order.into_iter() {
+            let user_type = match sessions.remove(&session_id) {
+                Some(user_type) => user_type,
+                None => continue
+            };
+
+            if (user_type == RoomUserType::Spectator) != spectators {
+                continue;
+            }
+
+            let user_id: String = redis_result!(redis.hget::<_, _, String>(format!("{}session-user", self.config.redis_prefix), session_id.to_string()));
+            let name = redis_result!(redis.hget::<_, _, String>(format!("{}users:{}", self.config.redis_prefix, &user_id), "name"));
+            user_infos.push(RoomUserInformation {
+                name: if name.is_empty() { None } else { Some(name) },
+                user_id: Arc::new(UserId::from(user_id)),
+                user_type
+            })
+        }
+
+        user_infos
+    }
+
+    /* Friend set is loaded from the database on the first access, it expires with the cache duration to heal the missed changes */
+    fn load_user_friends(&self, redis: &mut redis::Connection, user_id: &UserId) -> Result<String, YummyStateError> {
+        let key = format!("{}user-friends:{}", self.config.redis_prefix, user_id.to_string());
+        if redis_result!(redis.exists::<_, bool>(&key)) {
+            return Ok(key);
+        }
+
+        let friends = self.user_friends.get(user_id)?.unwrap_or_default();
+        let mut pipe = redis::pipe();
+        pipe.atomic().sadd(&key, FRIEND_SET_MARKER).ignore();
+
+        for friend in friends.into_iter() {
+            pipe.sadd(&key, friend.to_string()).ignore();
+        }
+
+        pipe.expire(&key, self.config.cache_duration.as_secs() as usize).ignore();
+        redis_result!(pipe.query::<()>(redis));
+        Ok(key)
+    }
+
+    #[tracing::instrument(name="get_user_friends", skip(self))]
+    pub fn get_user_friends(&self, user_id: &UserId) -> Result<HashSet<UserId>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let key = self.load_user_friends(&mut redis, user_id)?;
+                let friends = redis_result!(redis.smembers::<_, Vec<String>>(key));
+                Ok(friends.into_iter().filter(|item| item != FRIEND_SET_MARKER).map(UserId::from).collect())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="is_friend", skip(self))]
+    pub fn is_friend(&self, user_id: &UserId, target_user_id: &UserId) -> Result<bool, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let key = self.load_user_friends(&mut redis, user_id)?;
+                Ok(redis_result!(redis.sismember::<_, _, bool>(key, target_user_id.to_string())))
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* Database is the source of the friendship, cached sets are removed and loaded again with the next access */
+    #[tracing::instrument(name="add_friend", skip(self))]
+    pub fn add_friend(&self, user_id: &UserId, friend_user_id: &UserId) -> Result<(), YummyStateError> {
+        self.remove_friend_sets(user_id, friend_user_id)
+    }
+
+    #[tracing::instrument(name="remove_friend", skip(self))]
+    pub fn remove_friend(&self, user_id: &UserId, friend_user_id: &UserId) -> Result<(), YummyStateError> {
+        self.remove_friend_sets(user_id, friend_user_id)
+    }
+
+    fn remove_friend_sets(&self, user_id: &UserId, friend_user_id: &UserId) -> Result<(), YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                redis_result!(redis.del::<_, usize>(vec![
+                    format!("{}user-friends:{}", self.config.redis_prefix, user_id.to_string()),
+                    format!("{}user-friends:{}", self.config.redis_prefix, friend_user_id.to_string())]));
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* User informations and metas are not cached at redis, friend lists are loaded from the database with the first access.
+       Users hash carries the live session informations, so it is removed only if the user does not have any session */
+    #[tracing::instrument(name="invalidate_user_cache", skip(self))]
+    pub fn invalidate_user_cache(&self, user_id: &UserId) -> Result<(), YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let user_id = user_id.to_string();
+                if redis_result!(redis.scard::<_, usize>(format!("{}user-sessions:{}", self.config.redis_prefix, &user_id))) == 0 {
+                    redis_result!(redis.del::<_, usize>(format!("{}users:{}", self.config.redis_prefix, &user_id)));
+                }
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
         }
     }
 
+    /* Room metas are written into redis with set_room_info, nothing to invalidate */
+    #[tracing::instrument(name="invalidate_room_meta_cache", skip(self))]
+    pub fn invalidate_room_meta_cache(&self, _room_id: &RoomId) -> Result<(), YummyStateError> {
+        Ok(())
+    }
+
     #[tracing::instrument(name="ban_user_from_room", skip(self))]
-    pub fn ban_user_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<(), YummyStateError> {
+    pub fn ban_user_from_room(&self, room_id: &RoomId, user_id: &UserId, ban: RoomBan) -> Result<(), YummyStateError> {
         match self.redis.get() {
             Ok(mut redis) => {
-                redis_result!(redis.sadd::<_, _, i32>(format!("{}room-banned:{}", self.config.redis_prefix, room_id.to_string()), user_id.to_string()));
+                let value = serde_json::to_string(&ban).map_err(|error| YummyStateError::CacheError(error.into()))?;
+                redis_result!(redis.hset::<_, _, _, i32>(format!("{}room-bans:{}", self.config.redis_prefix, room_id.to_string()), user_id.to_string(), value));
                 Ok(())
             },
             Err(_) => Err(YummyStateError::CacheCouldNotReaded)
         }
     }
 
+    #[tracing::instrument(name="unban_user_from_room", skip(self))]
+    pub fn unban_user_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<bool, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+                let banned_key = format!("{}room-bans:{}", self.config.redis_prefix, room_id.to_string());
+                let (ban, _): (Option<String>, usize) = redis_result!(redis::pipe()
+                    .atomic()
+                    .cmd("HGET").arg(&banned_key).arg(user_id.to_string())
+                    .cmd("HDEL").arg(&banned_key).arg(user_id.to_string())
+                    .query(&mut redis));
+
+                Ok(ban.and_then(|ban| serde_json::from_str::<RoomBan>(&ban).ok()).map(|ban| ban.is_active(now)).unwrap_or_default())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    fn get_active_bans(&self, redis: &mut redis::Connection, room_id: &str) -> HashMap<UserId, RoomBan> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        redis_result!(redis.hgetall::<_, HashMap<UserId, String>>(format!("{}room-bans:{}", self.config.redis_prefix, room_id)))
+            .into_iter()
+            .filter_map(|(user_id, ban)| serde_json::from_str::<RoomBan>(&ban).ok().map(|ban| (user_id, ban)))
+            .filter(|(_, ban)| ban.is_active(now))
+            .collect()
+    }
+
     #[tracing::instrument(name="is_user_online", skip(self))]
     pub fn is_empty(&self) -> bool {
         match self.redis.get() {
@@ -90,6 +656,7 @@ impl YummyState {
                 Some(1) => Ok(Some(RoomUserType::User)),
                 Some(2) => Ok(Some(RoomUserType::Moderator)),
                 Some(3) => Ok(Some(RoomUserType::Owner)),
+                Some(4) => Ok(Some(RoomUserType::Spectator)),
                 _ => Ok(None)
             },
             Err(_) => Ok(None)
@@ -101,7 +668,31 @@ impl YummyState {
         match self.redis.get() {
             Ok(mut redis) => {
                 let session_id = self.get_user_session_id(user_id, room_id)?;
-                redis_result!(redis.hset::<_, _, _, i32>(format!("{}room-sessions:{}", self.config.redis_prefix, room_id.to_string()), session_id.to_string(), user_type as i32));
+                let room_sessions_key = format!("{}room-sessions:{}", self.config.redis_prefix, room_id.to_string());
+                let room_info_key = format!("{}room:{}", self.config.redis_prefix, room_id.to_string());
+                let old_user_type = RoomUserType::from(redis_result!(redis.hget::<_, _, Option<i32>>(&room_sessions_key, session_id.to_string())).unwrap_or_default());
+
+                /* Spectators and players have separate capacities, moved user needs a free slot on the other side */
+                if Self::get_length_field(&old_user_type) != Self::get_length_field(&user_type) {
+                    let room_info = redis_result!(redis::cmd("HMGET")
+                        .arg(&room_info_key)
+                        .arg("user-len")
+                        .arg("max-user")
+                        .arg("spec-len")
+                        .arg("max-spec")
+                        .query::<Vec<usize>>(&mut redis));
+
+                    Self::has_free_slot(&room_info, &user_type)?;
+                }
+
+                // Spectators and players are counted separately
+                redis_result!(redis::pipe()
+                    .atomic()
+                    .cmd("HINCRBY").arg(&room_info_key).arg(Self::get_length_field(&old_user_type)).arg(-1).ignore()
+                    .cmd("HINCRBY").arg(&room_info_key).arg(Self::get_length_field(&user_type)).arg(1).ignore()
+                    .cmd("HSET").arg(&room_sessions_key).arg(session_id.to_string()).arg(user_type.clone() as i32).ignore()
+                    .cmd("ZINCRBY").arg(format!("{}rooms-users", self.config.redis_prefix)).arg(Self::get_user_count_change(&user_type) - Self::get_user_count_change(&old_user_type)).arg(room_id.to_string()).ignore()
+                    .query::<()>(&mut redis));
                 Ok(())
             },
             Err(_) => Err(YummyStateError::CacheCouldNotReaded)
@@ -116,6 +707,14 @@ impl YummyState {
         }
     }
 
+    #[tracing::instrument(name="get_user_sessions", skip(self))]
+    pub fn get_user_sessions(&self, user_id: &UserId) -> Vec<SessionId> {
+        match self.redis.get() {
+            Ok(mut redis) => redis_result!(redis.smembers::<_, Vec<SessionId>>(format!("{}user-sessions:{}", self.config.redis_prefix, user_id.to_string()))),
+            Err(_) => Vec::new()
+        }
+    }
+
     #[tracing::instrument(name="get_user_session_id", skip(self))]
     pub fn get_user_session_id(&self, user_id: &UserId, room_id: &RoomId) -> Result<SessionId, YummyStateError> {
         match self.redis.get() {
@@ -142,15 +741,90 @@ impl YummyState {
         };
     }
 
+    /* Sessions are ordered by creation, oldest session is the first one */
+    #[tracing::instrument(name="get_user_session_list", skip(self))]
+    pub fn get_user_session_list(&self, user_id: &UserId) -> Vec<UserSession> {
+        let session_ids = self.get_user_sessions(user_id);
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let mut pipe = redis::pipe();
+                for session_id in session_ids.iter() {
+                    pipe.cmd("HMGET").arg(format!("{}session-info:{}", self.config.redis_prefix, session_id.to_string()))
+                        .arg("order")
+                        .arg("created")
+                        .arg("activity")
+                        .arg("ip")
+                        .arg("client");
+                }
+
+                let infos = redis_result!(pipe.query::<Vec<(Option<usize>, Option<i32>, Option<i32>, Option<String>, Option<String>)>>(&mut redis));
+                let mut sessions = session_ids
+                    .into_iter()
+                    .zip(infos.into_iter())
+                    .filter_map(|(session_id, (order, insert_date, last_activity, ip, client))| Some((order?, UserSession {
+                        session_id,
+                        insert_date: insert_date.unwrap_or_default(),
+                        last_activity: last_activity.unwrap_or_default(),
+                        ip: ip.filter(|ip| !ip.is_empty()),
+                        client: client.filter(|client| !client.is_empty())
+                    })))
+                    .collect::<Vec<_>>();
+
+                sessions.sort_by_key(|(order, _)| *order);
+                sessions.into_iter().map(|(_, session)| session).collect()
+            },
+            Err(_) => Vec::new()
+        }
+    }
+
+    #[tracing::instrument(name="update_session_activity", skip(self))]
+    pub fn update_session_activity(&self, session_id: &SessionId, client: Option<ClientInfo>) {
+        if let Ok(mut redis) = self.redis.get() {
+            let session_info_key = format!("{}session-info:{}", self.config.redis_prefix, session_id.to_string());
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+            /* Closed sessions should not be created again */
+            if !redis_result!(redis.exists::<_, bool>(&session_info_key)) {
+                return;
+            }
+
+            let mut pipe = redis::pipe();
+            pipe.cmd("HSET").arg(&session_info_key).arg("activity").arg(now).ignore();
+
+            /* New connection of the session could be on another server */
+            if let Some(client) = client {
+                pipe.cmd("HSET").arg(&session_info_key)
+                    .arg("ip").arg(client.ip.unwrap_or_default())
+                    .arg("client").arg(client.client.unwrap_or_default())
+                    .arg("loc").arg(&self.config.server_name)
+                    .ignore();
+            }
+
+            redis_result!(pipe.query::<()>(&mut redis));
+        }
+    }
+
     #[tracing::instrument(name="new_session", skip(self))]
-    pub fn new_session(&mut self, user_id: &UserId, name: Option<String>, user_type: UserType) -> SessionId {
+    pub fn new_session(&mut self, user_id: &UserId, name: Option<String>, user_type: UserType, client: ClientInfo) -> SessionId {
         let session_id = SessionId::new();
         if let Ok(mut redis) = self.redis.get() {
             let user_id = user_id.to_string();
             let session_id_str = session_id.to_string();
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+            let order = redis_result!(redis.incr::<_, _, usize>(format!("{}session-counter", self.config.redis_prefix), 1));
         
             redis_result!(redis::pipe()
                 .atomic()
+                .cmd("HSET").arg(format!("{}session-info:{}", self.config.redis_prefix, &session_id_str))
+                    .arg("order").arg(order)
+                    .arg("created").arg(now)
+                    .arg("activity").arg(now)
+                    .arg("ip").arg(client.ip.unwrap_or_default())
+                    .arg("client").arg(client.client.unwrap_or_default())
+                    .arg("loc").arg(&self.config.server_name)
+                    .ignore()
+
                 .cmd("SADD").arg(format!("{}online-users", self.config.redis_prefix))
                     .arg(&user_id)
                     .ignore()
@@ -158,6 +832,10 @@ impl YummyState {
                 .cmd("HSET").arg(format!("{}session-user", self.config.redis_prefix))
                     .arg(&session_id_str).arg(&user_id)
                     .ignore()
+
+                .cmd("SADD").arg(format!("{}user-sessions:{}", self.config.redis_prefix, &user_id))
+                    .arg(&session_id_str)
+                    .ignore()
                 
                 .cmd("HSET").arg(format!("{}users:{}", self.config.redis_prefix, &user_id))
                     .arg("type").arg(i32::from(user_type))
@@ -182,6 +860,13 @@ impl YummyState {
                     .cmd("HDEL").arg(format!("{}session-user", self.config.redis_prefix))
                         .arg(&session_id_str)
                         .ignore()
+
+                    .cmd("SREM").arg(format!("{}user-sessions:{}", self.config.redis_prefix, user_id_str))
+                        .arg(&session_id_str)
+                        .ignore()
+
+                    .cmd("DEL").arg(format!("{}session-info:{}", self.config.redis_prefix, session_id_str))
+                        .ignore()
                     
                     .cmd("HGET").arg(format!("{}users:{}", self.config.redis_prefix, user_id_str))
                         .arg("room")
@@ -213,7 +898,7 @@ impl YummyState {
 
     #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(name="create_room", skip(self))]
-    pub fn create_room(&self, room_id: &RoomId, insert_date: i32, name: Option<String>,  description: Option<String>, access_type: CreateRoomAccessType, max_user: usize, tags: Vec<String>, metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>, join_request: bool) {
+    pub fn create_room(&self, room_id: &RoomId, insert_date: i32, name: Option<String>,  description: Option<String>, access_type: CreateRoomAccessType, max_user: usize, max_spectator: usize, tags: Vec<String>, metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>, join_request: bool, password: Option<String>, auto_start: bool) {
         if let Ok(mut redis) = self.redis.get() {
             let room_id = room_id.to_string();
             
@@ -224,12 +909,21 @@ impl YummyState {
                 .cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id))
                     .arg("max-user").arg(max_user)
                     .arg("user-len").arg(0_usize)
+                    .arg("max-spec").arg(max_spectator)
+                    .arg("spec-len").arg(0_usize)
                     .arg("name").arg(name.unwrap_or_default())
                     .arg("access").arg(access_type as i32)
                     .arg("idate").arg(insert_date)
                     .arg("join").arg(join_request)
                     .arg("desc").arg(description.unwrap_or_default())
-                    .ignore();
+                    .arg("pass").arg(password.unwrap_or_default())
+                    .arg("auto").arg(auto_start)
+                    .arg("state").arg(i32::from(RoomGameState::default()))
+                    .ignore()
+                .cmd("ZADD").arg(format!("{}rooms-date", self.config.redis_prefix)).arg(insert_date).arg(&room_id).ignore()
+                .cmd("ZADD").arg(format!("{}rooms-users", self.config.redis_prefix)).arg(0_usize).arg(&room_id).ignore()
+                .cmd("SADD").arg(format!("{}rooms-access:{}", self.config.redis_prefix, i32::from(access_type))).arg(&room_id).ignore()
+                .cmd("SADD").arg(format!("{}rooms-state:{}", self.config.redis_prefix, i32::from(RoomGameState::default()))).arg(&room_id).ignore();
 
             if !tags.is_empty() {
                 for tag in tags.iter() {
@@ -242,10 +936,11 @@ impl YummyState {
                 let room_meta_value = format!("{}room-meta-val:{}", self.config.redis_prefix, &room_id);
                 let room_meta_type = format!("{}room-meta-type:{}", self.config.redis_prefix, &room_id);
                 let room_meta_per = format!("{}room-meta-acc:{}", self.config.redis_prefix, &room_id);
+                let room_meta_expiring = format!("{}room-meta-exp:{}", self.config.redis_prefix, &room_id);
 
                 for (meta, value) in metas.iter() {
-                    pipes = match value {
-                        MetaType::Null => pipes,
+                    pipes = match value.get_value() {
+                        MetaType::Null | MetaType::Expiring(_, _) => pipes,
                         MetaType::Number(value, per) => {
                             pipes.cmd("HSET").arg(&room_meta_value).arg(meta).arg(value).ignore();
                             pipes.cmd("HSET").arg(&room_meta_type).arg(meta).arg(1).ignore();
@@ -265,8 +960,14 @@ impl YummyState {
                             pipes.cmd("HSET").arg(&room_meta_value).arg(meta).arg(serde_json::to_string(value.deref()).unwrap_or_default()).ignore();
                             pipes.cmd("HSET").arg(&room_meta_type).arg(meta).arg(4).ignore();
                             pipes.cmd("HSET").arg(&room_meta_per).arg(meta).arg(i32::from(*per)).ignore()
+                        },
+                        MetaType::Object(value, per) => {
+                            pipes.cmd("HSET").arg(&room_meta_value).arg(meta).arg(serde_json::to_string(value.deref()).unwrap_or_default()).ignore();
+                            pipes.cmd("HSET").arg(&room_meta_type).arg(meta).arg(5).ignore();
+                            pipes.cmd("HSET").arg(&room_meta_per).arg(meta).arg(i32::from(*per)).ignore()
                         }
                     }
+                    pipes = Self::expire_meta_fields(pipes, [&room_meta_value, &room_meta_type, &room_meta_per], &room_meta_expiring, meta, value.get_expire_date());
                 }
             }
             
@@ -289,29 +990,25 @@ impl YummyState {
                         .arg(format!("{}room:{}", self.config.redis_prefix, &room_id))
                         .arg("user-len")
                         .arg("max-user")
+                        .arg("spec-len")
+                        .arg("max-spec")
                         .query::<Vec<usize>>(&mut redis));
 
-                    let user_len = room_info.first().copied().unwrap_or_default();
-                    let max_user = room_info.get(1).copied().unwrap_or_default();
+                    Self::has_free_slot(&room_info, &user_type)?;
 
-                    // If the max_user 0 or lower than users count, add to room
-                    if max_user == 0 || max_user > user_len {
-                        let is_member = redis_result!(redis.hexists(&room_request_key, &user_id));
-    
-                        // User alread in the room
-                        if is_member {
-                            return Err(YummyStateError::AlreadyRequested);
-                        }
+                    let is_member = redis_result!(redis.hexists(&room_request_key, &user_id));
 
-                        redis_result!(redis::pipe()
-                            .atomic()
-                            .cmd("HSET").arg(format!("{}user-jrequest:{}", self.config.redis_prefix, &user_id)).arg(&room_id).arg(session_id.to_string())
-                            .cmd("HSET").arg(room_request_key).arg(&user_id).arg(user_type as i32).ignore()
-                            .query::<()>(&mut redis));
-                        Ok(())
-                    } else {
-                        Err(YummyStateError::RoomHasMaxUsers)
+                    // User alread in the room
+                    if is_member {
+                        return Err(YummyStateError::AlreadyRequested);
                     }
+
+                    redis_result!(redis::pipe()
+                        .atomic()
+                        .cmd("HSET").arg(format!("{}user-jrequest:{}", self.config.redis_prefix, &user_id)).arg(&room_id).arg(session_id.to_string())
+                        .cmd("HSET").arg(room_request_key).arg(&user_id).arg(user_type as i32).ignore()
+                        .query::<()>(&mut redis));
+                    Ok(())
                 }
                 false => Err(YummyStateError::RoomNotFound)
             },
@@ -335,31 +1032,31 @@ impl YummyState {
                         .arg(format!("{}room:{}", self.config.redis_prefix, &room_id))
                         .arg("user-len")
                         .arg("max-user")
+                        .arg("spec-len")
+                        .arg("max-spec")
                         .query::<Vec<usize>>(&mut redis));
 
-                    let user_len = room_info.first().copied().unwrap_or_default();
-                    let max_user = room_info.get(1).copied().unwrap_or_default();
+                    Self::has_free_slot(&room_info, &user_type)?;
 
-                    // If the max_user 0 or lower than users count, add to room
-                    if max_user == 0 || max_user > user_len {
-                        let is_member = redis_result!(redis.hexists(&room_sessions_key, &session_id));
-    
-                        // User alread in the room
-                        if is_member {
-                            return Err(YummyStateError::UserAlreadInRoom);
-                        }
+                    let is_member = redis_result!(redis.hexists(&room_sessions_key, &session_id));
 
-                        redis_result!(redis::pipe()
-                            .atomic()
-                            .cmd("HSET").arg(format!("{}user-room:{}", self.config.redis_prefix, &user_id)).arg(&room_id).arg(&session_id).ignore()
-                            .cmd("SADD").arg(format!("{}session-room:{}", self.config.redis_prefix, &session_id)).arg(&room_id)
-                            .cmd("HINCRBY").arg(&room_info_key).arg("user-len").arg(1).ignore()
-                            .cmd("HSET").arg(room_sessions_key).arg(&session_id).arg(user_type as i32).ignore()
-                            .query::<()>(&mut redis));
-                        Ok(())
-                    } else {
-                        Err(YummyStateError::RoomHasMaxUsers)
+                    // User alread in the room
+                    if is_member {
+                        return Err(YummyStateError::UserAlreadInRoom);
                     }
+
+                    let join_order = redis_result!(redis.hincr::<_, _, _, usize>(&room_info_key, "join-seq", 1));
+
+                    redis_result!(redis::pipe()
+                        .atomic()
+                        .cmd("HSET").arg(format!("{}user-room:{}", self.config.redis_prefix, &user_id)).arg(&room_id).arg(&session_id).ignore()
+                        .cmd("SADD").arg(format!("{}session-room:{}", self.config.redis_prefix, &session_id)).arg(&room_id)
+                        .cmd("HINCRBY").arg(&room_info_key).arg(Self::get_length_field(&user_type)).arg(1).ignore()
+                        .cmd("HSET").arg(room_sessions_key).arg(&session_id).arg(user_type.clone() as i32).ignore()
+                        .cmd("ZADD").arg(format!("{}room-join:{}", self.config.redis_prefix, &room_id)).arg(join_order).arg(&session_id).ignore()
+                        .cmd("ZINCRBY").arg(format!("{}rooms-users", self.config.redis_prefix)).arg(Self::get_user_count_change(&user_type)).arg(&room_id).ignore()
+                        .query::<()>(&mut redis));
+                    Ok(())
                 }
                 false => Err(YummyStateError::RoomNotFound)
             },
@@ -376,21 +1073,31 @@ impl YummyState {
                 let session_id = session_id.to_string();
                 let room_info_key = format!("{}room:{}", self.config.redis_prefix, &room_id);
                 let room_sessions_key = &format!("{}room-sessions:{}", self.config.redis_prefix, &room_id);
+                let user_type = RoomUserType::from(redis_result!(redis.hget::<_, _, Option<i32>>(room_sessions_key, &session_id)).unwrap_or_default());
 
-                let (user_len,) =  redis_result!(redis::pipe()
+                let (user_len, spectator_len) =  redis_result!(redis::pipe()
                     .atomic()
                     .cmd("SREM").arg(format!("{}session-room:{}", self.config.redis_prefix, &session_id)).arg(&room_id).ignore()
-                    .cmd("HDEL").arg(room_sessions_key).arg(session_id).ignore()
+                    .cmd("HDEL").arg(room_sessions_key).arg(&session_id).ignore()
+                    .cmd("ZREM").arg(format!("{}room-join:{}", self.config.redis_prefix, &room_id)).arg(&session_id).ignore()
                     .cmd("HDEL").arg(format!("{}user-room:{}", self.config.redis_prefix, &user_id)).arg(&room_id).ignore()
-                    .cmd("HINCRBY").arg(&room_info_key).arg("user-len").arg(-1)
-                    .query::<(i32,)>(&mut redis));
+                    .cmd("SREM").arg(format!("{}room-ready:{}", self.config.redis_prefix, &room_id)).arg(&user_id).ignore()
+                    .cmd("HINCRBY").arg(&room_info_key).arg(Self::get_length_field(&user_type)).arg(-1).ignore()
+                    .cmd("ZINCRBY").arg(format!("{}rooms-users", self.config.redis_prefix)).arg(-Self::get_user_count_change(&user_type)).arg(&room_id).ignore()
+                    .cmd("HGET").arg(&room_info_key).arg("user-len")
+                    .cmd("HGET").arg(&room_info_key).arg("spec-len")
+                    .query::<(i32, i32)>(&mut redis));
                     
-                let no_user = user_len == 0;
+                let no_user = user_len + spectator_len <= 0;
 
                 if no_user {
-                    let (tags,) = redis_result!(redis::pipe()
+                    let (access_type, game_state, tags) = redis_result!(redis::pipe()
                         .atomic()
                         .cmd("SREM").arg(format!("{}rooms", self.config.redis_prefix)).arg(&room_id).ignore()
+                        .cmd("ZREM").arg(format!("{}rooms-date", self.config.redis_prefix)).arg(&room_id).ignore()
+                        .cmd("ZREM").arg(format!("{}rooms-users", self.config.redis_prefix)).arg(&room_id).ignore()
+                        .cmd("HGET").arg(&room_info_key).arg("access")
+                        .cmd("HGET").arg(&room_info_key).arg("state")
                         .cmd("DEL").arg(room_sessions_key).ignore()
                         .cmd("DEL").arg(room_info_key).ignore()
                         .cmd("SMEMBERS").arg(format!("{}room-tag:{}", self.config.redis_prefix, &room_id))
@@ -398,8 +1105,20 @@ impl YummyState {
                         .cmd("DEL").arg(format!("{}room-meta-val:{}", self.config.redis_prefix, room_id)).ignore()
                         .cmd("DEL").arg(format!("{}room-meta-type:{}", self.config.redis_prefix, room_id)).ignore()
                         .cmd("DEL").arg(format!("{}room-meta-acc:{}", self.config.redis_prefix, room_id)).ignore()
+                        .cmd("DEL").arg(format!("{}room-meta-exp:{}", self.config.redis_prefix, room_id)).ignore()
                         .cmd("DEL").arg(format!("{}room-request:{}", self.config.redis_prefix, room_id)).ignore()
-                        .query::<(Vec<String>,)>(&mut redis));
+                        .cmd("DEL").arg(format!("{}room-ready:{}", self.config.redis_prefix, room_id)).ignore()
+                        .cmd("DEL").arg(format!("{}room-join:{}", self.config.redis_prefix, room_id)).ignore()
+                        .cmd("DEL").arg(format!("{}room-messages:{}", self.config.redis_prefix, room_id)).ignore()
+                        .cmd("DEL").arg(format!("{}room-invite:{}", self.config.redis_prefix, room_id)).ignore()
+                        .cmd("DEL").arg(format!("{}room-bans:{}", self.config.redis_prefix, room_id)).ignore()
+                        .query::<(Option<i32>, Option<i32>, Vec<String>)>(&mut redis));
+
+                    // Remove from indexes
+                    redis_result!(redis::pipe()
+                        .cmd("SREM").arg(format!("{}rooms-access:{}", self.config.redis_prefix, access_type.unwrap_or_default())).arg(&room_id).ignore()
+                        .cmd("SREM").arg(format!("{}rooms-state:{}", self.config.redis_prefix, game_state.unwrap_or_default())).arg(&room_id).ignore()
+                        .query::<()>(&mut redis));
 
                     // Remove tags
                     if !tags.is_empty() {
@@ -419,6 +1138,29 @@ impl YummyState {
         Ok(room_removed)
     }
 
+    #[tracing::instrument(name="set_room_user_ready", skip(self))]
+    pub fn set_room_user_ready(&self, room_id: &RoomId, user_id: &UserId, ready: bool) -> Result<(), YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let room_id = room_id.to_string();
+                let user_id = user_id.to_string();
+
+                let is_member = redis_result!(redis.hexists::<_, _, bool>(format!("{}user-room:{}", self.config.redis_prefix, &user_id), &room_id));
+                if !is_member {
+                    return Err(YummyStateError::UserCouldNotFoundInRoom);
+                }
+
+                let room_ready_key = format!("{}room-ready:{}", self.config.redis_prefix, &room_id);
+                match ready {
+                    true => redis_result!(redis.sadd::<_, _, ()>(room_ready_key, &user_id)),
+                    false => redis_result!(redis.srem::<_, _, ()>(room_ready_key, &user_id))
+                };
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
     #[tracing::instrument(name="get_users_from_room", skip(self))]
     pub fn get_users_from_room(&mut self, room_id: &RoomId) -> Result<Vec<Arc<UserId>>, YummyStateError> {
         let room_id = room_id.get();
@@ -452,6 +1194,18 @@ impl YummyState {
         }
     }
 
+    /* User's sessions could be connected to different servers, user location only keeps the last one */
+    #[tracing::instrument(name="get_session_location", skip(self))]
+    pub fn get_session_location(&self, session_id: &SessionId) -> Option<String> {
+        match self.redis.get() {
+            Ok(mut redis) => match redis.hget::<_, _, String>(format!("{}session-info:{}", self.config.redis_prefix, session_id.to_string()), "loc") {
+                Ok(result) => Some(result),
+                Err(_) => None
+            },
+            Err(_) => None
+        }
+    }
+
     #[tracing::instrument(name="get_join_requests", skip(self))]
     pub fn get_join_requests(&self, room_id: &RoomId) -> Result<HashMap<Arc<UserId>, RoomUserType>, YummyStateError> {
         match self.redis.get() {
@@ -489,7 +1243,23 @@ impl YummyState {
     #[tracing::instrument(name="is_user_banned_from_room", skip(self))]
     pub fn is_user_banned_from_room(&self, room_id: &RoomId, user_id: &UserId) -> Result<bool, YummyStateError> {
         match self.redis.get() {
-            Ok(mut redis) => Ok(redis_result!(redis.sismember(format!("{}room-banned:{}", self.config.redis_prefix, room_id.to_string()), user_id.to_string()))),
+            Ok(mut redis) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+                let banned_key = format!("{}room-bans:{}", self.config.redis_prefix, room_id.to_string());
+
+                let ban = match redis_result!(redis.hget::<_, _, Option<String>>(&banned_key, user_id.to_string())) {
+                    Some(ban) => serde_json::from_str::<RoomBan>(&ban).map_err(|error| YummyStateError::CacheError(error.into()))?,
+                    None => return Ok(false)
+                };
+
+                // Expired ban is removed
+                if !ban.is_active(now) {
+                    redis_result!(redis.hdel::<_, _, usize>(&banned_key, user_id.to_string()));
+                    return Ok(false);
+                }
+
+                Ok(true)
+            },
             Err(_) => Err(YummyStateError::CacheCouldNotReaded)
         }
     }
@@ -516,14 +1286,21 @@ impl YummyState {
                         RoomInfoTypeVariant::RoomName => request = request.arg("name"),
                         RoomInfoTypeVariant::Description => request = request.arg("desc"),
                         RoomInfoTypeVariant::Users => request = request.arg("users"),
+                        RoomInfoTypeVariant::Spectators => request = request.arg("spectators"), // Dummy data, dont remove
                         RoomInfoTypeVariant::MaxUser => request = request.arg("max-user"),
                         RoomInfoTypeVariant::UserLength => request = request.arg("user-len"),
+                        RoomInfoTypeVariant::MaxSpectator => request = request.arg("max-spec"),
+                        RoomInfoTypeVariant::SpectatorLength => request = request.arg("spec-len"),
                         RoomInfoTypeVariant::AccessType => request = request.arg("access"),
                         RoomInfoTypeVariant::JoinRequest => request = request.arg("join"),
+                        RoomInfoTypeVariant::Password => request = request.arg("pass"),
                         RoomInfoTypeVariant::InsertDate => request = request.arg("idate"),
                         RoomInfoTypeVariant::Tags => request = request.arg("tags"), // Dummy data, dont remove
                         RoomInfoTypeVariant::BannedUsers => request = request.arg("bu"), // Dummy data, dont remove
                         RoomInfoTypeVariant::Metas => request = request.arg("metas"), // Dummy data, dont remove
+                        RoomInfoTypeVariant::GameState => request = request.arg("state"),
+                        RoomInfoTypeVariant::ReadyUsers => request = request.arg("ready"), // Dummy data, dont remove
+                        RoomInfoTypeVariant::AutoStart => request = request.arg("auto"),
                     };
                 }
 
@@ -539,33 +1316,31 @@ impl YummyState {
                             let description: String = FromRedisValue::from_redis_value(&room_info).unwrap_or_default();
                             result.items.push(RoomInfoType::Description(if description.is_empty() { None } else { Some(description) }));
                         },
-                        RoomInfoTypeVariant::Users => {
-
-                            // This request is slow compare to other. We should change it to lua script to increase performance
-                            let mut user_infos = Vec::new();
-                            let sessions = redis_result!(redis.hgetall::<_, HashMap<SessionId, RoomUserType>>(format!("{}room-sessions:{}", self.config.redis_prefix, &room_id)));
-
-                            for (session_id, user_type) in sessions.into_iter() {
-                                
-                                let user_id: String = redis_result!(redis.hget::<_, _, String>(format!("{}session-user", self.config.redis_prefix), session_id.to_string()));
-                                let name = redis_result!(redis.hget::<_, _, String>(format!("{}users:{}", self.config.redis_prefix, &user_id), "name"));
-                                user_infos.push(RoomUserInformation {
-                                    name: if name.is_empty() { None } else { Some(name) },
-                                    user_id: Arc::new(UserId::from(user_id)),
-                                    user_type
-                                })
-                            }
-                            result.items.push(RoomInfoType::Users(user_infos));
-                        },
+                        RoomInfoTypeVariant::Users => result.items.push(RoomInfoType::Users(self.get_room_users(&mut redis, &room_id, false))),
+                        RoomInfoTypeVariant::Spectators => result.items.push(RoomInfoType::Spectators(self.get_room_users(&mut redis, &room_id, true))),
                         RoomInfoTypeVariant::BannedUsers => {
-                            let users = redis_result!(redis.hgetall::<_, HashSet<UserId>>(format!("{}room-banned:{}", self.config.redis_prefix, &room_id)));
-                            result.items.push(RoomInfoType::BannedUsers(users));
+                            result.items.push(RoomInfoType::BannedUsers(self.get_active_bans(&mut redis, &room_id)));
                         },
                         RoomInfoTypeVariant::AccessType => result.items.push(RoomInfoType::AccessType(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
                         RoomInfoTypeVariant::JoinRequest => result.items.push(RoomInfoType::JoinRequest(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
+                        RoomInfoTypeVariant::Password => {
+                            let password: String = FromRedisValue::from_redis_value(&room_info).unwrap_or_default();
+                            result.items.push(RoomInfoType::Password(if password.is_empty() || access_level < RoomMetaAccess::Moderator { None } else { Some(password) }));
+                        },
                         RoomInfoTypeVariant::InsertDate => result.items.push(RoomInfoType::InsertDate(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
                         RoomInfoTypeVariant::MaxUser => result.items.push(RoomInfoType::MaxUser(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
                         RoomInfoTypeVariant::UserLength => result.items.push(RoomInfoType::UserLength(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
+                        RoomInfoTypeVariant::MaxSpectator => result.items.push(RoomInfoType::MaxSpectator(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
+                        RoomInfoTypeVariant::SpectatorLength => result.items.push(RoomInfoType::SpectatorLength(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
+                        RoomInfoTypeVariant::GameState => {
+                            let game_state: i32 = FromRedisValue::from_redis_value(&room_info).unwrap_or_default();
+                            result.items.push(RoomInfoType::GameState(RoomGameState::from(game_state)));
+                        },
+                        RoomInfoTypeVariant::ReadyUsers => {
+                            let users = redis_result!(redis.smembers::<_, HashSet<UserId>>(format!("{}room-ready:{}", self.config.redis_prefix, &room_id)));
+                            result.items.push(RoomInfoType::ReadyUsers(users));
+                        },
+                        RoomInfoTypeVariant::AutoStart => result.items.push(RoomInfoType::AutoStart(FromRedisValue::from_redis_value(&room_info).unwrap_or_default())),
                         RoomInfoTypeVariant::Tags => {
                             let tags = redis_result!(redis.smembers::<_, Vec<String>>(format!("{}room-tag:{}", self.config.redis_prefix, &room_id)));
                             result.items.push(RoomInfoType::Tags(tags));
@@ -609,18 +1384,7 @@ impl YummyState {
                                 let mut metas = HashMap::new();
                                 
                                 for (((key, type_info), value), access) in keys.into_iter().zip(types.into_iter()).zip(values.into_iter()).zip(access.into_iter()) {
-                                    let value = match type_info {
-                                        1 => MetaType::Number(FromRedisValue::from_redis_value(&value).unwrap_or_default(), RoomMetaAccess::from(access)),
-                                        2 => MetaType::String(FromRedisValue::from_redis_value(&value).unwrap_or_default(), RoomMetaAccess::from(access)),
-                                        3 => MetaType::Bool(FromRedisValue::from_redis_value(&value).unwrap_or_default(), RoomMetaAccess::from(access)),
-                                        4 => {
-                                            let value: String = FromRedisValue::from_redis_value(&value).unwrap_or_default();
-                                            MetaType::List(Box::new(serde_json::from_str(&value).unwrap_or_default()), RoomMetaAccess::from(access))
-                                        },
-                                        _ => MetaType::Number(FromRedisValue::from_redis_value(&value).unwrap_or_default(), RoomMetaAccess::from(access)),
-                                    };
-    
-                                    metas.insert(key, value);
+                                    metas.insert(key, Self::parse_room_meta(type_info, &value, access));
                                 }
     
                                 result.items.push(RoomInfoType::Metas(metas));
@@ -637,6 +1401,25 @@ impl YummyState {
         }
     }
 
+    #[tracing::instrument(name="apply_user_meta_operation", skip(self, persist))]
+    pub fn apply_user_meta_operation<F>(&self, user_id: &UserId, key: String, access: UserMetaAccess, operation: &UserMetaOperation, persist: F) -> Result<UserMetaType, YummyStateError> where F: FnMut(&UserMetaType) -> anyhow::Result<()> {
+        self.apply_meta_operation("user", &user_id.to_string(), &key, access, operation, persist)
+    }
+
+    #[tracing::instrument(name="apply_room_meta_operation", skip(self, persist))]
+    pub fn apply_room_meta_operation<F>(&self, room_id: &RoomId, key: String, access: RoomMetaAccess, operation: &RoomMetaOperation, persist: F) -> Result<RoomMetaType, YummyStateError> where F: FnMut(&RoomMetaType) -> anyhow::Result<()> {
+        let room_exists = match self.redis.get() {
+            Ok(mut redis) => redis_result!(redis.exists::<_, bool>(format!("{}room:{}", self.config.redis_prefix, room_id.to_string()))),
+            Err(_) => return Err(YummyStateError::CacheCouldNotReaded)
+        };
+
+        if !room_exists {
+            return Err(YummyStateError::RoomNotFound);
+        }
+
+        self.apply_meta_operation("room", &room_id.to_string(), &key, access, operation, persist)
+    }
+
     #[tracing::instrument(name="set_room_info", skip(self))]
     pub fn set_room_info(&self, room_id: &RoomId, query: Vec<RoomInfoType>) {
         if query.is_empty() {
@@ -655,8 +1438,18 @@ impl YummyState {
                     RoomInfoType::BannedUsers(_) => (),
                     RoomInfoType::MaxUser(max_user) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("max-user").arg(max_user).ignore(),
                     RoomInfoType::UserLength(_) => (),
-                    RoomInfoType::AccessType(access_type) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("access").arg(i32::from(access_type)).ignore(),
+                    RoomInfoType::Spectators(_) => (),
+                    RoomInfoType::MaxSpectator(max_spectator) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("max-spec").arg(max_spectator).ignore(),
+                    RoomInfoType::SpectatorLength(_) => (),
+                    RoomInfoType::AccessType(access_type) => {
+                        let old_access_type = redis_result!(redis.hget::<_, _, Option<i32>>(format!("{}room:{}", self.config.redis_prefix, &room_id), "access")).unwrap_or_default();
+                        command = command
+                            .cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("access").arg(i32::from(access_type)).ignore()
+                            .cmd("SREM").arg(format!("{}rooms-access:{}", self.config.redis_prefix, old_access_type)).arg(&room_id).ignore()
+                            .cmd("SADD").arg(format!("{}rooms-access:{}", self.config.redis_prefix, i32::from(access_type))).arg(&room_id).ignore()
+                    },
                     RoomInfoType::JoinRequest(join_request) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("join").arg(i32::from(join_request)).ignore(),
+                    RoomInfoType::Password(password) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("pass").arg(password.unwrap_or_default()).ignore(),
                     RoomInfoType::Tags(tags) => {
                         
                         // Remove old tags
@@ -681,19 +1474,40 @@ impl YummyState {
                         }
                     },
                     RoomInfoType::InsertDate(_) => (),
+                    RoomInfoType::GameState(game_state) => {
+                        let old_game_state = redis_result!(redis.hget::<_, _, Option<i32>>(format!("{}room:{}", self.config.redis_prefix, &room_id), "state")).unwrap_or_default();
+                        command = command
+                            .cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("state").arg(i32::from(game_state)).ignore()
+                            .cmd("SREM").arg(format!("{}rooms-state:{}", self.config.redis_prefix, old_game_state)).arg(&room_id).ignore()
+                            .cmd("SADD").arg(format!("{}rooms-state:{}", self.config.redis_prefix, i32::from(game_state))).arg(&room_id).ignore()
+                    },
+                    RoomInfoType::AutoStart(auto_start) => command = command.cmd("HSET").arg(format!("{}room:{}", self.config.redis_prefix, &room_id)).arg("auto").arg(auto_start).ignore(),
+                    RoomInfoType::ReadyUsers(ready_users) => {
+                        command = command.cmd("DEL").arg(format!("{}room-ready:{}", self.config.redis_prefix, &room_id)).ignore();
+
+                        if !ready_users.is_empty() {
+                            command = command.cmd("SADD").arg(format!("{}room-ready:{}", self.config.redis_prefix, &room_id));
+                            for user_id in ready_users.iter() {
+                                command = command.arg(user_id.to_string());
+                            }
+                            command = command.ignore();
+                        }
+                    },
                     RoomInfoType::Metas(metas) => {
                         command = command
                             .cmd("DEL").arg(format!("{}room-meta-val:{}", self.config.redis_prefix, &room_id)).ignore()
                             .cmd("DEL").arg(format!("{}room-meta-type:{}", self.config.redis_prefix, &room_id)).ignore()
-                            .cmd("DEL").arg(format!("{}room-meta-acc:{}", self.config.redis_prefix, &room_id)).ignore();
+                            .cmd("DEL").arg(format!("{}room-meta-acc:{}", self.config.redis_prefix, &room_id)).ignore()
+                            .cmd("DEL").arg(format!("{}room-meta-exp:{}", self.config.redis_prefix, &room_id)).ignore();
 
                         let room_meta_value = format!("{}room-meta-val:{}", self.config.redis_prefix, &room_id);
                         let room_meta_type = format!("{}room-meta-type:{}", self.config.redis_prefix, &room_id);
                         let room_meta_per = format!("{}room-meta-acc:{}", self.config.redis_prefix, &room_id);
+                        let room_meta_expiring = format!("{}room-meta-exp:{}", self.config.redis_prefix, &room_id);
         
                         for (meta, value) in metas.iter() {
-                            command = match value {
-                                MetaType::Null => command,
+                            command = match value.get_value() {
+                                MetaType::Null | MetaType::Expiring(_, _) => command,
                                 MetaType::Number(value, per) => {
                                     command.cmd("HSET").arg(&room_meta_value).arg(meta).arg(value).ignore();
                                     command.cmd("HSET").arg(&room_meta_type).arg(meta).arg(1).ignore();
@@ -713,8 +1527,14 @@ impl YummyState {
                                     command.cmd("HSET").arg(&room_meta_value).arg(meta).arg(serde_json::to_string(value.deref()).unwrap_or_default()).ignore();
                                     command.cmd("HSET").arg(&room_meta_type).arg(meta).arg(4).ignore();
                                     command.cmd("HSET").arg(&room_meta_per).arg(meta).arg(i32::from(*per)).ignore()
+                                },
+                                MetaType::Object(value, per) => {
+                                    command.cmd("HSET").arg(&room_meta_value).arg(meta).arg(serde_json::to_string(value.deref()).unwrap_or_default()).ignore();
+                                    command.cmd("HSET").arg(&room_meta_type).arg(meta).arg(5).ignore();
+                                    command.cmd("HSET").arg(&room_meta_per).arg(meta).arg(i32::from(*per)).ignore()
                                 }
                             }
+                            command = Self::expire_meta_fields(command, [&room_meta_value, &room_meta_type, &room_meta_per], &room_meta_expiring, meta, value.get_expire_date());
                         }
                     },
                 };
@@ -725,19 +1545,14 @@ impl YummyState {
     }
 
     #[tracing::instrument(name="get_rooms", skip(self))]
-    pub fn get_rooms(&self, tag: &Option<String>, query: &[RoomInfoTypeVariant]) -> Result<Vec<RoomInfoTypeCollection>, YummyStateError> {
-        use std::collections::HashMap;
-
+    pub fn get_rooms(&self, filter: &RoomListFilter, query: &[RoomInfoTypeVariant]) -> Result<(Vec<RoomInfoTypeCollection>, Option<String>), YummyStateError> {
         use redis::FromRedisValue;
         
         match self.redis.get() {
             Ok(mut redis) => {
                 let mut results = Vec::new();
-                
-                let rooms = match tag {
-                    Some(tag) => redis_result!(redis.smembers::<_, Vec<String>>(format!("{}tag:{}", self.config.redis_prefix, &tag))),
-                    None => redis_result!(redis.smembers::<_, Vec<String>>(format!("{}rooms", self.config.redis_prefix)))
-                };
+                let limit = filter.get_limit(self.config.max_room_list_page_size);
+                let (rooms, next_cursor) = self.get_room_list_page(&mut redis, filter, limit);
 
                 let mut command = &mut redis::pipe();
                 for room_id in rooms.iter() {
@@ -750,12 +1565,19 @@ impl YummyState {
                             RoomInfoTypeVariant::Users => command = command.arg("users"),
                             RoomInfoTypeVariant::MaxUser => command = command.arg("max-user"),
                             RoomInfoTypeVariant::UserLength => command = command.arg("user-len"),
+                            RoomInfoTypeVariant::Spectators => command = command.arg("spectators"), // Dummy data, dont remove
+                            RoomInfoTypeVariant::MaxSpectator => command = command.arg("max-spec"),
+                            RoomInfoTypeVariant::SpectatorLength => command = command.arg("spec-len"),
                             RoomInfoTypeVariant::AccessType => command = command.arg("access"),
                             RoomInfoTypeVariant::InsertDate => command = command.arg("idate"),
                             RoomInfoTypeVariant::JoinRequest => command = command.arg("join"),
+                            RoomInfoTypeVariant::Password => command = command.arg("pass"),
                             RoomInfoTypeVariant::Tags => command = command.arg("tags"), // Dummy data, dont remove
                             RoomInfoTypeVariant::BannedUsers => command = command.arg("bu"), // Dummy data, dont remove
                             RoomInfoTypeVariant::Metas => command = command.arg("metas"), // Dummy data, dont remove
+                            RoomInfoTypeVariant::GameState => command = command.arg("state"),
+                            RoomInfoTypeVariant::ReadyUsers => command = command.arg("ready"), // Dummy data, dont remove
+                            RoomInfoTypeVariant::AutoStart => command = command.arg("auto"),
                         };
                     }
                 }
@@ -786,36 +1608,32 @@ impl YummyState {
                                 let description: String = FromRedisValue::from_redis_value(redis_value).unwrap_or_default();
                                 room_info.items.push(RoomInfoType::Description(if description.is_empty() { None } else { Some(description) }));
                             },
-                            RoomInfoTypeVariant::Users => {
-    
-                                // This request is slow compare to other. We should change it to lua script to increase performance
-                                let mut user_infos = Vec::new();
-                                let users = redis_result!(redis.hgetall::<_, HashMap<UserId, RoomUserType>>(format!("{}room-sessions:{}", self.config.redis_prefix, &room_id_str)));
-                                for (session_id, user_type) in users.into_iter() {
-                                    let user_id: String = redis_result!(redis.hget::<_, _, String>(format!("{}session-user", self.config.redis_prefix), session_id.to_string()));
-                                    
-                                    let name = redis_result!(redis.hget::<_, _, String>(format!("{}users:{}", self.config.redis_prefix, &user_id.to_string()), "name"));
-                                    user_infos.push(RoomUserInformation {
-                                        name: if name.is_empty() { None } else { Some(name) },
-                                        user_id: Arc::new(UserId::from(user_id)),
-                                        user_type
-                                    })
-                                }
-                                room_info.items.push(RoomInfoType::Users(user_infos));
-                            },
+                            RoomInfoTypeVariant::Users => room_info.items.push(RoomInfoType::Users(self.get_room_users(&mut redis, &room_id_str, false))),
+                            RoomInfoTypeVariant::Spectators => room_info.items.push(RoomInfoType::Spectators(self.get_room_users(&mut redis, &room_id_str, true))),
                             RoomInfoTypeVariant::BannedUsers => {
-                                let users = redis_result!(redis.hgetall::<_, HashSet<UserId>>(format!("{}room-banned:{}", self.config.redis_prefix, &room_id_str)));
-                                room_info.items.push(RoomInfoType::BannedUsers(users));
+                                room_info.items.push(RoomInfoType::BannedUsers(self.get_active_bans(&mut redis, &room_id_str)));
                             },
                             RoomInfoTypeVariant::AccessType => room_info.items.push(RoomInfoType::AccessType(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
                             RoomInfoTypeVariant::InsertDate => room_info.items.push(RoomInfoType::InsertDate(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
                             RoomInfoTypeVariant::JoinRequest => room_info.items.push(RoomInfoType::JoinRequest(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
+                            RoomInfoTypeVariant::Password => room_info.items.push(RoomInfoType::Password(None)),
                             RoomInfoTypeVariant::MaxUser => room_info.items.push(RoomInfoType::MaxUser(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
                             RoomInfoTypeVariant::UserLength => room_info.items.push(RoomInfoType::UserLength(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
+                            RoomInfoTypeVariant::MaxSpectator => room_info.items.push(RoomInfoType::MaxSpectator(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
+                            RoomInfoTypeVariant::SpectatorLength => room_info.items.push(RoomInfoType::SpectatorLength(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
                             RoomInfoTypeVariant::Tags => {
                                 let tags = redis_result!(redis.smembers::<_, Vec<String>>(format!("{}room-tag:{}", self.config.redis_prefix, &room_id_str)));
                                 room_info.items.push(RoomInfoType::Tags(tags));
                             },
+                            RoomInfoTypeVariant::GameState => {
+                                let game_state: i32 = FromRedisValue::from_redis_value(redis_value).unwrap_or_default();
+                                room_info.items.push(RoomInfoType::GameState(RoomGameState::from(game_state)));
+                            },
+                            RoomInfoTypeVariant::ReadyUsers => {
+                                let users = redis_result!(redis.smembers::<_, HashSet<UserId>>(format!("{}room-ready:{}", self.config.redis_prefix, &room_id_str)));
+                                room_info.items.push(RoomInfoType::ReadyUsers(users));
+                            },
+                            RoomInfoTypeVariant::AutoStart => room_info.items.push(RoomInfoType::AutoStart(FromRedisValue::from_redis_value(redis_value).unwrap_or_default())),
                             RoomInfoTypeVariant::Metas => {
                             }
                         }
@@ -824,7 +1642,350 @@ impl YummyState {
                     results.push(room_info);
                 }
 
-                Ok(results)
+                Ok((results, next_cursor))
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* Only the last messages are kept, history size 0 disables the history but messages still get an id */
+    #[tracing::instrument(name="add_room_message", skip(self))]
+    pub fn add_room_message(&self, room_id: &RoomId, user_id: Option<&UserId>, message: serde_json::Value, insert_date: i32) -> Result<RoomMessage, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let room_info_key = format!("{}room:{}", self.config.redis_prefix, room_id);
+                if !redis_result!(redis.exists::<_, bool>(&room_info_key)) {
+                    return Err(YummyStateError::RoomNotFound);
+                }
+
+                let message = RoomMessage {
+                    id: redis_result!(redis.hincr::<_, _, _, usize>(&room_info_key, "msg-seq", 1)),
+                    user_id: user_id.cloned(),
+                    message,
+                    insert_date
+                };
+
+                if self.config.room_message_history_size > 0 {
+                    let messages_key = format!("{}room-messages:{}", self.config.redis_prefix, room_id);
+                    let value = serde_json::to_string(&message).map_err(|error| YummyStateError::CacheError(error.into()))?;
+
+                    redis_result!(redis::pipe()
+                        .atomic()
+                        .cmd("RPUSH").arg(&messages_key).arg(value).ignore()
+                        .cmd("LTRIM").arg(&messages_key).arg(-(self.config.room_message_history_size as i64)).arg(-1).ignore()
+                        .query::<()>(&mut redis));
+                }
+
+                Ok(message)
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="get_room_messages", skip(self))]
+    pub fn get_room_messages(&self, room_id: &RoomId, before: Option<usize>, limit: usize) -> Result<Vec<RoomMessage>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                if !redis_result!(redis.exists::<_, bool>(format!("{}room:{}", self.config.redis_prefix, room_id))) {
+                    return Err(YummyStateError::RoomNotFound);
+                }
+
+                let messages = redis_result!(redis.lrange::<_, Vec<String>>(format!("{}room-messages:{}", self.config.redis_prefix, room_id), 0, -1));
+
+                // Other server instances could push messages at the same time
+                let mut messages = messages.into_iter()
+                    .filter_map(|message| serde_json::from_str::<RoomMessage>(&message).ok())
+                    .filter(|message| before.map(|before| message.id < before).unwrap_or(true))
+                    .collect::<Vec<_>>();
+                messages.sort_by_key(|message| message.id);
+
+                Ok(messages.split_off(messages.len().saturating_sub(limit)))
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* Previous invitation for the same user is replaced */
+    #[tracing::instrument(name="add_room_invite", skip(self))]
+    pub fn add_room_invite(&self, room_id: &RoomId, invite: RoomInvite) -> Result<(), YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                if !redis_result!(redis.exists::<_, bool>(format!("{}room:{}", self.config.redis_prefix, room_id))) {
+                    return Err(YummyStateError::RoomNotFound);
+                }
+
+                let value = serde_json::to_string(&invite).map_err(|error| YummyStateError::CacheError(error.into()))?;
+                redis_result!(redis.hset::<_, _, _, ()>(format!("{}room-invite:{}", self.config.redis_prefix, room_id), invite.user_id.to_string(), value));
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="get_room_invite", skip(self))]
+    pub fn get_room_invite(&self, room_id: &RoomId, user_id: &UserId, now: i32) -> Result<Option<RoomInvite>, YummyStateError> {
+        Ok(self.get_room_invites(room_id, now)?
+            .into_iter()
+            .find(|invite| &invite.user_id == user_id))
+    }
+
+    #[tracing::instrument(name="get_room_invites", skip(self))]
+    pub fn get_room_invites(&self, room_id: &RoomId, now: i32) -> Result<Vec<RoomInvite>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                if !redis_result!(redis.exists::<_, bool>(format!("{}room:{}", self.config.redis_prefix, room_id))) {
+                    return Err(YummyStateError::RoomNotFound);
+                }
+
+                let invites_key = format!("{}room-invite:{}", self.config.redis_prefix, room_id);
+                let invites = redis_result!(redis.hvals::<_, Vec<String>>(&invites_key))
+                    .into_iter()
+                    .filter_map(|invite| serde_json::from_str::<RoomInvite>(&invite).ok())
+                    .collect::<Vec<_>>();
+
+                let (mut invites, expired): (Vec<_>, Vec<_>) = invites.into_iter().partition(|invite| invite.expire_date > now);
+                if !expired.is_empty() {
+                    redis_result!(redis.hdel::<_, _, ()>(&invites_key, expired.iter().map(|invite| invite.user_id.to_string()).collect::<Vec<_>>()));
+                }
+
+                invites.sort_by_key(|invite| invite.insert_date);
+                Ok(invites)
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="remove_room_invite", skip(self))]
+    pub fn remove_room_invite(&self, room_id: &RoomId, user_id: &UserId) -> Result<Option<RoomInvite>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                if !redis_result!(redis.exists::<_, bool>(format!("{}room:{}", self.config.redis_prefix, room_id))) {
+                    return Err(YummyStateError::RoomNotFound);
+                }
+
+                let invites_key = format!("{}room-invite:{}", self.config.redis_prefix, room_id);
+                let (invite, _): (Option<String>, usize) = redis_result!(redis::pipe()
+                    .atomic()
+                    .cmd("HGET").arg(&invites_key).arg(user_id.to_string())
+                    .cmd("HDEL").arg(&invites_key).arg(user_id.to_string())
+                    .query(&mut redis));
+
+                Ok(invite.and_then(|invite| serde_json::from_str::<RoomInvite>(&invite).ok()))
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="add_matchmaking_ticket", skip(self))]
+    pub fn add_matchmaking_ticket(&self, queue: &str, ticket: MatchmakingTicket) -> Result<(), YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let value = serde_json::to_string(&ticket).map_err(|error| YummyStateError::CacheError(error.into()))?;
+                let (added,): (bool,) = redis_result!(redis::pipe()
+                    .atomic()
+                    .cmd("HSETNX").arg(format!("{}mm:{}", self.config.redis_prefix, queue)).arg(ticket.session_id.to_string()).arg(value)
+                    .cmd("SADD").arg(format!("{}mm-queues", self.config.redis_prefix)).arg(queue).ignore()
+                    .query(&mut redis));
+
+                match added {
+                    true => Ok(()),
+                    false => Err(YummyStateError::UserAlreadyInQueue)
+                }
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* Empty queue should not stay in the queue list */
+    #[tracing::instrument(name="remove_matchmaking_ticket", skip(self))]
+    pub fn remove_matchmaking_ticket(&self, queue: &str, session_id: &SessionId) -> Result<bool, YummyStateError> {
+        let script = redis::Script::new(r"
+            local removed = redis.call('HDEL', KEYS[1], ARGV[1])
+            if redis.call('HLEN', KEYS[1]) == 0 then
+                redis.call('SREM', KEYS[2], ARGV[2])
+            end
+            return removed
+        ");
+
+        match self.redis.get() {
+            Ok(mut redis) => Ok(redis_result!(script
+                .key(format!("{}mm:{}", self.config.redis_prefix, queue))
+                .key(format!("{}mm-queues", self.config.redis_prefix))
+                .arg(session_id.to_string())
+                .arg(queue)
+                .invoke::<usize>(&mut redis)) > 0),
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="get_matchmaking_queues", skip(self))]
+    pub fn get_matchmaking_queues(&self) -> Result<Vec<String>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => Ok(redis_result!(redis.smembers::<_, Vec<String>>(format!("{}mm-queues", self.config.redis_prefix)))),
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="get_matchmaking_tickets", skip(self))]
+    pub fn get_matchmaking_tickets(&self, queue: &str) -> Result<Vec<MatchmakingTicket>, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let tickets = redis_result!(redis.hvals::<_, Vec<String>>(format!("{}mm:{}", self.config.redis_prefix, queue)));
+                Ok(tickets.into_iter().filter_map(|ticket| serde_json::from_str(&ticket).ok()).collect())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* Multiple servers could try to claim the same tickets, script guarantees that only one of them succeeds */
+    #[tracing::instrument(name="claim_matchmaking_tickets", skip(self))]
+    pub fn claim_matchmaking_tickets(&self, queue: &str, session_ids: &[SessionId]) -> Result<bool, YummyStateError> {
+        let script = redis::Script::new(r"
+            for index = 2, #ARGV do
+                if redis.call('HEXISTS', KEYS[1], ARGV[index]) == 0 then
+                    return 0
+                end
+            end
+
+            for index = 2, #ARGV do
+                redis.call('HDEL', KEYS[1], ARGV[index])
+            end
+
+            if redis.call('HLEN', KEYS[1]) == 0 then
+                redis.call('SREM', KEYS[2], ARGV[1])
+            end
+            return 1
+        ");
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let mut invocation = script.key(format!("{}mm:{}", self.config.redis_prefix, queue));
+                invocation.key(format!("{}mm-queues", self.config.redis_prefix));
+                invocation.arg(queue);
+                for session_id in session_ids.iter() {
+                    invocation.arg(session_id.to_string());
+                }
+
+                Ok(redis_result!(invocation.invoke::<i32>(&mut redis)) == 1)
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="create_token_family", skip(self))]
+    pub fn create_token_family(&self, user_id: &UserId, family: &TokenFamilyId, refresh_token: &TokenId) -> Result<(), YummyStateError> {
+        let lifetime = self.config.refresh_token_lifetime.as_secs();
+        let family_key = format!("{}token-family:{}", self.config.redis_prefix, family.to_string());
+        let user_key = format!("{}user-token-families:{}", self.config.redis_prefix, user_id.to_string());
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                redis_result!(redis::pipe()
+                    .atomic()
+                    .cmd("HSET").arg(&family_key).arg("user").arg(user_id.to_string()).arg("refresh").arg(refresh_token.to_string()).ignore()
+                    .cmd("EXPIRE").arg(&family_key).arg(lifetime).ignore()
+                    .cmd("SADD").arg(&user_key).arg(family.to_string()).ignore()
+                    .cmd("EXPIRE").arg(&user_key).arg(lifetime).ignore()
+                    .query::<()>(&mut redis));
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="is_token_family_active", skip(self))]
+    pub fn is_token_family_active(&self, family: &TokenFamilyId) -> Result<bool, YummyStateError> {
+        match self.redis.get() {
+            Ok(mut redis) => Ok(redis_result!(redis.exists::<_, bool>(format!("{}token-family:{}", self.config.redis_prefix, family.to_string())))),
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    /* The refresh token can be used only once. If an old one comes back, the token is stolen and the whole family is revoked.
+       User's family set lives as long as the latest rotated family, otherwise revoke_user_tokens could miss the active families */
+    #[tracing::instrument(name="rotate_refresh_token", skip(self))]
+    pub fn rotate_refresh_token(&self, family: &TokenFamilyId, refresh_token: &TokenId, new_refresh_token: &TokenId) -> Result<RefreshTokenRotation, YummyStateError> {
+        let script = redis::Script::new(r"
+            local current = redis.call('HGET', KEYS[1], 'refresh')
+            if not current then
+                return 0
+            end
+
+            local user_key = ARGV[4] .. 'user-token-families:' .. redis.call('HGET', KEYS[1], 'user')
+            if current ~= ARGV[1] then
+                redis.call('DEL', KEYS[1])
+                redis.call('SREM', user_key, ARGV[5])
+                return 2
+            end
+
+            redis.call('HSET', KEYS[1], 'refresh', ARGV[2])
+            redis.call('EXPIRE', KEYS[1], ARGV[3])
+            redis.call('EXPIRE', user_key, ARGV[3])
+            return 1
+        ");
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let result = redis_result!(script
+                    .key(format!("{}token-family:{}", self.config.redis_prefix, family.to_string()))
+                    .arg(refresh_token.to_string())
+                    .arg(new_refresh_token.to_string())
+                    .arg(self.config.refresh_token_lifetime.as_secs())
+                    .arg(&self.config.redis_prefix)
+                    .arg(family.to_string())
+                    .invoke::<i32>(&mut redis));
+
+                Ok(match result {
+                    1 => RefreshTokenRotation::Rotated,
+                    2 => RefreshTokenRotation::Reused,
+                    _ => RefreshTokenRotation::NotFound
+                })
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="revoke_token_family", skip(self))]
+    pub fn revoke_token_family(&self, family: &TokenFamilyId) -> Result<(), YummyStateError> {
+        let script = redis::Script::new(r"
+            local user = redis.call('HGET', KEYS[1], 'user')
+            redis.call('DEL', KEYS[1])
+            if user then
+                redis.call('SREM', ARGV[1] .. 'user-token-families:' .. user, ARGV[2])
+            end
+            return 1
+        ");
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                redis_result!(script
+                    .key(format!("{}token-family:{}", self.config.redis_prefix, family.to_string()))
+                    .arg(&self.config.redis_prefix)
+                    .arg(family.to_string())
+                    .invoke::<i32>(&mut redis));
+                Ok(())
+            },
+            Err(_) => Err(YummyStateError::CacheCouldNotReaded)
+        }
+    }
+
+    #[tracing::instrument(name="revoke_user_tokens", skip(self))]
+    pub fn revoke_user_tokens(&self, user_id: &UserId) -> Result<(), YummyStateError> {
+        let user_key = format!("{}user-token-families:{}", self.config.redis_prefix, user_id.to_string());
+
+        match self.redis.get() {
+            Ok(mut redis) => {
+                let families = redis_result!(redis.smembers::<_, Vec<String>>(&user_key));
+                let mut pipe = redis::pipe();
+                pipe.atomic();
+
+                for family in families.into_iter() {
+                    pipe.cmd("DEL").arg(format!("{}token-family:{}", self.config.redis_prefix, family)).ignore();
+                }
+
+                pipe.cmd("DEL").arg(&user_key).ignore();
+                redis_result!(pipe.query::<()>(&mut redis));
+                Ok(())
             },
             Err(_) => Err(YummyStateError::CacheCouldNotReaded)
         }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 /* **************************************************************************************************************** */
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
@@ -16,13 +17,16 @@ use yummy_model::SessionId;
 use yummy_model::UserInformationModel;
 use yummy_model::*;
 use yummy_model::meta::RoomMetaAccess;
+use yummy_model::meta::UserMetaAccess;
 use yummy_model::meta::collection::RoomMetaCollection;
 use yummy_model::meta::collection::UserMetaCollection;
 use yummy_model::meta::MetaType;
+use yummy_model::meta::operation::RoomMetaOperation;
 use yummy_model::state::RoomInfoType;
 use yummy_model::state::RoomUserInformation;
 use yummy_model::state::YummyStateError;
 use yummy_model::state::RoomInfoTypeVariant;
+use yummy_model::state::{RoomListFilter, RoomListSort, RoomMetaFilter, RoomInvite, RoomBan, ClientInfo};
 
 use crate::cache::YummyCacheResource;
 
@@ -45,6 +49,7 @@ pub struct DummyUserInformationResource;
 pub struct DummyUserMetaResource;
 pub struct DummyUserTypeResource;
 pub struct DummyRoomMetaResource;
+pub struct DummyUserFriendResource;
 
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
@@ -80,6 +85,10 @@ impl YummyCacheResourceFactory for DummyResourceFactory {
     fn room_metas(&self) -> Box<dyn YummyCacheResource<K=RoomId, V=yummy_model::meta::collection::RoomMetaCollection>> {
         Box::new(DummyRoomMetaResource {})
     }
+
+    fn user_friends(&self) -> Box<dyn YummyCacheResource<K=UserId, V=HashSet<UserId>>> {
+        Box::new(DummyUserFriendResource {})
+    }
 }
 
 impl YummyCacheResource for DummyUserInformationResource {
@@ -110,6 +119,13 @@ impl YummyCacheResource for DummyRoomMetaResource {
     fn get(&self, _: &Self::K) -> anyhow::Result<Option<Self::V>> { Ok(None) }
 }
 
+impl YummyCacheResource for DummyUserFriendResource {
+    type K=UserId;
+    type V=HashSet<UserId>;
+
+    fn get(&self, _: &Self::K) -> anyhow::Result<Option<Self::V>> { Ok(None) }
+}
+
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */
 /* ************************************************** UNIT TESTS ************************************************** */
@@ -126,16 +142,18 @@ async fn state_1() -> anyhow::Result<()> {
     DummyActor{}.start().recipient::<SendMessage>();
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
     let user_id = UserId::new();
-    let session_id = state.new_session(&user_id, None, UserType::Mod);
+    let session_id = state.new_session(&user_id, None, UserType::Mod, ClientInfo::default());
     assert_eq!(state.get_user_type(&user_id)?, Some(UserType::Mod));
 
     assert!(state.is_session_online(&session_id));
     assert!(state.is_user_online(&user_id));
+    assert_eq!(state.get_user_sessions(&user_id), vec![session_id.clone()]);
 
     state.close_session(&user_id, &session_id);
 
     assert!(!state.is_session_online(&session_id));
     assert!(!state.is_user_online(&user_id));
+    assert!(state.get_user_sessions(&user_id).is_empty());
 
     Ok(())
 }
@@ -162,6 +180,50 @@ async fn state_2() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix::test]
+async fn session_list_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = get_configuration();
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+
+    #[allow(unused_mut)]
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+    let user_id = UserId::new();
+
+    let session_1 = state.new_session(&user_id, None, UserType::User, ClientInfo { ip: Some("127.0.0.1".to_string()), client: Some("Yummy client".to_string()) });
+    let session_2 = state.new_session(&user_id, None, UserType::User, ClientInfo::default());
+    let session_3 = state.new_session(&user_id, None, UserType::User, ClientInfo::default());
+
+    let sessions = state.get_user_session_list(&user_id);
+    assert_eq!(sessions.iter().map(|session| session.session_id.clone()).collect::<Vec<_>>(), vec![session_1.clone(), session_2.clone(), session_3.clone()]);
+    assert_eq!(sessions[0].ip, Some("127.0.0.1".to_string()));
+    assert_eq!(sessions[0].client, Some("Yummy client".to_string()));
+    assert_eq!(sessions[1].ip, None);
+
+    state.update_session_activity(&session_2, Some(ClientInfo { ip: Some("10.0.0.1".to_string()), client: None }));
+    let sessions = state.get_user_session_list(&user_id);
+    assert_eq!(sessions[1].ip, Some("10.0.0.1".to_string()));
+    assert!(sessions[1].last_activity >= sessions[1].insert_date);
+
+    state.close_session(&user_id, &session_1);
+    let sessions = state.get_user_session_list(&user_id);
+    assert_eq!(sessions.iter().map(|session| session.session_id.clone()).collect::<Vec<_>>(), vec![session_2.clone(), session_3.clone()]);
+
+    /* Closed session should not be created again */
+    state.update_session_activity(&session_1, None);
+    assert_eq!(state.get_user_session_list(&user_id).len(), 2);
+
+    state.close_session(&user_id, &session_2);
+    state.close_session(&user_id, &session_3);
+    assert!(state.get_user_session_list(&user_id).is_empty());
+
+    Ok(())
+}
+
 #[actix::test]
 async fn room_tests() -> anyhow::Result<()> {
     configure_environment();
@@ -183,21 +245,21 @@ async fn room_tests() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
     
     let room_1 = RoomId::new();
-    state.create_room(&room_1, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
+    state.create_room(&room_1, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, 0, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
         ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
         ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
         ("postcode".to_string(), MetaType::Number(1000.0, RoomMetaAccess::Moderator)),
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
         ("temp_admin".to_string(), MetaType::Bool(true, RoomMetaAccess::Admin)),
-    ])), false);
+    ])), false, None, false);
 
     let user_1 = UserId::new();
     let user_2 = UserId::new();
     let user_3 = UserId::new();
 
-    let user_1_session = state.new_session(&user_1, None, UserType::User);
-    let user_2_session = state.new_session(&user_2, None, UserType::User);
-    let user_3_session = state.new_session(&user_3, None, UserType::User);
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+    let user_3_session = state.new_session(&user_3, None, UserType::User, ClientInfo::default());
     
     state.join_to_room(&room_1, &user_1, &user_1_session, RoomUserType::Owner)?;
     assert_eq!(state.get_users_room_type(&user_1_session, &room_1)?.unwrap(), RoomUserType::Owner);
@@ -249,18 +311,352 @@ async fn room_unlimited_users_tests() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
 
     let room = RoomId::new();
-    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 0, Vec::new(), None, false);
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 0, 0, Vec::new(), None, false, None, false);
 
     for _ in 0..100_000 {
         let user_id = UserId::new();
         let session_id = SessionId::new();
-        state.new_session(&user_id, None, UserType::User);
+        state.new_session(&user_id, None, UserType::User, ClientInfo::default());
         state.join_to_room(&room, &user_id, &session_id, RoomUserType::Owner)?
     }
 
     Ok(())
 }
 
+#[actix::test]
+async fn room_spectator_tests() -> anyhow::Result<()> {
+    configure_environment();
+    #[allow(unused_mut)]
+    let mut config = get_configuration().deref().clone();
+
+    #[cfg(feature = "stateless")] {  
+        use rand::Rng;     
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 1, 1, Vec::new(), None, false, None, false);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let user_3 = UserId::new();
+
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+    let user_3_session = state.new_session(&user_3, None, UserType::User, ClientInfo::default());
+
+    // Spectators are not using the player slots
+    state.join_to_room(&room, &user_1, &user_1_session, RoomUserType::Owner)?;
+    state.join_to_room(&room, &user_2, &user_2_session, RoomUserType::Spectator)?;
+    assert_eq!(state.get_users_room_type(&user_2_session, &room)?.unwrap(), RoomUserType::Spectator);
+
+    let result = state.join_to_room(&room, &user_3, &user_3_session, RoomUserType::Spectator).err().unwrap();
+    if let YummyStateError::RoomHasMaxSpectators = result {} else { assert!(false, "expected RoomHasMaxSpectators")};
+
+    let result = state.join_to_room(&room, &user_3, &user_3_session, RoomUserType::User).err().unwrap();
+    if let YummyStateError::RoomHasMaxUsers = result {} else { assert!(false, "expected RoomHasMaxUsers")};
+
+    // Moved users need a free slot on the other side
+    let result = state.set_users_room_type(&user_2, &room, RoomUserType::User).err().unwrap();
+    if let YummyStateError::RoomHasMaxUsers = result {} else { assert!(false, "expected RoomHasMaxUsers")};
+    assert_eq!(state.get_users_room_type(&user_2_session, &room)?.unwrap(), RoomUserType::Spectator);
+
+    let result = state.set_users_room_type(&user_1, &room, RoomUserType::Spectator).err().unwrap();
+    if let YummyStateError::RoomHasMaxSpectators = result {} else { assert!(false, "expected RoomHasMaxSpectators")};
+    assert_eq!(state.get_users_room_type(&user_1_session, &room)?.unwrap(), RoomUserType::Owner);
+
+    // Spectators are receiving the room messages
+    assert_eq!(state.get_users_from_room(&room)?.len(), 2);
+
+    let result = state.get_room_info(&room, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::Users, RoomInfoTypeVariant::UserLength, RoomInfoTypeVariant::Spectators, RoomInfoTypeVariant::SpectatorLength, RoomInfoTypeVariant::MaxSpectator])?;
+    assert_eq!(result.get_users().len(), 1);
+    assert_eq!(result.get_users()[0].user_id.deref(), &user_1);
+    assert_eq!(result.get_user_length().into_owned(), 1);
+    assert_eq!(result.get_spectators().len(), 1);
+    assert_eq!(result.get_spectators()[0].user_id.deref(), &user_2);
+    assert_eq!(result.get_spectator_length().into_owned(), 1);
+    assert_eq!(result.get_max_spectator().into_owned(), 1);
+
+    // Spectator slot is free again
+    assert_eq!(state.disconnect_from_room(&room, &user_2, &user_2_session)?, false);
+    state.join_to_room(&room, &user_3, &user_3_session, RoomUserType::Spectator)?;
+
+    let result = state.get_room_info(&room, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::SpectatorLength, RoomInfoTypeVariant::UserLength])?;
+    assert_eq!(result.get_spectator_length().into_owned(), 1);
+    assert_eq!(result.get_user_length().into_owned(), 1);
+
+    assert_eq!(state.disconnect_from_room(&room, &user_1, &user_1_session)?, false);
+    assert_eq!(state.disconnect_from_room(&room, &user_3, &user_3_session)?, true);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_users_join_order_tests() -> anyhow::Result<()> {
+    configure_environment();
+    #[allow(unused_mut)]
+    let mut config = get_configuration().deref().clone();
+
+    #[cfg(feature = "stateless")] {  
+        use rand::Rng;     
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 3, 0, Vec::new(), None, false, None, false);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let user_3 = UserId::new();
+
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+    let user_3_session = state.new_session(&user_3, None, UserType::User, ClientInfo::default());
+
+    state.join_to_room(&room, &user_3, &user_3_session, RoomUserType::Owner)?;
+    state.join_to_room(&room, &user_1, &user_1_session, RoomUserType::User)?;
+    state.join_to_room(&room, &user_2, &user_2_session, RoomUserType::User)?;
+
+    // Users are listed by the join order
+    let result = state.get_room_info(&room, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::Users])?;
+    let users = result.get_users().iter().map(|user| user.user_id.deref().clone()).collect::<Vec<_>>();
+    assert_eq!(users, vec![user_3.clone(), user_1.clone(), user_2.clone()]);
+
+    // Changing the user type should not change the order and the user length
+    state.disconnect_from_room(&room, &user_3, &user_3_session)?;
+    state.set_users_room_type(&user_1, &room, RoomUserType::Owner)?;
+
+    let result = state.get_room_info(&room, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::Users, RoomInfoTypeVariant::UserLength])?;
+    assert_eq!(result.get_user_length().into_owned(), 2);
+
+    let users = result.get_users();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].user_id.deref(), &user_1);
+    assert_eq!(users[0].user_type, RoomUserType::Owner);
+    assert_eq!(users[1].user_id.deref(), &user_2);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_game_state_tests() -> anyhow::Result<()> {
+    configure_environment();
+    #[allow(unused_mut)]
+    let mut config = get_configuration().deref().clone();
+
+    #[cfg(feature = "stateless")] {  
+        use rand::Rng;     
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room_1 = RoomId::new();
+    let room_2 = RoomId::new();
+    state.create_room(&room_1, 1234, None, None, CreateRoomAccessType::Public, 2, 0, Vec::new(), None, false, None, true);
+    state.create_room(&room_2, 1234, None, None, CreateRoomAccessType::Public, 2, 0, Vec::new(), None, false, None, false);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+
+    state.join_to_room(&room_1, &user_1, &user_1_session, RoomUserType::Owner)?;
+    state.join_to_room(&room_1, &user_2, &user_2_session, RoomUserType::User)?;
+
+    // New rooms are waiting for the players
+    let result = state.get_room_info(&room_1, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::ReadyUsers, RoomInfoTypeVariant::AutoStart])?;
+    assert_eq!(result.get_game_state().into_owned(), RoomGameState::Waiting);
+    assert!(result.get_ready_users().is_empty());
+    assert!(result.get_auto_start().into_owned());
+
+    // Only room members can be ready
+    assert!(state.set_room_user_ready(&room_2, &user_1, true).is_err());
+
+    state.set_room_user_ready(&room_1, &user_1, true)?;
+    state.set_room_user_ready(&room_1, &user_2, true)?;
+    state.set_room_user_ready(&room_1, &user_2, false)?;
+
+    let result = state.get_room_info(&room_1, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::ReadyUsers])?;
+    assert_eq!(result.get_ready_users().into_owned(), HashSet::from([user_1.clone()]));
+
+    state.set_room_info(&room_1, vec![RoomInfoType::GameState(RoomGameState::Playing), RoomInfoType::ReadyUsers(HashSet::new())]);
+
+    let result = state.get_room_info(&room_1, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::ReadyUsers])?;
+    assert_eq!(result.get_game_state().into_owned(), RoomGameState::Playing);
+    assert!(result.get_ready_users().is_empty());
+
+    // Room list can be filtered by the game state
+    let (rooms, _) = state.get_rooms(&RoomListFilter { game_state: Some(RoomGameState::Playing), ..Default::default() }, &[RoomInfoTypeVariant::GameState])?;
+    assert_eq!(rooms.len(), 1);
+    assert_eq!(rooms[0].room_id, Some(room_1));
+
+    let (rooms, _) = state.get_rooms(&RoomListFilter { game_state: Some(RoomGameState::Waiting), ..Default::default() }, &[RoomInfoTypeVariant::GameState])?;
+    assert_eq!(rooms.len(), 1);
+    assert_eq!(rooms[0].room_id, Some(room_2));
+
+    // Disconnected users are not ready anymore
+    state.set_room_user_ready(&room_1, &user_2, true)?;
+    state.disconnect_from_room(&room_1, &user_2, &user_2_session)?;
+
+    let result = state.get_room_info(&room_1, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::ReadyUsers])?;
+    assert!(result.get_ready_users().is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_list_filters() -> anyhow::Result<()> {
+    configure_environment();
+    let mut config = get_configuration().deref().clone();
+    config.max_room_list_page_size = 2;
+
+    #[cfg(feature = "stateless")] {
+        use rand::Rng;
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room_1 = RoomId::new();
+    let room_2 = RoomId::new();
+    let room_3 = RoomId::new();
+
+    state.create_room(&room_1, 100, None, None, CreateRoomAccessType::Public, 1, 0, vec!["eu".to_string(), "ranked".to_string()], Some(HashMap::from([("level".to_string(), MetaType::Number(10.0, RoomMetaAccess::Anonymous))])), false, None, false);
+    state.create_room(&room_2, 200, None, None, CreateRoomAccessType::Private, 4, 0, vec!["eu".to_string()], Some(HashMap::from([("level".to_string(), MetaType::Number(20.0, RoomMetaAccess::Anonymous))])), false, None, false);
+    state.create_room(&room_3, 300, None, None, CreateRoomAccessType::Public, 4, 0, vec!["us".to_string()], Some(HashMap::from([("level".to_string(), MetaType::Number(30.0, RoomMetaAccess::User))])), false, None, false);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+
+    state.join_to_room(&room_1, &user_1, &user_1_session, RoomUserType::Owner)?;
+    state.join_to_room(&room_3, &user_2, &user_2_session, RoomUserType::Owner)?;
+    state.set_room_info(&room_3, vec![RoomInfoType::GameState(RoomGameState::Playing)]);
+
+    let get_room_ids = |state: &YummyState, filter: RoomListFilter| -> anyhow::Result<(Vec<RoomId>, Option<String>)> {
+        let (rooms, cursor) = state.get_rooms(&filter, &[RoomInfoTypeVariant::RoomName])?;
+        Ok((rooms.into_iter().map(|room| room.room_id.unwrap()).collect(), cursor))
+    };
+
+    // Pages are limited with the configuration
+    let (rooms, cursor) = get_room_ids(&state, RoomListFilter { limit: 10, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1, room_2]);
+    assert!(cursor.is_some());
+
+    let (rooms, cursor) = get_room_ids(&state, RoomListFilter { cursor, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_3]);
+    assert!(cursor.is_none());
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { descending: true, limit: 1, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_3]);
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { sort: RoomListSort::UserCount, descending: true, ..Default::default() })?;
+    assert_eq!(rooms.len(), 2);
+    assert!(!rooms.contains(&room_2));
+
+    // Tags
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { tags: vec!["ranked".to_string(), "us".to_string()], ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1, room_3]);
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { tags: vec!["eu".to_string(), "ranked".to_string()], match_all_tags: true, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1]);
+
+    // Access type, free slot and joinable state
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { access_type: Some(CreateRoomAccessType::Public), ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1, room_3]);
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { has_free_slot: true, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_2, room_3]);
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { joinable: true, ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1, room_2]);
+
+    // Only public metas are used
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { metas: vec![RoomMetaFilter { key: "level".to_string(), min: Some(15.0), ..Default::default() }], ..Default::default() })?;
+    assert_eq!(rooms, vec![room_2]);
+
+    let (rooms, _) = get_room_ids(&state, RoomListFilter { metas: vec![RoomMetaFilter { key: "level".to_string(), value: Some(MetaType::Number(10.0, RoomMetaAccess::Anonymous)), ..Default::default() }], ..Default::default() })?;
+    assert_eq!(rooms, vec![room_1]);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_message_history() -> anyhow::Result<()> {
+    use serde_json::json;
+
+    configure_environment();
+    let mut config = get_configuration().deref().clone();
+    config.room_message_history_size = 3;
+    let config = Arc::new(config);
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    let user_id = UserId::new();
+
+    assert!(state.add_room_message(&room, None, json!("hello"), 1).is_err());
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 4, 0, Vec::new(), None, false, None, false);
+    assert!(state.get_room_messages(&room, None, 10)?.is_empty());
+
+    for index in 1..=5 {
+        let message = state.add_room_message(&room, Some(&user_id), json!(index), index)?;
+        assert_eq!(message.id, index as usize);
+    }
+
+    // Only the last 3 messages are kept
+    let messages = state.get_room_messages(&room, None, 10)?;
+    assert_eq!(messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![3, 4, 5]);
+    assert_eq!(messages[0].user_id, Some(user_id.clone()));
+    assert_eq!(messages[0].message, json!(3));
+
+    let messages = state.get_room_messages(&room, None, 2)?;
+    assert_eq!(messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![4, 5]);
+
+    let messages = state.get_room_messages(&room, Some(4), 10)?;
+    assert_eq!(messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![3]);
+
+    Ok(())
+}
+
 #[actix::test]
 async fn get_room() -> anyhow::Result<()> {
     configure_environment();
@@ -274,7 +670,7 @@ async fn get_room() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
 
     let room = RoomId::new();
-    state.create_room(&room, 1234, Some("Room 1".to_string()), None, CreateRoomAccessType::Private, 10, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], None, false);
+    state.create_room(&room, 1234, Some("Room 1".to_string()), None, CreateRoomAccessType::Private, 10, 0, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], None, false, None, false);
 
     let result = state.get_room_info(&room, RoomMetaAccess::Admin, &[])?;
     assert_eq!(result.items.len(), 0);
@@ -321,9 +717,9 @@ async fn get_room() -> anyhow::Result<()> {
     let user_2 = UserId::new();
     let user_3 = UserId::new();
 
-    let user_1_session = state.new_session(&user_1, Some("user1".to_string()), UserType::User);
-    let user_2_session = state.new_session(&user_2, Some("user2".to_string()), UserType::Mod);
-    let user_3_session = state.new_session(&user_3, Some("user3".to_string()), UserType::Admin);
+    let user_1_session = state.new_session(&user_1, Some("user1".to_string()), UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, Some("user2".to_string()), UserType::Mod, ClientInfo::default());
+    let user_3_session = state.new_session(&user_3, Some("user3".to_string()), UserType::Admin, ClientInfo::default());
 
     assert_eq!(state.get_user_type(&user_1)?, Some(UserType::User));
     assert_eq!(state.get_user_type(&user_2)?, Some(UserType::Mod));
@@ -354,6 +750,38 @@ async fn get_room() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix::test]
+async fn room_password_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = get_configuration();
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 2, 0, Vec::new(), None, false, Some("ABCD".to_string()), false);
+
+    let result = state.get_room_info(&room, RoomMetaAccess::Owner, &[RoomInfoTypeVariant::Password])?;
+    assert_eq!(result.get_password().into_owned(), Some("ABCD".to_string()));
+
+    // Password should not be visible for normal users
+    let result = state.get_room_info(&room, RoomMetaAccess::User, &[RoomInfoTypeVariant::Password])?;
+    assert_eq!(result.get_password().into_owned(), None);
+
+    state.set_room_info(&room, vec![RoomInfoType::Password(Some("WXYZ".to_string()))]);
+    let result = state.get_room_info(&room, RoomMetaAccess::System, &[RoomInfoTypeVariant::Password])?;
+    assert_eq!(result.get_password().into_owned(), Some("WXYZ".to_string()));
+
+    state.set_room_info(&room, vec![RoomInfoType::Password(None)]);
+    let result = state.get_room_info(&room, RoomMetaAccess::System, &[RoomInfoTypeVariant::Password])?;
+    assert_eq!(result.get_password().into_owned(), None);
+
+    Ok(())
+}
+
 macro_rules! meta_validation {
     ($state: expr, $room_id: expr, $access: expr, $len: expr, $map: expr) => {
         let metas = $state.get_room_info(&$room_id, $access, &[RoomInfoTypeVariant::Metas])?;
@@ -377,13 +805,13 @@ async fn room_meta_read_test() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
     
     let room_id = RoomId::new();
-    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
+    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, 0, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
         ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
         ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
         ("postcode".to_string(), MetaType::Number(1000.0, RoomMetaAccess::Moderator)),
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
         ("temp_admin".to_string(), MetaType::Bool(true, RoomMetaAccess::Admin)),
-    ])), false);
+    ])), false, None, false);
 
     meta_validation!(state, room_id, RoomMetaAccess::Anonymous, 1, HashMap::from([
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous))
@@ -413,6 +841,40 @@ async fn room_meta_read_test() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix::test]
+async fn expiring_meta_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = get_configuration();
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    #[allow(unused_mut)]
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+    
+    let room_id = RoomId::new();
+    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, 0, Vec::new(), Some(HashMap::from([
+        ("boost".to_string(), MetaType::Expiring(Box::new(MetaType::Number(2.0, RoomMetaAccess::User)), i32::MAX)),
+        ("cooldown".to_string(), MetaType::Expiring(Box::new(MetaType::Bool(true, RoomMetaAccess::User)), 1)),
+    ])), false, None, false);
+
+    // Expired metas are hidden
+    meta_validation!(state, room_id, RoomMetaAccess::User, 1, HashMap::from([
+        ("boost".to_string(), MetaType::Expiring(Box::new(MetaType::Number(2.0, RoomMetaAccess::User)), i32::MAX))
+    ]));
+
+    let user_id = UserId::new();
+    state.set_user_meta(&user_id, "boost".to_string(), MetaType::Expiring(Box::new(MetaType::Number(2.0, UserMetaAccess::Friend)), i32::MAX))?;
+    state.set_user_meta(&user_id, "cooldown".to_string(), MetaType::Expiring(Box::new(MetaType::Bool(true, UserMetaAccess::Friend)), 1))?;
+
+    let metas = state.get_user_meta(&user_id, UserMetaAccess::System)?;
+    assert_eq!(metas.len(), 1);
+    assert_eq!(metas[0].name, "boost");
+
+    Ok(())
+}
+
 #[actix::test]
 async fn room_meta_update_test() -> anyhow::Result<()> {
     configure_environment();
@@ -426,13 +888,13 @@ async fn room_meta_update_test() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
     
     let room_id = RoomId::new();
-    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
+    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, 0, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
         ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
         ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
         ("postcode".to_string(), MetaType::Number(1000.0, RoomMetaAccess::Moderator)),
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
         ("temp_admin".to_string(), MetaType::Bool(true, RoomMetaAccess::Admin)),
-    ])), false);
+    ])), false, None, false);
 
     meta_validation!(state, room_id, RoomMetaAccess::Anonymous, 1, HashMap::from([
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous))
@@ -500,6 +962,56 @@ async fn room_meta_update_test() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix::test]
+async fn room_meta_operation_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = get_configuration();
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    #[allow(unused_mut)]
+    let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+    
+    let room_id = RoomId::new();
+    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Public, 2, 0, Vec::new(), Some(HashMap::from([
+        ("round".to_string(), MetaType::Number(1.0, RoomMetaAccess::User)),
+        ("secret".to_string(), MetaType::Number(1.0, RoomMetaAccess::Admin)),
+    ])), false, None, false);
+
+    // Number operations
+    for _ in 0..10 {
+        state.apply_room_meta_operation(&room_id, "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Increment { value: 1.0, min: None, max: None }, |_| Ok(()))?;
+    }
+
+    assert_eq!(state.get_room_meta(&room_id, RoomMetaAccess::User)?.get_with_name("round").map(|item| item.meta.clone()), Some(MetaType::Number(11.0, RoomMetaAccess::User)));
+
+    // Clamp
+    assert_eq!(state.apply_room_meta_operation(&room_id, "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Decrement { value: 20.0, min: Some(0.0), max: None }, |_| Ok(()))?, MetaType::Number(0.0, RoomMetaAccess::User));
+
+    // Access level check
+    assert!(matches!(state.apply_room_meta_operation(&room_id, "secret".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Increment { value: 1.0, min: None, max: None }, |_| Ok(())), Err(YummyStateError::MetaAccessLevelNotEnough)));
+
+    // Persist failure should not change the state
+    assert!(state.apply_room_meta_operation(&room_id, "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Increment { value: 1.0, min: None, max: None }, |_| Err(anyhow::anyhow!("failed"))).is_err());
+    assert_eq!(state.get_room_meta(&room_id, RoomMetaAccess::User)?.get_with_name("round").map(|item| item.meta.clone()), Some(MetaType::Number(0.0, RoomMetaAccess::User)));
+
+    // List operations
+    state.apply_room_meta_operation(&room_id, "players".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Append { value: MetaType::String("erhan".to_string(), RoomMetaAccess::Anonymous) }, |_| Ok(()))?;
+    state.apply_room_meta_operation(&room_id, "players".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Append { value: MetaType::String("baris".to_string(), RoomMetaAccess::Anonymous) }, |_| Ok(()))?;
+    assert_eq!(state.apply_room_meta_operation(&room_id, "players".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Remove { value: MetaType::String("erhan".to_string(), RoomMetaAccess::Anonymous) }, |_| Ok(()))?, MetaType::List(Box::new(vec![MetaType::String("baris".to_string(), RoomMetaAccess::Anonymous)]), RoomMetaAccess::Anonymous));
+
+    // Compare and set
+    assert!(matches!(state.apply_room_meta_operation(&room_id, "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::CompareAndSet { expected: MetaType::Number(5.0, RoomMetaAccess::Anonymous), value: MetaType::Number(10.0, RoomMetaAccess::User) }, |_| Ok(())), Err(YummyStateError::MetaOperationFailed(_))));
+    state.apply_room_meta_operation(&room_id, "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::CompareAndSet { expected: MetaType::Number(0.0, RoomMetaAccess::Anonymous), value: MetaType::Null }, |_| Ok(()))?;
+
+    assert!(state.get_room_meta(&room_id, RoomMetaAccess::User)?.get_with_name("round").is_none());
+    assert!(matches!(state.apply_room_meta_operation(&RoomId::new(), "round".to_string(), RoomMetaAccess::User, &RoomMetaOperation::Increment { value: 1.0, min: None, max: None }, |_| Ok(())), Err(YummyStateError::RoomNotFound)));
+
+    Ok(())
+}
+
 #[actix::test]
 async fn join_request_test() -> anyhow::Result<()> {
     configure_environment();
@@ -521,23 +1033,23 @@ async fn join_request_test() -> anyhow::Result<()> {
     let mut state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
     
     let room_id = RoomId::new();
-    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
+    state.create_room(&room_id, 1234, Some("room".to_string()), None, CreateRoomAccessType::Friend, 2, 0, vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()], Some(HashMap::from([
         ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
         ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
         ("postcode".to_string(), MetaType::Number(1000.0, RoomMetaAccess::Moderator)),
         ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
         ("temp_admin".to_string(), MetaType::Bool(true, RoomMetaAccess::Admin)),
-    ])), true);
+    ])), true, None, false);
 
     let user_1 = UserId::new();
     let user_2 = UserId::new();
     let user_3 = UserId::new();
     let user_4 = UserId::new();
 
-    let user_1_session = state.new_session(&user_1, None, UserType::User);
-    let user_2_session = state.new_session(&user_2, None, UserType::User);
-    let user_3_session = state.new_session(&user_3, None, UserType::User);
-    let user_4_session = state.new_session(&user_4, None, UserType::User);
+    let user_1_session = state.new_session(&user_1, None, UserType::User, ClientInfo::default());
+    let user_2_session = state.new_session(&user_2, None, UserType::User, ClientInfo::default());
+    let user_3_session = state.new_session(&user_3, None, UserType::User, ClientInfo::default());
+    let user_4_session = state.new_session(&user_4, None, UserType::User, ClientInfo::default());
     
     state.join_to_room(&room_id, &user_1, &user_1_session, RoomUserType::Owner)?;
     state.join_to_room_request(&room_id, &user_2, &user_2_session, RoomUserType::User)?;
@@ -564,3 +1076,269 @@ async fn join_request_test() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[actix::test]
+async fn friend_test() -> anyhow::Result<()> {
+    configure_environment();
+    #[allow(unused_mut)]
+    let mut config = get_configuration().deref().clone();
+
+    #[cfg(feature = "stateless")] {  
+        use rand::Rng;     
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let user_3 = UserId::new();
+
+    assert!(state.get_user_friends(&user_1)?.is_empty());
+    assert!(!state.is_friend(&user_1, &user_2)?);
+
+    state.add_friend(&user_1, &user_2)?;
+    state.add_friend(&user_1, &user_3)?;
+
+    assert!(state.is_friend(&user_1, &user_2)?);
+    assert!(state.is_friend(&user_2, &user_1)?);
+    assert!(state.is_friend(&user_3, &user_1)?);
+    assert!(!state.is_friend(&user_2, &user_3)?);
+    assert_eq!(state.get_user_friends(&user_1)?, HashSet::from([user_2.clone(), user_3.clone()]));
+
+    state.remove_friend(&user_2, &user_1)?;
+    assert!(!state.is_friend(&user_1, &user_2)?);
+    assert!(!state.is_friend(&user_2, &user_1)?);
+    assert_eq!(state.get_user_friends(&user_1)?, HashSet::from([user_3.clone()]));
+
+    Ok(())
+}
+
+#[cfg(not(feature = "stateless"))]
+#[actix::test]
+async fn invalidate_user_cache_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = Arc::new(get_configuration().deref().clone());
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}));
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+
+    state.add_friend(&user_1, &user_2)?;
+    state.set_user_meta(&user_1, "score".to_string(), MetaType::Number(1.0, UserMetaAccess::Friend))?;
+    assert_eq!(state.get_user_metas(&user_1)?.len(), 1);
+
+    // Values are loaded again from the resource
+    state.invalidate_user_cache(&user_1)?;
+    assert!(state.get_user_friends(&user_1)?.is_empty());
+    assert!(state.get_user_metas(&user_1)?.is_empty());
+    assert!(state.is_friend(&user_2, &user_1)?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "stateless"))]
+#[actix::test]
+async fn invalidate_room_meta_cache_test() -> anyhow::Result<()> {
+    configure_environment();
+    let config = Arc::new(get_configuration().deref().clone());
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}));
+
+    let room_id = RoomId::new();
+    state.set_room_meta(&room_id, "round".to_string(), MetaType::Number(1.0, RoomMetaAccess::User))?;
+    assert_eq!(state.get_room_metas(&room_id)?.len(), 1);
+
+    // Values are loaded again from the resource
+    state.invalidate_room_meta_cache(&room_id)?;
+    assert!(state.get_room_metas(&room_id)?.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn matchmaking_test() -> anyhow::Result<()> {
+    use yummy_model::matchmaking::MatchmakingTicket;
+
+    configure_environment();
+    let config = get_configuration();
+    
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let queue = format!("queue-{}", uuid::Uuid::new_v4());
+    let ticket_1 = MatchmakingTicket { user_id: Arc::new(UserId::new()), session_id: SessionId::new(), properties: HashMap::new(), insert_date: 1234 };
+    let ticket_2 = MatchmakingTicket { user_id: Arc::new(UserId::new()), session_id: SessionId::new(), properties: HashMap::new(), insert_date: 1235 };
+
+    assert!(state.get_matchmaking_tickets(&queue)?.is_empty());
+
+    state.add_matchmaking_ticket(&queue, ticket_1.clone())?;
+    state.add_matchmaking_ticket(&queue, ticket_2.clone())?;
+    assert!(state.add_matchmaking_ticket(&queue, ticket_1.clone()).is_err());
+
+    assert!(state.get_matchmaking_queues()?.contains(&queue));
+    assert_eq!(state.get_matchmaking_tickets(&queue)?.len(), 2);
+
+    // Ticket 2 left the queue, so claim should fail
+    assert!(state.remove_matchmaking_ticket(&queue, &ticket_2.session_id)?);
+    assert!(!state.remove_matchmaking_ticket(&queue, &ticket_2.session_id)?);
+    assert!(!state.claim_matchmaking_tickets(&queue, &[ticket_1.session_id.clone(), ticket_2.session_id.clone()])?);
+    assert_eq!(state.get_matchmaking_tickets(&queue)?, vec![ticket_1.clone()]);
+
+    state.add_matchmaking_ticket(&queue, ticket_2.clone())?;
+    assert!(state.claim_matchmaking_tickets(&queue, &[ticket_1.session_id, ticket_2.session_id])?);
+    assert!(state.get_matchmaking_tickets(&queue)?.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_invite() -> anyhow::Result<()> {
+    configure_environment();
+    let config = get_configuration();
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    let inviter = UserId::new();
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+
+    let invite = |user_id: &UserId, insert_date: i32, expire_date: i32| RoomInvite {
+        user_id: user_id.clone(),
+        inviter: inviter.clone(),
+        room_user_type: RoomUserType::User,
+        insert_date,
+        expire_date
+    };
+
+    assert!(state.add_room_invite(&room, invite(&user_1, 1, 10)).is_err());
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Private, 4, 0, Vec::new(), None, false, None, false);
+    assert!(state.get_room_invites(&room, 1)?.is_empty());
+
+    state.add_room_invite(&room, invite(&user_1, 1, 10))?;
+    state.add_room_invite(&room, invite(&user_2, 2, 20))?;
+
+    assert_eq!(state.get_room_invite(&room, &user_1, 5)?, Some(invite(&user_1, 1, 10)));
+    assert_eq!(state.get_room_invites(&room, 5)?.len(), 2);
+
+    // First invite is expired
+    assert_eq!(state.get_room_invite(&room, &user_1, 10)?, None);
+    assert_eq!(state.get_room_invites(&room, 10)?, vec![invite(&user_2, 2, 20)]);
+
+    // Same user invited again, previous invite replaced
+    state.add_room_invite(&room, invite(&user_2, 3, 30))?;
+    assert_eq!(state.get_room_invite(&room, &user_2, 25)?, Some(invite(&user_2, 3, 30)));
+
+    assert_eq!(state.remove_room_invite(&room, &user_2)?, Some(invite(&user_2, 3, 30)));
+    assert_eq!(state.remove_room_invite(&room, &user_2)?, None);
+    assert!(state.get_room_invites(&room, 1)?.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_ban() -> anyhow::Result<()> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    configure_environment();
+    let config = get_configuration();
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let room = RoomId::new();
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let user_3 = UserId::new();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+    state.create_room(&room, 1234, None, None, CreateRoomAccessType::Public, 4, 0, Vec::new(), None, false, None, false);
+
+    state.ban_user_from_room(&room, &user_1, RoomBan { reason: Some("Spam".to_string()), insert_date: now, expire_date: None })?;
+    state.ban_user_from_room(&room, &user_2, RoomBan { reason: None, insert_date: now, expire_date: Some(now + 100) })?;
+    state.ban_user_from_room(&room, &user_3, RoomBan { reason: None, insert_date: now - 100, expire_date: Some(now - 1) })?;
+
+    assert!(state.is_user_banned_from_room(&room, &user_1)?);
+    assert!(state.is_user_banned_from_room(&room, &user_2)?);
+
+    // Ban is expired
+    assert!(!state.is_user_banned_from_room(&room, &user_3)?);
+    assert!(!state.unban_user_from_room(&room, &user_3)?);
+
+    let banned_users = state.get_room_info(&room, RoomMetaAccess::Admin, &[RoomInfoTypeVariant::BannedUsers])?.get_banned_users().into_owned();
+    assert_eq!(banned_users.len(), 2);
+    assert_eq!(banned_users.get(&user_1).and_then(|ban| ban.reason.clone()), Some("Spam".to_string()));
+
+    assert!(state.unban_user_from_room(&room, &user_1)?);
+    assert!(!state.unban_user_from_room(&room, &user_1)?);
+    assert!(!state.is_user_banned_from_room(&room, &user_1)?);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn token_family() -> anyhow::Result<()> {
+    use yummy_model::auth::RefreshTokenRotation;
+
+    configure_environment();
+    let config = get_configuration();
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    DummyActor{}.start().recipient::<SendMessage>();
+    let state = YummyState::new(config, Box::new(DummyResourceFactory{}), #[cfg(feature = "stateless")] conn);
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+    let family_1 = TokenFamilyId::new();
+    let family_2 = TokenFamilyId::new();
+    let family_3 = TokenFamilyId::new();
+    let token_1 = TokenId::new();
+    let token_2 = TokenId::new();
+    let token_3 = TokenId::new();
+
+    state.create_token_family(&user_1, &family_1, &token_1)?;
+    state.create_token_family(&user_1, &family_2, &token_1)?;
+    state.create_token_family(&user_2, &family_3, &token_1)?;
+    assert!(state.is_token_family_active(&family_1)?);
+    assert!(!state.is_token_family_active(&TokenFamilyId::new())?);
+
+    assert_eq!(state.rotate_refresh_token(&family_1, &token_1, &token_2)?, RefreshTokenRotation::Rotated);
+    assert_eq!(state.rotate_refresh_token(&family_1, &token_2, &token_3)?, RefreshTokenRotation::Rotated);
+    assert_eq!(state.rotate_refresh_token(&TokenFamilyId::new(), &token_1, &token_2)?, RefreshTokenRotation::NotFound);
+
+    // Old refresh token used again, family should be revoked
+    assert_eq!(state.rotate_refresh_token(&family_1, &token_2, &token_3)?, RefreshTokenRotation::Reused);
+    assert!(!state.is_token_family_active(&family_1)?);
+    assert_eq!(state.rotate_refresh_token(&family_1, &token_3, &token_1)?, RefreshTokenRotation::NotFound);
+
+    state.revoke_user_tokens(&user_1)?;
+    assert!(!state.is_token_family_active(&family_2)?);
+    assert!(state.is_token_family_active(&family_3)?);
+
+    state.revoke_token_family(&family_3)?;
+    assert!(!state.is_token_family_active(&family_3)?);
+
+    Ok(())
+}
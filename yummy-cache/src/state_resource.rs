@@ -2,7 +2,7 @@
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
-use std::{sync::Arc, marker::PhantomData};
+use std::{sync::Arc, marker::PhantomData, collections::HashSet};
 
 use yummy_database::DatabaseTrait;
 use yummy_general::database::Pool;
@@ -40,6 +40,11 @@ pub struct RoomMetaResource<DB: DatabaseTrait + ?Sized> {
     _marker: PhantomData<DB>
 }
 
+pub struct UserFriendResource<DB: DatabaseTrait + ?Sized> {
+    database: Arc<Pool>,
+    _marker: PhantomData<DB>
+}
+
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
 /* ************************************************** FUNCTIONS *************************************************** */
@@ -92,6 +97,15 @@ impl<DB: DatabaseTrait + ?Sized> RoomMetaResource<DB> {
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized> UserFriendResource<DB> {
+    pub fn new(database: Arc<Pool>) -> Self {
+        Self {
+            database,
+            _marker: PhantomData
+        }
+    }
+}
+
 /* **************************************************************************************************************** */
 /* ********************************************** TRAIT IMPLEMENTS ************************************************ */
 /* **************************************************************************************************************** */
@@ -112,6 +126,10 @@ impl<DB: DatabaseTrait + ?Sized + 'static> YummyCacheResourceFactory for Resourc
     fn room_metas(&self) -> Box<dyn YummyCacheResource<K=RoomId, V=RoomMetaCollection>> {
         Box::new(RoomMetaResource::<DB>::new(self.database.clone()))
     }
+
+    fn user_friends(&self) -> Box<dyn YummyCacheResource<K=UserId, V=HashSet<UserId>>> {
+        Box::new(UserFriendResource::<DB>::new(self.database.clone()))
+    }
 }
 
 impl<DB: DatabaseTrait + ?Sized> YummyCacheResource for UserInformationResource<DB> {
@@ -157,6 +175,17 @@ impl<DB: DatabaseTrait + ?Sized> YummyCacheResource for RoomMetaResource<DB> {
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized> YummyCacheResource for UserFriendResource<DB> {
+    type K=UserId;
+    type V=HashSet<UserId>;
+
+    fn get(&self, key: &Self::K) -> anyhow::Result<Option<Self::V>> {
+        let mut connection = self.database.get()?;
+        let result = DB::get_friends(&mut connection, key)?;
+        Ok(Some(result.into_iter().collect()))
+    }
+}
+
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */
 /* ************************************************** UNIT TESTS ************************************************** */
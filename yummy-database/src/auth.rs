@@ -1,11 +1,14 @@
 use diesel::*;
 use yummy_model::password::Password;
-use yummy_model::{UserId, UserType};
-use yummy_model::user::UserInsert;
+use yummy_model::{UserId, UserIdentityId, UserTokenId, UserType};
+use yummy_model::user::{UserInsert, UserIdentityInsert, UserTokenInsert, UserTokenType};
 use yummy_model::user::LoginInfo;
 use yummy_model::schema::user as user_schema;
+use yummy_model::schema::user_identity as user_identity_schema;
+use yummy_model::schema::user_token as user_token_schema;
 use std::borrow::Borrow;
 use std::time::{SystemTime, UNIX_EPOCH};
+use blake2::{Blake2s256, Digest};
 
 use crate::SqliteStore;
 use crate::PooledConnection;
@@ -14,12 +17,28 @@ pub trait AuthStoreTrait: Sized {
     fn user_login_via_email(connection: &mut PooledConnection, email: &str) -> anyhow::Result<Option<LoginInfo>>;
     fn user_login_via_device_id(connection: &mut PooledConnection, device_id: &str) -> anyhow::Result<Option<LoginInfo>>;
     fn user_login_via_custom_id(connection: &mut PooledConnection, custom_id: &str) -> anyhow::Result<Option<LoginInfo>>;
+    fn user_login_via_identity(connection: &mut PooledConnection, provider: &str, subject: &str) -> anyhow::Result<Option<LoginInfo>>;
 
     fn update_last_login(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<()>;
 
     fn create_user_via_email(connection: &mut PooledConnection, email: &str, password: &Password) -> anyhow::Result<UserId>;
     fn create_user_via_device_id(connection: &mut PooledConnection, device_id: &str) -> anyhow::Result<UserId>;
     fn create_user_via_custom_id(connection: &mut PooledConnection, custom_id: &str) -> anyhow::Result<UserId>;
+    fn create_user_via_identity(connection: &mut PooledConnection, provider: &str, subject: &str) -> anyhow::Result<UserId>;
+
+    fn add_user_identity(connection: &mut PooledConnection, user_id: &UserId, provider: &str, subject: &str) -> anyhow::Result<()>;
+    fn remove_user_identity(connection: &mut PooledConnection, user_id: &UserId, provider: &str) -> anyhow::Result<usize>;
+    fn remove_user_email(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize>;
+    fn count_user_login_methods(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize>;
+
+    fn create_user_token(connection: &mut PooledConnection, user_id: &UserId, token_type: UserTokenType, token: &str, expire_date: i32) -> anyhow::Result<()>;
+    fn consume_user_token(connection: &mut PooledConnection, token_type: UserTokenType, token: &str) -> anyhow::Result<Option<UserId>>;
+    fn get_user_token_date(connection: &mut PooledConnection, user_id: &UserId, token_type: UserTokenType) -> anyhow::Result<Option<i32>>;
+}
+
+/* Tokens are stored as hash, database records should not be usable as a token */
+fn hash_user_token(token: &str) -> String {
+    Blake2s256::digest(token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
 }
 
 impl AuthStoreTrait for SqliteStore {
@@ -27,18 +46,19 @@ impl AuthStoreTrait for SqliteStore {
     fn user_login_via_email(connection: &mut PooledConnection, email: &str) -> anyhow::Result<Option<LoginInfo>> {
         let result = user_schema::table
             .filter(user_schema::email.eq(email))
-            .select((user_schema::id, user_schema::name, user_schema::password, user_schema::user_type))
-            .first::<(UserId, Option<String>, Option<String>, i32)>(connection)
+            .select((user_schema::id, user_schema::name, user_schema::password, user_schema::user_type, user_schema::email_verified))
+            .first::<(UserId, Option<String>, Option<String>, i32, i32)>(connection)
             .optional()?
-            .map(|(id, name, password, user_type)| (id, name, password.unwrap_or_default(), user_type.into()));
+            .map(|(id, name, password, user_type, email_verified)| (id, name, password.unwrap_or_default(), user_type.into(), email_verified == 1));
 
         match result {
-            Some((user_id, name, password, user_type)) => Ok(Some(LoginInfo {
+            Some((user_id, name, password, user_type, email_verified)) => Ok(Some(LoginInfo {
                 user_id,
                 name,
                 email: None,
                 password: Some(password),
-                user_type
+                user_type,
+                email_verified
             })),
             None => Ok(None)
         }
@@ -48,19 +68,20 @@ impl AuthStoreTrait for SqliteStore {
     fn user_login_via_device_id(connection: &mut PooledConnection, device_id: &str) -> anyhow::Result<Option<LoginInfo>> {
         let result = user_schema::table
             .filter(user_schema::device_id.eq(device_id))
-            .select((user_schema::id, user_schema::name, user_schema::email, user_schema::user_type))
-            .first::<(UserId, Option<String>, Option<String>, i32)>(connection)
+            .select((user_schema::id, user_schema::name, user_schema::email, user_schema::user_type, user_schema::email_verified))
+            .first::<(UserId, Option<String>, Option<String>, i32, i32)>(connection)
             .optional()?;
 
         match result {
-            Some((user_id, name, email, user_type)) => {
+            Some((user_id, name, email, user_type, email_verified)) => {
                 Self::update_last_login(connection, &user_id)?;
                 Ok(Some(LoginInfo {
                     user_id,
                     name,
                     password: None,
                     email,
-                    user_type: user_type.into()
+                    user_type: user_type.into(),
+                    email_verified: email_verified == 1
                 }))
             },
             None => Ok(None)
@@ -71,19 +92,46 @@ impl AuthStoreTrait for SqliteStore {
     fn user_login_via_custom_id(connection: &mut PooledConnection, custom_id: &str) -> anyhow::Result<Option<LoginInfo>> {
         let result = user_schema::table
             .filter(user_schema::custom_id.eq(custom_id))
-            .select((user_schema::id, user_schema::name, user_schema::email, user_schema::user_type))
-            .first::<(UserId, Option<String>, Option<String>, i32)>(connection)
+            .select((user_schema::id, user_schema::name, user_schema::email, user_schema::user_type, user_schema::email_verified))
+            .first::<(UserId, Option<String>, Option<String>, i32, i32)>(connection)
             .optional()?;
 
         match result {
-            Some((user_id, name, email, user_type)) => {
+            Some((user_id, name, email, user_type, email_verified)) => {
                 Self::update_last_login(connection, &user_id)?;
                 Ok(Some(LoginInfo {
                     user_id,
                     name,
                     password: None,
                     email,
-                    user_type: user_type.into()
+                    user_type: user_type.into(),
+                    email_verified: email_verified == 1
+                }))
+            },
+            None => Ok(None)
+        }
+    }
+
+    #[tracing::instrument(name="User login via identity", skip(connection))]
+    fn user_login_via_identity(connection: &mut PooledConnection, provider: &str, subject: &str) -> anyhow::Result<Option<LoginInfo>> {
+        let result = user_identity_schema::table
+            .inner_join(user_schema::table.on(user_schema::id.eq(user_identity_schema::user_id)))
+            .filter(user_identity_schema::provider.eq(provider))
+            .filter(user_identity_schema::subject.eq(subject))
+            .select((user_schema::id, user_schema::name, user_schema::email, user_schema::user_type, user_schema::email_verified))
+            .first::<(UserId, Option<String>, Option<String>, i32, i32)>(connection)
+            .optional()?;
+
+        match result {
+            Some((user_id, name, email, user_type, email_verified)) => {
+                Self::update_last_login(connection, &user_id)?;
+                Ok(Some(LoginInfo {
+                    user_id,
+                    name,
+                    password: None,
+                    email,
+                    user_type: user_type.into(),
+                    email_verified: email_verified == 1
                 }))
             },
             None => Ok(None)
@@ -101,12 +149,13 @@ impl AuthStoreTrait for SqliteStore {
     fn create_user_via_email(connection: &mut PooledConnection, email: &str, password: &Password) -> anyhow::Result<UserId> {
         
         let user_id = UserId::default();
+        let password = password.hash()?;
         let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
         let model = UserInsert {
             id: &user_id,
             insert_date,
             last_login_date: insert_date,
-            password: Some(password.get()),
+            password: Some(&password),
             email: Some(email),
             user_type: UserType::default().into(),
             custom_id: None,
@@ -161,4 +210,128 @@ impl AuthStoreTrait for SqliteStore {
 
         Ok(user_id)
     }
+
+    /* Name and email are not copied from the external token, both are unique and email could be used to take over an existing account */
+    #[tracing::instrument(name="User create via identity", skip(connection))]
+    fn create_user_via_identity(connection: &mut PooledConnection, provider: &str, subject: &str) -> anyhow::Result<UserId> {
+
+        let user_id = UserId::default();
+        let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let model = UserInsert {
+            id: &user_id,
+            insert_date,
+            last_login_date: insert_date,
+            user_type: UserType::default().into(),
+            custom_id: None,
+            device_id: None,
+            email: None,
+            name: None,
+            password: None
+        };
+        
+        diesel::insert_into(user_schema::table).values(&vec![model]).execute(connection)?;
+        Self::add_user_identity(connection, &user_id, provider, subject)?;
+
+        Ok(user_id)
+    }
+
+    #[tracing::instrument(name="Add user identity", skip(connection))]
+    fn add_user_identity(connection: &mut PooledConnection, user_id: &UserId, provider: &str, subject: &str) -> anyhow::Result<()> {
+        let model = UserIdentityInsert {
+            id: UserIdentityId::default(),
+            user_id,
+            provider,
+            subject,
+            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default()
+        };
+
+        diesel::insert_into(user_identity_schema::table).values(&vec![model]).execute(connection)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name="Remove user identity", skip(connection))]
+    fn remove_user_identity(connection: &mut PooledConnection, user_id: &UserId, provider: &str) -> anyhow::Result<usize> {
+        Ok(diesel::delete(user_identity_schema::table.filter(user_identity_schema::user_id.eq(user_id)).filter(user_identity_schema::provider.eq(provider))).execute(connection)?)
+    }
+
+    /* Password, verification state and one-time tokens are only used with the email, they are removed as well */
+    #[tracing::instrument(name="Remove user email", skip(connection))]
+    fn remove_user_email(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize> {
+        diesel::delete(user_token_schema::table.filter(user_token_schema::user_id.eq(user_id))).execute(connection)?;
+        Ok(diesel::update(user_schema::table.filter(user_schema::id.eq(user_id)).filter(user_schema::email.is_not_null()))
+            .set((user_schema::email.eq(None::<String>), user_schema::password.eq(None::<String>), user_schema::email_verified.eq(0)))
+            .execute(connection)?)
+    }
+
+    #[tracing::instrument(name="Count user login methods", skip(connection))]
+    fn count_user_login_methods(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize> {
+        let logins = user_schema::table
+            .select((user_schema::email, user_schema::device_id, user_schema::custom_id))
+            .filter(user_schema::id.eq(user_id))
+            .first::<(Option<String>, Option<String>, Option<String>)>(connection)
+            .optional()?;
+
+        let identities = user_identity_schema::table
+            .filter(user_identity_schema::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(connection)?;
+
+        Ok(match logins {
+            Some((email, device_id, custom_id)) => [email, device_id, custom_id].iter().filter(|item| item.is_some()).count() + identities as usize,
+            None => 0
+        })
+    }
+
+    /* Only the last token is valid, previous tokens with the same type are removed */
+    #[tracing::instrument(name="Create user token", skip(connection, token))]
+    fn create_user_token(connection: &mut PooledConnection, user_id: &UserId, token_type: UserTokenType, token: &str, expire_date: i32) -> anyhow::Result<()> {
+        diesel::delete(user_token_schema::table.filter(user_token_schema::user_id.eq(user_id)).filter(user_token_schema::token_type.eq(token_type as i32))).execute(connection)?;
+
+        let token = hash_user_token(token);
+        let model = UserTokenInsert {
+            id: UserTokenId::default(),
+            user_id,
+            token_type: token_type as i32,
+            token: &token,
+            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            expire_date
+        };
+        diesel::insert_into(user_token_schema::table).values(&vec![model]).execute(connection)?;
+        Ok(())
+    }
+
+    /* Token removed even if it is expired */
+    #[tracing::instrument(name="Consume user token", skip(connection, token))]
+    fn consume_user_token(connection: &mut PooledConnection, token_type: UserTokenType, token: &str) -> anyhow::Result<Option<UserId>> {
+        let token = hash_user_token(token);
+        let result = user_token_schema::table
+            .select((user_token_schema::user_id, user_token_schema::expire_date))
+            .filter(user_token_schema::token_type.eq(token_type as i32))
+            .filter(user_token_schema::token.eq(&token))
+            .first::<(UserId, i32)>(connection)
+            .optional()?;
+
+        match result {
+            Some((user_id, expire_date)) => {
+                diesel::delete(user_token_schema::table.filter(user_token_schema::token.eq(&token))).execute(connection)?;
+
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+                Ok(match expire_date > now {
+                    true => Some(user_id),
+                    false => None
+                })
+            },
+            None => Ok(None)
+        }
+    }
+
+    #[tracing::instrument(name="Get user token date", skip(connection))]
+    fn get_user_token_date(connection: &mut PooledConnection, user_id: &UserId, token_type: UserTokenType) -> anyhow::Result<Option<i32>> {
+        Ok(user_token_schema::table
+            .select(user_token_schema::insert_date)
+            .filter(user_token_schema::user_id.eq(user_id))
+            .filter(user_token_schema::token_type.eq(token_type as i32))
+            .first::<i32>(connection)
+            .optional()?)
+    }
 }
@@ -37,9 +37,46 @@ pub fn create_connection(database_url: &str) -> anyhow::Result<Pool> {
     Ok(r2d2::Pool::builder().build(ConnectionManager::<ConnectionType>::new(database_url))?)
 }
 
+/* Columns added after the tables were created, older databases get them on every start */
+const COLUMN_MIGRATIONS: [(&str, &str, &str); 6] = [
+    ("user", "email_verified", "INTEGER NOT NULL DEFAULT 0"),
+    ("user_meta", "expire_date", "INTEGER"),
+    ("room_meta", "expire_date", "INTEGER"),
+    ("room", "max_spectator", "INTEGER NOT NULL DEFAULT 0"),
+    ("room_user_ban", "reason", "TEXT"),
+    ("room_user_ban", "expire_date", "INTEGER"),
+];
+
+#[derive(QueryableByName)]
+struct ColumnCount {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64
+}
+
+fn add_column_if_not_exists(connection: &mut PooledConnection, table: &str, column: &str, definition: &str) -> anyhow::Result<()> {
+    let result = sql_query("SELECT COUNT(*) AS count FROM pragma_table_info(?) WHERE name = ?")
+        .bind::<diesel::sql_types::Text, _>(table)
+        .bind::<diesel::sql_types::Text, _>(column)
+        .get_result::<ColumnCount>(connection)?;
+
+    if result.count == 0 {
+        sql_query(format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition)).execute(connection)?;
+    }
+
+    Ok(())
+}
+
+pub fn migrate_database(connection: &mut PooledConnection) -> anyhow::Result<()> {
+    for (table, column, definition) in COLUMN_MIGRATIONS.iter() {
+        add_column_if_not_exists(connection, table, column, definition)?;
+    }
+
+    Ok(())
+}
+
 pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()> {
     sql_query(
-        r#"CREATE TABLE user (
+        r#"CREATE TABLE IF NOT EXISTS user (
             id TEXT PRIMARY KEY,
             name TEXT UNIQUE,
             device_id TEXT UNIQUE,
@@ -47,6 +84,7 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
             email TEXT UNIQUE,
             password TEXT,
             user_type INTEGER,
+            email_verified INTEGER NOT NULL DEFAULT 0,
             insert_date INTEGER NOT NULL,
             last_login_date INTEGER NOT NULL
         );"#,
@@ -54,24 +92,95 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE user_meta (
+        CREATE TABLE IF NOT EXISTS user_meta (
             id TEXT PRIMARY KEY,
             user_id TEXT NOT NULL,
             key TEXT NOT NULL,
             value TEXT NOT NULL,
             meta_type INTEGER NOT NULL,
             access INTEGER NOT NULL,
+            insert_date INTEGER NOT NULL,
+            expire_date INTEGER
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_friend_request (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            target_user_id TEXT NOT NULL,
+            insert_date INTEGER NOT NULL,
+            UNIQUE(user_id, target_user_id)
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_friend (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            friend_user_id TEXT NOT NULL,
+            insert_date INTEGER NOT NULL,
+            UNIQUE(user_id, friend_user_id)
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_inbox (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            sender_user_id TEXT NOT NULL,
+            message TEXT NOT NULL,
             insert_date INTEGER NOT NULL
         );"#,
     )
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room (
+        CREATE TABLE IF NOT EXISTS user_suspension (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            suspender_user_id TEXT NOT NULL,
+            reason TEXT,
+            insert_date INTEGER NOT NULL,
+            expire_date INTEGER
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_identity (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            subject TEXT NOT NULL,
+            insert_date INTEGER NOT NULL,
+            UNIQUE(provider, subject)
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS user_token (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            token_type INTEGER NOT NULL,
+            token TEXT NOT NULL UNIQUE,
+            insert_date INTEGER NOT NULL,
+            expire_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS room (
             id TEXT PRIMARY KEY,
             name TEXT,
             description TEXT,
             max_user INTEGER NOT NULL,
+            max_spectator INTEGER NOT NULL,
             password TEXT,
             access_type INTEGER NOT NULL,
             join_request INTEGER NOT NULL,
@@ -81,7 +190,7 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room_tag (
+        CREATE TABLE IF NOT EXISTS room_tag (
             id TEXT PRIMARY KEY,
             room_id TEXT NOT NULL,
             tag name TEXT,
@@ -91,7 +200,7 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room_user (
+        CREATE TABLE IF NOT EXISTS room_user (
             id TEXT PRIMARY KEY,
             room_id TEXT NOT NULL,
             user_id TEXT NOT NULL,
@@ -102,7 +211,7 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room_user_request (
+        CREATE TABLE IF NOT EXISTS room_user_request (
             id TEXT PRIMARY KEY,
             room_id TEXT NOT NULL,
             user_id TEXT NOT NULL,
@@ -115,27 +224,42 @@ pub fn create_database(connection: &mut PooledConnection) -> anyhow::Result<()>
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room_user_ban (
+        CREATE TABLE IF NOT EXISTS room_user_ban (
             id TEXT PRIMARY KEY,
             room_id TEXT NOT NULL,
             user_id TEXT NOT NULL,
             blocker_user_id TEXT,
-            insert_date INTEGER NOT NULL
+            reason TEXT,
+            insert_date INTEGER NOT NULL,
+            expire_date INTEGER
         );"#,
     )
     .execute(connection)?;
     sql_query(
         r#"
-        CREATE TABLE room_meta (
+        CREATE TABLE IF NOT EXISTS room_meta (
             id TEXT PRIMARY KEY,
             room_id TEXT NOT NULL,
             key TEXT NOT NULL,
             value TEXT NOT NULL,
             meta_type INTEGER NOT NULL,
             access INTEGER NOT NULL,
+            insert_date INTEGER NOT NULL,
+            expire_date INTEGER
+        );"#,
+    )
+    .execute(connection)?;
+    sql_query(
+        r#"
+        CREATE TABLE IF NOT EXISTS room_message (
+            id TEXT PRIMARY KEY,
+            room_id TEXT NOT NULL,
+            sequence INTEGER NOT NULL,
+            user_id TEXT,
+            message TEXT NOT NULL,
             insert_date INTEGER NOT NULL
         );"#,
     )
     .execute(connection)?;
-    Ok(())
+    migrate_database(connection)
 }
@@ -5,11 +5,13 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use diesel::RunQueryDsl;
 use diesel::QueryDsl;
 use diesel::ExpressionMethods;
+use diesel::BoolExpressionMethods;
 use yummy_model::meta::RoomMetaAccess;
 use yummy_model::meta::MetaType;
 use yummy_model::meta::collection::RoomMetaCollection;
-use yummy_model::user::{RoomUpdate, RoomInsert, RoomTagInsert, RoomUserInsert, RoomUserRequestInsert, RoomUserBanInsert, RoomMetaInsert, RoomMetaModel};
-use yummy_model::{RoomId, RoomUserRequestId, RoomUserBanId};
+use yummy_model::state::{RoomMessage, RoomBan};
+use yummy_model::user::{RoomUpdate, RoomInsert, RoomTagInsert, RoomUserInsert, RoomUserRequestInsert, RoomUserBanInsert, RoomMetaInsert, RoomMetaModel, RoomMessageInsert, RoomMessageModel};
+use yummy_model::{RoomId, RoomUserRequestId, RoomUserBanId, RoomMessageId};
 use yummy_model::RoomMetaId;
 use yummy_model::RoomTagId;
 use yummy_model::RoomUserId;
@@ -17,30 +19,39 @@ use yummy_model::UserId;
 use yummy_model::UserMetaId;
 use yummy_model::{CreateRoomAccessType, RoomUserType};
 
-use yummy_model::schema::{room, room_tag, room_meta, room_user, room_user_ban, room_user_request};
+use yummy_model::schema::{room, room_tag, room_meta, room_user, room_user_ban, room_user_request, room_message};
 use crate::{SqliteStore, PooledConnection};
 
 pub trait RoomStoreTrait: Sized {
-    fn create_room(connection: &mut PooledConnection, name: Option<String>, access_type: CreateRoomAccessType, max_user: usize, join_request: bool, tags: &[String]) -> anyhow::Result<RoomId>;
+    #[allow(clippy::too_many_arguments)]
+    fn create_room(connection: &mut PooledConnection, name: Option<String>, access_type: CreateRoomAccessType, max_user: usize, max_spectator: usize, join_request: bool, tags: &[String], password: Option<String>) -> anyhow::Result<RoomId>;
     fn join_to_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, user_type: RoomUserType) -> anyhow::Result<()>;
     fn join_to_room_request(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, user_type: RoomUserType) -> anyhow::Result<()>;
     fn update_join_to_room_request(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, updater_user_id: &UserId, status: bool) -> anyhow::Result<()>;
     fn disconnect_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId) -> anyhow::Result<()>;
+    fn delete_room(connection: &mut PooledConnection, room_id: &RoomId) -> anyhow::Result<()>;
     fn get_join_requested_users(connection: &mut PooledConnection, room_id: &RoomId) -> anyhow::Result<Vec<(UserId, RoomUserType, bool)>>;
     fn get_room_meta(connection: &mut PooledConnection, room_id: &RoomId, filter: RoomMetaAccess) -> anyhow::Result<RoomMetaCollection>;
     fn get_room_tag(connection: &mut PooledConnection, room_id: &RoomId) -> anyhow::Result<Vec<(RoomTagId, String)>>;
     fn remove_room_tags(connection: &mut PooledConnection, ids: Vec<RoomTagId>) -> anyhow::Result<()>;
     fn remove_room_metas(connection: &mut PooledConnection, ids: Vec<RoomMetaId>) -> anyhow::Result<()>;
+    fn remove_expired_room_metas(connection: &mut PooledConnection) -> anyhow::Result<usize>;
     fn insert_room_metas(connection: &mut PooledConnection, room_id: &RoomId, metas: &[(&String, &MetaType<RoomMetaAccess>)]) -> anyhow::Result<()>;
     fn insert_room_tags(connection: &mut PooledConnection, room_id: &RoomId, tags: &[String]) -> anyhow::Result<()>;
     fn update_room(connection: &mut PooledConnection, room_id: &RoomId, update_request: &RoomUpdate) -> anyhow::Result<usize>;
     fn update_room_user_permissions(connection: &mut PooledConnection, room_id: &RoomId, permissions: &HashMap<UserId, RoomUserType>) -> anyhow::Result<()>;
-    fn ban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, blocker_user_id: &UserId) -> anyhow::Result<()>;
+    fn ban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, blocker_user_id: &UserId, ban: &RoomBan) -> anyhow::Result<()>;
+    fn unban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId) -> anyhow::Result<usize>;
+    fn remove_expired_room_bans(connection: &mut PooledConnection) -> anyhow::Result<usize>;
+    fn insert_room_message(connection: &mut PooledConnection, room_id: &RoomId, message: &RoomMessage) -> anyhow::Result<()>;
+    fn get_room_messages(connection: &mut PooledConnection, room_id: &RoomId, before: Option<usize>, limit: usize) -> anyhow::Result<Vec<RoomMessage>>;
+    fn remove_old_room_messages(connection: &mut PooledConnection, insert_date: i32) -> anyhow::Result<usize>;
 }
 
 impl RoomStoreTrait for SqliteStore {
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(name="Create room", skip(connection))]
-    fn create_room(connection: &mut PooledConnection, name: Option<String>, access_type: CreateRoomAccessType, max_user: usize, join_request: bool, tags: &[String]) -> anyhow::Result<RoomId> {
+    fn create_room(connection: &mut PooledConnection, name: Option<String>, access_type: CreateRoomAccessType, max_user: usize, max_spectator: usize, join_request: bool, tags: &[String], password: Option<String>) -> anyhow::Result<RoomId> {
         let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
 
         let mut model = RoomInsert {
@@ -48,8 +59,10 @@ impl RoomStoreTrait for SqliteStore {
             access_type: 0,
             insert_date,
             name,
+            password,
             join_request: join_request as i32,
-            max_user: max_user as i32
+            max_user: max_user as i32,
+            max_spectator: max_spectator as i32
         };
         
         let access = match access_type {
@@ -112,7 +125,7 @@ impl RoomStoreTrait for SqliteStore {
     #[tracing::instrument(name="update room user permissions", skip(connection))]
     fn update_room_user_permissions(connection: &mut PooledConnection, room_id: &RoomId, permissions: &HashMap<UserId, RoomUserType>) -> anyhow::Result<()> {
         for (user_id, permission) in permissions {
-            diesel::update(room_user::table.filter(room_user::room_id.eq(room_id))).set((room_user::user_id.eq(user_id), room_user::room_user_type.eq(permission.clone() as i32))).execute(connection)?;
+            diesel::update(room_user::table.filter(room_user::room_id.eq(room_id)).filter(room_user::user_id.eq(user_id))).set(room_user::room_user_type.eq(permission.clone() as i32)).execute(connection)?;
         }
         Ok(())
     }
@@ -173,6 +186,7 @@ impl RoomStoreTrait for SqliteStore {
                 1 => RoomUserType::User,
                 2 => RoomUserType::Moderator,
                 3 => RoomUserType::Owner,
+                4 => RoomUserType::Spectator,
                 _ => RoomUserType::User,
             };
 
@@ -191,26 +205,45 @@ impl RoomStoreTrait for SqliteStore {
         Ok(())
     }
 
+    #[tracing::instrument(name="Delete room", skip(connection))]
+    fn delete_room(connection: &mut PooledConnection, room_id: &RoomId) -> anyhow::Result<()> {
+        diesel::delete(room_user::table.filter(room_user::room_id.eq(&room_id))).execute(connection)?;
+        diesel::delete(room_user_request::table.filter(room_user_request::room_id.eq(&room_id))).execute(connection)?;
+        diesel::delete(room_user_ban::table.filter(room_user_ban::room_id.eq(&room_id))).execute(connection)?;
+        diesel::delete(room_tag::table.filter(room_tag::room_id.eq(&room_id))).execute(connection)?;
+        diesel::delete(room_meta::table.filter(room_meta::room_id.eq(&room_id))).execute(connection)?;
+        diesel::delete(room_message::table.filter(room_message::room_id.eq(&room_id))).execute(connection)?;
+
+        let affected_rows = diesel::delete(room::table.filter(room::id.eq(&room_id))).execute(connection)?;
+        if affected_rows == 0 {
+            return Err(anyhow::anyhow!("No row removed"));
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(name="Get room meta", skip(connection))]
     fn get_room_meta(connection: &mut PooledConnection, room_id: &RoomId, filter: RoomMetaAccess) -> anyhow::Result<RoomMetaCollection> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
         let records: Vec<RoomMetaModel> = room_meta::table
-            .select((room_meta::id, room_meta::key, room_meta::value, room_meta::meta_type, room_meta::access))
+            .select((room_meta::id, room_meta::key, room_meta::value, room_meta::meta_type, room_meta::access, room_meta::expire_date))
             .filter(room_meta::room_id.eq(room_id))
             .filter(room_meta::access.le(i32::from(filter)))
+            .filter(room_meta::expire_date.is_null().or(room_meta::expire_date.gt(now)))
             .load::<RoomMetaModel>(connection)?;
 
         let mut results = RoomMetaCollection::new();
         for record in records.into_iter() {
-            let RoomMetaModel { id, key, value, meta_type, access } = record;
+            let RoomMetaModel { id, key, value, meta_type, access, expire_date } = record;
 
             let meta = match meta_type {
                 1 => MetaType::Number(value.parse::<f64>().unwrap_or_default(), access.into()),
                 2 => MetaType::String(value, access.into()),
                 3 => MetaType::Bool(value.parse::<bool>().unwrap_or_default(), access.into()),
                 4 => MetaType::List(Box::new(serde_json::from_str(&value[..]).unwrap_or_default()), access.into()),
+                5 => MetaType::Object(Box::new(serde_json::from_str(&value[..]).unwrap_or_default()), access.into()),
                 _ => MetaType::String("".to_string(), access.into()),
             };
-            results.add_with_id(id, key, meta);
+            results.add_with_id(id, key, meta.with_expire_date(expire_date));
         }
             
         Ok(results)
@@ -238,18 +271,25 @@ impl RoomStoreTrait for SqliteStore {
         Ok(())
     }
 
+    #[tracing::instrument(name="Remove expired metas", skip(connection))]
+    fn remove_expired_room_metas(connection: &mut PooledConnection) -> anyhow::Result<usize> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        Ok(diesel::delete(room_meta::table.filter(room_meta::expire_date.le(now))).execute(connection)?)
+    }
+
     #[tracing::instrument(name="Insert metas", skip(connection))]
     fn insert_room_metas(connection: &mut PooledConnection, room_id: &RoomId, metas: &[(&String, &MetaType<RoomMetaAccess>)]) -> anyhow::Result<()> {
         let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
         let mut inserts = Vec::new();
 
         for (key, meta) in metas.iter() {
-            let (value, access, meta_type) = match meta {
-                MetaType::Null => continue,
+            let (value, access, meta_type) = match meta.get_value() {
+                MetaType::Null | MetaType::Expiring(_, _) => continue,
                 MetaType::Number(value, access) => (value.to_string(), access, 1),
                 MetaType::String(value, access) => (value.clone(), access, 2),
                 MetaType::Bool(value, access) => (value.to_string(), access, 3),
                 MetaType::List(value, access) => (serde_json::to_string(value.deref()).unwrap_or_default(), access, 4),
+                MetaType::Object(value, access) => (serde_json::to_string(value.deref()).unwrap_or_default(), access, 5),
             };
 
             let insert = RoomMetaInsert {
@@ -259,7 +299,8 @@ impl RoomStoreTrait for SqliteStore {
                 value,
                 access: i32::from(*access),
                 meta_type,
-                insert_date
+                insert_date,
+                expire_date: meta.get_expire_date()
             };
 
             inserts.push(insert);
@@ -269,10 +310,12 @@ impl RoomStoreTrait for SqliteStore {
     }
 
     #[tracing::instrument(name="ban_from_room", skip(connection))]
-    fn ban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, blocker_user_id: &UserId) -> anyhow::Result<()> {
+    fn ban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId, blocker_user_id: &UserId, ban: &RoomBan) -> anyhow::Result<()> {
         let insert = RoomUserBanInsert {
             id: RoomUserBanId::default(),
-            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            insert_date: ban.insert_date,
+            reason: ban.reason.as_deref(),
+            expire_date: ban.expire_date,
             room_id,
             user_id,
             blocker_user_id
@@ -284,4 +327,65 @@ impl RoomStoreTrait for SqliteStore {
         }
         Ok(())
     }
+
+    #[tracing::instrument(name="unban_from_room", skip(connection))]
+    fn unban_user_from_room(connection: &mut PooledConnection, room_id: &RoomId, user_id: &UserId) -> anyhow::Result<usize> {
+        Ok(diesel::delete(room_user_ban::table.filter(room_user_ban::room_id.eq(room_id)).filter(room_user_ban::user_id.eq(user_id))).execute(connection)?)
+    }
+
+    #[tracing::instrument(name="remove_expired_room_bans", skip(connection))]
+    fn remove_expired_room_bans(connection: &mut PooledConnection) -> anyhow::Result<usize> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        Ok(diesel::delete(room_user_ban::table.filter(room_user_ban::expire_date.le(now))).execute(connection)?)
+    }
+
+    #[tracing::instrument(name="insert_room_message", skip(connection))]
+    fn insert_room_message(connection: &mut PooledConnection, room_id: &RoomId, message: &RoomMessage) -> anyhow::Result<()> {
+        let insert = RoomMessageInsert {
+            id: RoomMessageId::default(),
+            room_id,
+            sequence: message.id as i32,
+            user_id: message.user_id.as_ref(),
+            message: serde_json::to_string(&message.message)?,
+            insert_date: message.insert_date
+        };
+
+        let affected_rows = diesel::insert_into(room_message::table).values(&vec![insert]).execute(connection)?;
+        if affected_rows == 0 {
+            return Err(anyhow::anyhow!("No row inserted"));
+        }
+        Ok(())
+    }
+
+    /* Returns the last messages before the given message id, in the sending order */
+    #[tracing::instrument(name="get_room_messages", skip(connection))]
+    fn get_room_messages(connection: &mut PooledConnection, room_id: &RoomId, before: Option<usize>, limit: usize) -> anyhow::Result<Vec<RoomMessage>> {
+        let mut query = room_message::table
+            .select((room_message::sequence, room_message::user_id, room_message::message, room_message::insert_date))
+            .filter(room_message::room_id.eq(room_id))
+            .order(room_message::sequence.desc())
+            .limit(limit as i64)
+            .into_boxed();
+
+        if let Some(before) = before {
+            query = query.filter(room_message::sequence.lt(before as i32));
+        }
+
+        let mut messages = Vec::new();
+        for model in query.load::<RoomMessageModel>(connection)?.into_iter().rev() {
+            messages.push(RoomMessage {
+                id: model.sequence as usize,
+                user_id: model.user_id,
+                message: serde_json::from_str(&model.message)?,
+                insert_date: model.insert_date
+            });
+        }
+
+        Ok(messages)
+    }
+
+    #[tracing::instrument(name="remove_old_room_messages", skip(connection))]
+    fn remove_old_room_messages(connection: &mut PooledConnection, insert_date: i32) -> anyhow::Result<usize> {
+        Ok(diesel::delete(room_message::table.filter(room_message::insert_date.lt(insert_date))).execute(connection)?)
+    }
 }
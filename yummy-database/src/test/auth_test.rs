@@ -1,6 +1,12 @@
 use anyhow::Ok;
+use diesel::*;
+use yummy_model::schema::user_token;
+use yummy_model::password::{Password, PasswordVerification};
+
+use yummy_model::user::{UserTokenType, UserUpdate};
 
 use crate::auth::AuthStoreTrait;
+use crate::user::UserStoreTrait;
 use crate::{create_database, create_connection, PooledConnection};
 
 use crate::SqliteStore;
@@ -11,6 +17,48 @@ fn db_conection() -> anyhow::Result<PooledConnection> {
     Ok(connection)
 }
 
+/* Databases created before the new columns are migrated on start */
+#[test]
+fn migrate_old_database() -> anyhow::Result<()> {
+    let mut connection = create_connection(":memory:")?.get()?;
+    sql_query(
+        r#"CREATE TABLE user (
+            id TEXT PRIMARY KEY,
+            name TEXT UNIQUE,
+            device_id TEXT UNIQUE,
+            custom_id TEXT UNIQUE,
+            email TEXT UNIQUE,
+            password TEXT,
+            user_type INTEGER,
+            insert_date INTEGER NOT NULL,
+            last_login_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(&mut connection)?;
+    sql_query(
+        r#"CREATE TABLE user_meta (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            meta_type INTEGER NOT NULL,
+            access INTEGER NOT NULL,
+            insert_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(&mut connection)?;
+
+    create_database(&mut connection)?;
+    create_database(&mut connection)?;
+
+    let created_user_id = SqliteStore::create_user_via_email(&mut connection, "erhanbaris@gmail.com", &"erhan".into())?;
+    let result = SqliteStore::user_login_via_email(&mut connection, "erhanbaris@gmail.com")?.unwrap();
+    assert_eq!(created_user_id, result.user_id);
+    assert_eq!(SqliteStore::remove_expired_user_metas(&mut connection)?, 0);
+
+    Ok(())
+}
+
 /* email unit tests */
 #[test]
 fn create_user_via_email() -> anyhow::Result<()> {
@@ -29,7 +77,12 @@ fn login_via_email() -> anyhow::Result<()> {
 
     assert_eq!(created_user_id, result.user_id);
     assert!(result.name.is_none());
-    assert_eq!(result.password.unwrap_or_default().as_str(), "erhan");
+
+    let password = result.password.unwrap_or_default();
+    assert_ne!(password.as_str(), "erhan");
+    assert!(password.starts_with("$argon2id$"));
+    assert_eq!(Password::from("erhan".to_string()).verify(&password), PasswordVerification::Valid);
+    assert_eq!(Password::from("wrong".to_string()).verify(&password), PasswordVerification::Invalid);
 
     Ok(())
 }
@@ -105,4 +158,86 @@ fn failed_login_via_custom_id() -> anyhow::Result<()> {
 
     assert!(SqliteStore::user_login_via_custom_id(&mut connection, "1234567890")?.is_none());
     Ok(())
-}
\ No newline at end of file
+}
+
+/* identity unit tests */
+#[test]
+fn login_via_identity() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let created_user_id = SqliteStore::create_user_via_identity(&mut connection, "google", "1234567890")?;
+    let result = SqliteStore::user_login_via_identity(&mut connection, "google", "1234567890")?.unwrap();
+
+    assert_eq!(created_user_id, result.user_id);
+    assert!(result.name.is_none());
+    assert!(result.email.is_none());
+
+    // Same subject from a different provider is a different identity
+    assert!(SqliteStore::user_login_via_identity(&mut connection, "apple", "1234567890")?.is_none());
+    assert!(SqliteStore::create_user_via_identity(&mut connection, "google", "1234567890").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn failed_login_via_identity() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    assert!(SqliteStore::user_login_via_identity(&mut connection, "google", "1234567890")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn link_and_unlink_identity() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let user_id = SqliteStore::create_user_via_email(&mut connection, "erhanbaris@gmail.com", &"erhan".into())?;
+    assert_eq!(SqliteStore::count_user_login_methods(&mut connection, &user_id)?, 1);
+
+    SqliteStore::add_user_identity(&mut connection, &user_id, "google", "1234567890")?;
+    assert_eq!(SqliteStore::count_user_login_methods(&mut connection, &user_id)?, 2);
+    assert_eq!(SqliteStore::user_login_via_identity(&mut connection, "google", "1234567890")?.unwrap().user_id, user_id);
+
+    assert_eq!(SqliteStore::remove_user_email(&mut connection, &user_id)?, 1);
+    assert_eq!(SqliteStore::remove_user_email(&mut connection, &user_id)?, 0);
+    assert!(SqliteStore::user_login_via_email(&mut connection, "erhanbaris@gmail.com")?.is_none());
+    assert_eq!(SqliteStore::count_user_login_methods(&mut connection, &user_id)?, 1);
+
+    assert_eq!(SqliteStore::remove_user_identity(&mut connection, &user_id, "google")?, 1);
+    assert_eq!(SqliteStore::remove_user_identity(&mut connection, &user_id, "google")?, 0);
+    assert_eq!(SqliteStore::count_user_login_methods(&mut connection, &user_id)?, 0);
+
+    Ok(())
+}
+
+/* one-time token unit tests */
+#[test]
+fn user_token() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let user_id = SqliteStore::create_user_via_email(&mut connection, "erhanbaris@gmail.com", &"erhan".into())?;
+    assert!(!SqliteStore::user_login_via_email(&mut connection, "erhanbaris@gmail.com")?.unwrap().email_verified);
+
+    SqliteStore::create_user_token(&mut connection, &user_id, UserTokenType::EmailVerification, "token-1", i32::MAX)?;
+    SqliteStore::create_user_token(&mut connection, &user_id, UserTokenType::EmailVerification, "token-2", i32::MAX)?;
+
+    // Token is stored as hash, creation date is kept for the cooldown
+    assert_ne!(user_token::table.select(user_token::token).first::<String>(&mut connection)?, "token-2".to_string());
+    assert!(SqliteStore::get_user_token_date(&mut connection, &user_id, UserTokenType::EmailVerification)?.is_some());
+    assert!(SqliteStore::get_user_token_date(&mut connection, &user_id, UserTokenType::PasswordReset)?.is_none());
+
+    // Only the last token is valid and it could be used once
+    assert!(SqliteStore::consume_user_token(&mut connection, UserTokenType::EmailVerification, "token-1")?.is_none());
+    assert!(SqliteStore::consume_user_token(&mut connection, UserTokenType::PasswordReset, "token-2")?.is_none());
+    assert_eq!(SqliteStore::consume_user_token(&mut connection, UserTokenType::EmailVerification, "token-2")?, Some(user_id.clone()));
+    assert!(SqliteStore::consume_user_token(&mut connection, UserTokenType::EmailVerification, "token-2")?.is_none());
+
+    // Expired token
+    SqliteStore::create_user_token(&mut connection, &user_id, UserTokenType::PasswordReset, "token-3", 1)?;
+    assert!(SqliteStore::consume_user_token(&mut connection, UserTokenType::PasswordReset, "token-3")?.is_none());
+
+    SqliteStore::update_user(&mut connection, &user_id, &UserUpdate { email_verified: Some(1), ..Default::default() })?;
+    assert!(SqliteStore::user_login_via_email(&mut connection, "erhanbaris@gmail.com")?.unwrap().email_verified);
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Ok;
 use yummy_model::meta::{MetaType, RoomMetaAccess};
 use yummy_model::*;
@@ -12,18 +14,75 @@ fn db_conection() -> anyhow::Result<PooledConnection> {
     Ok(connection)
 }
 
+/* Databases created before the new columns are migrated on start */
+#[test]
+fn migrate_old_room_tables() -> anyhow::Result<()> {
+    use diesel::{sql_query, RunQueryDsl};
+
+    let mut connection = create_connection(":memory:")?.get()?;
+    sql_query(
+        r#"CREATE TABLE room_meta (
+            id TEXT PRIMARY KEY,
+            room_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            meta_type INTEGER NOT NULL,
+            access INTEGER NOT NULL,
+            insert_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(&mut connection)?;
+    sql_query(
+        r#"CREATE TABLE room (
+            id TEXT PRIMARY KEY,
+            name TEXT,
+            description TEXT,
+            max_user INTEGER NOT NULL,
+            password TEXT,
+            access_type INTEGER NOT NULL,
+            join_request INTEGER NOT NULL,
+            insert_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(&mut connection)?;
+    sql_query(
+        r#"CREATE TABLE room_user_ban (
+            id TEXT PRIMARY KEY,
+            room_id TEXT NOT NULL,
+            user_id TEXT NOT NULL,
+            blocker_user_id TEXT,
+            insert_date INTEGER NOT NULL
+        );"#,
+    )
+    .execute(&mut connection)?;
+
+    create_database(&mut connection)?;
+    create_database(&mut connection)?;
+
+    assert_eq!(SqliteStore::remove_expired_room_metas(&mut connection)?, 0);
+    let room_id = SqliteStore::create_room(&mut connection, Some("room".to_string()), CreateRoomAccessType::Public, 2, 1, false, &[], None)?;
+
+    SqliteStore::ban_user_from_room(&mut connection, &room_id, &UserId::new(), &UserId::default(), &yummy_model::state::RoomBan {
+        reason: Some("Spam".to_string()),
+        insert_date: 1,
+        expire_date: Some(100)
+    })?;
+    assert_eq!(SqliteStore::remove_expired_room_bans(&mut connection)?, 1);
+    Ok(())
+}
+
 #[test]
 fn create_room_1() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
-    SqliteStore::create_room(&mut connection, Some("room 1".to_string()), CreateRoomAccessType::Public, 2, false,  &vec!["tag 1".to_string(), "tag 2".to_string()])?;
+    SqliteStore::create_room(&mut connection, Some("room 1".to_string()), CreateRoomAccessType::Public, 2, 0, false,  &vec!["tag 1".to_string(), "tag 2".to_string()], None)?;
     Ok(())
 }
 
 #[test]
 fn create_room_2() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
-    let room_1 = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Public, 2, false, &Vec::new())?;
-    let room_2 = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Public, 20, false, &Vec::new())?;
+    let room_1 = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Public, 2, 0, false, &Vec::new(), None)?;
+    let room_2 = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Public, 20, 0, false, &Vec::new(), None)?;
 
     assert_ne!(room_1, room_2);
     Ok(())
@@ -32,7 +91,7 @@ fn create_room_2() -> anyhow::Result<()> {
 #[test]
 fn join_to_room() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
-    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, false, &Vec::new())?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, 0, false, &Vec::new(), None)?;
     SqliteStore::join_to_room(&mut connection, &room, &UserId::default(), RoomUserType::User)?;
     Ok(())
 }
@@ -40,7 +99,7 @@ fn join_to_room() -> anyhow::Result<()> {
 #[test]
 fn join_to_room_request() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
-    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, false, &Vec::new())?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, 0, false, &Vec::new(), None)?;
 
     let user_1 = UserId::new();
     let user_2 = UserId::new();
@@ -61,7 +120,7 @@ fn join_to_room_request() -> anyhow::Result<()> {
 #[test]
 fn disconnect_from_room() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
-    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, false, &Vec::new())?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, 0, false, &Vec::new(), None)?;
     let user = UserId::default();
     
     assert!(SqliteStore::disconnect_from_room(&mut connection, &room, &user).is_err());
@@ -72,11 +131,27 @@ fn disconnect_from_room() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn delete_room() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, 0, false, &["tag".to_string()], None)?;
+    let user = UserId::default();
+
+    SqliteStore::join_to_room(&mut connection, &room, &user, RoomUserType::Owner)?;
+    SqliteStore::delete_room(&mut connection, &room)?;
+
+    assert!(SqliteStore::get_room_tag(&mut connection, &room)?.is_empty());
+    assert!(SqliteStore::disconnect_from_room(&mut connection, &room, &user).is_err());
+    assert!(SqliteStore::delete_room(&mut connection, &room).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn meta() -> anyhow::Result<()> {
     let mut connection = db_conection()?;
 
-    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, false, &Vec::new())?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, 0, false, &Vec::new(), None)?;
     
     // New meta
     SqliteStore::insert_room_metas(&mut connection, &room, &vec![
@@ -121,12 +196,150 @@ fn meta() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn object_meta() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, 0, false, &Vec::new(), None)?;
+    let position = MetaType::Object(Box::new(HashMap::from([
+        ("x".to_string(), MetaType::Number(1.0, RoomMetaAccess::Anonymous)),
+        ("y".to_string(), MetaType::Number(2.0, RoomMetaAccess::Anonymous)),
+        ("tags".to_string(), MetaType::List(Box::new(vec![MetaType::String("start".to_string(), RoomMetaAccess::Anonymous)]), RoomMetaAccess::Anonymous)),
+    ])), RoomMetaAccess::Owner);
+
+    SqliteStore::insert_room_metas(&mut connection, &room, &[(&"position".to_string(), &position)])?;
+
+    let meta = SqliteStore::get_room_meta(&mut connection, &room, RoomMetaAccess::Owner)?;
+    assert_eq!(meta.len(), 1);
+    assert_eq!(meta[0].meta, position);
+    assert_eq!(SqliteStore::get_room_meta(&mut connection, &room, RoomMetaAccess::User)?.len(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn expiring_meta() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, 0, false, &Vec::new(), None)?;
+    let boost = MetaType::Expiring(Box::new(MetaType::Number(2.0, RoomMetaAccess::User)), i32::MAX);
+    let cooldown = MetaType::Expiring(Box::new(MetaType::Bool(true, RoomMetaAccess::User)), 1);
+
+    SqliteStore::insert_room_metas(&mut connection, &room, &[(&"boost".to_string(), &boost), (&"cooldown".to_string(), &cooldown)])?;
+
+    // Expired metas are hidden
+    let meta = SqliteStore::get_room_meta(&mut connection, &room, RoomMetaAccess::System)?;
+    assert_eq!(meta.len(), 1);
+    assert_eq!(meta[0].name, "boost");
+    assert_eq!(meta[0].meta, boost);
+
+    assert_eq!(SqliteStore::remove_expired_room_metas(&mut connection)?, 1);
+    assert_eq!(SqliteStore::remove_expired_room_metas(&mut connection)?, 0);
+    assert_eq!(SqliteStore::get_room_meta(&mut connection, &room, RoomMetaAccess::System)?.len(), 1);
+
+    Ok(())
+}
+
 #[test]
 fn ban_user() -> anyhow::Result<()> {
+    use yummy_model::state::RoomBan;
+
+    let mut connection = db_conection()?;
+
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, 0, false, &Vec::new(), None)?;
+    let user_id = UserId::default();
+
+    SqliteStore::ban_user_from_room(&mut connection, &room, &user_id, &UserId::default(), &RoomBan {
+        reason: Some("Spam".to_string()),
+        insert_date: 1,
+        expire_date: Some(100)
+    })?;
+
+    assert_eq!(SqliteStore::unban_user_from_room(&mut connection, &room, &user_id)?, 1);
+    assert_eq!(SqliteStore::unban_user_from_room(&mut connection, &room, &user_id)?, 0);
+
+    /* Only the expired bans are removed, permanent ones are kept */
+    for expire_date in [Some(100), None] {
+        SqliteStore::ban_user_from_room(&mut connection, &room, &UserId::new(), &UserId::default(), &RoomBan {
+            reason: None,
+            insert_date: 1,
+            expire_date
+        })?;
+    }
+
+    assert_eq!(SqliteStore::remove_expired_room_bans(&mut connection)?, 1);
+    assert_eq!(SqliteStore::remove_expired_room_bans(&mut connection)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn update_room_user_permissions() -> anyhow::Result<()> {
+    use std::collections::HashMap;
+    use diesel::{QueryDsl, RunQueryDsl, ExpressionMethods};
+    use yummy_model::schema::room_user;
+
     let mut connection = db_conection()?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Private, 2, 0, false, &Vec::new(), None)?;
+
+    let user_1 = UserId::new();
+    let user_2 = UserId::new();
+
+    SqliteStore::join_to_room(&mut connection, &room, &user_1, RoomUserType::Owner)?;
+    SqliteStore::join_to_room(&mut connection, &room, &user_2, RoomUserType::User)?;
+    SqliteStore::update_room_user_permissions(&mut connection, &room, &HashMap::from([(user_2.clone(), RoomUserType::Moderator)]))?;
+
+    let user_type = |connection: &mut PooledConnection, user_id: &UserId| room_user::table
+        .filter(room_user::room_id.eq(&room))
+        .filter(room_user::user_id.eq(user_id))
+        .select(room_user::room_user_type)
+        .first::<i32>(connection);
+
+    // Other users should not be affected
+    assert_eq!(user_type(&mut connection, &user_1)?, RoomUserType::Owner as i32);
+    assert_eq!(user_type(&mut connection, &user_2)?, RoomUserType::Moderator as i32);
+
+    Ok(())
+}
+
+#[test]
+fn room_messages() -> anyhow::Result<()> {
+    use serde_json::json;
+    use yummy_model::state::RoomMessage;
+
+    let mut connection = db_conection()?;
+    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Public, 2, 0, false, &Vec::new(), None)?;
+    let user_id = UserId::new();
+
+    assert!(SqliteStore::get_room_messages(&mut connection, &room, None, 10)?.is_empty());
+
+    for id in 1..=5 {
+        SqliteStore::insert_room_message(&mut connection, &room, &RoomMessage {
+            id,
+            user_id: Some(user_id.clone()),
+            message: json!({ "index": id }),
+            insert_date: id as i32 * 100
+        })?;
+    }
+
+    SqliteStore::insert_room_message(&mut connection, &room, &RoomMessage { id: 6, user_id: None, message: json!("system"), insert_date: 600 })?;
+
+    // Last messages in the sending order
+    let messages = SqliteStore::get_room_messages(&mut connection, &room, None, 3)?;
+    assert_eq!(messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![4, 5, 6]);
+    assert_eq!(messages[0].user_id, Some(user_id.clone()));
+    assert_eq!(messages[0].message, json!({ "index": 4 }));
+    assert_eq!(messages[2].user_id, None);
+
+    // Previous page
+    let messages = SqliteStore::get_room_messages(&mut connection, &room, Some(4), 10)?;
+    assert_eq!(messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    // Other rooms are not affected
+    assert!(SqliteStore::get_room_messages(&mut connection, &RoomId::new(), None, 10)?.is_empty());
 
-    let room = SqliteStore::create_room(&mut connection, None, CreateRoomAccessType::Friend, 2, false, &Vec::new())?;
-    SqliteStore::ban_user_from_room(&mut connection, &room, &UserId::default(), &UserId::default())?;
+    assert_eq!(SqliteStore::remove_old_room_messages(&mut connection, 300)?, 2);
+    assert_eq!(SqliteStore::get_room_messages(&mut connection, &room, None, 10)?.len(), 4);
 
     Ok(())
 }
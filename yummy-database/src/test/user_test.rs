@@ -189,5 +189,167 @@ fn meta() -> anyhow::Result<()> {
         ("location".to_string(), MetaType::String("copenhagen".to_string(), UserMetaAccess::Anonymous)),
         ("score".to_string(), MetaType::Number(123.0, UserMetaAccess::Friend))]);
 
+    // Expired metas are hidden and removed by the sweeper
+    SqliteStore::insert_user_metas(&mut connection, &user_id, vec![
+        (&"boost".to_string(), &MetaType::Expiring(Box::new(MetaType::Number(2.0, UserMetaAccess::Friend)), i32::MAX)),
+        (&"cooldown".to_string(), &MetaType::Expiring(Box::new(MetaType::Bool(true, UserMetaAccess::Friend)), 1))])?;
+
+    let meta = SqliteStore::get_user_meta(&mut connection, &user_id, UserMetaAccess::System)?;
+    assert_eq!(meta.len(), 3);
+    assert!(meta.iter().any(|item| item.name == "boost" && item.meta == MetaType::Expiring(Box::new(MetaType::Number(2.0, UserMetaAccess::Friend)), i32::MAX)));
+
+    assert_eq!(SqliteStore::remove_expired_user_metas(&mut connection)?, 1);
+    assert_eq!(SqliteStore::get_user_meta(&mut connection, &user_id, UserMetaAccess::System)?.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn friend() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let user_1 = SqliteStore::create_user_via_custom_id(&mut connection, "user1")?;
+    let user_2 = SqliteStore::create_user_via_custom_id(&mut connection, "user2")?;
+
+    assert!(!SqliteStore::is_friend_requested(&mut connection, &user_1, &user_2)?);
+    assert!(SqliteStore::get_friend_requests(&mut connection, &user_2)?.is_empty());
+
+    // Send request
+    assert!(SqliteStore::create_friend_request(&mut connection, &user_1, &user_2)?);
+    assert!(!SqliteStore::create_friend_request(&mut connection, &user_1, &user_2)?);
+    assert!(SqliteStore::is_friend_requested(&mut connection, &user_1, &user_2)?);
+    assert!(!SqliteStore::is_friend_requested(&mut connection, &user_2, &user_1)?);
+    assert_eq!(SqliteStore::get_friend_requests(&mut connection, &user_2)?, vec![user_1.clone()]);
+    assert!(SqliteStore::get_friend_requests(&mut connection, &user_1)?.is_empty());
+
+    // Accept request
+    assert_eq!(SqliteStore::remove_friend_request(&mut connection, &user_1, &user_2)?, 1);
+    assert!(SqliteStore::add_friend(&mut connection, &user_2, &user_1)?);
+    assert!(!SqliteStore::add_friend(&mut connection, &user_1, &user_2)?);
+    assert!(SqliteStore::get_friend_requests(&mut connection, &user_2)?.is_empty());
+    assert_eq!(SqliteStore::get_friends(&mut connection, &user_1)?, vec![user_2.clone()]);
+    assert_eq!(SqliteStore::get_friends(&mut connection, &user_2)?, vec![user_1.clone()]);
+
+    // Remove friend
+    assert_eq!(SqliteStore::remove_friend(&mut connection, &user_1, &user_2)?, 2);
+    assert!(SqliteStore::get_friends(&mut connection, &user_1)?.is_empty());
+    assert!(SqliteStore::get_friends(&mut connection, &user_2)?.is_empty());
+    assert_eq!(SqliteStore::remove_friend(&mut connection, &user_1, &user_2)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn inbox() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let user_1 = SqliteStore::create_user_via_custom_id(&mut connection, "user1")?;
+    let user_2 = SqliteStore::create_user_via_custom_id(&mut connection, "user2")?;
+
+    assert!(SqliteStore::get_inbox_messages(&mut connection, &user_2, None, None)?.is_empty());
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &user_2, None)?, 0);
+
+    SqliteStore::insert_inbox_message(&mut connection, &user_2, &user_1, "\"hello\"".to_string())?;
+    SqliteStore::insert_inbox_message(&mut connection, &user_2, &user_1, "\"world\"".to_string())?;
+    SqliteStore::insert_inbox_message(&mut connection, &user_2, &user_1, "\"yummy\"".to_string())?;
+
+    assert!(SqliteStore::get_inbox_messages(&mut connection, &user_1, None, None)?.is_empty());
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &user_2, None)?, 3);
+
+    let messages = SqliteStore::get_inbox_messages(&mut connection, &user_2, None, Some(2))?;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].sender_user_id, user_1);
+
+    // Reading does not remove the messages, next page starts after the cursor
+    let last = messages.last().unwrap();
+    let next_page = SqliteStore::get_inbox_messages(&mut connection, &user_2, Some((last.insert_date, &last.id)), None)?;
+    assert_eq!(next_page.len(), 1);
+    assert!(messages.iter().all(|message| message.id != next_page[0].id));
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &user_2, Some((last.insert_date, &last.id)))?, 1);
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &user_2, None)?, 3);
+
+    // Only the owner can remove the messages
+    assert_eq!(SqliteStore::remove_inbox_messages(&mut connection, &user_1, messages.iter().map(|message| message.id.clone()).collect())?, 0);
+    assert_eq!(SqliteStore::remove_inbox_messages(&mut connection, &user_2, messages.into_iter().map(|message| message.id).collect())?, 2);
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &user_2, None)?, 1);
+    assert_eq!(SqliteStore::get_inbox_messages(&mut connection, &user_2, None, None)?.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn suspension() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let admin = SqliteStore::create_user_via_custom_id(&mut connection, "admin")?;
+    let user_1 = SqliteStore::create_user_via_custom_id(&mut connection, "user1")?;
+    let user_2 = SqliteStore::create_user_via_custom_id(&mut connection, "user2")?;
+
+    assert!(SqliteStore::get_user_suspension(&mut connection, &user_1)?.is_none());
+    assert!(SqliteStore::get_suspended_users(&mut connection)?.is_empty());
+
+    // Permanent suspension
+    SqliteStore::suspend_user(&mut connection, &user_1, &admin, Some("cheating"), None)?;
+    let suspension = SqliteStore::get_user_suspension(&mut connection, &user_1)?.unwrap();
+    assert_eq!(suspension.suspender_user_id, admin);
+    assert_eq!(suspension.reason, Some("cheating".to_string()));
+    assert_eq!(suspension.expire_date, None);
+
+    // Suspending again replaces the old record
+    SqliteStore::suspend_user(&mut connection, &user_1, &admin, None, Some(i32::MAX))?;
+    let suspension = SqliteStore::get_user_suspension(&mut connection, &user_1)?.unwrap();
+    assert_eq!(suspension.reason, None);
+    assert_eq!(suspension.expire_date, Some(i32::MAX));
+
+    // Expired suspension is not active
+    SqliteStore::suspend_user(&mut connection, &user_2, &admin, None, Some(1))?;
+    assert!(SqliteStore::get_user_suspension(&mut connection, &user_2)?.is_none());
+
+    let users = SqliteStore::get_suspended_users(&mut connection)?;
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].user_id, user_1);
+
+    assert_eq!(SqliteStore::unsuspend_user(&mut connection, &user_1)?, 1);
+    assert!(SqliteStore::get_user_suspension(&mut connection, &user_1)?.is_none());
+    assert_eq!(SqliteStore::unsuspend_user(&mut connection, &user_1)?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn merge() -> anyhow::Result<()> {
+    let mut connection = db_conection()?;
+
+    let primary = SqliteStore::create_user_via_device_id(&mut connection, "device")?;
+    let secondary = SqliteStore::create_user_via_email(&mut connection, "erhanbaris@gmail.com", &"erhan".into())?;
+    let friend = SqliteStore::create_user_via_custom_id(&mut connection, "friend")?;
+
+    SqliteStore::insert_user_metas(&mut connection, &primary, vec![(&"score".to_string(), &MetaType::Number(1.0, UserMetaAccess::Friend))])?;
+    SqliteStore::insert_user_metas(&mut connection, &secondary, vec![
+        (&"score".to_string(), &MetaType::Number(2.0, UserMetaAccess::Friend)),
+        (&"location".to_string(), &MetaType::String("copenhagen".to_string(), UserMetaAccess::Anonymous))])?;
+
+    SqliteStore::add_friend(&mut connection, &secondary, &friend)?;
+    SqliteStore::add_friend(&mut connection, &secondary, &primary)?;
+    SqliteStore::insert_inbox_message(&mut connection, &secondary, &friend, "\"hello\"".to_string())?;
+
+    SqliteStore::merge_users(&mut connection, &primary, &secondary)?;
+
+    // Primary user's metas are kept
+    let meta = SqliteStore::get_user_meta(&mut connection, &primary, UserMetaAccess::System)?;
+    assert_eq!(meta.into_iter().map(|item| (item.name, item.meta)).collect::<Vec<(String, MetaType<UserMetaAccess>)>>(), vec![
+        ("score".to_string(), MetaType::Number(1.0, UserMetaAccess::Friend)),
+        ("location".to_string(), MetaType::String("copenhagen".to_string(), UserMetaAccess::Anonymous))]);
+
+    assert_eq!(SqliteStore::get_friends(&mut connection, &primary)?, vec![friend.clone()]);
+    assert_eq!(SqliteStore::get_friends(&mut connection, &friend)?, vec![primary.clone()]);
+    assert_eq!(SqliteStore::count_inbox_messages(&mut connection, &primary, None)?, 1);
+
+    // Secondary user removed and email moved
+    assert!(SqliteStore::get_user_information(&mut connection, &secondary, UserMetaAccess::System)?.is_none());
+    assert_eq!(SqliteStore::user_login_via_email(&mut connection, "erhanbaris@gmail.com")?.unwrap().user_id, primary);
+    assert_eq!(SqliteStore::user_login_via_device_id(&mut connection, "device")?.unwrap().user_id, primary);
+    assert_eq!(SqliteStore::count_user_login_methods(&mut connection, &primary)?, 2);
+
     Ok(())
 }
@@ -11,21 +11,43 @@ use std::time::UNIX_EPOCH;
 use diesel::QueryDsl;
 use diesel::ExpressionMethods;
 use diesel::RunQueryDsl;
+use diesel::BoolExpressionMethods;
 use diesel::result::OptionalExtension;
 use yummy_model::meta::MetaType;
 use yummy_model::meta::UserMetaAccess;
+use yummy_model::RoomId;
+use yummy_model::UserFriendId;
+use yummy_model::UserFriendRequestId;
 use yummy_model::UserId;
+use yummy_model::UserInboxId;
 use yummy_model::UserInformationModel;
 use yummy_model::UserMetaId;
+use yummy_model::UserSuspensionId;
 use yummy_model::UserType;
 use yummy_model::meta::collection::UserMetaCollection;
+use yummy_model::user::UserFriendInsert;
+use yummy_model::user::UserFriendRequestInsert;
+use yummy_model::user::UserInboxInsert;
+use yummy_model::user::UserInboxModel;
 use yummy_model::user::UserMetaInsert;
 use yummy_model::user::UserMetaModel;
+use yummy_model::user::UserSuspensionInsert;
+use yummy_model::user::UserSuspensionModel;
 use yummy_model::user::UserUpdate;
 
 use crate::SqliteStore;
 use yummy_model::schema::user_meta;
+use yummy_model::schema::user_friend;
+use yummy_model::schema::user_friend_request;
+use yummy_model::schema::user_inbox;
+use yummy_model::schema::user_suspension;
 use yummy_model::schema::user;
+use yummy_model::schema::user_identity;
+use yummy_model::schema::user_token;
+use yummy_model::schema::room_user;
+use yummy_model::schema::room_user_request;
+use yummy_model::schema::room_user_ban;
+use yummy_model::schema::room_message;
 use crate::PooledConnection;
 
 /* **************************************************************************************************************** */
@@ -40,10 +62,27 @@ pub trait UserStoreTrait: Sized {
     fn update_user(connection: &mut PooledConnection, user_id: &UserId, update_request: &UserUpdate) -> anyhow::Result<usize>;
     fn get_user_meta(connection: &mut PooledConnection, user_id: &UserId, filter: UserMetaAccess) -> anyhow::Result<UserMetaCollection>;
     fn remove_user_metas(connection: &mut PooledConnection, meta_ids: Vec<UserMetaId>) -> anyhow::Result<()>;
+    fn remove_expired_user_metas(connection: &mut PooledConnection) -> anyhow::Result<usize>;
     fn insert_user_metas<'a>(connection: &mut PooledConnection, user_id: &UserId, metas: Vec<(&'a String, &'a MetaType<UserMetaAccess>)>) -> anyhow::Result<()>;
     fn get_user_information(connection: &mut PooledConnection, user_id: &UserId, access_type: UserMetaAccess) -> anyhow::Result<Option<UserInformationModel>>;
     fn set_user_type(connection: &mut PooledConnection, user_id: &UserId, user_type: UserType) -> anyhow::Result<()>;
     fn get_user_type(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<UserType>;
+    fn create_friend_request(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<bool>;
+    fn is_friend_requested(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<bool>;
+    fn get_friend_requests(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Vec<UserId>>;
+    fn remove_friend_request(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<usize>;
+    fn add_friend(connection: &mut PooledConnection, user_id: &UserId, friend_user_id: &UserId) -> anyhow::Result<bool>;
+    fn remove_friend(connection: &mut PooledConnection, user_id: &UserId, friend_user_id: &UserId) -> anyhow::Result<usize>;
+    fn get_friends(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Vec<UserId>>;
+    fn insert_inbox_message(connection: &mut PooledConnection, user_id: &UserId, sender_user_id: &UserId, message: String) -> anyhow::Result<()>;
+    fn get_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, after: Option<(i32, &UserInboxId)>, limit: Option<usize>) -> anyhow::Result<Vec<UserInboxModel>>;
+    fn count_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, after: Option<(i32, &UserInboxId)>) -> anyhow::Result<usize>;
+    fn remove_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, ids: Vec<UserInboxId>) -> anyhow::Result<usize>;
+    fn suspend_user(connection: &mut PooledConnection, user_id: &UserId, suspender_user_id: &UserId, reason: Option<&str>, expire_date: Option<i32>) -> anyhow::Result<()>;
+    fn unsuspend_user(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize>;
+    fn get_user_suspension(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Option<UserSuspensionModel>>;
+    fn get_suspended_users(connection: &mut PooledConnection) -> anyhow::Result<Vec<UserSuspensionModel>>;
+    fn merge_users(connection: &mut PooledConnection, primary_user_id: &UserId, secondary_user_id: &UserId) -> anyhow::Result<()>;
 }
 
 /* **************************************************************************************************************** */
@@ -79,26 +118,29 @@ impl UserStoreTrait for SqliteStore {
 
     #[tracing::instrument(name="Get user meta", skip(connection))]
     fn get_user_meta(connection: &mut PooledConnection, user_id: &UserId, filter: UserMetaAccess) -> anyhow::Result<UserMetaCollection> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
         let records: Vec<UserMetaModel> = user_meta::table
-            .select((user_meta::id, user_meta::key, user_meta::value, user_meta::meta_type, user_meta::access))
+            .select((user_meta::id, user_meta::key, user_meta::value, user_meta::meta_type, user_meta::access, user_meta::expire_date))
             .filter(user_meta::user_id.eq(user_id))
             .filter(user_meta::access.le(i32::from(filter)))
+            .filter(user_meta::expire_date.is_null().or(user_meta::expire_date.gt(now)))
             .load::<UserMetaModel>(connection)?;
 
         let mut results = UserMetaCollection::new();
 
         for record in records.into_iter() {
-            let UserMetaModel { id, key, value, meta_type, access } = record;
+            let UserMetaModel { id, key, value, meta_type, access, expire_date } = record;
 
             let meta = match meta_type {
                 1 => MetaType::Number(value.parse::<f64>().unwrap_or_default(), access.into()),
                 2 => MetaType::String(value, access.into()),
                 3 => MetaType::Bool(value.parse::<bool>().unwrap_or_default(), access.into()),
                 4 => MetaType::List(Box::new(serde_json::from_str(&value[..]).unwrap_or_default()), access.into()),
+                5 => MetaType::Object(Box::new(serde_json::from_str(&value[..]).unwrap_or_default()), access.into()),
                 _ => MetaType::String("".to_string(), access.into()),
             };
 
-            results.add_with_id(id, key, meta);
+            results.add_with_id(id, key, meta.with_expire_date(expire_date));
         }
             
         Ok(results)
@@ -121,6 +163,12 @@ impl UserStoreTrait for SqliteStore {
         Ok(())
     }
 
+    #[tracing::instrument(name="Remove expired metas", skip(connection))]
+    fn remove_expired_user_metas(connection: &mut PooledConnection) -> anyhow::Result<usize> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        Ok(diesel::delete(user_meta::table.filter(user_meta::expire_date.le(now))).execute(connection)?)
+    }
+
     #[tracing::instrument(name="Insert metas", skip(connection))]
     fn insert_user_metas<'a>(connection: &mut PooledConnection, user_id: &UserId, metas: Vec<(&'a String, &'a MetaType<UserMetaAccess>)>) -> anyhow::Result<()> {
         let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
@@ -128,12 +176,13 @@ impl UserStoreTrait for SqliteStore {
 
         for (key, meta) in metas.into_iter() {
             let id = UserMetaId::default();
-            let (value, access, meta_type) = match meta {
-                MetaType::Null => continue,
+            let (value, access, meta_type) = match meta.get_value() {
+                MetaType::Null | MetaType::Expiring(_, _) => continue,
                 MetaType::Number(value, access) => (Cow::Owned(value.to_string()), access, 1),
                 MetaType::String(value, access) => (Cow::Borrowed(value), access, 2),
                 MetaType::Bool(value, access) => (Cow::Owned(value.to_string()), access, 3),
                 MetaType::List(value, access) => (Cow::Owned(serde_json::to_string(value.deref()).unwrap_or_default()), access, 4),
+                MetaType::Object(value, access) => (Cow::Owned(serde_json::to_string(value.deref()).unwrap_or_default()), access, 5),
             };
 
             let insert = UserMetaInsert {
@@ -143,7 +192,8 @@ impl UserStoreTrait for SqliteStore {
                 value,
                 access: access.clone().into(),
                 meta_type,
-                insert_date
+                insert_date,
+                expire_date: meta.get_expire_date()
             };
 
             inserts.push(insert);
@@ -157,6 +207,264 @@ impl UserStoreTrait for SqliteStore {
         diesel::update(user::table.filter(user::id.eq(user_id.borrow()))).set(user::user_type.eq::<i32>(user_type.into())).execute(connection)?;
         Ok(())
     }
+
+    #[tracing::instrument(name="Create friend request", skip(connection))]
+    /* Returns false if the request already exists */
+    fn create_friend_request(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<bool> {
+        let insert = UserFriendRequestInsert {
+            id: UserFriendRequestId::default(),
+            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            user_id,
+            target_user_id
+        };
+
+        let affected_rows = diesel::insert_or_ignore_into(user_friend_request::table).values(&vec![insert]).execute(connection)?;
+        Ok(affected_rows > 0)
+    }
+
+    #[tracing::instrument(name="Is friend requested", skip(connection))]
+    fn is_friend_requested(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<bool> {
+        Ok(user_friend_request::table
+            .select(user_friend_request::id)
+            .filter(user_friend_request::user_id.eq(user_id))
+            .filter(user_friend_request::target_user_id.eq(target_user_id))
+            .first::<UserFriendRequestId>(connection)
+            .optional()?
+            .is_some())
+    }
+
+    #[tracing::instrument(name="Get friend requests", skip(connection))]
+    fn get_friend_requests(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Vec<UserId>> {
+        Ok(user_friend_request::table
+            .select(user_friend_request::user_id)
+            .filter(user_friend_request::target_user_id.eq(user_id))
+            .order(user_friend_request::insert_date.asc())
+            .load::<UserId>(connection)?)
+    }
+
+    #[tracing::instrument(name="Remove friend request", skip(connection))]
+    fn remove_friend_request(connection: &mut PooledConnection, user_id: &UserId, target_user_id: &UserId) -> anyhow::Result<usize> {
+        Ok(diesel::delete(user_friend_request::table.filter(user_friend_request::user_id.eq(user_id)).filter(user_friend_request::target_user_id.eq(target_user_id))).execute(connection)?)
+    }
+
+    #[tracing::instrument(name="Add friend", skip(connection))]
+    /* Returns false if both users are already friends */
+    fn add_friend(connection: &mut PooledConnection, user_id: &UserId, friend_user_id: &UserId) -> anyhow::Result<bool> {
+        let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        /* Friendship is symmetric, keep one row for each side */
+        let inserts = vec![
+            UserFriendInsert { id: UserFriendId::default(), user_id, friend_user_id, insert_date },
+            UserFriendInsert { id: UserFriendId::default(), user_id: friend_user_id, friend_user_id: user_id, insert_date }
+        ];
+
+        let affected_rows = diesel::insert_or_ignore_into(user_friend::table).values(&inserts).execute(connection)?;
+        Ok(affected_rows > 0)
+    }
+
+    #[tracing::instrument(name="Remove friend", skip(connection))]
+    fn remove_friend(connection: &mut PooledConnection, user_id: &UserId, friend_user_id: &UserId) -> anyhow::Result<usize> {
+        let mut affected_rows = diesel::delete(user_friend::table.filter(user_friend::user_id.eq(user_id)).filter(user_friend::friend_user_id.eq(friend_user_id))).execute(connection)?;
+        affected_rows += diesel::delete(user_friend::table.filter(user_friend::user_id.eq(friend_user_id)).filter(user_friend::friend_user_id.eq(user_id))).execute(connection)?;
+        Ok(affected_rows)
+    }
+
+    #[tracing::instrument(name="Get friends", skip(connection))]
+    fn get_friends(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Vec<UserId>> {
+        Ok(user_friend::table
+            .select(user_friend::friend_user_id)
+            .filter(user_friend::user_id.eq(user_id))
+            .order(user_friend::insert_date.asc())
+            .load::<UserId>(connection)?)
+    }
+
+    #[tracing::instrument(name="Insert inbox message", skip(connection))]
+    fn insert_inbox_message(connection: &mut PooledConnection, user_id: &UserId, sender_user_id: &UserId, message: String) -> anyhow::Result<()> {
+        let insert = UserInboxInsert {
+            id: UserInboxId::default(),
+            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            user_id,
+            sender_user_id,
+            message
+        };
+
+        let affected_rows = diesel::insert_into(user_inbox::table).values(&vec![insert]).execute(connection)?;
+        if affected_rows == 0 {
+            return Err(anyhow::anyhow!("No row inserted"));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(name="Get inbox messages", skip(connection))]
+    fn get_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, after: Option<(i32, &UserInboxId)>, limit: Option<usize>) -> anyhow::Result<Vec<UserInboxModel>> {
+        let mut query = user_inbox::table
+            .select((user_inbox::id, user_inbox::sender_user_id, user_inbox::message, user_inbox::insert_date))
+            .filter(user_inbox::user_id.eq(user_id))
+            .order((user_inbox::insert_date.asc(), user_inbox::id.asc()))
+            .into_boxed();
+
+        /* Messages are ordered by date and id, so the cursor stays valid even if the message itself removed */
+        if let Some((insert_date, id)) = after {
+            query = query.filter(user_inbox::insert_date.gt(insert_date).or(user_inbox::insert_date.eq(insert_date).and(user_inbox::id.gt(id))));
+        }
+
+        if let Some(limit) = limit {
+            query = query.limit(limit as i64);
+        }
+
+        Ok(query.load::<UserInboxModel>(connection)?)
+    }
+
+    #[tracing::instrument(name="Count inbox messages", skip(connection))]
+    fn count_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, after: Option<(i32, &UserInboxId)>) -> anyhow::Result<usize> {
+        let mut query = user_inbox::table
+            .filter(user_inbox::user_id.eq(user_id))
+            .into_boxed();
+
+        if let Some((insert_date, id)) = after {
+            query = query.filter(user_inbox::insert_date.gt(insert_date).or(user_inbox::insert_date.eq(insert_date).and(user_inbox::id.gt(id))));
+        }
+
+        Ok(query.count().get_result::<i64>(connection)? as usize)
+    }
+
+    #[tracing::instrument(name="Remove inbox messages", skip(connection))]
+    fn remove_inbox_messages(connection: &mut PooledConnection, user_id: &UserId, ids: Vec<UserInboxId>) -> anyhow::Result<usize> {
+        Ok(diesel::delete(user_inbox::table.filter(user_inbox::user_id.eq(user_id)).filter(user_inbox::id.eq_any(ids))).execute(connection)?)
+    }
+
+    #[tracing::instrument(name="Suspend user", skip(connection))]
+    fn suspend_user(connection: &mut PooledConnection, user_id: &UserId, suspender_user_id: &UserId, reason: Option<&str>, expire_date: Option<i32>) -> anyhow::Result<()> {
+        /* Only one suspension is kept for each user, the new one replaces the old record */
+        diesel::delete(user_suspension::table.filter(user_suspension::user_id.eq(user_id))).execute(connection)?;
+
+        let insert = UserSuspensionInsert {
+            id: UserSuspensionId::default(),
+            insert_date: SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            user_id,
+            suspender_user_id,
+            reason,
+            expire_date
+        };
+
+        let affected_rows = diesel::insert_into(user_suspension::table).values(&vec![insert]).execute(connection)?;
+        if affected_rows == 0 {
+            return Err(anyhow::anyhow!("No row inserted"));
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(name="Unsuspend user", skip(connection))]
+    fn unsuspend_user(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<usize> {
+        Ok(diesel::delete(user_suspension::table.filter(user_suspension::user_id.eq(user_id))).execute(connection)?)
+    }
+
+    #[tracing::instrument(name="Get user suspension", skip(connection))]
+    fn get_user_suspension(connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<Option<UserSuspensionModel>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        Ok(user_suspension::table
+            .select((user_suspension::user_id, user_suspension::suspender_user_id, user_suspension::reason, user_suspension::insert_date, user_suspension::expire_date))
+            .filter(user_suspension::user_id.eq(user_id))
+            .filter(user_suspension::expire_date.is_null().or(user_suspension::expire_date.gt(now)))
+            .first::<UserSuspensionModel>(connection)
+            .optional()?)
+    }
+
+    #[tracing::instrument(name="Get suspended users", skip(connection))]
+    fn get_suspended_users(connection: &mut PooledConnection) -> anyhow::Result<Vec<UserSuspensionModel>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        Ok(user_suspension::table
+            .select((user_suspension::user_id, user_suspension::suspender_user_id, user_suspension::reason, user_suspension::insert_date, user_suspension::expire_date))
+            .filter(user_suspension::expire_date.is_null().or(user_suspension::expire_date.gt(now)))
+            .order(user_suspension::insert_date.asc())
+            .load::<UserSuspensionModel>(connection)?)
+    }
+
+    /* Secondary user's records are moved into the primary user and the secondary user is removed. Primary user's records are kept on conflicts */
+    #[tracing::instrument(name="Merge users", skip(connection))]
+    fn merge_users(connection: &mut PooledConnection, primary_user_id: &UserId, secondary_user_id: &UserId) -> anyhow::Result<()> {
+        /* Metas */
+        let primary_meta_keys = user_meta::table
+            .select(user_meta::key)
+            .filter(user_meta::user_id.eq(primary_user_id))
+            .load::<String>(connection)?;
+
+        diesel::update(user_meta::table.filter(user_meta::user_id.eq(secondary_user_id)).filter(user_meta::key.ne_all(&primary_meta_keys)))
+            .set(user_meta::user_id.eq(primary_user_id))
+            .execute(connection)?;
+        diesel::delete(user_meta::table.filter(user_meta::user_id.eq(secondary_user_id))).execute(connection)?;
+
+        /* Room memberships */
+        let primary_rooms = room_user::table
+            .select(room_user::room_id)
+            .filter(room_user::user_id.eq(primary_user_id))
+            .load::<RoomId>(connection)?;
+
+        diesel::update(room_user::table.filter(room_user::user_id.eq(secondary_user_id)).filter(room_user::room_id.ne_all(&primary_rooms)))
+            .set(room_user::user_id.eq(primary_user_id))
+            .execute(connection)?;
+        diesel::delete(room_user::table.filter(room_user::user_id.eq(secondary_user_id))).execute(connection)?;
+        diesel::delete(room_user_request::table.filter(room_user_request::user_id.eq(secondary_user_id))).execute(connection)?;
+        diesel::update(room_user_ban::table.filter(room_user_ban::user_id.eq(secondary_user_id))).set(room_user_ban::user_id.eq(primary_user_id)).execute(connection)?;
+        diesel::update(room_message::table.filter(room_message::user_id.eq(secondary_user_id))).set(room_message::user_id.eq(primary_user_id)).execute(connection)?;
+
+        /* Friends, both sides of the friendship are moved */
+        let mut primary_friends = Self::get_friends(connection, primary_user_id)?;
+        primary_friends.push(primary_user_id.clone());
+
+        diesel::update(user_friend::table.filter(user_friend::user_id.eq(secondary_user_id)).filter(user_friend::friend_user_id.ne_all(&primary_friends)))
+            .set(user_friend::user_id.eq(primary_user_id))
+            .execute(connection)?;
+        diesel::update(user_friend::table.filter(user_friend::friend_user_id.eq(secondary_user_id)).filter(user_friend::user_id.ne_all(&primary_friends)))
+            .set(user_friend::friend_user_id.eq(primary_user_id))
+            .execute(connection)?;
+        diesel::delete(user_friend::table.filter(user_friend::user_id.eq(secondary_user_id).or(user_friend::friend_user_id.eq(secondary_user_id)))).execute(connection)?;
+        diesel::delete(user_friend_request::table.filter(user_friend_request::user_id.eq(secondary_user_id).or(user_friend_request::target_user_id.eq(secondary_user_id)))).execute(connection)?;
+
+        /* Inbox */
+        diesel::update(user_inbox::table.filter(user_inbox::user_id.eq(secondary_user_id))).set(user_inbox::user_id.eq(primary_user_id)).execute(connection)?;
+        diesel::update(user_inbox::table.filter(user_inbox::sender_user_id.eq(secondary_user_id))).set(user_inbox::sender_user_id.eq(primary_user_id)).execute(connection)?;
+
+        /* Login methods, secondary user removed first to keep the unique columns valid */
+        diesel::update(user_identity::table.filter(user_identity::user_id.eq(secondary_user_id))).set(user_identity::user_id.eq(primary_user_id)).execute(connection)?;
+        diesel::delete(user_suspension::table.filter(user_suspension::user_id.eq(secondary_user_id))).execute(connection)?;
+        diesel::delete(user_token::table.filter(user_token::user_id.eq(secondary_user_id))).execute(connection)?;
+
+        let (primary_email, primary_device_id, primary_custom_id) = user::table
+            .select((user::email, user::device_id, user::custom_id))
+            .filter(user::id.eq(primary_user_id))
+            .first::<(Option<String>, Option<String>, Option<String>)>(connection)?;
+
+        let (email, password, email_verified, device_id, custom_id) = user::table
+            .select((user::email, user::password, user::email_verified, user::device_id, user::custom_id))
+            .filter(user::id.eq(secondary_user_id))
+            .first::<(Option<String>, Option<String>, i32, Option<String>, Option<String>)>(connection)?;
+
+        diesel::delete(user::table.filter(user::id.eq(secondary_user_id))).execute(connection)?;
+
+        let mut updates = UserUpdate::default();
+        if primary_email.is_none() && email.is_some() {
+            updates.email = email;
+            updates.password = password;
+            updates.email_verified = Some(email_verified);
+        }
+
+        if primary_device_id.is_none() && device_id.is_some() {
+            updates.device_id = Some(device_id);
+        }
+
+        if primary_custom_id.is_none() && custom_id.is_some() {
+            updates.custom_id = Some(custom_id);
+        }
+
+        if updates.email.is_some() || updates.device_id.is_some() || updates.custom_id.is_some() {
+            Self::update_user(connection, primary_user_id, &updates)?;
+        }
+
+        Ok(())
+    }
 }
 
 /* **************************************************************************************************************** */
@@ -5,6 +5,7 @@
 use std::fmt::Debug;
 
 use yummy_model::auth::UserJwt;
+use yummy_model::state::ClientInfo;
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -23,6 +24,14 @@ pub struct EmptyClient;
 pub trait ClientTrait: Debug {
     fn send(&self, message: String);
     fn authenticated(&self, user: UserJwt);
+
+    /* Transport specific informations, they are stored with the session */
+    fn client_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+
+    /* Session terminated by the server, connection should be closed */
+    fn disconnect(&self) {}
 }
 
 /* **************************************************************************************************************** */
@@ -4,6 +4,7 @@ pub mod test;
 pub mod tls;
 pub mod websocket;
 pub mod database;
+pub mod mailer;
 
 #[cfg(feature = "stateless")]
 pub mod pubsub;
\ No newline at end of file
@@ -0,0 +1,133 @@
+/* **************************************************************************************************************** */
+/* **************************************************** MODS ****************************************************** */
+/* *************************************************** IMPORTS **************************************************** */
+/* **************************************************************************************************************** */
+use std::fmt::Debug;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use yummy_model::config::YummyConfig;
+
+#[cfg(feature = "smtp")]
+use lettre::{Message, SmtpTransport, Transport, message::Mailbox};
+
+/* **************************************************************************************************************** */
+/* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
+/* **************************************************** MACROS **************************************************** */
+/* *************************************************** STRUCTS **************************************************** */
+/* **************************************************************************************************************** */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String
+}
+
+/* Development mailer, mails are appended to the file or written to the log */
+#[derive(Debug)]
+pub struct FileMailer {
+    from: String,
+    path: Option<PathBuf>
+}
+
+#[cfg(feature = "smtp")]
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: SmtpTransport
+}
+
+/* **************************************************************************************************************** */
+/* **************************************************** ENUMS ***************************************************** */
+/* ************************************************** FUNCTIONS *************************************************** */
+/* **************************************************************************************************************** */
+pub fn create_mailer(config: &YummyConfig) -> Arc<dyn Mailer> {
+    #[cfg(feature = "smtp")]
+    if let Some(url) = config.smtp_url.as_deref() {
+        return Arc::new(SmtpMailer::new(url, &config.mail_from).unwrap_or_else(|error| panic!("SMTP mailer could not be created. {}", error)));
+    }
+
+    #[cfg(not(feature = "smtp"))]
+    if config.smtp_url.is_some() {
+        log::warn!("SMTP_URL is ignored, yummy compiled without 'smtp' feature");
+    }
+
+    Arc::new(FileMailer::new(&config.mail_from, config.mail_file_path.as_deref()))
+}
+
+/* **************************************************************************************************************** */
+/* *************************************************** TRAITS ***************************************************** */
+/* **************************************************************************************************************** */
+pub trait Mailer: Debug + Send + Sync {
+    fn send(&self, mail: &Mail) -> anyhow::Result<()>;
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl FileMailer {
+    pub fn new(from: &str, path: Option<&str>) -> Self {
+        Self {
+            from: from.to_string(),
+            path: path.map(PathBuf::from)
+        }
+    }
+}
+
+#[cfg(feature = "smtp")]
+impl SmtpMailer {
+    pub fn new(url: &str, from: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            from: from.parse()?,
+            transport: SmtpTransport::from_url(url)?.build()
+        })
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ********************************************** TRAIT IMPLEMENTS ************************************************ */
+/* **************************************************************************************************************** */
+impl Mailer for FileMailer {
+    fn send(&self, mail: &Mail) -> anyhow::Result<()> {
+        let content = format!("From: {}\nTo: {}\nSubject: {}\n\n{}\n\n", self.from, mail.to, mail.subject, mail.body);
+
+        match &self.path {
+            Some(path) => OpenOptions::new().create(true).append(true).open(path)?.write_all(content.as_bytes())?,
+            None => log::info!("{}", content)
+        };
+        Ok(())
+    }
+}
+
+#[cfg(feature = "smtp")]
+impl Mailer for SmtpMailer {
+    fn send(&self, mail: &Mail) -> anyhow::Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(mail.to.parse()?)
+            .subject(&mail.subject)
+            .body(mail.body.clone())?;
+
+        /* SMTP round trip should not block the caller actor, delivery errors are only logged */
+        let transport = self.transport.clone();
+        std::thread::spawn(move || {
+            if let Err(error) = transport.send(&message) {
+                log::error!("Mail could not sent: {:?}", error);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(feature = "smtp")]
+impl Debug for SmtpMailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpMailer").field("from", &self.from).finish()
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************* MACROS CALL ************************************************** */
+/* ************************************************** UNIT TESTS ************************************************** */
+/* **************************************************************************************************************** */
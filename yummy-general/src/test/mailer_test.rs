@@ -0,0 +1,25 @@
+use yummy_model::config::get_configuration;
+use crate::mailer::{create_mailer, Mail};
+
+#[test]
+fn file_mailer() -> anyhow::Result<()> {
+    let file = tempfile::NamedTempFile::new()?;
+
+    let mut config = get_configuration().as_ref().clone();
+    config.mail_file_path = Some(file.path().to_str().unwrap().to_string());
+    let mailer = create_mailer(&config);
+
+    mailer.send(&Mail { to: "erhanbaris@gmail.com".to_string(), subject: "Hello".to_string(), body: "world".to_string() })?;
+    mailer.send(&Mail { to: "erhanbaris@gmail.com".to_string(), subject: "Yummy".to_string(), body: "yummy".to_string() })?;
+
+    let content = std::fs::read_to_string(file.path())?;
+    assert!(content.contains("To: erhanbaris@gmail.com\nSubject: Hello\n\nworld"));
+    assert!(content.contains("Subject: Yummy\n\nyummy"));
+    Ok(())
+}
+
+#[test]
+fn log_mailer() -> anyhow::Result<()> {
+    let mailer = create_mailer(&get_configuration());
+    mailer.send(&Mail { to: "erhanbaris@gmail.com".to_string(), subject: "Hello".to_string(), body: "world".to_string() })
+}
@@ -6,3 +6,6 @@ mod model_test;
 
 #[cfg(test)]
 mod meta_test;
+
+#[cfg(test)]
+mod mailer_test;
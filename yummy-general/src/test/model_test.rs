@@ -1,4 +1,5 @@
 use yummy_model::UserId;
+use yummy_model::password::{Password, PasswordVerification};
 
 #[test]
 fn user_id() -> anyhow::Result<()> {
@@ -16,3 +17,24 @@ fn user_id() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn password_hash() -> anyhow::Result<()> {
+    let password = Password::from("erhan".to_string());
+    let hash = password.hash()?;
+
+    assert!(hash.starts_with("$argon2id$"));
+    assert_ne!(hash, password.hash()?);
+
+    assert_eq!(password.verify(&hash), PasswordVerification::Valid);
+    assert_eq!(Password::from("baris".to_string()).verify(&hash), PasswordVerification::Invalid);
+    Ok(())
+}
+
+#[test]
+fn legacy_password() -> anyhow::Result<()> {
+    assert_eq!(Password::from("erhan".to_string()).verify("erhan"), PasswordVerification::ValidButLegacy);
+    assert_eq!(Password::from("baris".to_string()).verify("erhan"), PasswordVerification::Invalid);
+    assert_eq!(Password::from("erhan".to_string()).verify(""), PasswordVerification::Invalid);
+    Ok(())
+}
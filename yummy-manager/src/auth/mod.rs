@@ -10,30 +10,44 @@ pub mod model;
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
 use std::{ops::Deref, borrow::Cow};
+use std::time::{SystemTime, UNIX_EPOCH};
+use rand::Rng;
+use rand::distributions::Alphanumeric;
 use actix_broker::BrokerIssue;
 use yummy_cache::state::YummyState;
-use yummy_model::{auth::{generate_auth, UserJwt, validate_auth}, web::GenericAnswer, UserType};
+use yummy_model::{auth::{generate_auth, UserAuth, UserJwt, validate_auth, TokenType, RefreshTokenRotation}, web::{GenericAnswer, Answer}, UserType};
+use yummy_model::password::PasswordVerification;
+use yummy_model::user::{UserUpdate, UserTokenType};
+use yummy_model::external::ExternalAuthVerifier;
+use yummy_model::request::{RequestIdentity, RequestUnlinkIdentity};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::collections::HashMap;
-use yummy_model::config::YummyConfig;
+use yummy_model::config::{YummyConfig, SessionLimitPolicy};
 use actix_broker::BrokerSubscribe;
 
 use actix::{Context, Handler, Actor, AsyncContext, SpawnHandle};
 use yummy_database::DatabaseTrait;
 use anyhow::{anyhow, Ok};
-use yummy_model::{UserId, SessionId};
-use yummy_general::database::Pool;
+use yummy_model::{UserId, SessionId, TokenId, TokenFamilyId};
+use yummy_general::database::{Pool, PooledConnection};
+use yummy_general::mailer::{create_mailer, Mail, Mailer};
+use yummy_general::client::ClientTrait;
 
-use crate::YummyModel;
+use crate::{YummyModel, get_user_id_from_auth};
 use crate::plugin::PluginExecuter;
 use self::model::*;
 use crate::conn::model::UserConnected;
+use crate::conn::model::SessionDetached;
+use crate::conn::model::ReplaySessionMessages;
+use crate::conn::model::TerminateRemoteSession;
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
 /* **************************************************** MACROS **************************************************** */
 /* **************************************************************************************************************** */
+const USER_TOKEN_LENGTH: usize = 32;
+
 macro_rules! disconnect_if_already_auth {
     ($model: expr, $self:expr, $ctx: expr) => {
         if $model.auth.is_some() {
@@ -70,6 +84,8 @@ pub struct AuthManager<DB: DatabaseTrait + ?Sized> {
     states: YummyState,
     session_timeout_timers: HashMap<SessionId, SpawnHandle>,
     executer: Arc<PluginExecuter>,
+    external_auth: ExternalAuthVerifier,
+    mailer: Arc<dyn Mailer>,
     _auth: PhantomData<DB>
 }
 
@@ -79,9 +95,11 @@ pub struct AuthManager<DB: DatabaseTrait + ?Sized> {
 /* *************************************************** TRAITS ***************************************************** */
 /* ************************************************* IMPLEMENTS *************************************************** */
 /* **************************************************************************************************************** */
-impl<DB: DatabaseTrait + ?Sized> AuthManager<DB> {
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> AuthManager<DB> {
     pub fn new(config: Arc<YummyConfig>, states: YummyState, database: Arc<Pool>, executer: Arc<PluginExecuter>) -> Self {
         Self {
+            external_auth: config.external_auth.clone(),
+            mailer: create_mailer(&config),
             config,
             database,
             states,
@@ -91,7 +109,7 @@ impl<DB: DatabaseTrait + ?Sized> AuthManager<DB> {
         }
     }
 
-    pub fn generate_token(&self, id: &UserId, name: Option<String>, email: Option<String>, session: Option<SessionId>, user_type: UserType) -> anyhow::Result<(String, UserJwt)> {
+    pub fn generate_token(&self, id: &UserId, name: Option<String>, email: Option<String>, session: Option<SessionId>, user_type: UserType, family: &TokenFamilyId) -> anyhow::Result<(String, UserJwt)> {
         let user_jwt = UserJwt {
             id: Arc::new(id.clone()),
             session: session.unwrap_or_else(SessionId::new) ,
@@ -100,13 +118,124 @@ impl<DB: DatabaseTrait + ?Sized> AuthManager<DB> {
             user_type
         };
 
-        let token = match generate_auth(self.config.clone(), &user_jwt) {
-            Some(token) => token,
+        let token = match generate_auth(self.config.clone(), &user_jwt, family, TokenType::Access) {
+            Some((token, _)) => token,
             _ => return Err(anyhow::anyhow!(AuthError::TokenCouldNotGenerated))
         };
 
         Ok((token, user_jwt))
     }
+
+    pub fn generate_refresh_token(&self, user_jwt: &UserJwt, family: &TokenFamilyId) -> anyhow::Result<(String, TokenId)> {
+        match generate_auth(self.config.clone(), user_jwt, family, TokenType::Refresh) {
+            Some(token) => Ok(token),
+            _ => Err(anyhow::anyhow!(AuthError::TokenCouldNotGenerated))
+        }
+    }
+
+    /* Every login starts a new token family, refresh tokens are rotated inside of that family */
+    fn create_tokens(&self, id: &UserId, name: Option<String>, email: Option<String>, session: SessionId, user_type: UserType) -> anyhow::Result<(Authenticated, UserJwt)> {
+        let family = TokenFamilyId::new();
+        let (token, user_jwt) = self.generate_token(id, name, email, Some(session), user_type, &family)?;
+        let (refresh_token, refresh_token_id) = self.generate_refresh_token(&user_jwt, &family)?;
+
+        self.states.create_token_family(id, &family, &refresh_token_id)?;
+        Ok((Authenticated { token, refresh_token: Some(refresh_token) }, user_jwt))
+    }
+
+    fn check_suspension(&self, connection: &mut PooledConnection, user_id: &UserId) -> anyhow::Result<()> {
+        match DB::get_user_suspension(connection, user_id)? {
+            Some(_) => Err(anyhow!(AuthError::UserSuspended)),
+            None => Ok(())
+        }
+    }
+
+    /* Concurrent session limit is applied before creating the session. Current session of the socket will be replaced, so it is not counted */
+    fn create_session(&mut self, user_id: &UserId, name: Option<String>, user_type: UserType, auth: &Arc<Option<UserAuth>>, socket: &Arc<dyn ClientTrait + Sync + Send>, ctx: &mut Context<Self>) -> anyhow::Result<SessionId> {
+        if self.config.max_user_sessions > 0 {
+            let current_session = auth.deref().as_ref().map(|auth| &auth.session);
+            let sessions = self.states.get_user_session_list(user_id)
+                .into_iter()
+                .filter(|session| Some(&session.session_id) != current_session)
+                .collect::<Vec<_>>();
+
+            if sessions.len() >= self.config.max_user_sessions {
+                if self.config.session_limit_policy == SessionLimitPolicy::Reject {
+                    return Err(anyhow!(AuthError::MaxSessionsReached));
+                }
+
+                let kick_count = sessions.len() + 1 - self.config.max_user_sessions;
+                for session in sessions.into_iter().take(kick_count) {
+                    self.terminate_session(user_id, session.session_id, socket, ctx);
+                }
+            }
+        }
+
+        Ok(self.states.new_session(user_id, name, user_type, socket.client_info()))
+    }
+
+    /* Session is closed immediately, connection and room informations are cleaned with the disconnect message */
+    fn terminate_session(&mut self, user_id: &UserId, session_id: SessionId, socket: &Arc<dyn ClientTrait + Sync + Send>, ctx: &mut Context<Self>) {
+        if let Some(handle) = self.session_timeout_timers.remove(&session_id) {
+            ctx.cancel_future(handle);
+        }
+
+        /* Location should be read before closing the session, it is removed with the session informations */
+        let location = self.states.get_session_location(&session_id).or_else(|| self.states.get_user_location(Arc::new(user_id.clone())));
+        self.states.close_session(user_id, &session_id);
+
+        match location {
+            Some(server_name) if server_name != self.config.server_name => self.issue_system_async(TerminateRemoteSession {
+                server_name,
+                user_id: user_id.clone(),
+                session_id
+            }),
+            _ => self.issue_system_async(ConnUserDisconnect {
+                request_id: None,
+                auth: Arc::new(Some(UserAuth { user: user_id.clone(), session: session_id })),
+                send_message: false,
+                socket: socket.clone()
+            })
+        };
+    }
+
+    /* One-time token is stored at database and sent to the user's email address, previous tokens are not valid anymore */
+    fn send_user_token(&self, connection: &mut PooledConnection, user_id: &UserId, email: &str, token_type: UserTokenType) -> anyhow::Result<()> {
+        match self.create_user_token(connection, user_id, email, token_type)? {
+            Some(mail) => self.mailer.send(&mail),
+            None => Ok(())
+        }
+    }
+
+    /* New token is not created while the last one is in the cooldown, so the address could not be flooded with mails */
+    fn create_user_token(&self, connection: &mut PooledConnection, user_id: &UserId, email: &str, token_type: UserTokenType) -> anyhow::Result<Option<Mail>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        if let Some(insert_date) = DB::get_user_token_date(connection, user_id, token_type)? {
+            if u64::try_from(now - insert_date).map(|waited| waited < self.config.user_token_cooldown.as_secs()).unwrap_or(true) {
+                return Ok(None);
+            }
+        }
+
+        let token = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(USER_TOKEN_LENGTH)
+            .map(char::from)
+            .collect::<String>();
+
+        let (lifetime, subject, body) = match token_type {
+            UserTokenType::EmailVerification => (self.config.email_verification_lifetime, "Verify your email address", format!("Your email verification code: {}", token)),
+            UserTokenType::PasswordReset => (self.config.password_reset_lifetime, "Reset your password", format!("Your password reset code: {}", token))
+        };
+
+        DB::create_user_token(connection, user_id, token_type, &token, now + lifetime.as_secs() as i32)?;
+
+        Ok(Some(Mail {
+            to: email.to_string(),
+            subject: subject.to_string(),
+            body
+        }))
+    }
 }
 
 /* **************************************************************************************************************** */
@@ -117,42 +246,77 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Actor for AuthMa
 
     fn started(&mut self, ctx: &mut Self::Context) {
         self.subscribe_system_async::<AuthUserDisconnect>(ctx);
+        self.subscribe_system_async::<TerminateUserSessions>(ctx);
     }
 }
 
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<EmailAuthRequest> for AuthManager<DB> {
     type Result = anyhow::Result<()>;
 
-    #[tracing::instrument(name="EmailAuth", skip(self, _ctx))]
+    #[tracing::instrument(name="EmailAuth", skip(self, ctx))]
     #[yummy_macros::plugin_api(name="email_auth")]
-    fn handle(&mut self, model: EmailAuthRequest, _ctx: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, model: EmailAuthRequest, ctx: &mut Context<Self>) -> Self::Result {
         let mut connection = self.database.get()?;
         let user_info = DB::user_login_via_email(&mut connection, &model.email)?;
 
         let (user_id, name, user_type) = match (user_info, model.if_not_exist_create) {
             (Some(user_info), _) => {
-                if model.password.get() != &user_info.password.unwrap_or_default() {
-                    return Err(anyhow!(AuthError::EmailOrPasswordNotValid));
+                match model.password.verify(&user_info.password.unwrap_or_default()) {
+                    PasswordVerification::Valid => (),
+
+                    /* Legacy plain text password, replace it with the hashed version */
+                    PasswordVerification::ValidButLegacy => {
+                        let updates = UserUpdate {
+                            password: Some(model.password.hash()?),
+                            ..Default::default()
+                        };
+                        DB::update_user(&mut connection, &user_info.user_id, &updates)?;
+                    },
+                    PasswordVerification::Invalid => return Err(anyhow!(AuthError::EmailOrPasswordNotValid))
+                };
+
+                if !self.config.allow_unverified_login && !user_info.email_verified {
+                    return Err(anyhow!(AuthError::EmailNotVerified));
                 }
 
+                self.check_suspension(&mut connection, &user_info.user_id)?;
                 DB::update_last_login(&mut connection, &user_info.user_id)?;
                 (user_info.user_id, user_info.name, user_info.user_type)
             },
-            (None, true) => (DB::create_user_via_email(&mut connection, &model.email, &model.password)?, None, UserType::default()),
+            (None, true) => {
+                /* User should not be created without the verification token, mail is sent after the commit */
+                let (user_id, mail) = DB::transaction(&mut connection, |connection| {
+                    let user_id = DB::create_user_via_email(connection, &model.email, &model.password)?;
+                    let mail = self.create_user_token(connection, &user_id, &model.email, UserTokenType::EmailVerification)?;
+                    anyhow::Ok((user_id, mail))
+                })?;
+
+                if let Some(mail) = mail {
+                    self.mailer.send(&mail)?;
+                }
+
+                /* User created but it should verify the email address before login */
+                if !self.config.allow_unverified_login {
+                    return Err(anyhow!(AuthError::EmailNotVerified));
+                }
+
+                (user_id, None, UserType::default())
+            },
             _ => return Err(anyhow!(AuthError::EmailOrPasswordNotValid))
         };
 
-        let session_id = self.states.new_session(&user_id, name.clone(), user_type);
-        let (token, auth_jwt) = self.generate_token(&user_id, name, Some(model.email.to_string()), Some(session_id), user_type)?;
+        let session_id = self.create_session(&user_id, name.clone(), user_type, &model.auth, &model.socket, ctx)?;
+        let (authenticated, auth_jwt) = self.create_tokens(&user_id, name, Some(model.email.to_string()), session_id, user_type)?;
 
-        disconnect_if_already_auth_2!(model.auth, model.socket, self, _ctx);
+        disconnect_if_already_auth_2!(model.auth, model.socket, self, ctx);
 
         self.issue_system_async(UserConnected {
             user_id: Arc::new(user_id),
+            session_id: auth_jwt.session.clone(),
             socket: model.socket.clone()
         });
         model.socket.authenticated(auth_jwt);
-        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token }).into());
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), authenticated).into());
         Ok(())
     }
 }
@@ -160,29 +324,33 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<EmailAut
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<DeviceIdAuthRequest> for AuthManager<DB> {
     type Result = anyhow::Result<()>;
 
-    #[tracing::instrument(name="DeviceIdAuth", skip(self, _ctx))]
+    #[tracing::instrument(name="DeviceIdAuth", skip(self, ctx))]
     #[yummy_macros::plugin_api(name="deviceid_auth")]
-    fn handle(&mut self, model: DeviceIdAuthRequest, _ctx: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, model: DeviceIdAuthRequest, ctx: &mut Context<Self>) -> Self::Result {
         
         let mut connection = self.database.get()?;
         let user_info = DB::user_login_via_device_id(&mut connection, &model.id)?;
 
         let (user_id, name, email, user_type) = match user_info {
-            Some(user_info) => (user_info.user_id, user_info.name, user_info.email, user_info.user_type),
+            Some(user_info) => {
+                self.check_suspension(&mut connection, &user_info.user_id)?;
+                (user_info.user_id, user_info.name, user_info.email, user_info.user_type)
+            },
             None => (DB::create_user_via_device_id(&mut connection, &model.id)?, None, None, UserType::default())
         };
         
-        let session_id = self.states.new_session(&user_id, name.clone(), user_type);
-        let (token, auth) = self.generate_token(&user_id, name, email, Some(session_id), user_type)?;
+        let session_id = self.create_session(&user_id, name.clone(), user_type, &model.auth, &model.socket, ctx)?;
+        let (authenticated, auth) = self.create_tokens(&user_id, name, email, session_id, user_type)?;
         
-        disconnect_if_already_auth!(model, self, _ctx);
+        disconnect_if_already_auth!(model, self, ctx);
 
         self.issue_system_async(UserConnected {
             user_id: Arc::new(user_id),
+            session_id: auth.session.clone(),
             socket: model.socket.clone()
         });
         model.socket.authenticated(auth);
-        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token }).into());
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), authenticated).into());
         Ok(())
     }
 }
@@ -190,29 +358,365 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<DeviceId
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<CustomIdAuthRequest> for AuthManager<DB> {
     type Result = anyhow::Result<()>;
 
-    #[tracing::instrument(name="CustomIdAuth", skip(self, _ctx))]
+    #[tracing::instrument(name="CustomIdAuth", skip(self, ctx))]
     #[yummy_macros::plugin_api(name="customid_auth")]
-    fn handle(&mut self, model: CustomIdAuthRequest, _ctx: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, model: CustomIdAuthRequest, ctx: &mut Context<Self>) -> Self::Result {
         
         let mut connection = self.database.get()?;
         let user_info = DB::user_login_via_custom_id(&mut connection, &model.id)?;
 
         let (user_id, name, email, user_type) = match user_info {
-            Some(user_info) => (user_info.user_id, user_info.name, user_info.email, user_info.user_type),
+            Some(user_info) => {
+                self.check_suspension(&mut connection, &user_info.user_id)?;
+                (user_info.user_id, user_info.name, user_info.email, user_info.user_type)
+            },
             None => (DB::create_user_via_custom_id(&mut connection, &model.id)?, None, None, UserType::default())
         };
         
-        let session_id = self.states.new_session(&user_id, name.clone(), user_type);
-        let (token, auth) = self.generate_token(&user_id, name, email, Some(session_id), user_type)?;
+        let session_id = self.create_session(&user_id, name.clone(), user_type, &model.auth, &model.socket, ctx)?;
+        let (authenticated, auth) = self.create_tokens(&user_id, name, email, session_id, user_type)?;
+
+        disconnect_if_already_auth!(model, self, ctx);
+
+        self.issue_system_async(UserConnected {
+            user_id: Arc::new(user_id),
+            session_id: auth.session.clone(),
+            socket: model.socket.clone()
+        });
+        model.socket.authenticated(auth);
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), authenticated).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ExternalAuthRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="ExternalAuth", skip(self, ctx))]
+    #[yummy_macros::plugin_api(name="external_auth")]
+    fn handle(&mut self, model: ExternalAuthRequest, ctx: &mut Context<Self>) -> Self::Result {
+        let identity = self.external_auth.verify(&model.token)?;
+
+        let mut connection = self.database.get()?;
+        let user_info = DB::user_login_via_identity(&mut connection, &identity.provider, &identity.subject)?;
+
+        let (user_id, name, email, user_type) = match user_info {
+            Some(user_info) => {
+                self.check_suspension(&mut connection, &user_info.user_id)?;
+                (user_info.user_id, user_info.name, user_info.email, user_info.user_type)
+            },
+            None => {
+                let user_id = DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| DB::create_user_via_identity(connection, &identity.provider, &identity.subject))?;
+                (user_id, None, None, UserType::default())
+            }
+        };
+        
+        let session_id = self.create_session(&user_id, name.clone(), user_type, &model.auth, &model.socket, ctx)?;
+        let (authenticated, auth) = self.create_tokens(&user_id, name, email, session_id, user_type)?;
 
-        disconnect_if_already_auth!(model, self, _ctx);
+        disconnect_if_already_auth!(model, self, ctx);
 
         self.issue_system_async(UserConnected {
             user_id: Arc::new(user_id),
+            session_id: auth.session.clone(),
             socket: model.socket.clone()
         });
         model.socket.authenticated(auth);
-        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token }).into());
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), authenticated).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<LinkIdentityRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="LinkIdentity", skip(self, ctx))]
+    #[yummy_macros::plugin_api(name="link_identity")]
+    fn handle(&mut self, model: LinkIdentityRequest, ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model, Err(anyhow!(AuthError::UserNotLoggedIn)));
+        let mut connection = self.database.get()?;
+
+        /* Find the current owner of the identity, credentials are validated before linking */
+        let mut email_verified = false;
+        let (owner_user_id, external_identity) = match &model.identity {
+            RequestIdentity::Email { email, password } => match DB::user_login_via_email(&mut connection, email)? {
+                Some(user_info) => match password.verify(&user_info.password.unwrap_or_default()) {
+                    PasswordVerification::Invalid => return Err(anyhow!(AuthError::EmailOrPasswordNotValid)),
+                    _ => {
+                        email_verified = user_info.email_verified;
+                        (Some(user_info.user_id), None)
+                    }
+                },
+                None => (None, None)
+            },
+            RequestIdentity::DeviceId { id } => (DB::user_login_via_device_id(&mut connection, id)?.map(|user_info| user_info.user_id), None),
+            RequestIdentity::CustomId { id } => (DB::user_login_via_custom_id(&mut connection, id)?.map(|user_info| user_info.user_id), None),
+            RequestIdentity::External { token } => {
+                let identity = self.external_auth.verify(token)?;
+                (DB::user_login_via_identity(&mut connection, &identity.provider, &identity.subject)?.map(|user_info| user_info.user_id), Some(identity))
+            }
+        };
+
+        let secondary_user_id = match owner_user_id {
+            Some(owner_user_id) if &owner_user_id == user_id => return Err(anyhow!(AuthError::IdentityAlreadyLinked)),
+            Some(_) if !model.merge => return Err(anyhow!(AuthError::IdentityLinkedToAnotherUser)),
+            Some(owner_user_id) => {
+                self.check_suspension(&mut connection, &owner_user_id)?;
+                Some(owner_user_id)
+            },
+            None => None
+        };
+
+        let secondary_friends = match &secondary_user_id {
+            Some(secondary_user_id) => DB::get_friends(&mut connection, secondary_user_id)?,
+            None => Vec::new()
+        };
+
+        DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            if let Some(secondary_user_id) = &secondary_user_id {
+                DB::merge_users(connection, user_id, secondary_user_id)?;
+            }
+
+            match &model.identity {
+                /* Verification state belongs to the email address */
+                RequestIdentity::Email { email, password } => {
+                    DB::remove_user_email(connection, user_id)?;
+                    DB::update_user(connection, user_id, &UserUpdate {
+                        email: Some(email.to_string()),
+                        password: Some(password.hash()?),
+                        email_verified: Some(email_verified as i32),
+                        ..Default::default()
+                    })?;
+                },
+                RequestIdentity::DeviceId { id } => {
+                    DB::update_user(connection, user_id, &UserUpdate {
+                        device_id: Some(Some(id.to_string())),
+                        ..Default::default()
+                    })?;
+                },
+                RequestIdentity::CustomId { id } => {
+                    DB::update_user(connection, user_id, &UserUpdate {
+                        custom_id: Some(Some(id.to_string())),
+                        ..Default::default()
+                    })?;
+                },
+
+                /* Merged user's identities already moved to the current user */
+                RequestIdentity::External { .. } => if let (Some(identity), None) = (&external_identity, &secondary_user_id) {
+                    DB::add_user_identity(connection, user_id, &identity.provider, &identity.subject)?;
+                }
+            };
+            Ok(())
+        })?;
+
+        if let RequestIdentity::Email { email, .. } = &model.identity {
+            if !email_verified {
+                self.send_user_token(&mut connection, user_id, email, UserTokenType::EmailVerification)?;
+            }
+        }
+
+        if let Some(secondary_user_id) = &secondary_user_id {
+
+            /* Merged user does not exist anymore, live sessions are closed and removed from the rooms */
+            for session_id in self.states.get_user_sessions(secondary_user_id).into_iter() {
+                self.terminate_session(secondary_user_id, session_id, &model.socket, ctx);
+            }
+
+            for friend_user_id in secondary_friends.iter() {
+                self.states.remove_friend(secondary_user_id, friend_user_id)?;
+
+                if friend_user_id != user_id {
+                    self.states.add_friend(user_id, friend_user_id)?;
+                }
+            }
+
+            self.states.invalidate_user_cache(secondary_user_id)?;
+            self.states.revoke_user_tokens(secondary_user_id)?;
+        }
+
+        self.states.invalidate_user_cache(user_id)?;
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UnlinkIdentityRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UnlinkIdentity", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="unlink_identity")]
+    fn handle(&mut self, model: UnlinkIdentityRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model, Err(anyhow!(AuthError::UserNotLoggedIn)));
+        let mut connection = self.database.get()?;
+
+        DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            let login_methods = DB::count_user_login_methods(connection, user_id)?;
+
+            match &model.identity {
+                RequestUnlinkIdentity::Email => DB::remove_user_email(connection, user_id)?,
+                RequestUnlinkIdentity::DeviceId => DB::update_user(connection, user_id, &UserUpdate {
+                    device_id: Some(None),
+                    ..Default::default()
+                })?,
+                RequestUnlinkIdentity::CustomId => DB::update_user(connection, user_id, &UserUpdate {
+                    custom_id: Some(None),
+                    ..Default::default()
+                })?,
+                RequestUnlinkIdentity::External { provider } => DB::remove_user_identity(connection, user_id, provider)?
+            };
+
+            /* User should be able to login after unlinking, the transaction is rolled back otherwise */
+            match DB::count_user_login_methods(connection, user_id)? {
+                0 => Err(anyhow!(AuthError::LastIdentity)),
+                count if count == login_methods => Err(anyhow!(AuthError::IdentityNotLinked)),
+                _ => Ok(())
+            }
+        })?;
+
+        self.states.invalidate_user_cache(user_id)?;
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<VerifyEmailRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="VerifyEmail", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="verify_email")]
+    fn handle(&mut self, model: VerifyEmailRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let mut connection = self.database.get()?;
+
+        let user_id = match DB::consume_user_token(&mut connection, UserTokenType::EmailVerification, &model.token)? {
+            Some(user_id) => user_id,
+            None => return Err(anyhow!(AuthError::TokenNotValid))
+        };
+
+        DB::update_user(&mut connection, &user_id, &UserUpdate {
+            email_verified: Some(1),
+            ..Default::default()
+        })?;
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<SendVerificationEmailRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="SendVerificationEmail", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="send_verification_email")]
+    fn handle(&mut self, model: SendVerificationEmailRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let mut connection = self.database.get()?;
+
+        /* Response is the same for unknown emails, registered addresses should not be discoverable */
+        if let Some(user_info) = DB::user_login_via_email(&mut connection, &model.email)? {
+            if !user_info.email_verified {
+                self.send_user_token(&mut connection, &user_info.user_id, &model.email, UserTokenType::EmailVerification)?;
+            }
+        }
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ForgotPasswordRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="ForgotPassword", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="forgot_password")]
+    fn handle(&mut self, model: ForgotPasswordRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let mut connection = self.database.get()?;
+
+        /* Response is the same for unknown emails, registered addresses should not be discoverable */
+        if let Some(user_info) = DB::user_login_via_email(&mut connection, &model.email)? {
+            self.send_user_token(&mut connection, &user_info.user_id, &model.email, UserTokenType::PasswordReset)?;
+        }
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ResetPasswordRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="ResetPassword", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="reset_password")]
+    fn handle(&mut self, model: ResetPasswordRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let mut connection = self.database.get()?;
+
+        let user_id = match DB::consume_user_token(&mut connection, UserTokenType::PasswordReset, &model.token)? {
+            Some(user_id) => user_id,
+            None => return Err(anyhow!(AuthError::TokenNotValid))
+        };
+
+        /* Reset token was delivered to the email address, so the address is verified as well */
+        DB::update_user(&mut connection, &user_id, &UserUpdate {
+            password: Some(model.password.hash()?),
+            email_verified: Some(1),
+            ..Default::default()
+        })?;
+
+        /* Password changed, all issued tokens should be revoked */
+        self.states.revoke_user_tokens(&user_id)?;
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ListSessionsRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="ListSessions", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="list_sessions")]
+    fn handle(&mut self, model: ListSessionsRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model, Err(anyhow!(AuthError::UserNotLoggedIn)));
+        let sessions = self.states.get_user_session_list(user_id);
+
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), ListSessionsResponse { sessions }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<TerminateSessionRequest> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="TerminateSession", skip(self, ctx))]
+    #[yummy_macros::plugin_api(name="terminate_session")]
+    fn handle(&mut self, model: TerminateSessionRequest, ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model, Err(anyhow!(AuthError::UserNotLoggedIn))).clone();
+
+        if !self.states.get_user_sessions(&user_id).contains(&model.session_id) {
+            return Err(anyhow!(AuthError::SessionNotFound));
+        }
+
+        self.terminate_session(&user_id, model.session_id.clone(), &model.socket, ctx);
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<SessionActivity> for AuthManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="SessionActivity", skip(self, _ctx))]
+    fn handle(&mut self, model: SessionActivity, _ctx: &mut Context<Self>) -> Self::Result {
+        if let Some(user) = model.auth.deref() {
+            self.states.update_session_activity(&user.session, None);
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateExternalJwks> for AuthManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UpdateExternalJwks", skip(self, _ctx))]
+    fn handle(&mut self, model: UpdateExternalJwks, _ctx: &mut Context<Self>) -> Self::Result {
+        self.external_auth.set_jwks(&model.issuer, &model.jwks)?;
         Ok(())
     }
 }
@@ -223,6 +727,10 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<LogoutRe
     #[tracing::instrument(name="Logout", skip(self, _ctx))]
     #[yummy_macros::plugin_api(name="logout")]
     fn handle(&mut self, model: LogoutRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        if let Some(user) = model.auth.deref() {
+            self.states.revoke_user_tokens(&user.user)?;
+        }
+
         self.issue_system_async(ConnUserDisconnect {
             request_id: model.request_id,
             auth: model.auth.clone(),
@@ -243,12 +751,23 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RefreshT
         disconnect_if_already_auth!(model, self, ctx);        
         
         match validate_auth(self.config.clone(), &model.token[..]) {
-            Some(claims) => {
-                let (token, _) = self.generate_token(&claims.user.id, claims.user.name, claims.user.email, Some(claims.user.session), claims.user.user_type)?;
-                model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token }).into());
-                Ok(())
+            Some(claims) if claims.token_type == TokenType::Refresh => {
+                self.check_suspension(&mut self.database.get()?, &claims.user.id)?;
+                let (token, user_jwt) = self.generate_token(&claims.user.id, claims.user.name, claims.user.email, Some(claims.user.session), claims.user.user_type, &claims.family)?;
+                let (refresh_token, refresh_token_id) = self.generate_refresh_token(&user_jwt, &claims.family)?;
+
+                match self.states.rotate_refresh_token(&claims.family, &claims.jti, &refresh_token_id)? {
+                    RefreshTokenRotation::Rotated => {
+                        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token, refresh_token: Some(refresh_token) }).into());
+                        Ok(())
+                    },
+
+                    /* Refresh token already used, the whole family is revoked */
+                    RefreshTokenRotation::Reused => Err(anyhow!(AuthError::TokenNotValid)),
+                    RefreshTokenRotation::NotFound => Err(anyhow!(AuthError::TokenNotValid))
+                }
             },
-            None => Err(anyhow!(AuthError::TokenNotValid))
+            _ => Err(anyhow!(AuthError::TokenNotValid))
         }
     }
 }
@@ -260,29 +779,47 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RestoreT
     #[yummy_macros::plugin_api(name="restore_token")]
     fn handle(&mut self, model: RestoreTokenRequest, ctx: &mut Context<Self>) -> Self::Result {
         match validate_auth(self.config.clone(), &model.token[..]) {
-            Some(auth) => {
+            Some(auth) if auth.token_type == TokenType::Access => {
+                if !self.states.is_token_family_active(&auth.family)? {
+                    return Err(anyhow!(AuthError::TokenNotValid));
+                }
+
+                self.check_suspension(&mut self.database.get()?, &auth.user.id)?;
+
                 let session_id = if self.states.is_session_online(&auth.user.session) {
                     if let Some(handle) = self.session_timeout_timers.remove(&auth.user.session) {
                         ctx.cancel_future(handle);
                     }
+
+                    /* Session restored from a new connection */
+                    self.states.update_session_activity(&auth.user.session, Some(model.socket.client_info()));
                     auth.user.session
                 } else {
-                    self.states.new_session(&auth.user.id, auth.user.name.clone(), auth.user.user_type)
+                    self.create_session(&auth.user.id, auth.user.name.clone(), auth.user.user_type, &model.auth, &model.socket, ctx)?
                 };
 
-                let (token, auth) = self.generate_token(&auth.user.id, None, None, Some(session_id), auth.user.user_type)?; 
+                let (token, auth) = self.generate_token(&auth.user.id, None, None, Some(session_id), auth.user.user_type, &auth.family)?; 
 
-                disconnect_if_already_auth!(model, self, _ctx);
+                disconnect_if_already_auth!(model, self, ctx);
                 
                 self.issue_system_async(UserConnected {
                     user_id: auth.id.clone(),
+                    session_id: auth.session.clone(),
+                    socket: model.socket.clone()
+                });
+                model.socket.authenticated(auth.clone());
+                model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token, refresh_token: None }).into());
+
+                /* Messages received while the connection was lost */
+                self.issue_system_async(ReplaySessionMessages {
+                    user_id: auth.id,
+                    session_id: auth.session,
+                    last_sequence: model.last_sequence,
                     socket: model.socket.clone()
                 });
-                model.socket.authenticated(auth);
-                model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), Authenticated { token }).into());
                 Ok(())
             },
-            None => Err(anyhow!(AuthError::TokenNotValid))
+            _ => Err(anyhow!(AuthError::TokenNotValid))
         }
     }
 }
@@ -293,6 +830,7 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<StartUse
     #[tracing::instrument(name="StartUserTimeout", skip(self, ctx))]
     fn handle(&mut self, model: StartUserTimeout, ctx: &mut Context<Self>) -> Self::Result {
         let user = model.auth.clone();
+        let socket = model.socket.clone();
         
         let timer = ctx.run_later(self.config.connection_restore_wait_timeout, move |manager, _ctx| {            
 
@@ -305,10 +843,14 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<StartUse
             });
         });
         
-        match user.deref() {
-            Some(user) => self.session_timeout_timers.insert(user.session.clone(), timer),
-            None => None
-        };
+        if let Some(user) = user.deref() {
+            self.session_timeout_timers.insert(user.session.clone(), timer);
+            self.issue_system_async(SessionDetached {
+                user_id: Arc::new(user.user.clone()),
+                session_id: user.session.clone(),
+                socket
+            });
+        }
 
         Ok(())
     }
@@ -326,6 +868,17 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<AuthUser
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<TerminateUserSessions> for AuthManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="TerminateUserSessions", skip(self, ctx))]
+    fn handle(&mut self, model: TerminateUserSessions, ctx: &mut Context<Self>) -> Self::Result {
+        for session_id in self.states.get_user_sessions(&model.user_id).into_iter() {
+            self.terminate_session(&model.user_id, session_id, &model.socket, ctx);
+        }
+    }
+}
+
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<StopUserTimeout> for AuthManager<DB> {
     type Result = anyhow::Result<()>;
 
@@ -4,8 +4,11 @@
 /* **************************************************************************************************************** */
 use std::{fmt::Debug, sync::Arc};
 use actix::prelude::Message;
+use jsonwebtoken::jwk::JwkSet;
 use serde::Serialize;
-use yummy_model::{auth::UserAuth, SessionId, password::Password};
+use yummy_model::{auth::UserAuth, SessionId, UserId, password::Password};
+use yummy_model::state::UserSession;
+use yummy_model::request::{RequestIdentity, RequestUnlinkIdentity};
 use thiserror::Error;
 use validator::{Validate, ValidationError};
 use yummy_general::client::ClientTrait;
@@ -60,6 +63,9 @@ pub struct RestoreTokenRequest {
     #[validate(length(min = 275, max = 1024, message = "Length should be between 275 to 1024 chars"))]
     pub token: String,
 
+    /* Last buffered message sequence received by the client, older messages are not sent again */
+    pub last_sequence: Option<usize>,
+
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
@@ -113,6 +119,131 @@ pub struct CustomIdAuthRequest {
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="AuthExternal")]
+pub struct ExternalAuthRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(length(min = 32, max = 4096, message = "Length should be between 32 to 4096 chars"))]
+    pub token: String,
+
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="LinkIdentity")]
+pub struct LinkIdentityRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub identity: RequestIdentity,
+
+    /* Identity owned by another account merges that account into the current user */
+    pub merge: bool,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="UnlinkIdentity")]
+pub struct UnlinkIdentityRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub identity: RequestUnlinkIdentity,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="VerifyEmail")]
+pub struct VerifyEmailRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(length(min = 1, max = 128, message = "Length should be between 1 to 128 chars"))]
+    pub token: String,
+
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="SendVerificationEmail")]
+pub struct SendVerificationEmailRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(email(message="Email address is not valid"))]
+    pub email: String,
+
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="ForgotPassword")]
+pub struct ForgotPasswordRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(email(message="Email address is not valid"))]
+    pub email: String,
+
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="ResetPassword")]
+pub struct ResetPasswordRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(length(min = 1, max = 128, message = "Length should be between 1 to 128 chars"))]
+    pub token: String,
+
+    #[validate(custom(function="validate_unique_password", message="Length should be between 3 to 32 chars"))]
+    pub password: Password,
+
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="ListSessions")]
+pub struct ListSessionsRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Validate)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="TerminateSession")]
+pub struct TerminateSessionRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub session_id: SessionId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+/* Last activity of the session, socket sends it periodically while receiving requests */
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct SessionActivity {
+    pub auth: Arc<Option<UserAuth>>
+}
+
+/* Remote JWKS documents are downloaded by the server and shared with the manager */
+#[derive(Message, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct UpdateExternalJwks {
+    pub issuer: String,
+    pub jwks: JwkSet
+}
+
 #[derive(Message, Validate, Debug, Clone)]
 #[rtype(result = "()")]
 pub struct ConnUserDisconnect {
@@ -130,6 +261,14 @@ pub struct RoomUserDisconnect {
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
+/* All sessions of the user should be closed, including the ones connected to other servers */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct TerminateUserSessions {
+    pub user_id: UserId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
 #[derive(Message, Validate, Debug, Clone)]
 #[rtype(result = "()")]
 pub struct AuthUserDisconnect {
@@ -140,7 +279,15 @@ pub struct AuthUserDisconnect {
 
 #[derive(Serialize, Debug, Clone)]
 pub struct Authenticated {
-    pub token: String
+    pub token: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<UserSession>
 }
 
 /* **************************************************************************************************************** */
@@ -158,7 +305,31 @@ pub enum AuthError {
     TokenNotValid,
 
     #[error("User not logged in")]
-    UserNotLoggedIn
+    UserNotLoggedIn,
+
+    #[error("User is suspended")]
+    UserSuspended,
+
+    #[error("Identity already linked to user")]
+    IdentityAlreadyLinked,
+
+    #[error("Identity linked to another user")]
+    IdentityLinkedToAnotherUser,
+
+    #[error("Identity not linked to user")]
+    IdentityNotLinked,
+
+    #[error("Last identity could not be removed")]
+    LastIdentity,
+
+    #[error("Email address is not verified")]
+    EmailNotVerified,
+
+    #[error("Maximum session count reached")]
+    MaxSessionsReached,
+
+    #[error("Session not found")]
+    SessionNotFound
 }
 
 /* **************************************************************************************************************** */
@@ -7,6 +7,7 @@ mod test;
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -19,7 +20,7 @@ use yummy_cache::state::YummyState;
 use yummy_general::client::ClientTrait;
 use yummy_model::config::YummyConfig;
 use yummy_model::SendMessage;
-use yummy_model::UserId;
+use yummy_model::{UserId, SessionId};
 
 use actix_broker::*;
 
@@ -33,8 +34,24 @@ use crate::auth::model::ConnUserDisconnect;
 use crate::auth::model::RoomUserDisconnect;
 
 use crate::plugin::PluginExecuter;
+use crate::user::model::MessageFromUser;
 
+use self::model::BufferedMessage;
+use self::model::ReplaySessionMessages;
+use self::model::SendUserMessage;
+use self::model::SessionDetached;
+#[cfg(feature = "stateless")]
+use self::model::TerminateRemoteSession;
 use self::model::UserConnected;
+use self::model::UserMessageUndelivered;
+
+/* Outbound messages of the session, they are kept until the client acknowledges them */
+struct SessionBuffer {
+    user_id: Arc<UserId>,
+    detached: bool,
+    last_sequence: usize,
+    messages: VecDeque<(usize, String)>
+}
 
 pub struct ConnectionManager {
     #[allow(dead_code)]
@@ -43,6 +60,8 @@ pub struct ConnectionManager {
     #[allow(dead_code)]
     states: YummyState,
     users: HashMap<UserId, Arc<dyn ClientTrait + Sync + Send>>,
+    sessions: HashMap<SessionId, Arc<dyn ClientTrait + Sync + Send>>,
+    buffers: HashMap<SessionId, SessionBuffer>,
     executer: Arc<PluginExecuter>,
 
     // Fields for stateless informations
@@ -56,21 +75,125 @@ impl ConnectionManager {
             config,
             states,
             users: HashMap::default(),
+            sessions: HashMap::default(),
+            buffers: HashMap::default(),
             executer,
 
             #[cfg(feature = "stateless")] redis
         }
     }
+
+    /* Returns false if the user is not reachable from any server */
+    fn send_message(&mut self, model: &SendMessage) -> bool {
+        self.send_local_message(model) || self.send_remote_message(model)
+    }
+
+    fn send_local_message(&mut self, model: &SendMessage) -> bool {
+        match self.users.get(model.user_id.as_ref()) {
+            Some(socket) => {
+                socket.send(model.message.clone());
+                true
+            },
+            None => self.buffer_message(model)
+        }
+    }
+
+    /* Connection lost but the session is waiting to be restored, message will be sent after restoring the session */
+    fn buffer_message(&mut self, model: &SendMessage) -> bool {
+        let mut buffered = false;
+
+        for buffer in self.buffers.values_mut().filter(|buffer| buffer.detached && buffer.user_id == model.user_id) {
+            buffer.last_sequence += 1;
+            buffer.messages.push_back((buffer.last_sequence, model.message.clone()));
+
+            /* Oldest messages are dropped, client could detect it with the sequence numbers */
+            while buffer.messages.len() > self.config.outbound_buffer_size {
+                buffer.messages.pop_front();
+            }
+
+            buffered = true;
+        }
+
+        buffered
+    }
+
+    #[cfg(not(feature = "stateless"))]
+    fn send_remote_message(&self, _: &SendMessage) -> bool {
+        false
+    }
+
+    #[cfg(not(feature = "stateless"))]
+    fn send_remote_user_message(&self, _: &SendUserMessage) -> bool {
+        false
+    }
+
+    #[cfg(feature = "stateless")]
+    fn send_remote_message(&self, model: &SendMessage) -> bool {
+        self.publish_to_user_server(&model.user_id, &stateless::ServerMessage::Message(model.clone()))
+    }
+
+    /* Receiver server stores the message into the inbox if the user disconnected in the meantime */
+    #[cfg(feature = "stateless")]
+    fn send_remote_user_message(&self, model: &SendUserMessage) -> bool {
+        self.publish_to_user_server(&model.user_id, &stateless::ServerMessage::UserMessage(model.clone()))
+    }
+
+    #[cfg(feature = "stateless")]
+    fn publish_to_user_server(&self, user_id: &Arc<UserId>, message: &stateless::ServerMessage) -> bool {
+        match self.states.get_user_location(user_id.clone()) {
+            /* Local users are already checked, message should not be published to the same server again */
+            Some(server_name) if server_name != self.config.server_name => {
+                if let (Ok(mut redis), Ok(message)) = (self.redis.get(), serde_json::to_string(message)) {
+                    /* Server could be gone without cleaning the user location, nobody receives the message in that case */
+                    return matches!(redis.publish::<_, _, i32>(format!("m-{}", server_name), message), Ok(receivers) if receivers > 0);
+                }
+                false
+            },
+            _ => false
+        }
+    }
+}
+
+fn build_user_message(model: &SendUserMessage) -> SendMessage {
+    SendMessage {
+        user_id: model.user_id.clone(),
+        message: SendMessage::build("MessageFromUser", MessageFromUser {
+            id: None,
+            user_id: model.sender_user_id.deref().clone(),
+            message: model.message.clone(),
+            insert_date: None
+        })
+    }
 }
 
 #[cfg(feature = "stateless")]
 mod stateless {
+    use std::sync::Arc;
+
     use actix::{Message, Handler};
-    use yummy_model::SendMessage;
+    use redis::Commands;
+    use serde::{Serialize, Deserialize};
+    use yummy_model::auth::UserAuth;
+    use yummy_model::{SendMessage, UserId, SessionId};
     use actix::AsyncContext;
+    use actix_broker::BrokerIssue;
 
-    use super::ConnectionManager;
+    use crate::auth::model::ConnUserDisconnect;
 
+    use super::ConnectionManager;
+    use super::model::{SendUserMessage, TerminateRemoteSession, UserMessageUndelivered};
+
+    /* Server channel carries the messages and the session terminations, variant is stored in the type field */
+    #[derive(Serialize, Deserialize, Debug)]
+    #[serde(tag = "type")]
+    pub enum ServerMessage {
+        UserMessage(SendUserMessage),
+        Message(SendMessage),
+        TerminateSession {
+            user_id: UserId,
+            session_id: SessionId
+        }
+    }
 
     #[derive(Message, Debug, Clone)]
     #[rtype(result = "()")]
@@ -86,7 +209,7 @@ mod stateless {
     
         #[tracing::instrument(name="MessageToClientReceived", skip(self, ctx))]
         fn handle(&mut self, model: MessageToClientReceived, ctx: &mut Self::Context) -> Self::Result {
-            let message: SendMessage = match serde_json::from_str(&model.0) {
+            let message: ServerMessage = match serde_json::from_str(&model.0) {
                 Ok(message) => message,
                 Err(error) => {
                     println!("Message parse error : {}", error);
@@ -94,7 +217,48 @@ mod stateless {
                 }
             };
     
-            ctx.address().do_send(message);
+            match message {
+                ServerMessage::Message(message) => ctx.address().do_send(message),
+                ServerMessage::UserMessage(message) => {
+                    /* User left this server after the message was published, it should not be forwarded again */
+                    if !self.send_local_message(&super::build_user_message(&message)) {
+                        self.issue_system_async(UserMessageUndelivered(message));
+                    }
+                },
+                ServerMessage::TerminateSession { user_id, session_id } => {
+                    /* Session could be already closed on this server */
+                    let socket = match self.sessions.get(&session_id) {
+                        Some(socket) => socket.clone(),
+                        None => return
+                    };
+
+                    socket.disconnect();
+                    ctx.address().do_send(ConnUserDisconnect {
+                        request_id: None,
+                        auth: Arc::new(Some(UserAuth { user: user_id, session: session_id })),
+                        send_message: false,
+                        socket
+                    });
+                }
+            };
+        }
+    }
+
+    impl Handler<TerminateRemoteSession> for ConnectionManager {
+        type Result = ();
+
+        #[tracing::instrument(name="TerminateRemoteSession", skip(self, _ctx))]
+        fn handle(&mut self, model: TerminateRemoteSession, _ctx: &mut Self::Context) -> Self::Result {
+            let message = ServerMessage::TerminateSession {
+                user_id: model.user_id,
+                session_id: model.session_id
+            };
+
+            if let Ok(mut redis) = self.redis.get() {
+                if let Ok(message) = serde_json::to_string(&message) {
+                    redis.publish::<_, _, i32>(format!("m-{}", model.server_name), message).unwrap_or_default();
+                }
+            }
         }
     }
 }
@@ -105,7 +269,13 @@ impl Actor for ConnectionManager {
     fn started(&mut self, ctx: &mut Self::Context) {
         self.subscribe_system_async::<UserConnected>(ctx);
         self.subscribe_system_async::<ConnUserDisconnect>(ctx);
+        self.subscribe_system_async::<SessionDetached>(ctx);
+        self.subscribe_system_async::<ReplaySessionMessages>(ctx);
         self.subscribe_system_async::<SendMessage>(ctx);
+        self.subscribe_system_async::<SendUserMessage>(ctx);
+
+        #[cfg(feature = "stateless")]
+        self.subscribe_system_async::<TerminateRemoteSession>(ctx);
 
         #[cfg(feature = "stateless")]
         yummy_general::pubsub::subscribe::<stateless::MessageToClientReceived, _>(self, ctx, self.config.clone(), format!("m-{}", self.config.server_name));
@@ -118,6 +288,58 @@ impl Handler<UserConnected> for ConnectionManager {
     #[tracing::instrument(name="UserConnected", skip(self, _ctx))]
     #[yummy_macros::plugin_api(name="user_connected", no_return=true)]
     fn handle(&mut self, model: UserConnected, _ctx: &mut Self::Context) -> Self::Result {
+        self.sessions.insert(model.session_id.clone(), model.socket.clone());
+
+        /* Restored session will be reachable after sending the buffered messages, otherwise new messages could be received before them */
+        if !self.buffers.get(&model.session_id).map(|buffer| buffer.detached).unwrap_or_default() {
+            self.users.insert(model.user_id.deref().clone(), model.socket.clone());
+        }
+    }
+}
+
+impl Handler<SessionDetached> for ConnectionManager {
+    type Result = ();
+
+    #[tracing::instrument(name="SessionDetached", skip(self, _ctx))]
+    fn handle(&mut self, model: SessionDetached, _ctx: &mut Self::Context) -> Self::Result {
+        if let Some(socket) = self.users.get(model.user_id.as_ref()) {
+            if Arc::ptr_eq(socket, &model.socket) {
+                self.users.remove(model.user_id.as_ref());
+            }
+        }
+
+        if self.config.outbound_buffer_size > 0 {
+            self.buffers.entry(model.session_id).or_insert_with(|| SessionBuffer {
+                user_id: model.user_id.clone(),
+                detached: false,
+                last_sequence: 0,
+                messages: VecDeque::new()
+            }).detached = true;
+        }
+    }
+}
+
+impl Handler<ReplaySessionMessages> for ConnectionManager {
+    type Result = ();
+
+    #[tracing::instrument(name="ReplaySessionMessages", skip(self, _ctx))]
+    fn handle(&mut self, model: ReplaySessionMessages, _ctx: &mut Self::Context) -> Self::Result {
+        if let Some(buffer) = self.buffers.get_mut(&model.session_id) {
+            buffer.detached = false;
+
+            /* Acknowledged messages are not needed anymore, the rest of them are kept until the next acknowledge */
+            if let Some(last_sequence) = model.last_sequence {
+                buffer.messages.retain(|(sequence, _)| *sequence > last_sequence);
+            }
+
+            for (sequence, message) in buffer.messages.iter() {
+                model.socket.send(SendMessage::build("BufferedMessage", BufferedMessage {
+                    sequence: *sequence,
+                    message: serde_json::from_str(message).unwrap_or_default()
+                }));
+            }
+        }
+
         self.users.insert(model.user_id.deref().clone(), model.socket.clone());
     }
 }
@@ -128,8 +350,8 @@ impl Handler<ConnUserDisconnect> for ConnectionManager {
     #[tracing::instrument(name="ConnUserDisconnect", skip(self, _ctx))]
     #[yummy_macros::plugin_api(name="user_disconnected", no_return=true)]
     fn handle(&mut self, model: ConnUserDisconnect, _ctx: &mut Self::Context) -> Self::Result {
-        let user_id = match model.auth.deref() {
-            Some(user) => &user.user,
+        let (user_id, user) = match model.auth.deref() {
+            Some(user) => (&user.user, user),
             None => {
                 if model.send_message {
                     model.socket.send(Answer::fail(model.request_id, Cow::Borrowed(RequestAuthTypeVariant::Logout.into())).into());
@@ -138,12 +360,23 @@ impl Handler<ConnUserDisconnect> for ConnectionManager {
             }
         };
 
-        let user_removed = self.users.remove(user_id);
+        self.buffers.remove(&user.session);
+        let session_socket = self.sessions.remove(&user.session);
+        let user_removed = match (self.users.get(user_id), &session_socket) {
+            /* User's socket belongs to another session of the user, it should stay reachable */
+            (Some(socket), Some(session_socket)) if !Arc::ptr_eq(socket, session_socket) => Some(socket.clone()),
+            _ => self.users.remove(user_id)
+        };
 
-        if user_removed.is_none() {
-            if model.send_message {
-                model.socket.send(Answer::fail(model.request_id, Cow::Borrowed(RequestAuthTypeVariant::Logout.into())).into());
+        /* Session terminated from another connection, the session's own connection should be closed */
+        if let Some(session_socket) = &session_socket {
+            if !Arc::ptr_eq(session_socket, &model.socket) {
+                session_socket.disconnect();
             }
+        }
+
+        if user_removed.is_none() && model.send_message {
+            model.socket.send(Answer::fail(model.request_id, Cow::Borrowed(RequestAuthTypeVariant::Logout.into())).into());
             return;
         }
         
@@ -151,16 +384,19 @@ impl Handler<ConnUserDisconnect> for ConnectionManager {
             model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestAuthTypeVariant::Logout.into())).into());
         }
         
+        /* Room and auth cleanup belongs to the terminated session, not to the requester */
+        let socket = session_socket.unwrap_or_else(|| model.socket.clone());
+
         self.issue_system_async(RoomUserDisconnect {
             request_id: model.request_id,
             auth: model.auth.clone(),
-            socket: model.socket.clone()
+            socket: socket.clone()
         });
 
         self.issue_system_async(AuthUserDisconnect {
             request_id: model.request_id,
             auth: model.auth.clone(),
-            socket: model.socket.clone()
+            socket
         });
     }
 }
@@ -170,22 +406,20 @@ impl Handler<SendMessage> for ConnectionManager {
 
     #[tracing::instrument(name="SendMessage", skip(self, _ctx))]
     fn handle(&mut self, model: SendMessage, _ctx: &mut Self::Context) -> Self::Result {
-        #[allow(clippy::single_match)]
-        match self.users.get(model.user_id.as_ref()) {
-            Some(socket) => socket.send(model.message),
-            None => {
-                #[cfg(feature = "stateless")]
-                match self.states.get_user_location(model.user_id.clone()) {
-                    Some(server_name) => {
-                        if let Ok(mut redis) = self.redis.get() {
-                            if let Ok(message) = serde_json::to_string(&model) {
-                                redis.publish::<_, _, i32>(format!("m-{}", server_name), message).unwrap_or_default();
-                            }
-                        }
-                    },
-                    None => println!("no socket {:?}", model.user_id.get())
-                }
-            }
+        if !self.send_message(&model) {
+            log::debug!("No socket found for {:?}, message dropped", model.user_id.get());
+        }
+    }
+}
+
+impl Handler<SendUserMessage> for ConnectionManager {
+    type Result = ();
+
+    #[tracing::instrument(name="SendUserMessage", skip(self, _ctx))]
+    fn handle(&mut self, model: SendUserMessage, _ctx: &mut Self::Context) -> Self::Result {
+        // User is offline, message will be stored into the inbox
+        if !self.send_local_message(&build_user_message(&model)) && !self.send_remote_user_message(&model) {
+            self.issue_system_async(UserMessageUndelivered(model));
         }
     }
 }
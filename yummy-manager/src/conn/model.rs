@@ -2,15 +2,65 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use actix::prelude::Message;
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
 use yummy_general::client::ClientTrait;
 use validator::Validate;
 
-use yummy_model::UserId;
+use yummy_model::{UserId, SessionId};
 
 
 #[derive(Message, Validate, Debug, Clone)]
 #[rtype(result = "()")]
 pub struct UserConnected {
     pub user_id: Arc<UserId>,
+    pub session_id: SessionId,
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
+
+#[derive(Message, Serialize, Deserialize, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct SendUserMessage {
+    pub user_id: Arc<UserId>,
+    pub sender_user_id: Arc<UserId>,
+    pub message: Value
+}
+
+/* Connection is lost but the session is waiting to be restored, messages are buffered until that time */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct SessionDetached {
+    pub user_id: Arc<UserId>,
+    pub session_id: SessionId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+/* Session restored, buffered messages after the last received sequence should be sent to the new connection */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct ReplaySessionMessages {
+    pub user_id: Arc<UserId>,
+    pub session_id: SessionId,
+    pub last_sequence: Option<usize>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+/* Terminated session is connected to another server, that server should close the connection */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct TerminateRemoteSession {
+    pub server_name: String,
+    pub user_id: UserId,
+    pub session_id: SessionId
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BufferedMessage {
+    pub sequence: usize,
+    pub message: Value
+}
+
+/* Receiver is not online anymore, message should be stored into the inbox */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct UserMessageUndelivered(pub SendUserMessage);
@@ -2,6 +2,7 @@ pub mod auth;
 pub mod user;
 pub mod room;
 pub mod conn;
+pub mod matchmaking;
 pub mod plugin;
 
 mod macros;
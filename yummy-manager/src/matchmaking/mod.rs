@@ -0,0 +1,336 @@
+/* **************************************************************************************************************** */
+/* **************************************************** MODS ****************************************************** */
+/* **************************************************************************************************************** */
+pub mod model;
+
+/* **************************************************************************************************************** */
+/* *************************************************** IMPORTS **************************************************** */
+/* **************************************************************************************************************** */
+#[cfg(test)]
+mod test;
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{marker::PhantomData, ops::Deref};
+use std::sync::Arc;
+use actix::{Context, Actor, Handler, AsyncContext};
+use actix_broker::{BrokerSubscribe, BrokerIssue};
+use yummy_cache::state::YummyState;
+use yummy_database::DatabaseTrait;
+
+use yummy_model::config::YummyConfig;
+use yummy_model::matchmaking::MatchmakingTicket;
+use yummy_model::{RoomId, RoomUserType, SendMessage, CreateRoomAccessType};
+use yummy_model::web::Answer;
+use yummy_general::database::{Pool, PooledConnection};
+
+use crate::YummyModel;
+use crate::auth::model::{AuthError, AuthUserDisconnect};
+use crate::plugin::PluginExecuter;
+use crate::get_user_session_id_from_auth;
+
+use self::model::*;
+
+/* **************************************************************************************************************** */
+/* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
+/* **************************************************** MACROS **************************************************** */
+/* *************************************************** STRUCTS **************************************************** */
+/* **************************************************************************************************************** */
+pub struct MatchmakingManager<DB: DatabaseTrait + ?Sized> {
+    config: Arc<YummyConfig>,
+    database: Arc<Pool>,
+    states: YummyState,
+    executer: Arc<PluginExecuter>,
+    _marker: PhantomData<DB>
+}
+
+/* **************************************************************************************************************** */
+/* **************************************************** ENUMS ***************************************************** */
+/* ************************************************** FUNCTIONS *************************************************** */
+/* *************************************************** TRAITS ***************************************************** */
+/* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl<DB: DatabaseTrait + ?Sized> MatchmakingManager<DB> {
+    pub fn new(config: Arc<YummyConfig>, states: YummyState, database: Arc<Pool>, executer: Arc<PluginExecuter>) -> Self {
+        Self {
+            config,
+            database,
+            states,
+            executer,
+            _marker: PhantomData
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> MatchmakingManager<DB> {
+    fn get_now() -> i32 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default()
+    }
+
+    /* Tolerance is getting wider while the ticket waits in the queue */
+    fn get_tolerance(&self, ticket: &MatchmakingTicket, now: i32) -> f64 {
+        let waited = (now - ticket.insert_date).max(0) as f64;
+        (self.config.matchmaking_initial_tolerance + (self.config.matchmaking_tolerance_step * waited)).min(self.config.matchmaking_max_tolerance)
+    }
+
+    /* Both tickets should accept each other, so the narrowest tolerance is used */
+    fn is_compatible(&self, queue: &str, left: &MatchmakingTicket, right: &MatchmakingTicket, now: i32) -> bool {
+        if left.user_id == right.user_id {
+            return false;
+        }
+
+        match self.check_compatibility(queue, left, right, now) {
+            Ok(compatible) => compatible,
+            Err(error) => {
+                log::error!("Matchmaking compatibility could not checked: {:?}", error);
+                false
+            }
+        }
+    }
+
+    fn check_compatibility(&self, queue: &str, left: &MatchmakingTicket, right: &MatchmakingTicket, now: i32) -> anyhow::Result<bool> {
+        let tolerance = self.get_tolerance(left, now).min(self.get_tolerance(right, now));
+
+        let model = self.executer.pre_check_match_compatibility(CheckMatchCompatibility {
+            queue: queue.to_string(),
+            left: left.clone(),
+            right: right.clone(),
+            tolerance,
+            compatible: left.is_compatible(right, tolerance)
+        })?;
+
+        let compatible = model.compatible;
+        self.executer.post_check_match_compatibility(model, compatible)?;
+        Ok(compatible)
+    }
+
+    fn find_matches(&mut self, ctx: &mut Context<Self>) {
+        let room_size = self.config.matchmaking_room_size;
+        let now = Self::get_now();
+
+        let queues = match self.states.get_matchmaking_queues() {
+            Ok(queues) => queues,
+            Err(error) => {
+                log::error!("Matchmaking queues could not readed: {:?}", error);
+                return;
+            }
+        };
+
+        for queue in queues.into_iter() {
+            let mut tickets = self.states.get_matchmaking_tickets(&queue).unwrap_or_default();
+            if tickets.len() < room_size {
+                continue;
+            }
+
+            /* Oldest tickets have priority */
+            tickets.sort_by_key(|ticket| ticket.insert_date);
+
+            let mut used = vec![false; tickets.len()];
+
+            for index in 0..tickets.len() {
+                if used[index] {
+                    continue;
+                }
+
+                let mut group = vec![index];
+
+                for candidate in (index + 1)..tickets.len() {
+                    if used[candidate] {
+                        continue;
+                    }
+
+                    if group.iter().all(|item| self.is_compatible(&queue, &tickets[*item], &tickets[candidate], now)) {
+                        group.push(candidate);
+
+                        if group.len() == room_size {
+                            break;
+                        }
+                    }
+                }
+
+                if group.len() != room_size {
+                    continue;
+                }
+
+                for item in group.iter() {
+                    used[*item] = true;
+                }
+
+                ctx.notify(MatchFound {
+                    queue: queue.clone(),
+                    tickets: group.into_iter().map(|item| tickets[item].clone()).collect(),
+                    room_name: None,
+                    max_user: room_size,
+                    tags: Vec::new()
+                });
+            }
+        }
+    }
+
+    fn create_match_room(&mut self, model: &MatchFound) -> anyhow::Result<RoomId> {
+        let mut connection = self.database.get()?;
+
+        /* The oldest ticket owns the room */
+        let room_user_type = |index: usize| match index {
+            0 => RoomUserType::Owner,
+            _ => RoomUserType::User
+        };
+
+        /* Sessions could be closed after the tickets are claimed, room should not be created for them */
+        for ticket in model.tickets.iter() {
+            if !self.states.is_session_online(&ticket.session_id) {
+                return Err(anyhow::anyhow!(MatchmakingError::SessionClosed));
+            }
+        }
+
+        let room_id = DB::transaction(&mut connection, |connection| {
+            let room_id = DB::create_room(connection, model.room_name.clone(), CreateRoomAccessType::Private, model.max_user, 0, false, &model.tags, None)?;
+
+            for (index, ticket) in model.tickets.iter().enumerate() {
+                DB::join_to_room(connection, &room_id, &ticket.user_id, room_user_type(index))?;
+            }
+
+            anyhow::Ok(room_id)
+        })?;
+
+        /* States are updated after the commit, failed transaction should not leave a room behind */
+        self.states.create_room(&room_id, Self::get_now(), model.room_name.clone(), None, CreateRoomAccessType::Private, model.max_user, 0, model.tags.clone(), None, false, None, false);
+
+        for (index, ticket) in model.tickets.iter().enumerate() {
+            if let Err(error) = self.states.join_to_room(&room_id, &ticket.user_id, &ticket.session_id, room_user_type(index)) {
+                self.remove_match_room(&mut connection, &room_id, &model.tickets[..index]);
+                return Err(error.into());
+            }
+        }
+
+        Ok(room_id)
+    }
+
+    /* Half created match room is removed from the states and the database, tickets are requeued by the caller */
+    fn remove_match_room(&mut self, connection: &mut PooledConnection, room_id: &RoomId, joined_tickets: &[MatchmakingTicket]) {
+        for ticket in joined_tickets.iter() {
+            if let Err(error) = self.states.disconnect_from_room(room_id, &ticket.user_id, &ticket.session_id) {
+                log::error!("Matchmaking user could not disconnected from room: {:?}", error);
+            }
+        }
+
+        if let Err(error) = DB::delete_room(connection, room_id) {
+            log::error!("Matchmaking room could not deleted: {:?}", error);
+        }
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ********************************************** TRAIT IMPLEMENTS ************************************************ */
+/* **************************************************************************************************************** */
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Actor for MatchmakingManager<DB> {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.subscribe_system_async::<AuthUserDisconnect>(ctx);
+        ctx.run_interval(self.config.matchmaking_tick_interval, |manager, ctx| manager.find_matches(ctx));
+    }
+}
+
+/* Tickets belong to the session, they are removed when the session is closed */
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<AuthUserDisconnect> for MatchmakingManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="Matchmaking::User AuthUserDisconnect", skip(self, _ctx))]
+    fn handle(&mut self, model: AuthUserDisconnect, _ctx: &mut Self::Context) -> Self::Result {
+        if let Some(user) = model.auth.deref() {
+            for queue in self.states.get_matchmaking_queues().unwrap_or_default().into_iter() {
+                self.states.remove_matchmaking_ticket(&queue, &user.session).unwrap_or_default();
+            }
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<JoinMatchmakingQueue> for MatchmakingManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="JoinQueue", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="join_queue")]
+    fn handle(&mut self, model: JoinMatchmakingQueue, _ctx: &mut Context<Self>) -> Self::Result {
+        let (user_id, session_id) = get_user_session_id_from_auth!(model);
+
+        self.states.add_matchmaking_ticket(&model.queue, MatchmakingTicket {
+            user_id: Arc::new(user_id.clone()),
+            session_id: session_id.clone(),
+            properties: model.properties.clone(),
+            insert_date: Self::get_now()
+        })?;
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<LeaveMatchmakingQueue> for MatchmakingManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="LeaveQueue", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="leave_queue")]
+    fn handle(&mut self, model: LeaveMatchmakingQueue, _ctx: &mut Context<Self>) -> Self::Result {
+        let (_, session_id) = get_user_session_id_from_auth!(model);
+
+        match self.states.remove_matchmaking_ticket(&model.queue, session_id)? {
+            true => {
+                model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+                Ok(())
+            },
+            false => Err(anyhow::anyhow!(MatchmakingError::NotInQueue))
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<MatchFound> for MatchmakingManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="MatchFound", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="match_found", no_return=true)]
+    fn handle(&mut self, model: MatchFound, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_ids = model.tickets.iter().map(|ticket| ticket.session_id.clone()).collect::<Vec<_>>();
+
+        /* Other server instance or leave request could take the tickets */
+        match self.states.claim_matchmaking_tickets(&model.queue, &session_ids) {
+            Ok(true) => (),
+            Ok(false) => return,
+            Err(error) => {
+                log::error!("Matchmaking tickets could not claimed: {:?}", error);
+                return;
+            }
+        };
+
+        let room_id = match self.create_match_room(&model) {
+            Ok(room_id) => room_id,
+            Err(error) => {
+                log::error!("Matchmaking room could not created: {:?}", error);
+
+                /* Players should not lose their place in the queue, original tickets keep their priority */
+                for ticket in model.tickets.iter() {
+                    if !self.states.is_session_online(&ticket.session_id) {
+                        continue;
+                    }
+
+                    if let Err(error) = self.states.add_matchmaking_ticket(&model.queue, ticket.clone()) {
+                        log::error!("Matchmaking ticket could not requeued: {:?}", error);
+                    }
+                }
+                return;
+            }
+        };
+
+        let message = SendMessage::build("MatchFound", MatchFoundResponse {
+            queue: &model.queue,
+            room_id,
+            users: model.tickets.iter().map(|ticket| ticket.user_id.clone()).collect()
+        });
+
+        for ticket in model.tickets.iter() {
+            self.issue_system_async(SendMessage {
+                user_id: ticket.user_id.clone(),
+                message: message.clone()
+            });
+        }
+    }
+}
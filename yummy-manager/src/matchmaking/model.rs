@@ -0,0 +1,70 @@
+use std::{fmt::Debug, sync::Arc, collections::HashMap};
+
+use actix::prelude::Message;
+use serde_json::Value;
+use yummy_general::client::ClientTrait;
+use yummy_model::{auth::UserAuth, matchmaking::MatchmakingTicket, RoomId, UserId};
+use serde::Serialize;
+use thiserror::Error;
+use yummy_macros::model;
+use validator::Validate;
+
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="JoinQueue")]
+pub struct JoinMatchmakingQueue {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+
+    #[validate(length(min = 1, max = 64, message = "Length should be between 1 to 64 chars"))]
+    pub queue: String,
+    pub properties: HashMap<String, Value>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="LeaveQueue")]
+pub struct LeaveMatchmakingQueue {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub queue: String,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct MatchFound {
+    pub queue: String,
+    pub tickets: Vec<MatchmakingTicket>,
+    pub room_name: Option<String>,
+    pub max_user: usize,
+    pub tags: Vec<String>
+}
+
+/* Plugins can change the compatibility decision of the two tickets */
+#[derive(Debug, Clone)]
+pub struct CheckMatchCompatibility {
+    pub queue: String,
+    pub left: MatchmakingTicket,
+    pub right: MatchmakingTicket,
+    pub tolerance: f64,
+    pub compatible: bool
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MatchFoundResponse<'a> {
+    pub queue: &'a str,
+    pub room_id: RoomId,
+    pub users: Vec<Arc<UserId>>
+}
+
+#[derive(Error, Debug)]
+pub enum MatchmakingError {
+    #[error("User is not in the queue")]
+    NotInQueue,
+
+    #[error("Session is closed")]
+    SessionClosed
+}
@@ -0,0 +1,396 @@
+use yummy_database::DefaultDatabaseStore;
+use yummy_cache::state_resource::ResourceFactory;
+
+use yummy_model::config::configure_environment;
+use uuid::Uuid;
+
+use yummy_model::{UserId, SessionId};
+use yummy_model::auth::UserAuth;
+use yummy_model::auth::validate_auth;
+use yummy_model::config::YummyConfig;
+use yummy_model::config::get_configuration;
+use yummy_testing::client::DummyClient;
+use yummy_testing::model::AuthenticatedModel;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env::temp_dir;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::Actor;
+use actix::Addr;
+use anyhow::Ok;
+use serde_json::json;
+use yummy_database::{create_database, create_connection};
+
+use super::*;
+use crate::auth::AuthManager;
+use crate::auth::model::*;
+use crate::conn::ConnectionManager;
+use crate::plugin::{PluginBuilder, PluginExecuter, YummyPlugin, YummyPluginError, YummyPluginInstaller};
+
+
+macro_rules! email_auth {
+    ($auth_manager: expr, $config: expr, $email: expr, $password: expr, $create: expr, $recipient: expr) => {
+        {
+            $auth_manager.send(EmailAuthRequest {
+                request_id: None,
+                auth: Arc::new(None),
+                email: $email,
+                password: $password,
+                if_not_exist_create: $create,
+                socket: $recipient.clone()
+            }).await??;
+
+            let token: AuthenticatedModel = $recipient.clone().messages.lock().unwrap().pop_back().unwrap().into();
+            let token = token.token;
+
+            let user_jwt = validate_auth($config, token).unwrap().user;
+            Arc::new(Some(UserAuth {
+                user: user_jwt.id.deref().clone(),
+                session: user_jwt.session
+            }))
+        }
+    };
+}
+
+/* Accepts every ticket pair */
+struct AcceptAllPlugin;
+
+impl YummyPlugin for AcceptAllPlugin {
+    fn pre_check_match_compatibility(&self, model: Rc<RefCell<CheckMatchCompatibility>>) -> Result<(), YummyPluginError> {
+        model.borrow_mut().compatible = true;
+        Result::Ok(())
+    }
+}
+
+struct AcceptAllInstaller;
+
+impl YummyPluginInstaller for AcceptAllInstaller {
+    fn install(&self, executer: &mut PluginExecuter, _config: Arc<YummyConfig>) {
+        executer.add_plugin("accept_all".to_string(), Box::new(AcceptAllPlugin));
+    }
+}
+
+fn create_actor(builder: PluginBuilder) -> anyhow::Result<(Addr<MatchmakingManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState)> {
+    let mut db_location = temp_dir();
+    db_location.push(format!("{}.db", Uuid::new_v4()));
+    let connection = create_connection(db_location.to_str().unwrap())?;
+
+    configure_environment();
+
+    let mut config = get_configuration().deref().clone();
+    config.matchmaking_tick_interval = Duration::from_millis(100);
+    config.matchmaking_room_size = 2;
+    config.matchmaking_initial_tolerance = 10.0;
+    config.matchmaking_tolerance_step = 50.0;
+    config.matchmaking_max_tolerance = 1000.0;
+
+    #[cfg(feature = "stateless")] {
+        use rand::Rng;
+        config.redis_prefix = format!("{}:", rand::thread_rng().gen::<usize>().to_string());
+    }
+
+    let config = Arc::new(config);
+
+    #[cfg(feature = "stateless")]
+    let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
+
+    let resource_factory = ResourceFactory::<DefaultDatabaseStore>::new(Arc::new(connection.clone()));
+    let states = YummyState::new(config.clone(), Box::new(resource_factory), #[cfg(feature = "stateless")] conn.clone());
+    let connection = Arc::new(connection);
+    let executer = Arc::new(builder.build(config.clone(), states.clone(), connection.clone()));
+
+    ConnectionManager::new(config.clone(), states.clone(), executer.clone(), #[cfg(feature = "stateless")] conn.clone()).start();
+
+    create_database(&mut connection.clone().get()?)?;
+    Ok((MatchmakingManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start(), AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer).start(), config, states.clone()))
+}
+
+#[actix::test]
+async fn close_skills_matched() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_1_socket = Arc::new(DummyClient::default());
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_1.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("skill".to_string(), json!(100)), ("region".to_string(), json!("eu"))]),
+        socket: user_1_socket.clone()
+    }).await??;
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_2.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("skill".to_string(), json!(105)), ("region".to_string(), json!("eu"))]),
+        socket: user_2_socket.clone()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+
+    let user_1_message: yummy_testing::model::MatchFound = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let user_2_message: yummy_testing::model::MatchFound = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+
+    assert_eq!(&user_1_message.class_type[..], "MatchFound");
+    assert_eq!(&user_1_message.queue[..], "ranked");
+    assert_eq!(user_1_message.room_id, user_2_message.room_id);
+    assert_eq!(user_1_message.users.len(), 2);
+
+    let session_1 = &user_1.deref().as_ref().unwrap().session;
+    let session_2 = &user_2.deref().as_ref().unwrap().session;
+    assert_eq!(states.get_user_rooms(session_1), Some(vec![user_1_message.room_id]));
+    assert_eq!(states.get_user_rooms(session_2), Some(vec![user_1_message.room_id]));
+    assert!(states.get_matchmaking_tickets("ranked")?.is_empty());
+    assert!(states.get_matchmaking_queues()?.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn tolerance_widening() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_1_socket = Arc::new(DummyClient::default());
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_1.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("skill".to_string(), json!(100))]),
+        socket: user_1_socket.clone()
+    }).await??;
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_2.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("skill".to_string(), json!(200))]),
+        socket: user_2_socket.clone()
+    }).await??;
+
+    // Skill difference is bigger than initial tolerance
+    actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(states.get_matchmaking_tickets("ranked")?.len(), 2);
+
+    // Tolerance reaches to the skill difference after 2 seconds, tickets are moved back in time instead of waiting
+    for mut ticket in states.get_matchmaking_tickets("ranked")?.into_iter() {
+        states.remove_matchmaking_ticket("ranked", &ticket.session_id)?;
+        ticket.insert_date -= 2;
+        states.add_matchmaking_ticket("ranked", ticket)?;
+    }
+
+    actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+    assert!(states.get_matchmaking_tickets("ranked")?.is_empty());
+
+    let user_1_message: yummy_testing::model::MatchFound = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let user_2_message: yummy_testing::model::MatchFound = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert_eq!(user_1_message.room_id, user_2_message.room_id);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn different_region_not_matched() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_1_socket = Arc::new(DummyClient::default());
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_1.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("region".to_string(), json!("eu"))]),
+        socket: user_1_socket.clone()
+    }).await??;
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_2.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("region".to_string(), json!("us"))]),
+        socket: user_2_socket.clone()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(states.get_matchmaking_tickets("ranked")?.len(), 2);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn plugin_changes_compatibility() -> anyhow::Result<()> {
+    let mut builder = PluginBuilder::default();
+    builder.add_installer(Box::new(AcceptAllInstaller));
+
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(builder)?;
+
+    let user_1_socket = Arc::new(DummyClient::default());
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_1.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("region".to_string(), json!("eu"))]),
+        socket: user_1_socket.clone()
+    }).await??;
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user_2.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("region".to_string(), json!("us"))]),
+        socket: user_2_socket.clone()
+    }).await??;
+
+    // Plugin accepts different regions
+    actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+    assert!(states.get_matchmaking_tickets("ranked")?.is_empty());
+
+    let user_1_message: yummy_testing::model::MatchFound = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let user_2_message: yummy_testing::model::MatchFound = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert_eq!(user_1_message.room_id, user_2_message.room_id);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn closed_session_not_matched() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_socket = Arc::new(DummyClient::default());
+    let user = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("region".to_string(), json!("eu"))]),
+        socket: user_socket.clone()
+    }).await??;
+
+    // Ticket of a session that is already closed
+    let closed_ticket = MatchmakingTicket {
+        user_id: Arc::new(UserId::new()),
+        session_id: SessionId::new(),
+        properties: HashMap::from([("region".to_string(), json!("us"))]),
+        insert_date: 0
+    };
+    states.add_matchmaking_ticket("ranked", closed_ticket.clone())?;
+
+    let mut tickets = states.get_matchmaking_tickets("ranked")?;
+    tickets.sort_by_key(|ticket| ticket.session_id == closed_ticket.session_id);
+
+    matchmaking_manager.send(MatchFound {
+        queue: "ranked".to_string(),
+        tickets,
+        room_name: None,
+        max_user: 2,
+        tags: Vec::new()
+    }).await?;
+
+    // Room is not created and only the online session is requeued
+    let session = &user.deref().as_ref().unwrap().session;
+    assert!(states.get_user_rooms(session).unwrap_or_default().is_empty());
+
+    let tickets = states.get_matchmaking_tickets("ranked")?;
+    assert_eq!(tickets.len(), 1);
+    assert_eq!(&tickets[0].session_id, session);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn disconnect_removes_ticket() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_socket = Arc::new(DummyClient::default());
+    let user = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::new(),
+        socket: user_socket.clone()
+    }).await??;
+
+    assert_eq!(states.get_matchmaking_tickets("ranked")?.len(), 1);
+
+    matchmaking_manager.send(AuthUserDisconnect {
+        request_id: None,
+        auth: user.clone(),
+        socket: user_socket.clone()
+    }).await?;
+
+    assert!(states.get_matchmaking_tickets("ranked")?.is_empty());
+    assert!(states.get_matchmaking_queues()?.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn leave_queue() -> anyhow::Result<()> {
+    let (matchmaking_manager, auth_manager, config, states) = create_actor(PluginBuilder::default())?;
+
+    let user_socket = Arc::new(DummyClient::default());
+    let user = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_socket);
+
+    matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::new(),
+        socket: user_socket.clone()
+    }).await??;
+
+    // Same session could not join twice
+    assert!(matchmaking_manager.send(JoinMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        properties: HashMap::new(),
+        socket: user_socket.clone()
+    }).await?.is_err());
+
+    assert_eq!(states.get_matchmaking_tickets("ranked")?.len(), 1);
+
+    matchmaking_manager.send(LeaveMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        socket: user_socket.clone()
+    }).await??;
+
+    assert!(states.get_matchmaking_tickets("ranked")?.is_empty());
+    assert!(states.get_matchmaking_queues()?.is_empty());
+
+    assert!(matchmaking_manager.send(LeaveMatchmakingQueue {
+        request_id: None,
+        auth: user.clone(),
+        queue: "ranked".to_string(),
+        socket: user_socket.clone()
+    }).await?.is_err());
+
+    Ok(())
+}
@@ -14,7 +14,7 @@ use yummy_database::{DatabaseTrait, DefaultDatabaseStore};
 use yummy_general::database::Pool;
 use yummy_model::{config::YummyConfig, UserId, meta::{UserMetaAccess, MetaType}};
 
-use crate::{auth::model::{EmailAuthRequest, DeviceIdAuthRequest, CustomIdAuthRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ConnUserDisconnect}, conn::model::UserConnected, user::{model::{GetUserInformation, UpdateUser}, UserLogic}, room::{model::{CreateRoomRequest, UpdateRoom, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play}, logic::RoomLogic}};
+use crate::{auth::model::{EmailAuthRequest, DeviceIdAuthRequest, CustomIdAuthRequest, ExternalAuthRequest, LinkIdentityRequest, UnlinkIdentityRequest, VerifyEmailRequest, SendVerificationEmailRequest, ForgotPasswordRequest, ResetPasswordRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ListSessionsRequest, TerminateSessionRequest, ConnUserDisconnect}, conn::model::UserConnected, user::{model::{GetUserInformation, UpdateUser, AddFriend, ProcessFriendRequest, RemoveFriend, FriendList, MessageToUser, GetInbox, AckInbox, SuspendUser, UnsuspendUser, SuspendedUsers, UpdateUserMeta}, UserLogic}, room::{model::{CreateRoomRequest, UpdateRoom, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play, SetReady, StartGame, FinishGame, SelectNewOwner, RoomMessageHistory, InviteToRoom, AcceptRoomInvite, DeclineRoomInvite, RoomInvites, CancelRoomInvite, UnbanUserFromRoom, UpdateRoomMeta}, logic::RoomLogic}, matchmaking::model::{JoinMatchmakingQueue, LeaveMatchmakingQueue, MatchFound, CheckMatchCompatibility}};
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -115,9 +115,18 @@ pub trait YummyPlugin {
     create_plugin_func!(pre_email_auth, post_email_auth, EmailAuthRequest);
     create_plugin_func!(pre_deviceid_auth, post_deviceid_auth, DeviceIdAuthRequest);
     create_plugin_func!(pre_customid_auth, post_customid_auth, CustomIdAuthRequest);
+    create_plugin_func!(pre_external_auth, post_external_auth, ExternalAuthRequest);
+    create_plugin_func!(pre_link_identity, post_link_identity, LinkIdentityRequest);
+    create_plugin_func!(pre_unlink_identity, post_unlink_identity, UnlinkIdentityRequest);
+    create_plugin_func!(pre_verify_email, post_verify_email, VerifyEmailRequest);
+    create_plugin_func!(pre_send_verification_email, post_send_verification_email, SendVerificationEmailRequest);
+    create_plugin_func!(pre_forgot_password, post_forgot_password, ForgotPasswordRequest);
+    create_plugin_func!(pre_reset_password, post_reset_password, ResetPasswordRequest);
     create_plugin_func!(pre_logout, post_logout, LogoutRequest);
     create_plugin_func!(pre_refresh_token, post_refresh_token, RefreshTokenRequest);
     create_plugin_func!(pre_restore_token, post_restore_token, RestoreTokenRequest);
+    create_plugin_func!(pre_list_sessions, post_list_sessions, ListSessionsRequest);
+    create_plugin_func!(pre_terminate_session, post_terminate_session, TerminateSessionRequest);
 
     // Connection manager
     create_plugin_func!(pre_user_connected, post_user_connected, UserConnected);
@@ -126,6 +135,17 @@ pub trait YummyPlugin {
     // User manager
     create_plugin_func!(pre_get_user_information, post_get_user_information, GetUserInformation);
     create_plugin_func!(pre_update_user, post_update_user, UpdateUser);
+    create_plugin_func!(pre_add_friend, post_add_friend, AddFriend);
+    create_plugin_func!(pre_process_friend_request, post_process_friend_request, ProcessFriendRequest);
+    create_plugin_func!(pre_remove_friend, post_remove_friend, RemoveFriend);
+    create_plugin_func!(pre_friend_list, post_friend_list, FriendList);
+    create_plugin_func!(pre_message_to_user, post_message_to_user, MessageToUser);
+    create_plugin_func!(pre_get_inbox, post_get_inbox, GetInbox);
+    create_plugin_func!(pre_ack_inbox, post_ack_inbox, AckInbox);
+    create_plugin_func!(pre_suspend_user, post_suspend_user, SuspendUser);
+    create_plugin_func!(pre_unsuspend_user, post_unsuspend_user, UnsuspendUser);
+    create_plugin_func!(pre_suspended_users, post_suspended_users, SuspendedUsers);
+    create_plugin_func!(pre_update_user_meta, post_update_user_meta, UpdateUserMeta);
 
     // Room Manager
     create_plugin_func!(pre_create_room, post_create_room, CreateRoomRequest);
@@ -133,12 +153,30 @@ pub trait YummyPlugin {
     create_plugin_func!(pre_join_to_room, post_join_to_room, JoinToRoomRequest);
     create_plugin_func!(pre_process_waiting_user, post_process_waiting_user, ProcessWaitingUser);
     create_plugin_func!(pre_kick_user_from_room, post_kick_user_from_room, KickUserFromRoom);
+    create_plugin_func!(pre_unban_user_from_room, post_unban_user_from_room, UnbanUserFromRoom);
     create_plugin_func!(pre_disconnect_from_room, post_disconnect_from_room, DisconnectFromRoomRequest);
     create_plugin_func!(pre_message_to_room, post_message_to_room, MessageToRoomRequest);
     create_plugin_func!(pre_room_list_request, post_room_list_request, RoomListRequest);
     create_plugin_func!(pre_waiting_room_joins, post_waiting_room_joins, WaitingRoomJoins);
     create_plugin_func!(pre_get_room_request, post_get_room_request, GetRoomRequest);
     create_plugin_func!(pre_play, post_play, Play);
+    create_plugin_func!(pre_set_ready, post_set_ready, SetReady);
+    create_plugin_func!(pre_start_game, post_start_game, StartGame);
+    create_plugin_func!(pre_finish_game, post_finish_game, FinishGame);
+    create_plugin_func!(pre_select_new_owner, post_select_new_owner, SelectNewOwner);
+    create_plugin_func!(pre_room_message_history, post_room_message_history, RoomMessageHistory);
+    create_plugin_func!(pre_invite_to_room, post_invite_to_room, InviteToRoom);
+    create_plugin_func!(pre_accept_room_invite, post_accept_room_invite, AcceptRoomInvite);
+    create_plugin_func!(pre_decline_room_invite, post_decline_room_invite, DeclineRoomInvite);
+    create_plugin_func!(pre_room_invites, post_room_invites, RoomInvites);
+    create_plugin_func!(pre_cancel_room_invite, post_cancel_room_invite, CancelRoomInvite);
+    create_plugin_func!(pre_update_room_meta, post_update_room_meta, UpdateRoomMeta);
+
+    // Matchmaking Manager
+    create_plugin_func!(pre_join_queue, post_join_queue, JoinMatchmakingQueue);
+    create_plugin_func!(pre_leave_queue, post_leave_queue, LeaveMatchmakingQueue);
+    create_plugin_func!(pre_match_found, post_match_found, MatchFound);
+    create_plugin_func!(pre_check_match_compatibility, post_check_match_compatibility, CheckMatchCompatibility);
 }
 
 /* **************************************************************************************************************** */
@@ -150,7 +188,7 @@ impl PluginExecuter {
             plugins: Vec::new(),
             context: YummyPluginContext {
                 user_logic: UserLogic::new(config.clone(), states.clone(), database.clone()),
-                room_logic: RoomLogic::new(states),
+                room_logic: RoomLogic::new(config.clone(), states, database.clone()),
                 _marker: PhantomData
             }
         }
@@ -168,9 +206,18 @@ impl PluginExecuter {
     create_executer_func!(pre_email_auth, post_email_auth, EmailAuthRequest);
     create_executer_func!(pre_deviceid_auth, post_deviceid_auth, DeviceIdAuthRequest);
     create_executer_func!(pre_customid_auth, post_customid_auth, CustomIdAuthRequest);
+    create_executer_func!(pre_external_auth, post_external_auth, ExternalAuthRequest);
+    create_executer_func!(pre_link_identity, post_link_identity, LinkIdentityRequest);
+    create_executer_func!(pre_unlink_identity, post_unlink_identity, UnlinkIdentityRequest);
+    create_executer_func!(pre_verify_email, post_verify_email, VerifyEmailRequest);
+    create_executer_func!(pre_send_verification_email, post_send_verification_email, SendVerificationEmailRequest);
+    create_executer_func!(pre_forgot_password, post_forgot_password, ForgotPasswordRequest);
+    create_executer_func!(pre_reset_password, post_reset_password, ResetPasswordRequest);
     create_executer_func!(pre_logout, post_logout, LogoutRequest);
     create_executer_func!(pre_refresh_token, post_refresh_token, RefreshTokenRequest);
     create_executer_func!(pre_restore_token, post_restore_token, RestoreTokenRequest);
+    create_executer_func!(pre_list_sessions, post_list_sessions, ListSessionsRequest);
+    create_executer_func!(pre_terminate_session, post_terminate_session, TerminateSessionRequest);
     
     // Connection manager
     create_executer_func!(pre_user_connected, post_user_connected, UserConnected);
@@ -179,6 +226,17 @@ impl PluginExecuter {
     // User manager
     create_executer_func!(pre_get_user_information, post_get_user_information, GetUserInformation);
     create_executer_func!(pre_update_user, post_update_user, UpdateUser);
+    create_executer_func!(pre_add_friend, post_add_friend, AddFriend);
+    create_executer_func!(pre_process_friend_request, post_process_friend_request, ProcessFriendRequest);
+    create_executer_func!(pre_remove_friend, post_remove_friend, RemoveFriend);
+    create_executer_func!(pre_friend_list, post_friend_list, FriendList);
+    create_executer_func!(pre_message_to_user, post_message_to_user, MessageToUser);
+    create_executer_func!(pre_get_inbox, post_get_inbox, GetInbox);
+    create_executer_func!(pre_ack_inbox, post_ack_inbox, AckInbox);
+    create_executer_func!(pre_suspend_user, post_suspend_user, SuspendUser);
+    create_executer_func!(pre_unsuspend_user, post_unsuspend_user, UnsuspendUser);
+    create_executer_func!(pre_suspended_users, post_suspended_users, SuspendedUsers);
+    create_executer_func!(pre_update_user_meta, post_update_user_meta, UpdateUserMeta);
 
     // Room Manager
     create_executer_func!(pre_create_room, post_create_room, CreateRoomRequest);
@@ -186,12 +244,30 @@ impl PluginExecuter {
     create_executer_func!(pre_join_to_room, post_join_to_room, JoinToRoomRequest);
     create_executer_func!(pre_process_waiting_user, post_process_waiting_user, ProcessWaitingUser);
     create_executer_func!(pre_kick_user_from_room, post_kick_user_from_room, KickUserFromRoom);
+    create_executer_func!(pre_unban_user_from_room, post_unban_user_from_room, UnbanUserFromRoom);
     create_executer_func!(pre_disconnect_from_room, post_disconnect_from_room, DisconnectFromRoomRequest);
     create_executer_func!(pre_message_to_room, post_message_to_room, MessageToRoomRequest);
     create_executer_func!(pre_room_list_request, post_room_list_request, RoomListRequest);
     create_executer_func!(pre_waiting_room_joins, post_waiting_room_joins, WaitingRoomJoins);
     create_executer_func!(pre_get_room_request, post_get_room_request, GetRoomRequest);
     create_executer_func!(pre_play, post_play, Play);
+    create_executer_func!(pre_set_ready, post_set_ready, SetReady);
+    create_executer_func!(pre_start_game, post_start_game, StartGame);
+    create_executer_func!(pre_finish_game, post_finish_game, FinishGame);
+    create_executer_func!(pre_select_new_owner, post_select_new_owner, SelectNewOwner);
+    create_executer_func!(pre_room_message_history, post_room_message_history, RoomMessageHistory);
+    create_executer_func!(pre_invite_to_room, post_invite_to_room, InviteToRoom);
+    create_executer_func!(pre_accept_room_invite, post_accept_room_invite, AcceptRoomInvite);
+    create_executer_func!(pre_decline_room_invite, post_decline_room_invite, DeclineRoomInvite);
+    create_executer_func!(pre_room_invites, post_room_invites, RoomInvites);
+    create_executer_func!(pre_cancel_room_invite, post_cancel_room_invite, CancelRoomInvite);
+    create_executer_func!(pre_update_room_meta, post_update_room_meta, UpdateRoomMeta);
+
+    // Matchmaking Manager
+    create_executer_func!(pre_join_queue, post_join_queue, JoinMatchmakingQueue);
+    create_executer_func!(pre_leave_queue, post_leave_queue, LeaveMatchmakingQueue);
+    create_executer_func!(pre_match_found, post_match_found, MatchFound);
+    create_executer_func!(pre_check_match_compatibility, post_check_match_compatibility, CheckMatchCompatibility);
 }
 
 impl<DB: yummy_database::DatabaseTrait> YummyPluginContext<DB> {
@@ -30,19 +30,20 @@ use std::collections::HashMap;
 use std::ops::Deref;
 
 use crate::plugin::python::model::YummyPluginContextWrapper;
-use crate::plugin::python::modules::model::model::{DeviceIdAuthRequestWrapper, EmailAuthRequestWrapper, CustomIdAuthRequestWrapper, LogoutRequestWrapper, UserConnectedWrapper, ConnUserDisconnectWrapper, RefreshTokenRequestWrapper, RestoreTokenRequestWrapper, GetUserInformationWrapper, UpdateUserWrapper, CreateRoomRequestWrapper};
-use crate::room::model::Play;
+use crate::plugin::python::modules::model::model::{DeviceIdAuthRequestWrapper, EmailAuthRequestWrapper, CustomIdAuthRequestWrapper, ExternalAuthRequestWrapper, LinkIdentityRequestWrapper, UnlinkIdentityRequestWrapper, VerifyEmailRequestWrapper, SendVerificationEmailRequestWrapper, ForgotPasswordRequestWrapper, ResetPasswordRequestWrapper, LogoutRequestWrapper, UserConnectedWrapper, ConnUserDisconnectWrapper, RefreshTokenRequestWrapper, RestoreTokenRequestWrapper, ListSessionsRequestWrapper, TerminateSessionRequestWrapper, GetUserInformationWrapper, UpdateUserWrapper, CreateRoomRequestWrapper, AddFriendWrapper, ProcessFriendRequestWrapper, RemoveFriendWrapper, FriendListWrapper, MessageToUserWrapper, GetInboxWrapper, AckInboxWrapper, SuspendUserWrapper, UnsuspendUserWrapper, SuspendedUsersWrapper, UpdateUserMetaWrapper};
+use crate::room::model::{Play, SetReady, StartGame, FinishGame, SelectNewOwner, RoomMessageHistory, InviteToRoom, AcceptRoomInvite, DeclineRoomInvite, RoomInvites, CancelRoomInvite, UnbanUserFromRoom, UpdateRoomMeta};
+use crate::matchmaking::model::{JoinMatchmakingQueue, LeaveMatchmakingQueue, MatchFound, CheckMatchCompatibility};
 use crate::{
-    auth::model::{ConnUserDisconnect, CustomIdAuthRequest, DeviceIdAuthRequest, EmailAuthRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest},
+    auth::model::{ConnUserDisconnect, CustomIdAuthRequest, ExternalAuthRequest, LinkIdentityRequest, UnlinkIdentityRequest, VerifyEmailRequest, SendVerificationEmailRequest, ForgotPasswordRequest, ResetPasswordRequest, DeviceIdAuthRequest, EmailAuthRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ListSessionsRequest, TerminateSessionRequest},
     conn::model::UserConnected,
     room::model::{
         CreateRoomRequest, DisconnectFromRoomRequest, GetRoomRequest, JoinToRoomRequest, KickUserFromRoom, MessageToRoomRequest, ProcessWaitingUser, RoomListRequest, UpdateRoom, WaitingRoomJoins,
     },
-    user::model::{GetUserInformation, UpdateUser},
+    user::model::{GetUserInformation, UpdateUser, AddFriend, ProcessFriendRequest, RemoveFriend, FriendList, MessageToUser, GetInbox, AckInbox, SuspendUser, UnsuspendUser, SuspendedUsers, UpdateUserMeta},
 };
 use self::model::ModelWrapper;
 use self::modules::configure_modules;
-use self::modules::model::model::{UpdateRoomWrapper, JoinToRoomRequestWrapper, ProcessWaitingUserWrapper, KickUserFromRoomWrapper, DisconnectFromRoomRequestWrapper, MessageToRoomRequestWrapper, RoomListRequestWrapper, WaitingRoomJoinsWrapper, GetRoomRequestWrapper, PlayWrapper};
+use self::modules::model::model::{UpdateRoomWrapper, JoinToRoomRequestWrapper, ProcessWaitingUserWrapper, KickUserFromRoomWrapper, UnbanUserFromRoomWrapper, DisconnectFromRoomRequestWrapper, MessageToRoomRequestWrapper, RoomListRequestWrapper, WaitingRoomJoinsWrapper, GetRoomRequestWrapper, PlayWrapper, SetReadyWrapper, StartGameWrapper, FinishGameWrapper, SelectNewOwnerWrapper, RoomMessageHistoryWrapper, InviteToRoomWrapper, AcceptRoomInviteWrapper, DeclineRoomInviteWrapper, RoomInvitesWrapper, CancelRoomInviteWrapper, UpdateRoomMetaWrapper, JoinMatchmakingQueueWrapper, LeaveMatchmakingQueueWrapper, MatchFoundWrapper, CheckMatchCompatibilityWrapper};
 
 use super::{YummyPlugin, YummyPluginInstaller, YummyPluginError, PluginExecuter};
 
@@ -83,24 +84,60 @@ pub enum FunctionType {
     EmailAuth,
     DeviceidAuth,
     CustomidAuth,
+    ExternalAuth,
+    LinkIdentity,
+    UnlinkIdentity,
+    VerifyEmail,
+    SendVerificationEmail,
+    ForgotPassword,
+    ResetPassword,
     Logout,
     RefreshToken,
     RestoreToken,
+    ListSessions,
+    TerminateSession,
     UserConnected,
     UserDisconnected,
     GetUserInformation,
     UpdateUser,
+    AddFriend,
+    ProcessFriendRequest,
+    RemoveFriend,
+    FriendList,
+    MessageToUser,
+    GetInbox,
+    AckInbox,
+    SuspendUser,
+    UnsuspendUser,
+    SuspendedUsers,
+    UpdateUserMeta,
     CreateRoom,
     UpdateRoom,
     JoinToRoom,
     ProcessWaitingUser,
     KickUserFromRoom,
+    UnbanUserFromRoom,
     DisconnectFromRoomRequest,
     MessageToRoomRequest,
     RoomListRequest,
     WaitingRoomJoins,
     GetRoomRequest,
-    Play
+    Play,
+    SetReady,
+    StartGame,
+    FinishGame,
+    SelectNewOwner,
+    RoomMessageHistory,
+    InviteToRoom,
+    AcceptRoomInvite,
+    DeclineRoomInvite,
+    RoomInvites,
+    CancelRoomInvite,
+    UpdateRoomMeta,
+    JoinQueue,
+    LeaveQueue,
+    MatchFound,
+    CheckMatchCompatibility
 }
 
 /* **************************************************************************************************************** */
@@ -263,24 +300,60 @@ impl FunctionType {
             FunctionType::EmailAuth => "pre_email_auth",
             FunctionType::DeviceidAuth => "pre_deviceid_auth",
             FunctionType::CustomidAuth => "pre_customid_auth",
+            FunctionType::ExternalAuth => "pre_external_auth",
+            FunctionType::LinkIdentity => "pre_link_identity",
+            FunctionType::UnlinkIdentity => "pre_unlink_identity",
+            FunctionType::VerifyEmail => "pre_verify_email",
+            FunctionType::SendVerificationEmail => "pre_send_verification_email",
+            FunctionType::ForgotPassword => "pre_forgot_password",
+            FunctionType::ResetPassword => "pre_reset_password",
             FunctionType::Logout => "pre_logout",
             FunctionType::RefreshToken => "pre_refresh_token",
             FunctionType::RestoreToken => "pre_restore_token",
+            FunctionType::ListSessions => "pre_list_sessions",
+            FunctionType::TerminateSession => "pre_terminate_session",
             FunctionType::UserConnected => "pre_user_connected",
             FunctionType::UserDisconnected => "pre_user_disconnected",
             FunctionType::GetUserInformation => "pre_get_user_information",
             FunctionType::UpdateUser => "pre_update_user",
+            FunctionType::AddFriend => "pre_add_friend",
+            FunctionType::ProcessFriendRequest => "pre_process_friend_request",
+            FunctionType::RemoveFriend => "pre_remove_friend",
+            FunctionType::FriendList => "pre_friend_list",
+            FunctionType::MessageToUser => "pre_message_to_user",
+            FunctionType::GetInbox => "pre_get_inbox",
+            FunctionType::AckInbox => "pre_ack_inbox",
+            FunctionType::SuspendUser => "pre_suspend_user",
+            FunctionType::UnsuspendUser => "pre_unsuspend_user",
+            FunctionType::SuspendedUsers => "pre_suspended_users",
+            FunctionType::UpdateUserMeta => "pre_update_user_meta",
             FunctionType::CreateRoom => "pre_create_room",
             FunctionType::UpdateRoom => "pre_update_room",
             FunctionType::JoinToRoom => "pre_join_to_room",
             FunctionType::ProcessWaitingUser => "pre_process_waiting_user",
             FunctionType::KickUserFromRoom => "pre_kick_user_from_room",
+            FunctionType::UnbanUserFromRoom => "pre_unban_user_from_room",
             FunctionType::DisconnectFromRoomRequest => "pre_disconnect_from_room",
             FunctionType::MessageToRoomRequest => "pre_message_to_room",
             FunctionType::RoomListRequest => "pre_room_list_request",
             FunctionType::WaitingRoomJoins => "pre_waiting_room_joins",
             FunctionType::GetRoomRequest => "pre_get_room_request",
             FunctionType::Play => "pre_play",
+            FunctionType::SetReady => "pre_set_ready",
+            FunctionType::StartGame => "pre_start_game",
+            FunctionType::FinishGame => "pre_finish_game",
+            FunctionType::SelectNewOwner => "pre_select_new_owner",
+            FunctionType::RoomMessageHistory => "pre_room_message_history",
+            FunctionType::InviteToRoom => "pre_invite_to_room",
+            FunctionType::AcceptRoomInvite => "pre_accept_room_invite",
+            FunctionType::DeclineRoomInvite => "pre_decline_room_invite",
+            FunctionType::RoomInvites => "pre_room_invites",
+            FunctionType::CancelRoomInvite => "pre_cancel_room_invite",
+            FunctionType::UpdateRoomMeta => "pre_update_room_meta",
+            FunctionType::JoinQueue => "pre_join_queue",
+            FunctionType::LeaveQueue => "pre_leave_queue",
+            FunctionType::MatchFound => "pre_match_found",
+            FunctionType::CheckMatchCompatibility => "pre_check_match_compatibility",
         }
     }
 
@@ -289,24 +362,60 @@ impl FunctionType {
             FunctionType::EmailAuth => "post_email_auth",
             FunctionType::DeviceidAuth => "post_deviceid_auth",
             FunctionType::CustomidAuth => "post_customid_auth",
+            FunctionType::ExternalAuth => "post_external_auth",
+            FunctionType::LinkIdentity => "post_link_identity",
+            FunctionType::UnlinkIdentity => "post_unlink_identity",
+            FunctionType::VerifyEmail => "post_verify_email",
+            FunctionType::SendVerificationEmail => "post_send_verification_email",
+            FunctionType::ForgotPassword => "post_forgot_password",
+            FunctionType::ResetPassword => "post_reset_password",
             FunctionType::Logout => "post_logout",
             FunctionType::RefreshToken => "post_refresh_token",
             FunctionType::RestoreToken => "post_restore_token",
+            FunctionType::ListSessions => "post_list_sessions",
+            FunctionType::TerminateSession => "post_terminate_session",
             FunctionType::UserConnected => "post_user_connected",
             FunctionType::UserDisconnected => "post_user_disconnected",
             FunctionType::GetUserInformation => "post_get_user_information",
             FunctionType::UpdateUser => "post_update_user",
+            FunctionType::AddFriend => "post_add_friend",
+            FunctionType::ProcessFriendRequest => "post_process_friend_request",
+            FunctionType::RemoveFriend => "post_remove_friend",
+            FunctionType::FriendList => "post_friend_list",
+            FunctionType::MessageToUser => "post_message_to_user",
+            FunctionType::GetInbox => "post_get_inbox",
+            FunctionType::AckInbox => "post_ack_inbox",
+            FunctionType::SuspendUser => "post_suspend_user",
+            FunctionType::UnsuspendUser => "post_unsuspend_user",
+            FunctionType::SuspendedUsers => "post_suspended_users",
+            FunctionType::UpdateUserMeta => "post_update_user_meta",
             FunctionType::CreateRoom => "post_create_room",
             FunctionType::UpdateRoom => "post_update_room",
             FunctionType::JoinToRoom => "post_join_to_room",
             FunctionType::ProcessWaitingUser => "post_process_waiting_user",
             FunctionType::KickUserFromRoom => "post_kick_user_from_room",
+            FunctionType::UnbanUserFromRoom => "post_unban_user_from_room",
             FunctionType::DisconnectFromRoomRequest => "post_disconnect_from_room",
             FunctionType::MessageToRoomRequest => "post_message_to_room",
             FunctionType::RoomListRequest => "post_room_list_request",
             FunctionType::WaitingRoomJoins => "post_waiting_room_joins",
             FunctionType::GetRoomRequest => "post_get_room_request",
             FunctionType::Play => "post_play",
+            FunctionType::SetReady => "post_set_ready",
+            FunctionType::StartGame => "post_start_game",
+            FunctionType::FinishGame => "post_finish_game",
+            FunctionType::SelectNewOwner => "post_select_new_owner",
+            FunctionType::RoomMessageHistory => "post_room_message_history",
+            FunctionType::InviteToRoom => "post_invite_to_room",
+            FunctionType::AcceptRoomInvite => "post_accept_room_invite",
+            FunctionType::DeclineRoomInvite => "post_decline_room_invite",
+            FunctionType::RoomInvites => "post_room_invites",
+            FunctionType::CancelRoomInvite => "post_cancel_room_invite",
+            FunctionType::UpdateRoomMeta => "post_update_room_meta",
+            FunctionType::JoinQueue => "post_join_queue",
+            FunctionType::LeaveQueue => "post_leave_queue",
+            FunctionType::MatchFound => "post_match_found",
+            FunctionType::CheckMatchCompatibility => "post_check_match_compatibility",
         }
     }
 }
@@ -319,9 +428,18 @@ impl YummyPlugin for PythonPlugin {
     create_func!(pre_email_auth, post_email_auth, FunctionType::EmailAuth, EmailAuthRequest, EmailAuthRequestWrapper);
     create_func!(pre_deviceid_auth, post_deviceid_auth, FunctionType::DeviceidAuth, DeviceIdAuthRequest, DeviceIdAuthRequestWrapper);
     create_func!(pre_customid_auth, post_customid_auth, FunctionType::CustomidAuth, CustomIdAuthRequest, CustomIdAuthRequestWrapper);
+    create_func!(pre_external_auth, post_external_auth, FunctionType::ExternalAuth, ExternalAuthRequest, ExternalAuthRequestWrapper);
+    create_func!(pre_link_identity, post_link_identity, FunctionType::LinkIdentity, LinkIdentityRequest, LinkIdentityRequestWrapper);
+    create_func!(pre_unlink_identity, post_unlink_identity, FunctionType::UnlinkIdentity, UnlinkIdentityRequest, UnlinkIdentityRequestWrapper);
+    create_func!(pre_verify_email, post_verify_email, FunctionType::VerifyEmail, VerifyEmailRequest, VerifyEmailRequestWrapper);
+    create_func!(pre_send_verification_email, post_send_verification_email, FunctionType::SendVerificationEmail, SendVerificationEmailRequest, SendVerificationEmailRequestWrapper);
+    create_func!(pre_forgot_password, post_forgot_password, FunctionType::ForgotPassword, ForgotPasswordRequest, ForgotPasswordRequestWrapper);
+    create_func!(pre_reset_password, post_reset_password, FunctionType::ResetPassword, ResetPasswordRequest, ResetPasswordRequestWrapper);
     create_func!(pre_logout, post_logout, FunctionType::Logout, LogoutRequest, LogoutRequestWrapper);
     create_func!(pre_refresh_token, post_refresh_token, FunctionType::RefreshToken, RefreshTokenRequest, RefreshTokenRequestWrapper);
     create_func!(pre_restore_token, post_restore_token, FunctionType::RestoreToken, RestoreTokenRequest, RestoreTokenRequestWrapper);
+    create_func!(pre_list_sessions, post_list_sessions, FunctionType::ListSessions, ListSessionsRequest, ListSessionsRequestWrapper);
+    create_func!(pre_terminate_session, post_terminate_session, FunctionType::TerminateSession, TerminateSessionRequest, TerminateSessionRequestWrapper);
 
     // Connection manager
     create_func!(pre_user_connected, post_user_connected, FunctionType::UserConnected, UserConnected, UserConnectedWrapper);
@@ -330,6 +448,17 @@ impl YummyPlugin for PythonPlugin {
     // User manager
     create_func!(pre_get_user_information, post_get_user_information, FunctionType::GetUserInformation, GetUserInformation, GetUserInformationWrapper);
     create_func!(pre_update_user, post_update_user, FunctionType::UpdateUser, UpdateUser, UpdateUserWrapper);
+    create_func!(pre_add_friend, post_add_friend, FunctionType::AddFriend, AddFriend, AddFriendWrapper);
+    create_func!(pre_process_friend_request, post_process_friend_request, FunctionType::ProcessFriendRequest, ProcessFriendRequest, ProcessFriendRequestWrapper);
+    create_func!(pre_remove_friend, post_remove_friend, FunctionType::RemoveFriend, RemoveFriend, RemoveFriendWrapper);
+    create_func!(pre_friend_list, post_friend_list, FunctionType::FriendList, FriendList, FriendListWrapper);
+    create_func!(pre_message_to_user, post_message_to_user, FunctionType::MessageToUser, MessageToUser, MessageToUserWrapper);
+    create_func!(pre_get_inbox, post_get_inbox, FunctionType::GetInbox, GetInbox, GetInboxWrapper);
+    create_func!(pre_ack_inbox, post_ack_inbox, FunctionType::AckInbox, AckInbox, AckInboxWrapper);
+    create_func!(pre_suspend_user, post_suspend_user, FunctionType::SuspendUser, SuspendUser, SuspendUserWrapper);
+    create_func!(pre_unsuspend_user, post_unsuspend_user, FunctionType::UnsuspendUser, UnsuspendUser, UnsuspendUserWrapper);
+    create_func!(pre_suspended_users, post_suspended_users, FunctionType::SuspendedUsers, SuspendedUsers, SuspendedUsersWrapper);
+    create_func!(pre_update_user_meta, post_update_user_meta, FunctionType::UpdateUserMeta, UpdateUserMeta, UpdateUserMetaWrapper);
 
     // Room Manager
     create_func!(pre_create_room, post_create_room, FunctionType::CreateRoom, CreateRoomRequest, CreateRoomRequestWrapper);
@@ -337,12 +466,30 @@ impl YummyPlugin for PythonPlugin {
     create_func!(pre_join_to_room, post_join_to_room, FunctionType::JoinToRoom, JoinToRoomRequest, JoinToRoomRequestWrapper);
     create_func!(pre_process_waiting_user, post_process_waiting_user, FunctionType::ProcessWaitingUser, ProcessWaitingUser, ProcessWaitingUserWrapper);
     create_func!(pre_kick_user_from_room, post_kick_user_from_room, FunctionType::KickUserFromRoom, KickUserFromRoom, KickUserFromRoomWrapper);
+    create_func!(pre_unban_user_from_room, post_unban_user_from_room, FunctionType::UnbanUserFromRoom, UnbanUserFromRoom, UnbanUserFromRoomWrapper);
     create_func!(pre_disconnect_from_room, post_disconnect_from_room, FunctionType::DisconnectFromRoomRequest, DisconnectFromRoomRequest, DisconnectFromRoomRequestWrapper);
     create_func!(pre_message_to_room, post_message_to_room, FunctionType::MessageToRoomRequest, MessageToRoomRequest, MessageToRoomRequestWrapper);
     create_func!(pre_room_list_request, post_room_list_request, FunctionType::RoomListRequest, RoomListRequest, RoomListRequestWrapper);
     create_func!(pre_waiting_room_joins, post_waiting_room_joins, FunctionType::WaitingRoomJoins, WaitingRoomJoins, WaitingRoomJoinsWrapper);
     create_func!(pre_get_room_request, post_get_room_request, FunctionType::GetRoomRequest, GetRoomRequest, GetRoomRequestWrapper);
     create_func!(pre_play, post_play, FunctionType::Play, Play, PlayWrapper);
+    create_func!(pre_set_ready, post_set_ready, FunctionType::SetReady, SetReady, SetReadyWrapper);
+    create_func!(pre_start_game, post_start_game, FunctionType::StartGame, StartGame, StartGameWrapper);
+    create_func!(pre_finish_game, post_finish_game, FunctionType::FinishGame, FinishGame, FinishGameWrapper);
+    create_func!(pre_select_new_owner, post_select_new_owner, FunctionType::SelectNewOwner, SelectNewOwner, SelectNewOwnerWrapper);
+    create_func!(pre_room_message_history, post_room_message_history, FunctionType::RoomMessageHistory, RoomMessageHistory, RoomMessageHistoryWrapper);
+    create_func!(pre_invite_to_room, post_invite_to_room, FunctionType::InviteToRoom, InviteToRoom, InviteToRoomWrapper);
+    create_func!(pre_accept_room_invite, post_accept_room_invite, FunctionType::AcceptRoomInvite, AcceptRoomInvite, AcceptRoomInviteWrapper);
+    create_func!(pre_decline_room_invite, post_decline_room_invite, FunctionType::DeclineRoomInvite, DeclineRoomInvite, DeclineRoomInviteWrapper);
+    create_func!(pre_room_invites, post_room_invites, FunctionType::RoomInvites, RoomInvites, RoomInvitesWrapper);
+    create_func!(pre_cancel_room_invite, post_cancel_room_invite, FunctionType::CancelRoomInvite, CancelRoomInvite, CancelRoomInviteWrapper);
+    create_func!(pre_update_room_meta, post_update_room_meta, FunctionType::UpdateRoomMeta, UpdateRoomMeta, UpdateRoomMetaWrapper);
+
+    // Matchmaking Manager
+    create_func!(pre_join_queue, post_join_queue, FunctionType::JoinQueue, JoinMatchmakingQueue, JoinMatchmakingQueueWrapper);
+    create_func!(pre_leave_queue, post_leave_queue, FunctionType::LeaveQueue, LeaveMatchmakingQueue, LeaveMatchmakingQueueWrapper);
+    create_func!(pre_match_found, post_match_found, FunctionType::MatchFound, MatchFound, MatchFoundWrapper);
+    create_func!(pre_check_match_compatibility, post_check_match_compatibility, FunctionType::CheckMatchCompatibility, CheckMatchCompatibility, CheckMatchCompatibilityWrapper);
 }
 
 impl YummyPluginInstaller for PythonPluginInstaller {
@@ -4,7 +4,7 @@ pub mod _constants {
     /* **************************************************** MODS ****************************************************** */
     /* *************************************************** IMPORTS **************************************************** */
     /* **************************************************************************************************************** */
-    use yummy_model::{meta::{UserMetaAccess, MetaAction}, UserType, CreateRoomAccessType, RoomUserType};
+    use yummy_model::{meta::{UserMetaAccess, MetaAction}, UserType, CreateRoomAccessType, RoomUserType, RoomGameState};
 
     /* **************************************************************************************************************** */
     /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -58,6 +58,8 @@ pub mod _constants {
     const ROOM_USER_TYPE_MODERATOR: u32 = RoomUserType::Moderator as u32;
     #[pyattr]
     const ROOM_USER_TYPE_OWNER: u32 = RoomUserType::Owner as u32;
+    #[pyattr]
+    const ROOM_USER_TYPE_SPECTATOR: u32 = RoomUserType::Spectator as u32;
 
     #[pyattr]
     const ROOM_INFO_TYPE_ROOM_NAME: u32 = 0; // RoomInfoTypeVariant::RoomName;
@@ -81,6 +83,30 @@ pub mod _constants {
     const ROOM_INFO_TYPE_JOIN_REQUEST: u32 = 9; // RoomInfoTypeVariant::JoinRequest;
     #[pyattr]
     const ROOM_INFO_TYPE_BANNED_USERS: u32 = 10; // RoomInfoTypeVariant::BannedUsers;
+    #[pyattr]
+    const ROOM_INFO_TYPE_PASSWORD: u32 = 11; // RoomInfoTypeVariant::Password;
+    #[pyattr]
+    const ROOM_INFO_TYPE_SPECTATORS: u32 = 12; // RoomInfoTypeVariant::Spectators;
+    #[pyattr]
+    const ROOM_INFO_TYPE_MAX_SPECTATOR: u32 = 13; // RoomInfoTypeVariant::MaxSpectator;
+    #[pyattr]
+    const ROOM_INFO_TYPE_SPECTATOR_LENGTH: u32 = 14; // RoomInfoTypeVariant::SpectatorLength;
+    #[pyattr]
+    const ROOM_INFO_TYPE_GAME_STATE: u32 = 15; // RoomInfoTypeVariant::GameState;
+    #[pyattr]
+    const ROOM_INFO_TYPE_READY_USERS: u32 = 16; // RoomInfoTypeVariant::ReadyUsers;
+    #[pyattr]
+    const ROOM_INFO_TYPE_AUTO_START: u32 = 17; // RoomInfoTypeVariant::AutoStart;
+
+    /* RoomGameState */
+    #[pyattr]
+    const ROOM_GAME_STATE_WAITING: u32 = RoomGameState::Waiting as u32;
+    #[pyattr]
+    const ROOM_GAME_STATE_READY_CHECK: u32 = RoomGameState::ReadyCheck as u32;
+    #[pyattr]
+    const ROOM_GAME_STATE_PLAYING: u32 = RoomGameState::Playing as u32;
+    #[pyattr]
+    const ROOM_GAME_STATE_FINISHED: u32 = RoomGameState::Finished as u32;
 
     /* **************************************************************************************************************** */
     /* **************************************************** MACROS **************************************************** */
@@ -15,18 +15,39 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "DeviceIdAuth" => DeviceIdAuthRequestWrapper::make_class(&vm.ctx),
         "EmailAuth" => EmailAuthRequestWrapper::make_class(&vm.ctx),
         "CustomIdAuth" => CustomIdAuthRequestWrapper::make_class(&vm.ctx),
+        "ExternalAuth" => ExternalAuthRequestWrapper::make_class(&vm.ctx),
+        "LinkIdentity" => LinkIdentityRequestWrapper::make_class(&vm.ctx),
+        "UnlinkIdentity" => UnlinkIdentityRequestWrapper::make_class(&vm.ctx),
+        "VerifyEmail" => VerifyEmailRequestWrapper::make_class(&vm.ctx),
+        "SendVerificationEmail" => SendVerificationEmailRequestWrapper::make_class(&vm.ctx),
+        "ForgotPassword" => ForgotPasswordRequestWrapper::make_class(&vm.ctx),
+        "ResetPassword" => ResetPasswordRequestWrapper::make_class(&vm.ctx),
         "Logout" => LogoutRequestWrapper::make_class(&vm.ctx),
         "UserConnected" => UserConnectedWrapper::make_class(&vm.ctx),
         "UserDisconnected" => ConnUserDisconnectWrapper::make_class(&vm.ctx),
         "RefreshToken" => RefreshTokenRequestWrapper::make_class(&vm.ctx),
         "RestoreToken" => RestoreTokenRequestWrapper::make_class(&vm.ctx),
+        "ListSessions" => ListSessionsRequestWrapper::make_class(&vm.ctx),
+        "TerminateSession" => TerminateSessionRequestWrapper::make_class(&vm.ctx),
         "GetUserInformation" => GetUserInformationWrapper::make_class(&vm.ctx),
         "UpdateUser" => UpdateUserWrapper::make_class(&vm.ctx),
+        "AddFriend" => AddFriendWrapper::make_class(&vm.ctx),
+        "ProcessFriendRequest" => ProcessFriendRequestWrapper::make_class(&vm.ctx),
+        "RemoveFriend" => RemoveFriendWrapper::make_class(&vm.ctx),
+        "FriendList" => FriendListWrapper::make_class(&vm.ctx),
+        "MessageToUser" => MessageToUserWrapper::make_class(&vm.ctx),
+        "GetInbox" => GetInboxWrapper::make_class(&vm.ctx),
+        "AckInbox" => AckInboxWrapper::make_class(&vm.ctx),
+        "SuspendUser" => SuspendUserWrapper::make_class(&vm.ctx),
+        "UnsuspendUser" => UnsuspendUserWrapper::make_class(&vm.ctx),
+        "SuspendedUsers" => SuspendedUsersWrapper::make_class(&vm.ctx),
+        "UpdateUserMeta" => UpdateUserMetaWrapper::make_class(&vm.ctx),
         "CreateRoom" => CreateRoomRequestWrapper::make_class(&vm.ctx),
         "UpdateRoom" => UpdateRoomWrapper::make_class(&vm.ctx),
         "JoinToRoom" => JoinToRoomRequestWrapper::make_class(&vm.ctx),
         "ProcessWaitingUser" => ProcessWaitingUserWrapper::make_class(&vm.ctx),
         "KickUserFromRoom" => KickUserFromRoomWrapper::make_class(&vm.ctx),
+        "UnbanUserFromRoom" => UnbanUserFromRoomWrapper::make_class(&vm.ctx),
         "DisconnectFromRoom" => DisconnectFromRoomRequestWrapper::make_class(&vm.ctx),
         "MessageToRoom" => MessageToRoomRequestWrapper::make_class(&vm.ctx),
         "RoomListRequest" => RoomListRequestWrapper::make_class(&vm.ctx),
@@ -34,6 +55,21 @@ pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
         "GetRoomRequest" => GetRoomRequestWrapper::make_class(&vm.ctx),
         "YummyPluginContext" => YummyPluginContextWrapper::make_class(&vm.ctx),
         "Play" => PlayWrapper::make_class(&vm.ctx),
+        "SetReady" => SetReadyWrapper::make_class(&vm.ctx),
+        "StartGame" => StartGameWrapper::make_class(&vm.ctx),
+        "FinishGame" => FinishGameWrapper::make_class(&vm.ctx),
+        "SelectNewOwner" => SelectNewOwnerWrapper::make_class(&vm.ctx),
+        "RoomMessageHistory" => RoomMessageHistoryWrapper::make_class(&vm.ctx),
+        "InviteToRoom" => InviteToRoomWrapper::make_class(&vm.ctx),
+        "AcceptRoomInvite" => AcceptRoomInviteWrapper::make_class(&vm.ctx),
+        "DeclineRoomInvite" => DeclineRoomInviteWrapper::make_class(&vm.ctx),
+        "RoomInvites" => RoomInvitesWrapper::make_class(&vm.ctx),
+        "CancelRoomInvite" => CancelRoomInviteWrapper::make_class(&vm.ctx),
+        "UpdateRoomMeta" => UpdateRoomMetaWrapper::make_class(&vm.ctx),
+        "JoinQueue" => JoinMatchmakingQueueWrapper::make_class(&vm.ctx),
+        "LeaveQueue" => LeaveMatchmakingQueueWrapper::make_class(&vm.ctx),
+        "MatchFound" => MatchFoundWrapper::make_class(&vm.ctx),
+        "CheckMatchCompatibility" => CheckMatchCompatibilityWrapper::make_class(&vm.ctx),
     });
 
     module
@@ -45,7 +81,7 @@ pub mod model {
     /* **************************************************************************************************************** */
     /* **************************************************** MODS ****************************************************** */
     /* *************************************************** IMPORTS **************************************************** */
-    /* **************************************************************************************************************** */
+    use std::{rc::Rc, cell::RefCell, sync::Arc};
     use std::{rc::Rc, cell::RefCell};
     use std::ops::Deref;
     use std::collections::HashMap;
@@ -56,7 +92,8 @@ pub mod model {
     use rustpython_vm::{VirtualMachine, PyResult, PyObjectRef, TryFromBorrowedObject, PyRef, PyObject, py_serde};
     use yummy_model::state::RoomInfoTypeVariant;
     use yummy_model::password::Password;
-    use yummy_model::{UserType, CreateRoomAccessType, UserId, RoomUserType};
+    use yummy_model::request::{RequestIdentity, RequestUnlinkIdentity};
+    use yummy_model::{UserType, CreateRoomAccessType, UserId, RoomUserType, RoomGameState};
     use yummy_model::meta::{MetaAction, RoomMetaType, RoomMetaAccess};
     use yummy_model::{meta::{UserMetaAccess, UserMetaType}};
     use yummy_macros::yummy_pymodel;
@@ -66,8 +103,9 @@ pub mod model {
 
     use crate::plugin::python::modules::base::_base::PyYummyValidationError;
     use crate::plugin::python::util::MetaTypeUtil;
-    use crate::room::model::{UpdateRoom, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play};
-    use crate::{auth::model::{DeviceIdAuthRequest, EmailAuthRequest, CustomIdAuthRequest, ConnUserDisconnect, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest}, conn::model::UserConnected, user::model::{UpdateUser, GetUserInformation, GetUserInformationEnum}, room::model::CreateRoomRequest};
+    use crate::room::model::{UpdateRoom, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play, SetReady, StartGame, FinishGame, SelectNewOwner, RoomMessageHistory, InviteToRoom, AcceptRoomInvite, DeclineRoomInvite, RoomInvites, CancelRoomInvite, UnbanUserFromRoom, UpdateRoomMeta};
+    use crate::matchmaking::model::{JoinMatchmakingQueue, LeaveMatchmakingQueue, MatchFound, CheckMatchCompatibility};
+    use crate::{auth::model::{DeviceIdAuthRequest, EmailAuthRequest, CustomIdAuthRequest, ExternalAuthRequest, LinkIdentityRequest, UnlinkIdentityRequest, VerifyEmailRequest, SendVerificationEmailRequest, ForgotPasswordRequest, ResetPasswordRequest, ConnUserDisconnect, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ListSessionsRequest, TerminateSessionRequest}, conn::model::UserConnected, user::model::{UpdateUser, GetUserInformation, GetUserInformationEnum, AddFriend, ProcessFriendRequest, RemoveFriend, FriendList, MessageToUser, GetInbox, AckInbox, SuspendUser, UnsuspendUser, SuspendedUsers, UpdateUserMeta}, room::model::CreateRoomRequest};
     use crate::plugin::python::ModelWrapper;
 
     /* **************************************************************************************************************** */
@@ -204,24 +242,60 @@ pub mod model {
     model_wrapper_struct!(DeviceIdAuthRequest, DeviceIdAuthRequestWrapper, "DeviceIdAuth");
     model_wrapper_struct!(EmailAuthRequest, EmailAuthRequestWrapper, "EmailAuth");
     model_wrapper_struct!(CustomIdAuthRequest, CustomIdAuthRequestWrapper, "CustomIdAuth");
+    model_wrapper_struct!(ExternalAuthRequest, ExternalAuthRequestWrapper, "ExternalAuth");
+    model_wrapper_struct!(LinkIdentityRequest, LinkIdentityRequestWrapper, "LinkIdentity");
+    model_wrapper_struct!(UnlinkIdentityRequest, UnlinkIdentityRequestWrapper, "UnlinkIdentity");
+    model_wrapper_struct!(VerifyEmailRequest, VerifyEmailRequestWrapper, "VerifyEmail");
+    model_wrapper_struct!(SendVerificationEmailRequest, SendVerificationEmailRequestWrapper, "SendVerificationEmail");
+    model_wrapper_struct!(ForgotPasswordRequest, ForgotPasswordRequestWrapper, "ForgotPassword");
+    model_wrapper_struct!(ResetPasswordRequest, ResetPasswordRequestWrapper, "ResetPassword");
     model_wrapper_struct!(UserConnected, UserConnectedWrapper, "UserConnected");
     model_wrapper_struct!(ConnUserDisconnect, ConnUserDisconnectWrapper, "ConnUserDisconnect");
     model_wrapper_struct!(UpdateUser, UpdateUserWrapper, "UpdateUser");
+    model_wrapper_struct!(AddFriend, AddFriendWrapper, "AddFriend");
+    model_wrapper_struct!(ProcessFriendRequest, ProcessFriendRequestWrapper, "ProcessFriendRequest");
+    model_wrapper_struct!(RemoveFriend, RemoveFriendWrapper, "RemoveFriend");
+    model_wrapper_struct!(FriendList, FriendListWrapper, "FriendList");
+    model_wrapper_struct!(MessageToUser, MessageToUserWrapper, "MessageToUser");
+    model_wrapper_struct!(GetInbox, GetInboxWrapper, "GetInbox");
+    model_wrapper_struct!(AckInbox, AckInboxWrapper, "AckInbox");
+    model_wrapper_struct!(SuspendUser, SuspendUserWrapper, "SuspendUser");
+    model_wrapper_struct!(UnsuspendUser, UnsuspendUserWrapper, "UnsuspendUser");
+    model_wrapper_struct!(SuspendedUsers, SuspendedUsersWrapper, "SuspendedUsers");
+    model_wrapper_struct!(UpdateUserMeta, UpdateUserMetaWrapper, "UpdateUserMeta");
     model_wrapper_struct!(LogoutRequest, LogoutRequestWrapper, "Logout");
     model_wrapper_struct!(RefreshTokenRequest, RefreshTokenRequestWrapper, "RefreshToken");
     model_wrapper_struct!(RestoreTokenRequest, RestoreTokenRequestWrapper, "RestoreToken");
+    model_wrapper_struct!(ListSessionsRequest, ListSessionsRequestWrapper, "ListSessions");
+    model_wrapper_struct!(TerminateSessionRequest, TerminateSessionRequestWrapper, "TerminateSession");
     model_wrapper_struct!(GetUserInformation, GetUserInformationWrapper, "GetUserInformation");
     model_wrapper_struct!(CreateRoomRequest, CreateRoomRequestWrapper, "CreateRoom");
     model_wrapper_struct!(UpdateRoom, UpdateRoomWrapper, "UpdateRoom");
     model_wrapper_struct!(JoinToRoomRequest, JoinToRoomRequestWrapper, "JoinToRoom");
     model_wrapper_struct!(ProcessWaitingUser, ProcessWaitingUserWrapper, "ProcessWaitingUser");
     model_wrapper_struct!(KickUserFromRoom, KickUserFromRoomWrapper, "KickUserFromRoom");
+    model_wrapper_struct!(UnbanUserFromRoom, UnbanUserFromRoomWrapper, "UnbanUserFromRoom");
     model_wrapper_struct!(DisconnectFromRoomRequest, DisconnectFromRoomRequestWrapper, "DisconnectFromRoom");
     model_wrapper_struct!(MessageToRoomRequest, MessageToRoomRequestWrapper, "MessageToRoom");
     model_wrapper_struct!(RoomListRequest, RoomListRequestWrapper, "RoomListRequest");
     model_wrapper_struct!(WaitingRoomJoins, WaitingRoomJoinsWrapper, "WaitingRoomJoins");
     model_wrapper_struct!(GetRoomRequest, GetRoomRequestWrapper, "GetRoomRequest");
     model_wrapper_struct!(Play, PlayWrapper, "Play");
+    model_wrapper_struct!(SetReady, SetReadyWrapper, "SetReady");
+    model_wrapper_struct!(StartGame, StartGameWrapper, "StartGame");
+    model_wrapper_struct!(FinishGame, FinishGameWrapper, "FinishGame");
+    model_wrapper_struct!(SelectNewOwner, SelectNewOwnerWrapper, "SelectNewOwner");
+    model_wrapper_struct!(RoomMessageHistory, RoomMessageHistoryWrapper, "RoomMessageHistory");
+    model_wrapper_struct!(InviteToRoom, InviteToRoomWrapper, "InviteToRoom");
+    model_wrapper_struct!(AcceptRoomInvite, AcceptRoomInviteWrapper, "AcceptRoomInvite");
+    model_wrapper_struct!(DeclineRoomInvite, DeclineRoomInviteWrapper, "DeclineRoomInvite");
+    model_wrapper_struct!(RoomInvites, RoomInvitesWrapper, "RoomInvites");
+    model_wrapper_struct!(CancelRoomInvite, CancelRoomInviteWrapper, "CancelRoomInvite");
+    model_wrapper_struct!(UpdateRoomMeta, UpdateRoomMetaWrapper, "UpdateRoomMeta");
+    model_wrapper_struct!(JoinMatchmakingQueue, JoinMatchmakingQueueWrapper, "JoinQueue");
+    model_wrapper_struct!(LeaveMatchmakingQueue, LeaveMatchmakingQueueWrapper, "LeaveQueue");
+    model_wrapper_struct!(MatchFound, MatchFoundWrapper, "MatchFound");
+    model_wrapper_struct!(CheckMatchCompatibility, CheckMatchCompatibilityWrapper, "CheckMatchCompatibility");
 
     wrapper_struct!(UserMetaType, UserMetaTypeWrapper, "UserMetaType");
     wrapper_struct!(RoomMetaType, RoomMetaTypeWrapper, "RoomMetaType");
@@ -262,6 +336,11 @@ pub mod model {
         pub fn get_token(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
             Ok(vm.ctx.new_str(&self.data.borrow().token.to_string()[..]).into())
         }
+
+        #[pymethod]
+        pub fn get_last_sequence(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, last_sequence, vm)
+        }
     }
 
     #[yummy_pymodel(class_name="GetUserInformation", no_auth=true)]
@@ -343,6 +422,19 @@ pub mod model {
     #[pyclass(flags(BASETYPE))]
     impl LogoutRequestWrapper {}
 
+    #[yummy_pymodel(class_name="ListSessionsRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl ListSessionsRequestWrapper {}
+
+    #[yummy_pymodel(class_name="TerminateSessionRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl TerminateSessionRequestWrapper {
+        #[pymethod]
+        pub fn get_target_session_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().session_id.to_string()[..]).into())
+        }
+    }
+
     #[yummy_pymodel(class_name="UpdateUser")]
     #[pyclass(flags(BASETYPE))]
     impl UpdateUserWrapper {
@@ -446,7 +538,7 @@ pub mod model {
                 Some(data) => {
                     let dict = vm.ctx.new_dict();
                     
-                    for (key, value) in data.iter() {
+                    for (key, value) in data.metas.iter() {
                         dict.set_item(key, MetaTypeUtil::as_python_value(value, vm)?, vm)?;
                     }
 
@@ -478,7 +570,7 @@ pub mod model {
                         return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only dict type allowed. .".to_string()))
                     }
 
-                    Some(new_metas)
+                    Some(new_metas.into())
                 },
                 None => None
             };
@@ -486,6 +578,213 @@ pub mod model {
         }
     }
 
+    #[yummy_pymodel(class_name="AddFriend")]
+    #[pyclass(flags(BASETYPE))]
+    impl AddFriendWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="ProcessFriendRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl ProcessFriendRequestWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+
+        /* Status functions */
+        #[pymethod]
+        pub fn get_status(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_bool(self.data.borrow().status).into())
+        }
+
+        #[pymethod]
+        pub fn set_status(&self, status: bool) -> PyResult<()> {
+            self.data.borrow_mut().status = status;
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="RemoveFriend")]
+    #[pyclass(flags(BASETYPE))]
+    impl RemoveFriendWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="FriendList")]
+    #[pyclass(flags(BASETYPE))]
+    impl FriendListWrapper {
+        #[pymethod]
+        pub fn get_pending(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_bool(self.data.borrow().pending).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="MessageToUser")]
+    #[pyclass(flags(BASETYPE))]
+    impl MessageToUserWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+
+        /* Message function */
+        #[pymethod]
+        pub fn get_message(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match py_serde::deserialize(vm, self.data.borrow().message.clone()) {
+                Ok(message) => Ok(message),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn set_message(&self, message: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+            let obj_serializer = rustpython_vm::py_serde::PyObjectSerializer::new(vm, &message);
+            self.data.borrow_mut().message = match serde_json::value::to_value(obj_serializer) {
+                Ok(message) => message,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="GetInbox")]
+    #[pyclass(flags(BASETYPE))]
+    impl GetInboxWrapper {
+        #[pymethod]
+        pub fn get_limit(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, limit, vm)
+        }
+
+        #[pymethod]
+        pub fn set_limit(&self, limit: Option<usize>) -> PyResult<()> {
+            set_value!(self, limit, limit);
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_cursor(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, cursor, vm)
+        }
+
+        #[pymethod]
+        pub fn set_cursor(&self, cursor: Option<String>) -> PyResult<()> {
+            set_value!(self, cursor, cursor);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="AckInbox")]
+    #[pyclass(flags(BASETYPE))]
+    impl AckInboxWrapper {
+        #[pymethod]
+        pub fn get_ids(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let mut list = Vec::new();
+
+            for value in self.data.borrow().ids.iter() {
+                list.push(vm.ctx.new_str(&value.to_string()[..]).into());
+            }
+
+            Ok(vm.ctx.new_list(list).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="SuspendUser")]
+    #[pyclass(flags(BASETYPE))]
+    impl SuspendUserWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+
+        #[pymethod]
+        pub fn get_reason(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, reason, vm)
+        }
+
+        #[pymethod]
+        pub fn set_reason(&self, reason: Option<String>) -> PyResult<()> {
+            set_value!(self, reason, reason);
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_duration(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, duration, vm)
+        }
+
+        #[pymethod]
+        pub fn set_duration(&self, duration: Option<u64>) -> PyResult<()> {
+            set_value!(self, duration, duration);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="UnsuspendUser")]
+    #[pyclass(flags(BASETYPE))]
+    impl UnsuspendUserWrapper {
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="SuspendedUsers")]
+    #[pyclass(flags(BASETYPE))]
+    impl SuspendedUsersWrapper {}
+
+    #[yummy_pymodel(class_name="UpdateUserMeta")]
+    #[pyclass(flags(BASETYPE))]
+    impl UpdateUserMetaWrapper {
+        /* User function */
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, target_user_id, vm)
+        }
+
+        /* Key functions */
+        #[pymethod]
+        pub fn get_key(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, key, vm)
+        }
+
+        #[pymethod]
+        pub fn set_key(&self, key: String) -> PyResult<()> {
+            set_value!(self, key, key);
+            Ok(())
+        }
+
+        /* Operation functions */
+        #[pymethod]
+        pub fn get_operation(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let operation = match serde_json::to_value(&self.data.borrow().operation) {
+                Ok(operation) => operation,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, operation) {
+                Ok(operation) => Ok(operation),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn set_operation(&self, operation: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+            let obj_serializer = rustpython_vm::py_serde::PyObjectSerializer::new(vm, &operation);
+            self.data.borrow_mut().operation = match serde_json::value::to_value(obj_serializer).and_then(serde_json::from_value) {
+                Ok(operation) => operation,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+            Ok(())
+        }
+    }
+
     #[yummy_pymodel(class_name="DeviceIdAuthRequest")]
     #[pyclass(flags(BASETYPE))]
     impl DeviceIdAuthRequestWrapper {
@@ -554,6 +853,105 @@ pub mod model {
         }
     }
 
+    #[yummy_pymodel(class_name="ExternalAuthRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl ExternalAuthRequestWrapper {
+        #[pymethod]
+        pub fn get_token(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().token.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="LinkIdentityRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl LinkIdentityRequestWrapper {
+        #[pymethod]
+        pub fn get_identity(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let identity = match self.data.borrow().identity {
+                RequestIdentity::Email { .. } => "Email",
+                RequestIdentity::DeviceId { .. } => "DeviceId",
+                RequestIdentity::CustomId { .. } => "CustomId",
+                RequestIdentity::External { .. } => "External"
+            };
+            Ok(vm.ctx.new_str(identity).into())
+        }
+
+        #[pymethod]
+        pub fn get_merge(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, merge, vm)
+        }
+
+        #[pymethod]
+        pub fn set_merge(&self, merge: bool) -> PyResult<()> {
+            set_value!(self, merge, merge);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="UnlinkIdentityRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl UnlinkIdentityRequestWrapper {
+        #[pymethod]
+        pub fn get_identity(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let identity = match self.data.borrow().identity {
+                RequestUnlinkIdentity::Email => "Email",
+                RequestUnlinkIdentity::DeviceId => "DeviceId",
+                RequestUnlinkIdentity::CustomId => "CustomId",
+                RequestUnlinkIdentity::External { .. } => "External"
+            };
+            Ok(vm.ctx.new_str(identity).into())
+        }
+
+        #[pymethod]
+        pub fn get_provider(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match &self.data.borrow().identity {
+                RequestUnlinkIdentity::External { provider } => Ok(vm.ctx.new_str(&provider[..]).into()),
+                _ => Ok(vm.ctx.none())
+            }
+        }
+    }
+
+    #[yummy_pymodel(class_name="VerifyEmailRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl VerifyEmailRequestWrapper {
+        #[pymethod]
+        pub fn get_token(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, token, vm)
+        }
+    }
+
+    #[yummy_pymodel(class_name="SendVerificationEmailRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl SendVerificationEmailRequestWrapper {
+        #[pymethod]
+        pub fn get_email(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, email, vm)
+        }
+    }
+
+    #[yummy_pymodel(class_name="ForgotPasswordRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl ForgotPasswordRequestWrapper {
+        #[pymethod]
+        pub fn get_email(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, email, vm)
+        }
+    }
+
+    #[yummy_pymodel(class_name="ResetPasswordRequest")]
+    #[pyclass(flags(BASETYPE))]
+    impl ResetPasswordRequestWrapper {
+        #[pymethod]
+        pub fn get_token(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, token, vm)
+        }
+
+        #[pymethod]
+        pub fn get_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().password.get()[..]).into())
+        }
+    }
+
     #[yummy_pymodel(class_name="CreateRoomRequest")]
     #[pyclass(flags(BASETYPE))]
     impl CreateRoomRequestWrapper {
@@ -593,6 +991,42 @@ pub mod model {
             Ok(())
         }
 
+        /* Password functions */
+        #[pymethod]
+        pub fn get_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, password, vm)
+        }
+
+        #[pymethod]
+        pub fn set_password(&self, password: Option<String>) -> PyResult<()> {
+            set_value!(self, password, password);
+            Ok(())
+        }
+
+        /* Generate password functions */
+        #[pymethod]
+        pub fn get_generate_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, generate_password, vm)
+        }
+
+        #[pymethod]
+        pub fn set_generate_password(&self, generate_password: bool) -> PyResult<()> {
+            set_value!(self, generate_password, generate_password);
+            Ok(())
+        }
+
+        /* Auto start functions */
+        #[pymethod]
+        pub fn get_auto_start(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, auto_start, vm)
+        }
+
+        #[pymethod]
+        pub fn set_auto_start(&self, auto_start: bool) -> PyResult<()> {
+            set_value!(self, auto_start, auto_start);
+            Ok(())
+        }
+
         /* Access type functions */
         #[pymethod]
         pub fn get_access_type(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
@@ -617,6 +1051,18 @@ pub mod model {
             Ok(())
         }
 
+        /* Max spectator functions */
+        #[pymethod]
+        pub fn get_max_spectator(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_usize!(self, max_spectator, vm)
+        }
+
+        #[pymethod]
+        pub fn set_max_spectator(&self, max_spectator: usize) -> PyResult<()> {
+            set_value!(self, max_spectator, max_spectator);
+            Ok(())
+        }
+
         /* Metas functions */
         #[pymethod]
         pub fn get_metas(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
@@ -624,7 +1070,7 @@ pub mod model {
                 Some(data) => {
                     let dict = vm.ctx.new_dict();
                     
-                    for (key, value) in data.iter() {
+                    for (key, value) in data.metas.iter() {
                         dict.set_item(key, MetaTypeUtil::as_python_value(value, vm)?, vm)?;
                     }
 
@@ -656,7 +1102,7 @@ pub mod model {
                         return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only dict type allowed. .".to_string()))
                     }
 
-                    Some(new_metas)
+                    Some(new_metas.into())
                 },
                 None => None
             };
@@ -764,14 +1210,26 @@ pub mod model {
             Ok(())
         }
 
-        /* Metas functions */
+        /* Max spectator functions */
         #[pymethod]
-        pub fn get_metas(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
-            match self.data.borrow().metas.clone() {
-                Some(data) => {
+        pub fn get_max_spectator(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, max_spectator, vm)
+        }
+
+        #[pymethod]
+        pub fn set_max_spectator(&self, max_spectator: Option<usize>) -> PyResult<()> {
+            set_value!(self, max_spectator, max_spectator);
+            Ok(())
+        }
+
+        /* Metas functions */
+        #[pymethod]
+        pub fn get_metas(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match self.data.borrow().metas.clone() {
+                Some(data) => {
                     let dict = vm.ctx.new_dict();
                     
-                    for (key, value) in data.iter() {
+                    for (key, value) in data.metas.iter() {
                         dict.set_item(key, MetaTypeUtil::as_python_value(value, vm)?, vm)?;
                     }
 
@@ -803,7 +1261,7 @@ pub mod model {
                         return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only dict type allowed. .".to_string()))
                     }
 
-                    Some(new_metas)
+                    Some(new_metas.into())
                 },
                 None => None
             };
@@ -889,6 +1347,30 @@ pub mod model {
             self.data.borrow_mut().meta_action = MetaAction::from(meta_action);
             Ok(())
         }
+
+        /* Password functions */
+        #[pymethod]
+        pub fn get_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, password, vm)
+        }
+
+        #[pymethod]
+        pub fn set_password(&self, password: Option<String>) -> PyResult<()> {
+            set_value!(self, password, password);
+            Ok(())
+        }
+
+        /* Generate password functions */
+        #[pymethod]
+        pub fn get_generate_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, generate_password, vm)
+        }
+
+        #[pymethod]
+        pub fn set_generate_password(&self, generate_password: bool) -> PyResult<()> {
+            set_value!(self, generate_password, generate_password);
+            Ok(())
+        }
     }
 
     #[yummy_pymodel(class_name="JoinToRoomRequest")]
@@ -911,6 +1393,30 @@ pub mod model {
             self.data.borrow_mut().room_user_type = RoomUserType::from(room_user_type);
             Ok(())
         }
+
+        /* Password functions */
+        #[pymethod]
+        pub fn get_password(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, password, vm)
+        }
+
+        #[pymethod]
+        pub fn set_password(&self, password: Option<String>) -> PyResult<()> {
+            set_value!(self, password, password);
+            Ok(())
+        }
+
+        /* History functions */
+        #[pymethod]
+        pub fn get_history(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, history, vm)
+        }
+
+        #[pymethod]
+        pub fn set_history(&self, history: Option<usize>) -> PyResult<()> {
+            set_value!(self, history, history);
+            Ok(())
+        }
     }
 
     #[yummy_pymodel(class_name="ProcessWaitingUser")]
@@ -967,6 +1473,46 @@ pub mod model {
             self.data.borrow_mut().ban = status;
             Ok(())
         }
+
+        /* Reason functions */
+        #[pymethod]
+        pub fn get_reason(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, reason, vm)
+        }
+
+        #[pymethod]
+        pub fn set_reason(&self, reason: Option<String>) -> PyResult<()> {
+            set_value!(self, reason, reason);
+            Ok(())
+        }
+
+        /* Ban duration functions */
+        #[pymethod]
+        pub fn get_ban_duration(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, ban_duration, vm)
+        }
+
+        #[pymethod]
+        pub fn set_ban_duration(&self, ban_duration: Option<u64>) -> PyResult<()> {
+            set_value!(self, ban_duration, ban_duration);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="UnbanUserFromRoom")]
+    #[pyclass(flags(BASETYPE))]
+    impl UnbanUserFromRoomWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+        
+        /* User function */
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
     }
 
     #[yummy_pymodel(class_name="DisconnectFromRoomRequest")]
@@ -1011,14 +1557,84 @@ pub mod model {
     #[yummy_pymodel(class_name="RoomListRequest", no_auth=true)]
     #[pyclass(flags(BASETYPE))]
     impl RoomListRequestWrapper {
+        /* Single tag functions are kept for the old scripts, first tag is used */
         #[pymethod]
         pub fn get_tag(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
-            get_nullable_string!(self, tag, vm)
+            match self.data.borrow().filter.tags.first() {
+                Some(tag) => Ok(vm.ctx.new_str(&tag[..]).into()),
+                None => Ok(vm.ctx.none())
+            }
         }
 
         #[pymethod]
         pub fn set_tag(&self, tag: Option<String>) -> PyResult<()> {
-            set_value!(self, tag, tag);
+            self.data.borrow_mut().filter.tags = tag.into_iter().collect();
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_tags(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let mut list = Vec::new();
+            
+            for value in self.data.borrow().filter.tags.clone().into_iter() {
+                list.push(vm.ctx.new_str(&value[..]).into());
+            }
+
+            Ok(vm.ctx.new_list(list).into())
+        }
+
+        #[pymethod]
+        pub fn set_tags(&self, tags: Vec<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+            let mut new_tags = Vec::new();
+
+            for tag in tags {
+                if tag.class().fast_issubclass(vm.ctx.types.str_type) {
+                    new_tags.push(tag.payload::<PyStr>().unwrap().as_str().to_string());
+                } else {
+                    return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only string type allowed.".to_string()))
+                }
+            }
+            
+            self.data.borrow_mut().filter.tags = new_tags;
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_game_state(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match self.data.borrow().filter.game_state {
+                Some(game_state) => Ok(vm.ctx.new_bigint(&(game_state as u32).to_bigint().unwrap()).into()),
+                None => Ok(vm.ctx.none())
+            }
+        }
+
+        #[pymethod]
+        pub fn set_game_state(&self, game_state: Option<i32>) -> PyResult<()> {
+            self.data.borrow_mut().filter.game_state = game_state.map(RoomGameState::from);
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_cursor(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match self.data.borrow().filter.cursor.as_ref() {
+                Some(cursor) => Ok(vm.ctx.new_str(&cursor[..]).into()),
+                None => Ok(vm.ctx.none())
+            }
+        }
+
+        #[pymethod]
+        pub fn set_cursor(&self, cursor: Option<String>) -> PyResult<()> {
+            self.data.borrow_mut().filter.cursor = cursor;
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_limit(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_bigint(&(self.data.borrow().filter.limit as u32).to_bigint().unwrap()).into())
+        }
+
+        #[pymethod]
+        pub fn set_limit(&self, limit: usize) -> PyResult<()> {
+            self.data.borrow_mut().filter.limit = limit;
             Ok(())
         }
 
@@ -1124,6 +1740,430 @@ pub mod model {
         }
     }
 
+    #[yummy_pymodel(class_name="SetReady")]
+    #[pyclass(flags(BASETYPE))]
+    impl SetReadyWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* Ready functions */
+        #[pymethod]
+        pub fn get_ready(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, ready, vm)
+        }
+
+        #[pymethod]
+        pub fn set_ready(&self, ready: bool) -> PyResult<()> {
+            set_value!(self, ready, ready);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="StartGame")]
+    #[pyclass(flags(BASETYPE))]
+    impl StartGameWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="FinishGame")]
+    #[pyclass(flags(BASETYPE))]
+    impl FinishGameWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="RoomMessageHistory")]
+    #[pyclass(flags(BASETYPE))]
+    impl RoomMessageHistoryWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* Paging functions */
+        #[pymethod]
+        pub fn get_before(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, before, vm)
+        }
+
+        #[pymethod]
+        pub fn set_before(&self, before: Option<usize>) -> PyResult<()> {
+            set_value!(self, before, before);
+            Ok(())
+        }
+
+        #[pymethod]
+        pub fn get_limit(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, limit, vm)
+        }
+
+        #[pymethod]
+        pub fn set_limit(&self, limit: Option<usize>) -> PyResult<()> {
+            set_value!(self, limit, limit);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="InviteToRoom")]
+    #[pyclass(flags(BASETYPE))]
+    impl InviteToRoomWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* User function */
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+
+        /* Room user type functions */
+        #[pymethod]
+        pub fn get_room_user_type(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_bigint(&(self.data.borrow().room_user_type.clone() as u32).to_bigint().unwrap()).into())
+        }
+
+        #[pymethod]
+        pub fn set_room_user_type(&self, room_user_type: i32) -> PyResult<()> {
+            self.data.borrow_mut().room_user_type = RoomUserType::from(room_user_type);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="AcceptRoomInvite")]
+    #[pyclass(flags(BASETYPE))]
+    impl AcceptRoomInviteWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* History functions */
+        #[pymethod]
+        pub fn get_history(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_usize!(self, history, vm)
+        }
+
+        #[pymethod]
+        pub fn set_history(&self, history: Option<usize>) -> PyResult<()> {
+            set_value!(self, history, history);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="DeclineRoomInvite")]
+    #[pyclass(flags(BASETYPE))]
+    impl DeclineRoomInviteWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="RoomInvites")]
+    #[pyclass(flags(BASETYPE))]
+    impl RoomInvitesWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="CancelRoomInvite")]
+    #[pyclass(flags(BASETYPE))]
+    impl CancelRoomInviteWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* User function */
+        #[pymethod]
+        pub fn get_target_user_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().user_id.to_string()[..]).into())
+        }
+    }
+
+    #[yummy_pymodel(class_name="UpdateRoomMeta")]
+    #[pyclass(flags(BASETYPE))]
+    impl UpdateRoomMetaWrapper {
+        /* Room function */
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        /* Key functions */
+        #[pymethod]
+        pub fn get_key(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, key, vm)
+        }
+
+        #[pymethod]
+        pub fn set_key(&self, key: String) -> PyResult<()> {
+            set_value!(self, key, key);
+            Ok(())
+        }
+
+        /* Operation functions */
+        #[pymethod]
+        pub fn get_operation(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let operation = match serde_json::to_value(&self.data.borrow().operation) {
+                Ok(operation) => operation,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, operation) {
+                Ok(operation) => Ok(operation),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn set_operation(&self, operation: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+            let obj_serializer = rustpython_vm::py_serde::PyObjectSerializer::new(vm, &operation);
+            self.data.borrow_mut().operation = match serde_json::value::to_value(obj_serializer).and_then(serde_json::from_value) {
+                Ok(operation) => operation,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="JoinMatchmakingQueue")]
+    #[pyclass(flags(BASETYPE))]
+    impl JoinMatchmakingQueueWrapper {
+        /* Queue functions */
+        #[pymethod]
+        pub fn get_queue(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, queue, vm)
+        }
+
+        #[pymethod]
+        pub fn set_queue(&self, queue: String) -> PyResult<()> {
+            set_value!(self, queue, queue);
+            Ok(())
+        }
+
+        /* Properties functions */
+        #[pymethod]
+        pub fn get_properties(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let properties = match serde_json::to_value(&self.data.borrow().properties) {
+                Ok(properties) => properties,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, properties) {
+                Ok(properties) => Ok(properties),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn set_properties(&self, properties: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+            let obj_serializer = rustpython_vm::py_serde::PyObjectSerializer::new(vm, &properties);
+            self.data.borrow_mut().properties = match serde_json::value::to_value(obj_serializer).and_then(serde_json::from_value) {
+                Ok(properties) => properties,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="LeaveMatchmakingQueue")]
+    #[pyclass(flags(BASETYPE))]
+    impl LeaveMatchmakingQueueWrapper {
+        #[pymethod]
+        pub fn get_queue(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, queue, vm)
+        }
+    }
+
+    #[yummy_pymodel(class_name="MatchFound", no_request_id=true, no_auth=true)]
+    #[pyclass(flags(BASETYPE))]
+    impl MatchFoundWrapper {
+        #[pymethod]
+        pub fn get_queue(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, queue, vm)
+        }
+
+        #[pymethod]
+        pub fn get_user_ids(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let mut list = Vec::new();
+
+            for ticket in self.data.borrow().tickets.iter() {
+                list.push(vm.ctx.new_str(&ticket.user_id.to_string()[..]).into());
+            }
+
+            Ok(vm.ctx.new_list(list).into())
+        }
+
+        /* Room name functions */
+        #[pymethod]
+        pub fn get_room_name(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_nullable_string!(self, room_name, vm)
+        }
+
+        #[pymethod]
+        pub fn set_room_name(&self, room_name: Option<String>) -> PyResult<()> {
+            set_value!(self, room_name, room_name);
+            Ok(())
+        }
+
+        /* Max user functions */
+        #[pymethod]
+        pub fn get_max_user(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_usize!(self, max_user, vm)
+        }
+
+        #[pymethod]
+        pub fn set_max_user(&self, max_user: usize) -> PyResult<()> {
+            set_value!(self, max_user, max_user);
+            Ok(())
+        }
+
+        /* Tags functions */
+        #[pymethod]
+        pub fn get_tags(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let mut list = Vec::new();
+            
+            for value in self.data.borrow().tags.clone().into_iter() {
+                list.push(vm.ctx.new_str(&value[..]).into());
+            }
+
+            Ok(vm.ctx.new_list(list).into())
+        }
+
+        #[pymethod]
+        pub fn set_tags(&self, tags: Vec<PyObjectRef>, vm: &VirtualMachine) -> PyResult<()> {
+            let mut new_tags = Vec::new();
+
+            for tag in tags {
+                if tag.class().fast_issubclass(vm.ctx.types.str_type) {
+                    new_tags.push(tag.payload::<PyStr>().unwrap().as_str().to_string());
+                } else {
+                    return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only string type allowed.".to_string()))
+                }
+            }
+            
+            self.data.borrow_mut().tags = new_tags;
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="CheckMatchCompatibility", no_request_id=true, no_auth=true)]
+    #[pyclass(flags(BASETYPE))]
+    impl CheckMatchCompatibilityWrapper {
+        #[pymethod]
+        pub fn get_queue(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_string!(self, queue, vm)
+        }
+
+        /* Tickets functions */
+        #[pymethod]
+        pub fn get_left(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let left = match serde_json::to_value(&self.data.borrow().left) {
+                Ok(left) => left,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, left) {
+                Ok(left) => Ok(left),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn get_right(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let right = match serde_json::to_value(&self.data.borrow().right) {
+                Ok(right) => right,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, right) {
+                Ok(right) => Ok(right),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        #[pymethod]
+        pub fn get_tolerance(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_float(self.data.borrow().tolerance).into())
+        }
+
+        /* Compatible functions */
+        #[pymethod]
+        pub fn get_compatible(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            get_bool!(self, compatible, vm)
+        }
+
+        #[pymethod]
+        pub fn set_compatible(&self, compatible: bool) -> PyResult<()> {
+            set_value!(self, compatible, compatible);
+            Ok(())
+        }
+    }
+
+    #[yummy_pymodel(class_name="SelectNewOwner", no_request_id=true, no_auth=true)]
+    #[pyclass(flags(BASETYPE))]
+    impl SelectNewOwnerWrapper {
+        #[pymethod]
+        pub fn get_room_id(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().room_id.to_string()[..]).into())
+        }
+
+        #[pymethod]
+        pub fn get_previous_owner(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            Ok(vm.ctx.new_str(&self.data.borrow().previous_owner.to_string()[..]).into())
+        }
+
+        /* Users functions */
+        #[pymethod]
+        pub fn get_users(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            let users = match serde_json::to_value(&self.data.borrow().users) {
+                Ok(users) => users,
+                Err(error) => return Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            };
+
+            match py_serde::deserialize(vm, users) {
+                Ok(users) => Ok(users),
+                Err(error) => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), error.to_string()))
+            }
+        }
+
+        /* New owner functions */
+        #[pymethod]
+        pub fn get_new_owner(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+            match self.data.borrow().new_owner.as_ref() {
+                Some(new_owner) => Ok(vm.ctx.new_str(&new_owner.to_string()[..]).into()),
+                None => Ok(vm.ctx.none())
+            }
+        }
+
+        #[pymethod]
+        pub fn set_new_owner(&self, new_owner: Option<String>) -> PyResult<()> {
+            self.data.borrow_mut().new_owner = new_owner.map(|new_owner| Arc::new(UserId::from(new_owner)));
+            Ok(())
+        }
+    }
+
     /* **************************************************************************************************************** */
     /* ********************************************** TRAIT IMPLEMENTS ************************************************ */
     /* **************************************************************************************************************** */
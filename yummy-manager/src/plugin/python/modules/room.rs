@@ -4,8 +4,9 @@ pub mod _room {
     /* **************************************************** MODS ****************************************************** */
     /* *************************************************** IMPORTS **************************************************** */
     /* **************************************************************************************************************** */
+    use num_bigint::ToBigInt;
     use yummy_model::UserId;
-    use yummy_model::{meta::RoomMetaAccess, RoomId};
+    use yummy_model::{meta::{RoomMetaAccess, operation::RoomMetaOperation}, RoomId};
     use rustpython_vm::{VirtualMachine, PyResult, PyObjectRef, function::OptionalArg};
     use rustpython_vm::class::PyClassImpl;
 
@@ -171,6 +172,34 @@ pub mod _room {
         }
     }
 
+    #[pyfunction]
+    pub fn get_room_user_type(room_id: Option<String>, user_id: Option<String>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        /* Validate arguments */
+        let (room_id, user_id) = match (room_id, user_id) {
+
+            /* All arguments are valid */
+            (Some(room_id), Some(user_id)) => (room_id, user_id),
+
+            /* Return None if the arguments are not valid */
+            _ => return Ok(vm.ctx.none())
+        };
+
+        match RustPythonUtil::get_context(vm)?.data.room_logic.get_room_user_type(&RoomId::from(room_id), &UserId::from(user_id)) {
+
+            /* User is in the room */
+            Ok(Some(room_user_type)) => Ok(vm.ctx.new_bigint(&(room_user_type as u32).to_bigint().unwrap()).into()),
+
+            /* User is not in the room */
+            Ok(None) => Ok(vm.ctx.none()),
+
+            /* Something went wrong, but do not throw exception. Only return None and log error message */
+            Err(error) => {
+                log::error!("Context is failed to retrieve 'get_room_user_type'. Error: {}", error.to_string());
+                Ok(vm.ctx.none())
+            }
+        }
+    }
+
     #[pyfunction]
     pub fn message_to_room(room_id: Option<String>, message: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
         /* Validate arguments */
@@ -271,6 +300,98 @@ pub mod _room {
         }
     }
 
+    #[pyfunction]
+    pub fn increment_room_meta(room_id: Option<String>, key: Option<String>, value: PyObjectRef, min: OptionalArg<PyObjectRef>, max: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let (value, min, max) = match MetaTypeUtil::parse_number_arguments(vm, &value, min, max)? {
+            Some(arguments) => arguments,
+            None => return Ok(vm.ctx.none())
+        };
+
+        apply_room_meta_operation(room_id, key, RoomMetaOperation::Increment { value, min, max }, vm)
+    }
+
+    #[pyfunction]
+    pub fn decrement_room_meta(room_id: Option<String>, key: Option<String>, value: PyObjectRef, min: OptionalArg<PyObjectRef>, max: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let (value, min, max) = match MetaTypeUtil::parse_number_arguments(vm, &value, min, max)? {
+            Some(arguments) => arguments,
+            None => return Ok(vm.ctx.none())
+        };
+
+        apply_room_meta_operation(room_id, key, RoomMetaOperation::Decrement { value, min, max }, vm)
+    }
+
+    #[pyfunction]
+    pub fn append_room_meta(room_id: Option<String>, key: Option<String>, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let value = MetaTypeUtil::parse_room_meta(vm, &value, RoomMetaAccess::Anonymous)?.data;
+        apply_room_meta_operation(room_id, key, RoomMetaOperation::Append { value }, vm)
+    }
+
+    #[pyfunction]
+    pub fn remove_from_room_meta(room_id: Option<String>, key: Option<String>, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let value = MetaTypeUtil::parse_room_meta(vm, &value, RoomMetaAccess::Anonymous)?.data;
+        apply_room_meta_operation(room_id, key, RoomMetaOperation::Remove { value }, vm)
+    }
+
+    #[pyfunction]
+    pub fn compare_and_set_room_meta(room_id: Option<String>, key: Option<String>, expected: PyObjectRef, value: PyObjectRef, access_level: OptionalArg<RoomMetaAccessWrapper>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        
+        /* Validate arguments */
+        let (room_id, key) = match (room_id, key) {
+
+            /* All arguments are valid */
+            (Some(room_id), Some(key)) => (room_id, key),
+
+            /* Return False if the arguments are not valid */
+            _ => return Ok(vm.ctx.new_bool(false).into())
+        };
+
+        /* Configure meta access level */
+        let access_level = match access_level {
+            OptionalArg::Present(access_level) => access_level.data,
+            OptionalArg::Missing => RoomMetaAccess::System
+        };
+
+        let expected = MetaTypeUtil::parse_room_meta(vm, &expected, RoomMetaAccess::System)?.data;
+        let value = MetaTypeUtil::parse_room_meta(vm, &value, access_level)?.data;
+
+        match RustPythonUtil::get_context(vm)?.data.room_logic.apply_room_meta_operation(RoomId::from(room_id), key, RoomMetaOperation::CompareAndSet { expected, value }) {
+
+            /* Expected value matched and the meta updated, return True */
+            Ok(_) => Ok(vm.ctx.new_bool(true).into()),
+
+            /* Expected value is not matched or something went wrong. Only return False and log error message */
+            Err(error) => {
+                log::error!("Context is failed to retrieve 'compare_and_set_room_meta'. Error: {}", error.to_string());
+                Ok(vm.ctx.new_bool(false).into())
+            }
+        }
+    }
+
+    fn apply_room_meta_operation(room_id: Option<String>, key: Option<String>, operation: RoomMetaOperation, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+
+        /* Validate arguments */
+        let (room_id, key) = match (room_id, key) {
+
+            /* All arguments are valid */
+            (Some(room_id), Some(key)) => (room_id, key),
+
+            /* Return None if the arguments are not valid */
+            _ => return Ok(vm.ctx.none())
+        };
+
+        match RustPythonUtil::get_context(vm)?.data.room_logic.apply_room_meta_operation(RoomId::from(room_id), key, operation) {
+
+            /* Operation executed, return the new value */
+            Ok(room_meta) => MetaTypeUtil::as_python_value(&room_meta, vm),
+
+            /* Something went wrong, but do not throw exception. Only return None and log error message */
+            Err(error) => {
+                log::error!("Context is failed to retrieve 'apply_room_meta_operation'. Error: {}", error.to_string());
+                Ok(vm.ctx.none())
+            }
+        }
+    }
+
     /* **************************************************************************************************************** */
     /* *************************************************** TRAITS ***************************************************** */
     /* ************************************************* IMPLEMENTS *************************************************** */
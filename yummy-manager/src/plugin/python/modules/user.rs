@@ -4,7 +4,7 @@ pub mod _user {
     /* **************************************************** MODS ****************************************************** */
     /* *************************************************** IMPORTS **************************************************** */
     /* **************************************************************************************************************** */
-    use yummy_model::{meta::UserMetaAccess, UserId};
+    use yummy_model::{meta::{UserMetaAccess, operation::UserMetaOperation}, UserId};
     use rustpython_vm::{VirtualMachine, PyResult, PyObjectRef, function::OptionalArg};
 
     use crate::plugin::python::{util::{MetaTypeUtil, RustPythonUtil}, modules::model::model::UserMetaAccessWrapper};
@@ -197,6 +197,98 @@ pub mod _user {
         }
     }
 
+    #[pyfunction]
+    pub fn increment_user_meta(user_id: Option<String>, key: Option<String>, value: PyObjectRef, min: OptionalArg<PyObjectRef>, max: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let (value, min, max) = match MetaTypeUtil::parse_number_arguments(vm, &value, min, max)? {
+            Some(arguments) => arguments,
+            None => return Ok(vm.ctx.none())
+        };
+
+        apply_user_meta_operation(user_id, key, UserMetaOperation::Increment { value, min, max }, vm)
+    }
+
+    #[pyfunction]
+    pub fn decrement_user_meta(user_id: Option<String>, key: Option<String>, value: PyObjectRef, min: OptionalArg<PyObjectRef>, max: OptionalArg<PyObjectRef>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let (value, min, max) = match MetaTypeUtil::parse_number_arguments(vm, &value, min, max)? {
+            Some(arguments) => arguments,
+            None => return Ok(vm.ctx.none())
+        };
+
+        apply_user_meta_operation(user_id, key, UserMetaOperation::Decrement { value, min, max }, vm)
+    }
+
+    #[pyfunction]
+    pub fn append_user_meta(user_id: Option<String>, key: Option<String>, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let value = MetaTypeUtil::parse_user_meta(vm, &value, UserMetaAccess::User)?.data;
+        apply_user_meta_operation(user_id, key, UserMetaOperation::Append { value }, vm)
+    }
+
+    #[pyfunction]
+    pub fn remove_from_user_meta(user_id: Option<String>, key: Option<String>, value: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let value = MetaTypeUtil::parse_user_meta(vm, &value, UserMetaAccess::User)?.data;
+        apply_user_meta_operation(user_id, key, UserMetaOperation::Remove { value }, vm)
+    }
+
+    #[pyfunction]
+    pub fn compare_and_set_user_meta(user_id: Option<String>, key: Option<String>, expected: PyObjectRef, value: PyObjectRef, access_level: OptionalArg<UserMetaAccessWrapper>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        
+        /* Validate arguments */
+        let (user_id, key) = match (user_id, key) {
+
+            /* All arguments are valid */
+            (Some(user_id), Some(key)) => (user_id, key),
+
+            /* Return False if the arguments are not valid */
+            _ => return Ok(vm.ctx.new_bool(false).into())
+        };
+
+        /* Configure meta access level */
+        let access_level = match access_level {
+            OptionalArg::Present(access_level) => access_level.data,
+            OptionalArg::Missing => UserMetaAccess::System
+        };
+
+        let expected = MetaTypeUtil::parse_user_meta(vm, &expected, UserMetaAccess::System)?.data;
+        let value = MetaTypeUtil::parse_user_meta(vm, &value, access_level)?.data;
+
+        match RustPythonUtil::get_context(vm)?.data.user_logic.apply_user_meta_operation(UserId::from(user_id), key, UserMetaOperation::CompareAndSet { expected, value }) {
+
+            /* Expected value matched and the meta updated, return True */
+            Ok(_) => Ok(vm.ctx.new_bool(true).into()),
+
+            /* Expected value is not matched or something went wrong. Only return False and log error message */
+            Err(error) => {
+                log::error!("Context is failed to retrieve 'compare_and_set_user_meta'. Error: {}", error.to_string());
+                Ok(vm.ctx.new_bool(false).into())
+            }
+        }
+    }
+
+    fn apply_user_meta_operation(user_id: Option<String>, key: Option<String>, operation: UserMetaOperation, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+
+        /* Validate arguments */
+        let (user_id, key) = match (user_id, key) {
+
+            /* All arguments are valid */
+            (Some(user_id), Some(key)) => (user_id, key),
+
+            /* Return None if the arguments are not valid */
+            _ => return Ok(vm.ctx.none())
+        };
+
+        match RustPythonUtil::get_context(vm)?.data.user_logic.apply_user_meta_operation(UserId::from(user_id), key, operation) {
+
+            /* Operation executed, return the new value */
+            Ok(user_meta) => MetaTypeUtil::as_python_value(&user_meta, vm),
+
+            /* Something went wrong, but do not throw exception. Only return None and log error message */
+            Err(error) => {
+                log::error!("Context is failed to retrieve 'apply_user_meta_operation'. Error: {}", error.to_string());
+                Ok(vm.ctx.none())
+            }
+        }
+    }
+
     /* **************************************************************************************************************** */
     /* *************************************************** TRAITS ***************************************************** */
     /* ************************************************* IMPLEMENTS *************************************************** */
@@ -6,11 +6,14 @@ use std::collections::HashMap;
 use std::{sync::Arc, env::temp_dir};
 use std::io::Write;
 
-use yummy_model::state::RoomInfoTypeVariant;
+use yummy_model::state::{RoomInfoTypeVariant, RoomUserInformation, RoomListFilter};
 use yummy_model::password::Password;
-use yummy_model::meta::{MetaAction, UserMetaType, UserMetaAccess, RoomMetaAccess, RoomMetaType};
+use yummy_model::request::{RequestIdentity, RequestUnlinkIdentity};
+use yummy_model::meta::{MetaAction, MetaDefinitions, UserMetaType, UserMetaAccess, RoomMetaAccess, RoomMetaType};
+use yummy_model::meta::operation::{UserMetaOperation, RoomMetaOperation};
 use yummy_model::{UserId, SessionId, UserType, CreateRoomAccessType, RoomId, RoomUserType};
 use yummy_model::auth::UserAuth;
+use yummy_model::matchmaking::MatchmakingTicket;
 use yummy_model::config::YummyConfig;
 
 use yummy_cache::state::YummyState;
@@ -21,11 +24,12 @@ use yummy_testing::cache::DummyResourceFactory;
 use yummy_testing::client::DummyClient;
 use yummy_testing::database::get_database_pool;
 
-use crate::auth::model::{EmailAuthRequest, CustomIdAuthRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ConnUserDisconnect};
+use crate::auth::model::{EmailAuthRequest, CustomIdAuthRequest, ExternalAuthRequest, LinkIdentityRequest, UnlinkIdentityRequest, VerifyEmailRequest, SendVerificationEmailRequest, ForgotPasswordRequest, ResetPasswordRequest, LogoutRequest, RefreshTokenRequest, RestoreTokenRequest, ListSessionsRequest, TerminateSessionRequest, ConnUserDisconnect};
 use crate::conn::model::UserConnected;
 use crate::plugin::PluginExecuter;
-use crate::room::model::{CreateRoomRequest, UpdateRoom, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play};
-use crate::user::model::{GetUserInformation, GetUserInformationEnum, UpdateUser};
+use crate::room::model::{CreateRoomRequest, UpdateRoom, UpdateRoomMeta, JoinToRoomRequest, ProcessWaitingUser, KickUserFromRoom, DisconnectFromRoomRequest, MessageToRoomRequest, RoomListRequest, WaitingRoomJoins, GetRoomRequest, Play, SetReady, StartGame, FinishGame, SelectNewOwner, RoomMessageHistory, InviteToRoom, AcceptRoomInvite, DeclineRoomInvite, RoomInvites, CancelRoomInvite, UnbanUserFromRoom};
+use crate::user::model::{GetUserInformation, GetUserInformationEnum, UpdateUser, UpdateUserMeta, SuspendUser};
+use crate::matchmaking::model::{JoinMatchmakingQueue, LeaveMatchmakingQueue, MatchFound, CheckMatchCompatibility};
 use crate::{plugin::{PluginBuilder}, auth::model::DeviceIdAuthRequest};
 use super::PythonPluginInstaller;
 
@@ -346,6 +350,9 @@ def pre_deviceid_auth(model):
     assert(yummy.user.set_user_meta(model.get_user_id(), "key", [123, True, 321.123, "test"]))
     assert(yummy.user.get_user_meta(model.get_user_id(), "key") == [123, True, 321.123, "test"])
 
+    assert(yummy.user.set_user_meta(model.get_user_id(), "key", {"position": {"x": 1, "y": 2}, "items": [True, "sword"]}))
+    assert(yummy.user.get_user_meta(model.get_user_id(), "key") == {"position": {"x": 1, "y": 2}, "items": [True, "sword"]})
+
     assert(yummy.user.set_user_meta(model.get_user_id(), "key", None))
     assert(yummy.user.get_user_meta(model.get_user_id(), "key") is None)
 
@@ -395,6 +402,7 @@ def post_user_connected(model, success):
 
     let model = UserConnected {
         user_id: Arc::new(UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string())),
+        session_id: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string()),
         socket: Arc::new(DummyClient::default())
     };
 
@@ -474,6 +482,7 @@ def post_restore_token(model, success):
             session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
         })),
         token: "TOKEN".to_string(),
+        last_sequence: None,
         socket: Arc::new(DummyClient::default())
     };
 
@@ -683,7 +692,7 @@ def post_update_user(model, success):
         device_id: Some("device_id".to_string()),
         custom_id: Some("custom_id".to_string()),
         user_type: Some(UserType::Admin),
-        metas: Some(HashMap::new()),
+        metas: Some(MetaDefinitions::default()),
         meta_action: MetaAction::RemoveAllMetas,
         socket: Arc::new(DummyClient::default())
     };
@@ -815,13 +824,17 @@ def post_create_room(model, success):
         access_type: CreateRoomAccessType::Public,
         join_request: true,
         max_user: 1024,
-        metas: Some(HashMap::from([
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([
             ("meta1".to_string(), RoomMetaType::Null),
             ("meta2".to_string(), RoomMetaType::Number(10.1, RoomMetaAccess::User)),
             ("meta3".to_string(), RoomMetaType::Null),
             ("meta4".to_string(), RoomMetaType::Null),
         ])),
         tags: vec!["tag1".to_string(), "tag2".to_string(), "tag3".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket: Arc::new(DummyClient::default())
     };
 
@@ -836,6 +849,7 @@ def pre_create_room(model):
     model.set_name("names")
     model.set_description("descriptions")
     model.set_max_user(0)
+    model.set_max_spectator(16)
     model.set_join_request(False)
     model.set_tags(["y", "u", "m", "m", "y"])
     model.set_metas({"1": 1024})
@@ -845,6 +859,7 @@ def post_create_room(model, success):
     assert(model.get_name() == "names")
     assert(model.get_description() == "descriptions")
     assert(model.get_max_user() == 0)
+    assert(model.get_max_spectator() == 16)
     assert(model.get_join_request() is False)
     assert(model.get_tags() == ["y", "u", "m", "m", "y"])
     assert(model.get_metas() == {"1": 1024})
@@ -859,6 +874,7 @@ def post_create_room(model, success):
     assert_eq!(model.description, Some("descriptions".to_string()));
     assert_eq!(model.join_request, false);
     assert_eq!(model.max_user, 0);
+    assert_eq!(model.max_spectator, 16);
     assert_eq!(model.tags, vec!["y".to_string(), "u".to_string(), "m".to_string(), "m".to_string(), "y".to_string()]);
 
     if let Some(metas) = model.metas.as_ref() {
@@ -910,10 +926,13 @@ def post_update_room(model, success):
         access_type: None,
         join_request: None,
         max_user: None,
+        max_spectator: None,
         tags: None,
         metas: None,
         user_permission: None,
         meta_action: MetaAction::default(),
+        password: None,
+        generate_password: false,
         socket: Arc::new(DummyClient::default())
     };
 
@@ -963,12 +982,15 @@ import yummy
 def pre_join_to_room(model):
     assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
     assert(model.get_room_user_type() == yummy.constants.ROOM_USER_TYPE_USER)
+    assert(model.get_history() is None)
 
     model.set_room_user_type(yummy.constants.ROOM_USER_TYPE_MODERATOR)
+    model.set_history(20)
 
 def post_join_to_room(model, success):
     assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
     assert(model.get_room_user_type() == yummy.constants.ROOM_USER_TYPE_MODERATOR)
+    assert(model.get_history() == 20)
 "#);
 
     let model = JoinToRoomRequest {
@@ -979,6 +1001,8 @@ def post_join_to_room(model, success):
         })),
         room_id: RoomId::from("d508b370-6249-4fd3-9b3e-3aa66577a686".to_string()),
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket: Arc::new(DummyClient::default())
     };
 
@@ -986,6 +1010,7 @@ def post_join_to_room(model, success):
     let model = executer.post_join_to_room(model, true).expect("post_join_to_room returned Err");
 
     assert_eq!(model.room_user_type, RoomUserType::Moderator);
+    assert_eq!(model.history, Some(20));
 }
 
 #[test]
@@ -1033,13 +1058,19 @@ def pre_kick_user_from_room(model):
     assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
     assert(model.get_target_user_id() == "69531fc4-bb09-41a7-aeb0-364876f1ff79")
     assert(model.get_ban() == True)
+    assert(model.get_reason() is None)
+    assert(model.get_ban_duration() == 60)
 
     model.set_ban(False)
+    model.set_reason("Cheating")
+    model.set_ban_duration(None)
 
 def post_kick_user_from_room(model, success):
     assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
     assert(model.get_target_user_id() == "69531fc4-bb09-41a7-aeb0-364876f1ff79")
     assert(model.get_ban() == False)
+    assert(model.get_reason() == "Cheating")
+    assert(model.get_ban_duration() is None)
 "#);
 
     let model = KickUserFromRoom {
@@ -1051,6 +1082,8 @@ def post_kick_user_from_room(model, success):
         room_id: RoomId::from("d508b370-6249-4fd3-9b3e-3aa66577a686".to_string()),
         user_id: UserId::from("69531fc4-bb09-41a7-aeb0-364876f1ff79".to_string()),
         ban: true,
+        reason: None,
+        ban_duration: Some(60),
         socket: Arc::new(DummyClient::default())
     };
 
@@ -1058,6 +1091,8 @@ def post_kick_user_from_room(model, success):
     let model = executer.post_kick_user_from_room(model, true).expect("post_kick_user_from_room returned Err");
 
     assert_eq!(model.ban, false);
+    assert_eq!(model.reason, Some("Cheating".to_string()));
+    assert_eq!(model.ban_duration, None);
 }
 
 #[test]
@@ -1150,6 +1185,187 @@ def post_play(model, success):
     assert_eq!(&model.message, "world");
 }
 
+#[test]
+fn set_ready_test() {
+    let (executer, _) = create_python_environtment("set_ready_test.py", r#"
+import yummy
+
+def pre_set_ready(model):
+    assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
+    assert(model.get_ready() == True)
+
+    model.set_ready(False)
+
+def post_set_ready(model, success):
+    assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
+    assert(model.get_ready() == False)
+"#);
+
+    let model = SetReady {
+        request_id: Some(123),
+        auth: Arc::new(Some(UserAuth {
+            user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+            session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+        })),
+        room_id: RoomId::from("d508b370-6249-4fd3-9b3e-3aa66577a686".to_string()),
+        ready: true,
+        socket: Arc::new(DummyClient::default())
+    };
+
+    let model = executer.pre_set_ready(model).expect("pre_set_ready returned Err");
+    let model = executer.post_set_ready(model, true).expect("post_set_ready returned Err");
+    assert!(!model.ready);
+}
+
+#[test]
+fn select_new_owner_test() {
+    let (executer, _) = create_python_environtment("select_new_owner_test.py", r#"
+from yummy import constants
+
+def pre_select_new_owner(model):
+    assert(model.get_room_id() == "d508b370-6249-4fd3-9b3e-3aa66577a686")
+    assert(model.get_previous_owner() == "294a6097-b8ea-4daa-b699-9f0c0c119c6d")
+    assert(model.get_new_owner() == "1bca52a9-4b98-45dd-bda9-93468d1b583f")
+
+    users = model.get_users()
+    assert(len(users) == 2)
+    assert(users[1]["type"] == constants.ROOM_USER_TYPE_MODERATOR)
+
+    model.set_new_owner(users[1]["user_id"])
+
+def post_select_new_owner(model, success):
+    assert(model.get_new_owner() == "6c1ad6dc-0d55-4a6f-b7c8-2e29ab3cb1e2")
+"#);
+
+    let model = SelectNewOwner {
+        room_id: RoomId::from("d508b370-6249-4fd3-9b3e-3aa66577a686".to_string()),
+        previous_owner: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        users: vec![RoomUserInformation {
+            user_id: Arc::new(UserId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())),
+            name: None,
+            user_type: RoomUserType::User
+        }, RoomUserInformation {
+            user_id: Arc::new(UserId::from("6c1ad6dc-0d55-4a6f-b7c8-2e29ab3cb1e2".to_string())),
+            name: None,
+            user_type: RoomUserType::Moderator
+        }],
+        new_owner: Some(Arc::new(UserId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())))
+    };
+
+    let model = executer.pre_select_new_owner(model).expect("pre_select_new_owner returned Err");
+    let model = executer.post_select_new_owner(model, true).expect("post_select_new_owner returned Err");
+    assert_eq!(model.new_owner, Some(Arc::new(UserId::from("6c1ad6dc-0d55-4a6f-b7c8-2e29ab3cb1e2".to_string()))));
+}
+
+#[test]
+fn join_queue_test() {
+    let (executer, _) = create_python_environtment("join_queue_test.py", r#"
+import yummy
+
+def pre_join_queue(model):
+    assert(model.get_queue() == "ranked")
+    assert(model.get_properties() == {"skill": 10})
+
+    model.set_properties({"skill": 20, "region": "eu"})
+
+def post_join_queue(model, success):
+    assert(model.get_properties() == {"skill": 20, "region": "eu"})
+"#);
+
+    let model = JoinMatchmakingQueue {
+        request_id: Some(123),
+        auth: Arc::new(Some(UserAuth {
+            user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+            session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+        })),
+        queue: "ranked".to_string(),
+        properties: HashMap::from([("skill".to_string(), serde_json::json!(10))]),
+        socket: Arc::new(DummyClient::default())
+    };
+
+    let model = executer.pre_join_queue(model).expect("pre_join_queue returned Err");
+    let model = executer.post_join_queue(model, true).expect("post_join_queue returned Err");
+    assert_eq!(model.properties.get("region"), Some(&serde_json::json!("eu")));
+}
+
+#[test]
+fn match_found_test() {
+    let (executer, _) = create_python_environtment("match_found_test.py", r#"
+import yummy
+
+def pre_match_found(model):
+    assert(model.get_queue() == "ranked")
+    assert(model.get_user_ids() == ["294a6097-b8ea-4daa-b699-9f0c0c119c6d"])
+    assert(model.get_room_name() is None)
+    assert(model.get_max_user() == 2)
+
+    model.set_room_name("ranked room")
+    model.set_tags(["ranked"])
+
+def post_match_found(model, success):
+    assert(model.get_room_name() == "ranked room")
+    assert(model.get_tags() == ["ranked"])
+"#);
+
+    let model = MatchFound {
+        queue: "ranked".to_string(),
+        tickets: vec![MatchmakingTicket {
+            user_id: Arc::new(UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string())),
+            session_id: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string()),
+            properties: HashMap::new(),
+            insert_date: 0
+        }],
+        room_name: None,
+        max_user: 2,
+        tags: Vec::new()
+    };
+
+    let model = executer.pre_match_found(model).expect("pre_match_found returned Err");
+    let model = executer.post_match_found(model, true).expect("post_match_found returned Err");
+    assert_eq!(model.room_name, Some("ranked room".to_string()));
+}
+
+#[test]
+fn check_match_compatibility_test() {
+    let (executer, _) = create_python_environtment("check_match_compatibility_test.py", r#"
+import yummy
+
+def pre_check_match_compatibility(model):
+    assert(model.get_queue() == "ranked")
+    assert(model.get_left()["properties"] == {"mode": "duel"})
+    assert(model.get_right()["properties"] == {"mode": "team"})
+    assert(model.get_tolerance() == 10.0)
+    assert(model.get_compatible() == False)
+
+    model.set_compatible(True)
+
+def post_check_match_compatibility(model, success):
+    assert(model.get_compatible() == True)
+"#);
+
+    let model = CheckMatchCompatibility {
+        queue: "ranked".to_string(),
+        left: MatchmakingTicket {
+            user_id: Arc::new(UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string())),
+            session_id: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string()),
+            properties: HashMap::from([("mode".to_string(), serde_json::json!("duel"))]),
+            insert_date: 0
+        },
+        right: MatchmakingTicket {
+            user_id: Arc::new(UserId::from("6c1ad6dc-0d55-4a6f-b7c8-2e29ab3cb1e2".to_string())),
+            session_id: SessionId::from("d508b370-6249-4fd3-9b3e-3aa66577a686".to_string()),
+            properties: HashMap::from([("mode".to_string(), serde_json::json!("team"))]),
+            insert_date: 0
+        },
+        tolerance: 10.0,
+        compatible: false
+    };
+
+    let model = executer.pre_check_match_compatibility(model).expect("pre_check_match_compatibility returned Err");
+    let model = executer.post_check_match_compatibility(model, true).expect("post_check_match_compatibility returned Err");
+    assert!(model.compatible);
+}
+
 #[test]
 fn room_list_request_test() {
     let (executer, _) = create_python_environtment("room_list_request_test.py", r#"
@@ -1169,7 +1385,7 @@ def post_room_list_request(model, success):
 
     let model = RoomListRequest {
         request_id: Some(123),
-        tag: None,
+        filter: RoomListFilter::default(),
         members: vec![RoomInfoTypeVariant::RoomName],
         socket: Arc::new(DummyClient::default())
     };
@@ -1177,10 +1393,47 @@ def post_room_list_request(model, success):
     let model = executer.pre_room_list_request(model).expect("pre_room_list_request returned Err");
     let model = executer.post_room_list_request(model, true).expect("post_room_list_request returned Err");
     
-    assert_eq!(&model.tag, &Some("test".to_string()));
+    assert_eq!(&model.filter.tags, &vec!["test".to_string()]);
     assert_eq!(&model.members, &vec![RoomInfoTypeVariant::RoomName, RoomInfoTypeVariant::Metas]);
 }
 
+#[test]
+fn room_list_request_filter_test() {
+    let (executer, _) = create_python_environtment("room_list_request_filter_test.py", r#"
+import yummy
+
+def pre_room_list_request(model):
+    assert(model.get_tags() == ["first", "second"])
+    assert(model.get_tag() == "first")
+    model.set_tags(["test", "other"])
+
+    assert(model.get_cursor() is None)
+    model.set_cursor("10:d508b370-6249-4fd3-9b3e-3aa66577a686")
+
+    assert(model.get_limit() == 0)
+    model.set_limit(10)
+
+def post_room_list_request(model, success):
+    assert(model.get_tags() == ["test", "other"])
+    assert(model.get_cursor() == "10:d508b370-6249-4fd3-9b3e-3aa66577a686")
+    assert(model.get_limit() == 10)
+"#);
+
+    let model = RoomListRequest {
+        request_id: Some(123),
+        filter: RoomListFilter { tags: vec!["first".to_string(), "second".to_string()], ..Default::default() },
+        members: vec![RoomInfoTypeVariant::RoomName],
+        socket: Arc::new(DummyClient::default())
+    };
+
+    let model = executer.pre_room_list_request(model).expect("pre_room_list_request returned Err");
+    let model = executer.post_room_list_request(model, true).expect("post_room_list_request returned Err");
+
+    assert_eq!(&model.filter.tags, &vec!["test".to_string(), "other".to_string()]);
+    assert_eq!(model.filter.cursor.as_deref(), Some("10:d508b370-6249-4fd3-9b3e-3aa66577a686"));
+    assert_eq!(model.filter.limit, 10);
+}
+
 #[test]
 fn waiting_room_joins_test() {
     let (executer, _) = create_python_environtment("waiting_room_joins_test.py", r#"
@@ -1281,6 +1534,78 @@ model_tester!(custom_id_auth_tester, "custom_id_auth_tester.py", pre_customid_au
     socket: Arc::new(DummyClient::default())
 });
 
+model_tester!(external_auth_tester, "external_auth_tester.py", pre_external_auth, post_external_auth, ExternalAuthRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    token: "TOKEN".to_string(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(link_identity_tester, "link_identity_tester.py", pre_link_identity, post_link_identity, LinkIdentityRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    identity: RequestIdentity::DeviceId { id: "1234567890".to_string() },
+    merge: false,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(unlink_identity_tester, "unlink_identity_tester.py", pre_unlink_identity, post_unlink_identity, UnlinkIdentityRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    identity: RequestUnlinkIdentity::DeviceId,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(verify_email_tester, "verify_email_tester.py", pre_verify_email, post_verify_email, VerifyEmailRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    token: "TOKEN".to_string(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(send_verification_email_tester, "send_verification_email_tester.py", pre_send_verification_email, post_send_verification_email, SendVerificationEmailRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    email: "abc@gmail.com".to_string(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(forgot_password_tester, "forgot_password_tester.py", pre_forgot_password, post_forgot_password, ForgotPasswordRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    email: "abc@gmail.com".to_string(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(reset_password_tester, "reset_password_tester.py", pre_reset_password, post_reset_password, ResetPasswordRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    token: "TOKEN".to_string(),
+    password: Password::from("password123".to_string()),
+    socket: Arc::new(DummyClient::default())
+});
+
 model_tester!(logout_tester, "logout_tester.py", pre_logout, post_logout, LogoutRequest {
     request_id: Some(123),
     auth: Arc::new(Some(UserAuth {
@@ -1307,6 +1632,26 @@ model_tester!(restore_token, "restore_token.py", pre_restore_token, post_restore
         session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
     })),
     token: "TOKEN".to_string(),
+    last_sequence: None,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(list_sessions, "list_sessions.py", pre_list_sessions, post_list_sessions, ListSessionsRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(terminate_session, "terminate_session.py", pre_terminate_session, post_terminate_session, TerminateSessionRequest {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    session_id: SessionId::from("f1a0c3c5-3b5e-4c8e-9a4e-2f6d2a7c9b10".to_string()),
     socket: Arc::new(DummyClient::default())
 });
 
@@ -1328,6 +1673,18 @@ model_tester!(update_user, "update_user.py", pre_update_user, post_update_user,
     socket: Arc::new(DummyClient::default())
 });
 
+model_tester!(update_user_meta, "update_user_meta.py", pre_update_user_meta, post_update_user_meta, UpdateUserMeta {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    target_user_id: None,
+    key: "score".to_string(),
+    operation: UserMetaOperation::Increment { value: 1.0, min: None, max: None },
+    socket: Arc::new(DummyClient::default())
+});
+
 model_tester!(create_room, "create_room.py", pre_create_room, post_create_room, CreateRoomRequest {
     request_id: Some(123),
     auth: Arc::new(Some(UserAuth {
@@ -1339,8 +1696,12 @@ model_tester!(create_room, "create_room.py", pre_create_room, post_create_room,
     access_type: CreateRoomAccessType::Public,
     join_request: true,
     max_user: 1024,
+    max_spectator: 0,
     tags: Vec::new(),
     metas: None,
+    password: None,
+    generate_password: false,
+    auto_start: false,
     socket: Arc::new(DummyClient::default())
 });
 
@@ -1357,10 +1718,25 @@ model_tester!(update_room, "update_room.py", pre_update_room, post_update_room,
     access_type: None,
     join_request: None,
     max_user: None,
+    max_spectator: None,
     tags: None,
     metas: None,
     user_permission: None,
     meta_action: MetaAction::default(),
+    password: None,
+    generate_password: false,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(update_room_meta, "update_room_meta.py", pre_update_room_meta, post_update_room_meta, UpdateRoomMeta {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    key: "players".to_string(),
+    operation: RoomMetaOperation::Append { value: RoomMetaType::String("erhan".to_string(), RoomMetaAccess::Anonymous) },
     socket: Arc::new(DummyClient::default())
 });
 
@@ -1372,6 +1748,8 @@ model_tester!(join_to_room, "join_to_room.py", pre_join_to_room, post_join_to_ro
     })),
     room_id: RoomId::new(),
     room_user_type: RoomUserType::default(),
+    password: None,
+    history: None,
     socket: Arc::new(DummyClient::default())
 });
 
@@ -1396,6 +1774,31 @@ model_tester!(kick_user_from_room, "kick_user_from_room.py", pre_kick_user_from_
     room_id: RoomId::new(),
     user_id: UserId::new(),
     ban: true,
+    reason: None,
+    ban_duration: None,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(unban_user_from_room, "unban_user_from_room.py", pre_unban_user_from_room, post_unban_user_from_room, UnbanUserFromRoom {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    user_id: UserId::new(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(suspend_user, "suspend_user.py", pre_suspend_user, post_suspend_user, SuspendUser {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    user_id: UserId::new(),
+    reason: Some("cheating".to_string()),
+    duration: Some(60),
     socket: Arc::new(DummyClient::default())
 });
 
@@ -1451,3 +1854,99 @@ model_tester!(get_room_request, "waiting_room_joins.py", pre_get_room_request, p
     members: vec![RoomInfoTypeVariant::RoomName],
     socket: Arc::new(DummyClient::default())
 });
+
+model_tester!(leave_queue, "leave_queue.py", pre_leave_queue, post_leave_queue, LeaveMatchmakingQueue {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    queue: "ranked".to_string(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(start_game, "start_game.py", pre_start_game, post_start_game, StartGame {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(finish_game, "finish_game.py", pre_finish_game, post_finish_game, FinishGame {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(room_message_history, "room_message_history.py", pre_room_message_history, post_room_message_history, RoomMessageHistory {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    before: None,
+    limit: Some(10),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(invite_to_room, "invite_to_room.py", pre_invite_to_room, post_invite_to_room, InviteToRoom {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    user_id: UserId::new(),
+    room_user_type: RoomUserType::User,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(accept_room_invite, "accept_room_invite.py", pre_accept_room_invite, post_accept_room_invite, AcceptRoomInvite {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    history: None,
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(decline_room_invite, "decline_room_invite.py", pre_decline_room_invite, post_decline_room_invite, DeclineRoomInvite {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(room_invites, "room_invites.py", pre_room_invites, post_room_invites, RoomInvites {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    socket: Arc::new(DummyClient::default())
+});
+
+model_tester!(cancel_room_invite, "cancel_room_invite.py", pre_cancel_room_invite, post_cancel_room_invite, CancelRoomInvite {
+    request_id: Some(123),
+    auth: Arc::new(Some(UserAuth {
+        user: UserId::from("294a6097-b8ea-4daa-b699-9f0c0c119c6d".to_string()),
+        session: SessionId::from("1bca52a9-4b98-45dd-bda9-93468d1b583f".to_string())
+    })),
+    room_id: RoomId::new(),
+    user_id: UserId::new(),
+    socket: Arc::new(DummyClient::default())
+});
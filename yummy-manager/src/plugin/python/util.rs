@@ -3,11 +3,13 @@
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
 use std::fmt::Debug;
+use std::collections::HashMap;
 use yummy_model::meta::{UserMetaAccess, UserMetaType, MetaType, RoomMetaType, RoomMetaAccess};
 use num_bigint::ToBigInt;
 use num_traits::Zero;
-use rustpython::vm::{VirtualMachine, PyObjectRef, PyRef, builtins::{PyBaseException, PyFloat, PyInt, PyStr, PyList}, PyResult};
+use rustpython::vm::{VirtualMachine, PyObjectRef, PyRef, builtins::{PyBaseException, PyFloat, PyInt, PyStr, PyList, PyDict}, PyResult};
 use rustpython_vm::class::PyClassImpl;
+use rustpython_vm::function::OptionalArg;
 
 use crate::plugin::python::modules::base::_base::PyYummyValidationError;
 
@@ -76,6 +78,18 @@ impl MetaTypeUtil {
             return Ok(UserMetaTypeWrapper::new(UserMetaType::List(Box::new(meta_list), access)));
         }
 
+        /* Parse dict items, keys should be str */
+        if obj.class().fast_issubclass(vm.ctx.types.dict_type) {
+            let mut meta_object = HashMap::new();
+            let python_dict = obj.downcast_ref::<PyDict>().unwrap();
+            for (key, value) in python_dict {
+                let key = Self::parse_object_key(vm, &key)?;
+                meta_object.insert(key, Self::parse_user_meta(vm, &value, UserMetaAccess::User)?.data);
+            }
+
+            return Ok(UserMetaTypeWrapper::new(UserMetaType::Object(Box::new(meta_object), access)));
+        }
+
         /* Item is not valid or Null */
         Ok(UserMetaTypeWrapper { data: UserMetaType::Null })
     }
@@ -113,10 +127,58 @@ impl MetaTypeUtil {
             return Ok(RoomMetaTypeWrapper::new(RoomMetaType::List(Box::new(meta_list), access)));
         }
 
+        /* Parse dict items, keys should be str */
+        if obj.class().fast_issubclass(vm.ctx.types.dict_type) {
+            let mut meta_object = HashMap::new();
+            let python_dict = obj.downcast_ref::<PyDict>().unwrap();
+            for (key, value) in python_dict {
+                let key = Self::parse_object_key(vm, &key)?;
+                meta_object.insert(key, Self::parse_room_meta(vm, &value, RoomMetaAccess::User)?.data);
+            }
+
+            return Ok(RoomMetaTypeWrapper::new(RoomMetaType::Object(Box::new(meta_object), access)));
+        }
+
         /* Item is not valid or Null */
         Ok(RoomMetaTypeWrapper { data: RoomMetaType::Null })
     }
 
+    fn parse_object_key(vm: &VirtualMachine, key: &PyObjectRef) -> Result<String, PyRef<PyBaseException>> {
+        match key.downcast_ref::<PyStr>() {
+            Some(str) => Ok(str.as_str().to_string()),
+            None => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Only str type allowed for the key.".to_string()))
+        }
+    }
+
+    /* Integer and float objects are accepted as number, None means there is no value */
+    pub fn parse_number(vm: &VirtualMachine, obj: &PyObjectRef) -> Result<Option<f64>, PyRef<PyBaseException>> {
+        match Self::parse_user_meta(vm, obj, UserMetaAccess::System)?.data {
+            MetaType::Number(number, _) => Ok(Some(number)),
+            MetaType::Null => Ok(None),
+            _ => Err(vm.new_exception_msg(PyYummyValidationError::make_class(&vm.ctx), "Value is not number".to_string()))
+        }
+    }
+
+    /* Value is required, min and max are optional arguments for the number operations */
+    pub fn parse_number_arguments(vm: &VirtualMachine, value: &PyObjectRef, min: OptionalArg<PyObjectRef>, max: OptionalArg<PyObjectRef>) -> PyResult<Option<(f64, Option<f64>, Option<f64>)>> {
+        let value = match Self::parse_number(vm, value)? {
+            Some(value) => value,
+            None => return Ok(None)
+        };
+
+        let min = match min {
+            OptionalArg::Present(min) => Self::parse_number(vm, &min)?,
+            OptionalArg::Missing => None
+        };
+
+        let max = match max {
+            OptionalArg::Present(max) => Self::parse_number(vm, &max)?,
+            OptionalArg::Missing => None
+        };
+
+        Ok(Some((value, min, max)))
+    }
+
     pub fn as_python_value<T: Default + Debug + PartialEq + Clone + From<i32>>(meta: &MetaType<T>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
         Self::inner_as_python_value(meta, vm)
     }
@@ -134,6 +196,14 @@ impl MetaTypeUtil {
                 }
                 Ok(vm.ctx.new_list(items).into())
             },
+            MetaType::Object(value, _) => {
+                let dict = vm.ctx.new_dict();
+                for (key, item) in value.iter() {
+                    dict.set_item(&key[..], Self::inner_as_python_value(item, vm)?, vm)?;
+                }
+                Ok(dict.into())
+            },
+            MetaType::Expiring(meta, _) => Self::inner_as_python_value(meta, vm),
         }
     }
 
@@ -144,6 +214,8 @@ impl MetaTypeUtil {
             MetaType::String(_, _) => Ok(vm.ctx.new_bigint(&2.to_bigint().unwrap()).into()),
             MetaType::Bool(_, _) => Ok(vm.ctx.new_bigint(&3.to_bigint().unwrap()).into()),
             MetaType::List(_, _) => Ok(vm.ctx.new_bigint(&4.to_bigint().unwrap()).into()),
+            MetaType::Object(_, _) => Ok(vm.ctx.new_bigint(&5.to_bigint().unwrap()).into()),
+            MetaType::Expiring(meta, _) => Self::get_meta_type(meta, vm),
         }
     }
 }
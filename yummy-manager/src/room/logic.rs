@@ -9,11 +9,16 @@ use actix_broker::{SystemBroker, Broker};
 use serde_json::Value;
 use yummy_cache::state::YummyState;
 use yummy_database::DatabaseTrait;
-use yummy_model::meta::collection::RoomMetaCollectionInformation;
-use yummy_model::{RoomId, UserId, SendMessage};
-use yummy_model::meta::{RoomMetaType, RoomMetaAccess};
+use yummy_general::database::Pool;
+use yummy_model::config::YummyConfig;
+use yummy_model::meta::collection::{RoomMetaCollection, RoomMetaCollectionInformation};
+use yummy_model::{RoomId, UserId, SendMessage, RoomUserType, RoomMetaId};
+use yummy_model::meta::{RoomMetaType, RoomMetaAccess, MetaType};
+use yummy_model::meta::operation::RoomMetaOperation;
 
-use super::model::RoomResponse;
+use crate::user::model::UserError;
+
+use super::model::{RoomResponse, RoomError, RoomMetaChanges};
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -22,6 +27,8 @@ use super::model::RoomResponse;
 /* **************************************************************************************************************** */
 #[derive(Clone)]
 pub struct RoomLogic<DB: DatabaseTrait + ?Sized> {
+    config: Arc<YummyConfig>,
+    database: Arc<Pool>,
     states: YummyState,
     _marker: PhantomData<DB>
 }
@@ -33,8 +40,10 @@ pub struct RoomLogic<DB: DatabaseTrait + ?Sized> {
 /* ************************************************* IMPLEMENTS *************************************************** */
 /* **************************************************************************************************************** */
 impl<DB: DatabaseTrait + ?Sized> RoomLogic<DB> {
-    pub fn new(states: YummyState) -> Self {
+    pub fn new(config: Arc<YummyConfig>, states: YummyState, database: Arc<Pool>) -> Self {
         Self {
+            config,
+            database,
             states,
             _marker: PhantomData
         }
@@ -52,15 +61,87 @@ impl<DB: DatabaseTrait + ?Sized> RoomLogic<DB> {
     }
 
     pub fn set_room_meta(&self, room_id: RoomId, key: String, value: RoomMetaType) -> anyhow::Result<()> {
-        Ok(self.states.set_room_meta(&room_id, key, value)?)
+        let previous_metas = self.states.get_room_meta(&room_id, RoomMetaAccess::System)?;
+        self.states.set_room_meta(&room_id, key, value)?;
+        self.publish_meta_changes(&room_id, None, &previous_metas)
     }
 
     pub fn remove_all_metas(&self, room_id: RoomId) -> anyhow::Result<()> {
-        Ok(self.states.remove_all_room_metas(&room_id)?)
+        let previous_metas = self.states.get_room_meta(&room_id, RoomMetaAccess::System)?;
+        self.states.remove_all_room_metas(&room_id)?;
+        self.publish_meta_changes(&room_id, None, &previous_metas)
     }
 
     pub fn remove_room_meta(&self, room_id: RoomId, key: String) -> anyhow::Result<()> {
-        Ok(self.states.remove_room_meta(&room_id, key)?)
+        let previous_metas = self.states.get_room_meta(&room_id, RoomMetaAccess::System)?;
+        self.states.remove_room_meta(&room_id, key)?;
+        self.publish_meta_changes(&room_id, None, &previous_metas)
+    }
+
+    pub fn apply_room_meta_operation(&self, room_id: RoomId, key: String, operation: RoomMetaOperation) -> anyhow::Result<RoomMetaType> {
+        let previous_metas = self.states.get_room_meta(&room_id, RoomMetaAccess::System)?;
+        let meta = self.apply_meta_operation(&room_id, key, RoomMetaAccess::System, &operation, false)?;
+        self.publish_meta_changes(&room_id, None, &previous_metas)?;
+        Ok(meta)
+    }
+
+    /* Room manager sends the changes to the room members with the next tick */
+    pub fn publish_meta_changes(&self, room_id: &RoomId, user_id: Option<&UserId>, previous_metas: &RoomMetaCollection) -> anyhow::Result<()> {
+        let metas = self.states.get_room_meta(room_id, RoomMetaAccess::System)?.get_changes(previous_metas);
+
+        if !metas.is_empty() {
+            Broker::<SystemBroker>::issue_async(RoomMetaChanges {
+                room_id: *room_id,
+                user_id: user_id.map(|user_id| Arc::new(user_id.clone())),
+                metas
+            });
+        }
+
+        Ok(())
+    }
+
+    /* State executes the operation atomically, database record is replaced before the state updated */
+    pub fn apply_meta_operation(&self, room_id: &RoomId, key: String, access_level: RoomMetaAccess, operation: &RoomMetaOperation, validate: bool) -> anyhow::Result<RoomMetaType> {
+        if operation.get_access_level() > access_level {
+            return Err(anyhow!(UserError::MetaAccessLevelCannotBeBiggerThanUsersAccessLevel(key)));
+        }
+
+        let max_room_meta = self.config.max_room_meta;
+        let schema = &self.config.meta_schema.room;
+        let mut connection = self.database.get()?;
+
+        Ok(self.states.apply_room_meta_operation(room_id, key.clone(), access_level, operation, |meta| {
+            /* State keeps the previous value when the schema rejects the computed one */
+            if validate {
+                schema.validate(&key, meta)?;
+            }
+
+            DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+                let metas = DB::get_room_meta(connection, room_id, RoomMetaAccess::System)?;
+                let remove_list = metas.iter().filter(|item| item.name == key).filter_map(|item| item.id.clone()).collect::<Vec<RoomMetaId>>();
+
+                if let MetaType::Null = meta {
+                    return DB::remove_room_metas(connection, remove_list);
+                }
+
+                if remove_list.is_empty() && metas.len() >= max_room_meta {
+                    return Err(anyhow!(RoomError::MetaLimitOverToMaximum));
+                }
+
+                DB::remove_room_metas(connection, remove_list)?;
+                DB::insert_room_metas(connection, room_id, &[(&key, meta)])
+            })
+        })?)
+    }
+
+    /* Room manager calls it with the sweep interval */
+    pub fn remove_expired_metas(&self) -> anyhow::Result<usize> {
+        DB::remove_expired_room_metas(&mut self.database.get()?)
+    }
+
+    pub fn get_room_user_type(&self, room_id: &RoomId, user_id: &UserId) -> anyhow::Result<Option<RoomUserType>> {
+        let session_id = self.states.get_user_session_id(user_id, room_id)?;
+        self.states.clone().get_users_room_type(&session_id, room_id)
     }
 
     pub fn message_to_room(&self, room_id: &RoomId, sender_user_id: Option<&UserId>, message: &Value) -> anyhow::Result<()> {
@@ -10,26 +10,27 @@ pub mod logic;
 #[cfg(test)]
 mod test;
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{marker::PhantomData, ops::Deref};
 use std::sync::Arc;
-use actix::{Context, Actor, Handler};
+use actix::{Context, Actor, Handler, AsyncContext};
 use actix_broker::{BrokerSubscribe, BrokerIssue};
+use serde_json::Value;
 use yummy_cache::state::YummyState;
 use yummy_database::DatabaseTrait;
 
-use yummy_model::config::YummyConfig;
+use yummy_model::config::{YummyConfig, RoomOwnerSuccession};
 use yummy_model::meta::collection::RoomMetaCollection;
-use yummy_model::meta::{MetaType, MetaAction};
+use yummy_model::meta::{MetaType, MetaAction, MetaDefinitions};
 use yummy_model::meta::RoomMetaAccess;
 use yummy_model::request::RequestRoomTypeVariant;
 use yummy_model::user::RoomUpdate;
-use yummy_model::{RoomId, UserId, RoomUserType, UserType, SessionId, SendMessage};
+use yummy_model::{RoomId, UserId, RoomUserType, UserType, SessionId, SendMessage, CreateRoomAccessType, RoomGameState};
 use yummy_model::web::{GenericAnswer, Answer};
 use yummy_general::database::Pool;
 use yummy_general::database::PooledConnection;
-use yummy_model::state::{RoomInfoType, RoomInfoTypeVariant};
+use yummy_model::state::{RoomInfoType, RoomInfoTypeVariant, RoomUserInformation, RoomMessage, RoomInvite, RoomBan};
 
 use crate::YummyModel;
 use crate::auth::model::{AuthError, RoomUserDisconnect};
@@ -43,10 +44,13 @@ use self::model::*;
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
 /* **************************************************************************************************************** */
-const ALL_ROOM_INFO_TYPE_VARIANTS: [RoomInfoTypeVariant; 10] = [RoomInfoTypeVariant::Tags, RoomInfoTypeVariant::InsertDate, RoomInfoTypeVariant::RoomName, RoomInfoTypeVariant::AccessType, RoomInfoTypeVariant::Users, RoomInfoTypeVariant::MaxUser, RoomInfoTypeVariant::UserLength, RoomInfoTypeVariant::BannedUsers, RoomInfoTypeVariant::JoinRequest, RoomInfoTypeVariant::Metas];
+const ALL_ROOM_INFO_TYPE_VARIANTS: [RoomInfoTypeVariant; 17] = [RoomInfoTypeVariant::Tags, RoomInfoTypeVariant::InsertDate, RoomInfoTypeVariant::RoomName, RoomInfoTypeVariant::AccessType, RoomInfoTypeVariant::Users, RoomInfoTypeVariant::MaxUser, RoomInfoTypeVariant::UserLength, RoomInfoTypeVariant::BannedUsers, RoomInfoTypeVariant::JoinRequest, RoomInfoTypeVariant::Metas, RoomInfoTypeVariant::Password, RoomInfoTypeVariant::Spectators, RoomInfoTypeVariant::MaxSpectator, RoomInfoTypeVariant::SpectatorLength, RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::ReadyUsers, RoomInfoTypeVariant::AutoStart];
 
 type ConfigureMetasResult = anyhow::Result<(Option<HashMap<String, MetaType<RoomMetaAccess>>>, RoomMetaCollection)>;
 
+/* Changed key with the lowest access level and the last user who changed it */
+type PendingMetaChanges = HashMap<String, (RoomMetaAccess, Option<Arc<UserId>>)>;
+
 
 /* **************************************************************************************************************** */
 /* **************************************************** MACROS **************************************************** */
@@ -58,6 +62,7 @@ pub struct RoomManager<DB: DatabaseTrait + ?Sized> {
     states: YummyState,
     executer: Arc<PluginExecuter>,
     logic: RoomLogic<DB>,
+    meta_changes: HashMap<RoomId, PendingMetaChanges>,
     _marker: PhantomData<DB>
 }
 
@@ -74,14 +79,71 @@ impl<DB: DatabaseTrait + ?Sized> RoomManager<DB> {
             database: database.clone(),
             states: states.clone(),
             executer,
-            logic: RoomLogic::new(states),
+            logic: RoomLogic::new(config, states, database),
+            meta_changes: HashMap::new(),
             _marker: PhantomData
         }
     }
 }
 
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> RoomManager<DB> {
-    fn join_to_room(&mut self, connection: &mut PooledConnection, request_id: Option<usize>, room_id: &RoomId, user_id: &UserId, session_id: &SessionId, room_user_type: RoomUserType) -> anyhow::Result<()> {
+    fn generate_room_password(&self) -> String {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        (0..self.config.room_password_length)
+            .map(|_| self.config.room_password_charset[rng.gen_range(0..self.config.room_password_charset.len())] as char)
+            .collect()
+    }
+
+    /* Generated password has priority over the user defined one. Empty password means no password */
+    fn get_room_password(&self, password: &Option<String>, generate_password: bool) -> Option<String> {
+        match generate_password {
+            true => Some(self.generate_room_password()),
+            false => password.as_ref().map(|item| item.trim()).filter(|item| !item.is_empty()).map(|item| item.to_string())
+        }
+    }
+
+    /* Messages are kept in the room history ring and optionally in the database */
+    fn add_room_message(&self, room_id: &RoomId, user_id: Option<&UserId>, message: &Value) -> anyhow::Result<()> {
+        if self.config.room_message_history_size == 0 && !self.config.room_message_history_persist {
+            return Ok(());
+        }
+
+        let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let message = self.states.add_room_message(room_id, user_id, message.clone(), insert_date)?;
+
+        if self.config.room_message_history_persist {
+            let mut connection = self.database.get()?;
+            DB::insert_room_message(&mut connection, room_id, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_room_messages(&self, room_id: &RoomId, before: Option<usize>, limit: usize) -> anyhow::Result<Vec<RoomMessage>> {
+        let limit = limit.min(self.config.max_room_message_history_page_size);
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        match self.config.room_message_history_persist {
+            true => DB::get_room_messages(&mut self.database.get()?, room_id, before, limit),
+            false => Ok(self.states.get_room_messages(room_id, before, limit)?)
+        }
+    }
+
+    /* Room owner and moderators can manage the room */
+    fn check_room_manager(&mut self, session_id: &SessionId, room_id: &RoomId) -> anyhow::Result<RoomUserType> {
+        match self.states.get_users_room_type(session_id, room_id)? {
+            Some(room_user_type) if room_user_type == RoomUserType::Owner || room_user_type == RoomUserType::Moderator => Ok(room_user_type),
+            Some(_) => Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission)),
+            None => Err(anyhow::anyhow!(RoomError::UserNotInTheRoom))
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn join_to_room(&mut self, connection: &mut PooledConnection, request_id: Option<usize>, room_id: &RoomId, user_id: &UserId, session_id: &SessionId, room_user_type: RoomUserType, history: usize) -> anyhow::Result<()> {
         /* Room does not require approvement */
         let users = self.states.get_users_from_room(room_id)?;
         self.states.join_to_room(room_id, user_id, session_id, room_user_type.clone())?;
@@ -102,19 +164,22 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> RoomManager<DB>
 
         let access_level = self.get_access_level_for_room(user_id, session_id, room_id)?;
         
-        let infos = self.states.get_room_info(room_id, access_level, &[RoomInfoTypeVariant::RoomName, RoomInfoTypeVariant::Users, RoomInfoTypeVariant::Metas])?;
+        let infos = self.states.get_room_info(room_id, access_level, &[RoomInfoTypeVariant::RoomName, RoomInfoTypeVariant::Users, RoomInfoTypeVariant::Spectators, RoomInfoTypeVariant::Metas])?;
         let room_name = infos.get_room_name();
         let users = infos.get_users();
+        let spectators = infos.get_spectators();
         let metas = infos.get_metas();
+        let messages = self.get_room_messages(room_id, None, history)?;
         
         self.issue_system_async(SendMessage {
             user_id: Arc::new(user_id.clone()),
-            message: GenericAnswer::success(request_id, Cow::Borrowed("JoinToRoom"), JoinToRoom { result: "Joined", room_name, users, metas, room_id }).into()
+            message: GenericAnswer::success(request_id, Cow::Borrowed("JoinToRoom"), JoinToRoom { result: "Joined", room_name, users, spectators, metas, room_id, messages }).into()
         });
         Ok(())
     }
 
     fn disconnect_from_room(&mut self, room_id: &RoomId, user_id: &UserId, session_id: &SessionId) -> anyhow::Result<bool> {
+        let room_user_type = self.states.get_users_room_type(session_id, room_id)?;
         let room_removed = self.states.disconnect_from_room(room_id, user_id, session_id)?;
         let users = self.states.get_users_from_room(room_id).unwrap_or_default();
         
@@ -133,12 +198,142 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> RoomManager<DB>
             });
         }
 
+        /* Room should not stay without owner */
+        if !room_removed && room_user_type == Some(RoomUserType::Owner) {
+            if let Err(error) = self.select_new_owner(room_id, user_id) {
+                log::error!("New room owner could not selected: {:?}", error);
+            }
+        }
+
+        /* Remaining players might be all ready now */
+        if !room_removed {
+            if let Err(error) = self.auto_start_if_ready(room_id) {
+                log::error!("Auto start could not checked: {:?}", error);
+            }
+        }
+
         Ok(room_removed)
     }
 
-    fn configure_metas(&self, connection: &mut PooledConnection, room_id: &RoomId, metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>, meta_action: MetaAction, access_level: RoomMetaAccess) -> ConfigureMetasResult {
+    fn auto_start_if_ready(&mut self, room_id: &RoomId) -> anyhow::Result<()> {
+        let room_infos = self.states.get_room_info(room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::AutoStart])?;
+
+        if room_infos.get_auto_start().into_owned() && room_infos.get_game_state().into_owned() == RoomGameState::ReadyCheck && self.is_all_players_ready(room_id)? {
+            self.change_game_state(room_id, RoomGameState::Playing)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_default_new_owner(&self, users: &[RoomUserInformation]) -> Option<Arc<UserId>> {
+        let user = match self.config.room_owner_succession {
+            RoomOwnerSuccession::Oldest => users.first(),
+
+            /* Oldest one wins if there are multiple users with the same type */
+            RoomOwnerSuccession::HighestType => users.iter().rev().max_by_key(|user| i32::from(user.user_type.clone()))
+        };
+
+        user.map(|user| user.user_id.clone())
+    }
+
+    /* pre_select_new_owner is the selection hook, plugin's choice is used only if the user is still playing in the room */
+    fn select_new_owner(&mut self, room_id: &RoomId, previous_owner: &UserId) -> anyhow::Result<()> {
+        let users = self.states.get_room_info(room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::Users])?.get_users().into_owned();
+        let default_owner = self.get_default_new_owner(&users);
+
+        let selection = SelectNewOwner {
+            room_id: *room_id,
+            previous_owner: previous_owner.clone(),
+            users: users.clone(),
+            new_owner: default_owner.clone()
+        };
+
+        let mut model = match self.executer.pre_select_new_owner(selection.clone()) {
+            Ok(model) => model,
+            Err(error) => {
+                log::error!("Plugin could not select the new room owner, default selection is used: {:?}", error);
+                selection
+            }
+        };
+
+        let is_valid_owner = match model.new_owner.as_ref() {
+            Some(new_owner) => users.iter().any(|user| &user.user_id == new_owner),
+            None => users.is_empty()
+        };
+
+        /* Room should not stay without owner because of the plugin's choice */
+        if !is_valid_owner {
+            log::warn!("Plugin selected {:?} as the new room owner but the user is not playing in the room, default selection is used", model.new_owner);
+            model.users = users;
+            model.new_owner = default_owner;
+        }
+
+        let result = self.change_room_owner(&model);
+        self.executer.post_select_new_owner(model, result.is_ok())?;
+        result
+    }
+
+    fn change_room_owner(&mut self, model: &SelectNewOwner) -> anyhow::Result<()> {
+        let new_owner = match model.new_owner.as_ref() {
+            Some(new_owner) => new_owner,
+
+            /* Only spectators are left in the room */
+            None => return Ok(())
+        };
+
+        /* Plugin could select someone who is not playing in the room */
+        if !model.users.iter().any(|user| &user.user_id == new_owner) {
+            return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom));
+        }
+
+        let mut connection = self.database.get()?;
+        DB::update_room_user_permissions(&mut connection, &model.room_id, &HashMap::from([(new_owner.deref().clone(), RoomUserType::Owner)]))?;
+        self.states.set_users_room_type(new_owner, &model.room_id, RoomUserType::Owner)?;
+
+        self.send_to_room(&model.room_id, RoomResponse::OwnerChanged {
+            room_id: &model.room_id,
+            user_id: new_owner,
+            previous_owner: &model.previous_owner
+        }.into())
+    }
+
+    fn send_to_room(&mut self, room_id: &RoomId, message: String) -> anyhow::Result<()> {
+        for user_id in self.states.get_users_from_room(room_id)?.into_iter() {
+            self.issue_system_async(SendMessage {
+                message: message.clone(),
+                user_id
+            });
+        }
+
+        Ok(())
+    }
+
+    /* Only the players need to be ready, spectators are just watching the game */
+    fn is_all_players_ready(&self, room_id: &RoomId) -> anyhow::Result<bool> {
+        let room_infos = self.states.get_room_info(room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::Users, RoomInfoTypeVariant::ReadyUsers])?;
+        let ready_users = room_infos.get_ready_users();
+        let users = room_infos.get_users();
+
+        Ok(!users.is_empty() && users.iter().all(|user| ready_users.contains(user.user_id.deref())))
+    }
+
+    fn change_game_state(&mut self, room_id: &RoomId, game_state: RoomGameState) -> anyhow::Result<()> {
+        let mut query = vec![RoomInfoType::GameState(game_state)];
+
+        /* Every game starts with a new ready check */
+        if game_state == RoomGameState::Playing {
+            query.push(RoomInfoType::ReadyUsers(HashSet::new()));
+        }
+
+        self.states.set_room_info(room_id, query);
+        self.send_to_room(room_id, RoomResponse::GameStateChanged { room_id, game_state }.into())
+    }
+
+    fn configure_metas(&self, connection: &mut PooledConnection, room_id: &RoomId, metas: Option<MetaDefinitions<RoomMetaAccess>>, meta_action: MetaAction, access_level: RoomMetaAccess) -> ConfigureMetasResult {
         let room_access_level_code = access_level as u8;
-        let mut metas = metas;
+
+        /* Client metas should be valid before touching the database */
+        let mut metas = self.config.meta_schema.room.validate_metas(metas)?;
 
         let (to_be_inserted_metas, to_be_removed_metas, total_metas, remaining) = match meta_action {
 
@@ -259,12 +454,88 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> RoomManager<DB>
         Ok(())
     }
 
+    fn send_meta_changes(&mut self) {
+        for (room_id, changes) in std::mem::take(&mut self.meta_changes).into_iter() {
+            if let Err(error) = self.send_room_meta_changes(&room_id, &changes) {
+                log::error!("Room meta changes could not sent: {:?}", error);
+            }
+        }
+    }
+
+    fn remove_old_room_messages(&mut self) {
+        if !self.config.room_message_history_persist {
+            return;
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let result = self.database.get().map_err(anyhow::Error::from)
+            .and_then(|mut connection| DB::remove_old_room_messages(&mut connection, now.saturating_sub(self.config.room_message_history_lifetime.as_secs() as i32)));
+
+        if let Err(error) = result {
+            log::error!("Old room messages could not removed: {:?}", error);
+        }
+    }
+
+    fn remove_expired_bans(&mut self) {
+        let result = self.database.get().map_err(anyhow::Error::from)
+            .and_then(|mut connection| DB::remove_expired_room_bans(&mut connection));
+
+        if let Err(error) = result {
+            log::error!("Expired room bans could not removed: {:?}", error);
+        }
+    }
+
+    fn remove_expired_metas(&mut self) {
+        if let Err(error) = self.logic.remove_expired_metas() {
+            log::error!("Expired room metas could not removed: {:?}", error);
+        }
+    }
+
+    /* Every member only receives the keys that visible to the member */
+    fn send_room_meta_changes(&mut self, room_id: &RoomId, changes: &PendingMetaChanges) -> anyhow::Result<()> {
+
+        /* Room could be removed before the tick */
+        if !self.states.is_room_exists(room_id)? {
+            return Ok(());
+        }
+
+        let metas = self.states.get_room_meta(room_id, RoomMetaAccess::System)?;
+
+        for user_id in self.states.get_users_from_room(room_id)?.into_iter() {
+            let session_id = self.states.get_user_session_id(&user_id, room_id)?;
+            let access_level = self.get_access_level_for_room(&user_id, &session_id, room_id)?;
+
+            let changed_metas = changes.iter()
+
+                /* Last writer already knows the value */
+                .filter(|(_, (meta_access_level, changed_by))| *meta_access_level <= access_level && changed_by.as_ref() != Some(&user_id))
+                .map(|(key, _)| {
+                    let meta = metas.get_with_name(key)
+                        .map(|item| &item.meta)
+                        .filter(|meta| meta.get_access_level() <= access_level)
+                        .unwrap_or(&MetaType::Null);
+                    (&key[..], meta)
+                })
+                .collect::<HashMap<_, _>>();
+
+            if !changed_metas.is_empty() {
+                self.issue_system_async(SendMessage {
+                    message: RoomResponse::RoomMetaChanged { room_id, metas: changed_metas }.into(),
+                    user_id
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     fn get_access_level_for_room(&mut self, user_id: &UserId, session_id: &SessionId, room_id: &RoomId) -> anyhow::Result<RoomMetaAccess> {
         match self.states.get_user_type(user_id)? {
             Some(UserType::User) => match self.states.get_users_room_type(session_id, room_id)? {
                 Some(RoomUserType::User) => Ok(RoomMetaAccess::User),
                 Some(RoomUserType::Moderator) => Ok(RoomMetaAccess::Moderator),
                 Some(RoomUserType::Owner) => Ok(RoomMetaAccess::Owner),
+                Some(RoomUserType::Spectator) => Ok(RoomMetaAccess::Anonymous),
                 None => Err(anyhow::anyhow!(UserError::UserNotBelongToRoom))
             },
             Some(UserType::Mod) => Ok(RoomMetaAccess::Moderator),
@@ -283,6 +554,11 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Actor for RoomMa
     fn started(&mut self, ctx: &mut Self::Context) {
         self.subscribe_system_async::<RoomUserDisconnect>(ctx);
         self.subscribe_system_async::<DisconnectFromRoomRequest>(ctx);
+        self.subscribe_system_async::<RoomMetaChanges>(ctx);
+        ctx.run_interval(self.config.meta_change_tick_interval, |manager, _| manager.send_meta_changes());
+        ctx.run_interval(self.config.meta_sweep_interval, |manager, _| manager.remove_expired_metas());
+        ctx.run_interval(self.config.room_message_sweep_interval, |manager, _| manager.remove_old_room_messages());
+        ctx.run_interval(self.config.room_ban_sweep_interval, |manager, _| manager.remove_expired_bans());
     }
 }
 
@@ -315,9 +591,11 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<CreateRo
 
         let mut connection = self.database.get()?;
 
+        let password = self.get_room_password(&model.password, model.generate_password);
+
         let room_id = DB::transaction(&mut connection, |connection| {
             let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
-            let room_id = DB::create_room(connection, model.name.clone(), model.access_type, model.max_user, model.join_request, &model.tags)?;
+            let room_id = DB::create_room(connection, model.name.clone(), model.access_type, model.max_user, model.max_spectator, model.join_request, &model.tags, password.clone())?;
 
             DB::join_to_room(connection, &room_id, user_id, RoomUserType::Owner)?;
 
@@ -331,13 +609,13 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<CreateRo
             #[allow(unused_mut)]
             let (mut meta, _) = self.configure_metas(connection, &room_id, model.metas.clone(), MetaAction::OnlyAddOrUpdate, access_level)?;
             
-            self.states.create_room(&room_id, insert_date, model.name.clone(), model.description.clone(), model.access_type, model.max_user, model.tags.clone(), meta, model.join_request);
+            self.states.create_room(&room_id, insert_date, model.name.clone(), model.description.clone(), model.access_type, model.max_user, model.max_spectator, model.tags.clone(), meta, model.join_request, password.clone(), model.auto_start);
             self.states.join_to_room(&room_id, user_id, session_id, RoomUserType::Owner)?;
            
             anyhow::Ok(room_id)
         })?;
 
-        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomCreated { room_id }).into());
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomCreated { room_id, password }).into());
         Ok(())
     }
 }
@@ -351,7 +629,8 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRo
 
         let (user_id, session_id) = get_user_session_id_from_auth!(model);
 
-        let has_room_update = model.access_type.is_some() || model.max_user.is_some() || model.name.is_some() || model.description.is_some();
+        let has_password_update = model.password.is_some() || model.generate_password;
+        let has_room_update = model.access_type.is_some() || model.max_user.is_some() || model.max_spectator.is_some() || model.name.is_some() || model.description.is_some() || has_password_update;
 
         if !has_room_update && model.metas.is_none() {
             return Err(anyhow::anyhow!(RoomError::UpdateInformationMissing));
@@ -360,6 +639,18 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRo
         // Calculate room access level for user
         let access_level = self.get_access_level_for_room(user_id, session_id, &model.room_id)?;
 
+        /* Only room owner can change the room password */
+        if has_password_update && access_level < RoomMetaAccess::Owner {
+            return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission));
+        }
+
+        let password = match has_password_update {
+            true => Some(self.get_room_password(&model.password, model.generate_password)),
+            false => None
+        };
+
+        let previous_metas = self.states.get_room_meta(&model.room_id, RoomMetaAccess::System)?;
+
         let mut connection = self.database.get()?;
 
         DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
@@ -384,18 +675,22 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRo
             // Update user
             let updates = RoomUpdate {
                 max_user: model.max_user.map(|item| item as i32 ),
+                max_spectator: model.max_spectator.map(|item| item as i32 ),
                 access_type: model.access_type.as_ref().map(|item| item.clone().into() ),
                 join_request: model.join_request.map(|item| item.into() ),
                 name: model.name.as_ref().map(|item| match item.trim().is_empty() { true => None, false => Some(&item[..])} ),
-                description: model.description.as_ref().map(|item| match item.trim().is_empty() { true => None, false => Some(&item[..])} )
+                description: model.description.as_ref().map(|item| match item.trim().is_empty() { true => None, false => Some(&item[..])} ),
+                password: password.as_ref().map(|item| item.as_deref())
             };
 
-            match has_room_update {
-                true => match DB::update_room(connection, &model.room_id, &updates)? {
-                    0 => return Err(anyhow::anyhow!(UserError::UserNotFound)),
-                    _ => model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Update.into())).into())
-                },
-                false => model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Update.into())).into())
+            if has_room_update && DB::update_room(connection, &model.room_id, &updates)? == 0 {
+                return Err(anyhow::anyhow!(UserError::UserNotFound));
+            }
+
+            match (model.generate_password, &password) {
+                // Generated password need to be shared with the room owner
+                (true, Some(Some(password))) => model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Update.into()), RoomPasswordUpdated { room_id: &model.room_id, password }).into()),
+                _ => model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Update.into())).into())
             };
 
             // Update all caches
@@ -411,6 +706,14 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRo
                 room_update_query.push(RoomInfoType::MaxUser(max_user as usize));
             }
 
+            if let Some(max_spectator) = updates.max_spectator {
+                room_update_query.push(RoomInfoType::MaxSpectator(max_spectator as usize));
+            }
+
+            if let Some(password) = updates.password {
+                room_update_query.push(RoomInfoType::Password(password.map(|item| item.to_string())));
+            }
+
             if let Some(tags) = tags {
                 room_update_query.push(RoomInfoType::Tags(tags));
             }
@@ -426,7 +729,11 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRo
                 self.states.set_room_info(&model.room_id, room_update_query);
             }
             Ok(())
-        })
+        })?;
+
+        /* Meta cache is loaded again from the database with the next access */
+        self.states.invalidate_room_meta_cache(&model.room_id)?;
+        self.logic.publish_meta_changes(&model.room_id, Some(user_id), &previous_metas)
     }
 }
 
@@ -444,7 +751,7 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<WaitingR
             None => return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom))
         };
 
-        if user_type == RoomUserType::User {
+        if user_type == RoomUserType::User || user_type == RoomUserType::Spectator {
             return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission));
         }
 
@@ -463,7 +770,7 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<JoinToRo
         // Check user information
         let (user_id, session_id) = get_user_session_id_from_auth!(model);
 
-        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::JoinRequest])?;
+        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::JoinRequest, RoomInfoTypeVariant::AccessType, RoomInfoTypeVariant::Users, RoomInfoTypeVariant::Password])?;
         let join_require_approvement = room_infos.get_join_request();
         let mut connection = self.database.get()?;
 
@@ -471,6 +778,28 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<JoinToRo
             return Err(anyhow::anyhow!(RoomError::BannedFromRoom));
         }
 
+        /* Password protected rooms require the join code */
+        if let Some(password) = room_infos.get_password().as_ref() {
+            if model.password.as_deref().map(|item| item.trim()) != Some(&password[..]) {
+                return Err(anyhow::anyhow!(RoomError::RoomPasswordNotValid));
+            }
+        }
+
+        /* Friend only rooms accept the users who are friend with one of the room owners */
+        if room_infos.get_access_type().into_owned() == CreateRoomAccessType::Friend {
+            let mut is_friend = false;
+            for user in room_infos.get_users().iter().filter(|user| user.user_type == RoomUserType::Owner) {
+                if self.states.is_friend(&user.user_id, user_id)? {
+                    is_friend = true;
+                    break;
+                }
+            }
+
+            if !is_friend {
+                return Err(anyhow::anyhow!(RoomError::OnlyFriendsCanJoin));
+            }
+        }
+
         if join_require_approvement.into_owned() {
 
             /* Room require approvement before join to it */
@@ -497,7 +826,7 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<JoinToRo
             model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), JoinRequested { result: "Requested", room_id: &model.room_id }).into());
         } else {
             // User can directly try to join room
-            self.join_to_room(&mut connection,  model.request_id, &model.room_id, user_id, session_id, model.room_user_type.clone())?;
+            self.join_to_room(&mut connection,  model.request_id, &model.room_id, user_id, session_id, model.room_user_type.clone(), model.history.unwrap_or_default())?;
         }
         Ok(())
     }
@@ -521,7 +850,7 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ProcessW
             if model.status {
 
                 // Moderator or room owner approve join request
-                self.join_to_room(connection, model.request_id, &model.room_id, &model.user_id, &session_id, room_user_type)?;
+                self.join_to_room(connection, model.request_id, &model.room_id, &model.user_id, &session_id, room_user_type, 0)?;
             } else {
                 
                 // Room join request declined
@@ -541,6 +870,162 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ProcessW
 }
 
 
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<InviteToRoom> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="InviteToRoom", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="invite_to_room")]
+    fn handle(&mut self, model: InviteToRoom, _ctx: &mut Context<Self>) -> Self::Result {
+        let (user_id, session_id) = get_user_session_id_from_auth!(model);
+        let requester_user_type = self.check_room_manager(session_id, &model.room_id)?;
+
+        // Ownership can not be given with invite and only the owner can invite moderators
+        match model.room_user_type {
+            RoomUserType::Owner => return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission)),
+            RoomUserType::Moderator if requester_user_type != RoomUserType::Owner => return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission)),
+            _ => ()
+        };
+
+        if self.states.is_user_banned_from_room(&model.room_id, &model.user_id)? {
+            return Err(anyhow::anyhow!(RoomError::BannedFromRoom));
+        }
+
+        if self.states.get_user_session_id(&model.user_id, &model.room_id).is_ok() {
+            return Err(anyhow::anyhow!(RoomError::UserAlreadyInTheRoom));
+        }
+
+        let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let invite = RoomInvite {
+            user_id: model.user_id.clone(),
+            inviter: user_id.clone(),
+            room_user_type: model.room_user_type.clone(),
+            insert_date,
+            expire_date: insert_date + self.config.room_invite_lifetime.as_secs() as i32
+        };
+
+        self.states.add_room_invite(&model.room_id, invite.clone())?;
+
+        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::RoomName])?;
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message: SendMessage::build("RoomInvite", RoomInviteReceived {
+                room_id: &model.room_id,
+                room_name: room_infos.get_room_name(),
+                inviter: user_id,
+                room_user_type: invite.room_user_type,
+                expire_date: invite.expire_date
+            })
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Invite.into())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<AcceptRoomInvite> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="AcceptRoomInvite", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="accept_room_invite")]
+    fn handle(&mut self, model: AcceptRoomInvite, _ctx: &mut Context<Self>) -> Self::Result {
+        let (user_id, session_id) = get_user_session_id_from_auth!(model);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let invite = self.states.get_room_invite(&model.room_id, user_id, now)?.ok_or(RoomError::RoomInviteNotFound)?;
+
+        if self.states.is_user_banned_from_room(&model.room_id, user_id)? {
+            return Err(anyhow::anyhow!(RoomError::BannedFromRoom));
+        }
+
+        /* Invited users do not need password, friendship or approvement */
+        let mut connection = self.database.get()?;
+        self.join_to_room(&mut connection, model.request_id, &model.room_id, user_id, session_id, invite.room_user_type, model.history.unwrap_or_default())?;
+        self.states.remove_room_invite(&model.room_id, user_id)?;
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<DeclineRoomInvite> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="DeclineRoomInvite", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="decline_room_invite")]
+    fn handle(&mut self, model: DeclineRoomInvite, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let invite = self.states.get_room_invite(&model.room_id, user_id, now)?.ok_or(RoomError::RoomInviteNotFound)?;
+        self.states.remove_room_invite(&model.room_id, user_id)?;
+
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(invite.inviter),
+            message: SendMessage::build("RoomInviteDeclined", RoomInviteAnswered { room_id: &model.room_id, user_id })
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::DeclineInvite.into())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RoomInvites> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="RoomInvites", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="room_invites")]
+    fn handle(&mut self, model: RoomInvites, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+        self.check_room_manager(session_id, &model.room_id)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let invites = self.states.get_room_invites(&model.room_id, now)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomInvitesResponse { room_id: &model.room_id, invites }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<CancelRoomInvite> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="CancelRoomInvite", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="cancel_room_invite")]
+    fn handle(&mut self, model: CancelRoomInvite, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+        self.check_room_manager(session_id, &model.room_id)?;
+
+        if self.states.remove_room_invite(&model.room_id, &model.user_id)?.is_none() {
+            return Err(anyhow::anyhow!(RoomError::RoomInviteNotFound));
+        }
+
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message: SendMessage::build("RoomInviteCancelled", RoomInviteCancelled { room_id: &model.room_id })
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::CancelInvite.into())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateRoomMeta> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UpdateRoomMeta", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="update_room_meta")]
+    fn handle(&mut self, model: UpdateRoomMeta, _ctx: &mut Context<Self>) -> Self::Result {
+        let (user_id, session_id) = get_user_session_id_from_auth!(model);
+
+        // Calculate room access level for user
+        let access_level = self.get_access_level_for_room(user_id, session_id, &model.room_id)?;
+        let previous_metas = self.states.get_room_meta(&model.room_id, RoomMetaAccess::System)?;
+        let meta = self.logic.apply_meta_operation(&model.room_id, model.key.clone(), access_level, &model.operation, true)?;
+        self.logic.publish_meta_changes(&model.room_id, Some(user_id), &previous_metas)?;
+
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomMetaUpdated { room_id: &model.room_id, key: &model.key, value: &meta }).into());
+        Ok(())
+    }
+}
+
+
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<KickUserFromRoom> for RoomManager<DB> {
     type Result = anyhow::Result<()>;
 
@@ -552,28 +1037,47 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<KickUser
         let requester_user_type = self.states.get_users_room_type(session_id, &model.room_id)?.ok_or(RoomError::UserDoesNotHaveEnoughPermission)?;
 
         // User must be room owner or moderator
-        if requester_user_type == RoomUserType::User {
+        if requester_user_type == RoomUserType::User || requester_user_type == RoomUserType::Spectator {
             return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission));
         }
 
+        let insert_date = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+
+        // Ban without duration is permanent
+        let expire_date = match model.ban_duration {
+            Some(duration) => Some(i32::try_from(duration).ok().and_then(|duration| insert_date.checked_add(duration)).ok_or(RoomError::BanDurationNotValid)?),
+            None => None
+        };
+
         let session_id = self.states.get_user_session_id(&model.user_id, &model.room_id)?;
         
         // Disconnect user and send message to other users
         self.disconnect_from_room(&model.room_id, &model.user_id, &session_id)?;
 
-        if model.ban {
-            
-            // Update state
-            self.states.ban_user_from_room(&model.room_id, &model.user_id)?;
-
-            // Update database
-            let mut connection = self.database.get()?;
-            DB::ban_user_from_room(&mut connection, &model.room_id, &model.user_id, user_id)?;
-        }
+        let ban = match model.ban {
+            true => {
+                let ban = RoomBan {
+                    reason: model.reason.clone(),
+                    insert_date,
+                    expire_date
+                };
+
+                // Update state
+                self.states.ban_user_from_room(&model.room_id, &model.user_id, ban.clone())?;
+
+                // Update database
+                let mut connection = self.database.get()?;
+                DB::ban_user_from_room(&mut connection, &model.room_id, &model.user_id, user_id, &ban)?;
+                Some(ban)
+            },
+            false => None
+        };
 
         // Send message to use about disconnected from room
         if let Ok(message) = serde_json::to_string(&RoomResponse::DisconnectedFromRoom {
-            room_id: &model.room_id
+            room_id: &model.room_id,
+            reason: model.reason.as_deref(),
+            ban: ban.as_ref()
         }) {
             self.issue_system_async(SendMessage {
                 message,
@@ -586,6 +1090,45 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<KickUser
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UnbanUserFromRoom> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UnbanUserFromRoom", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="unban_user_from_room")]
+    fn handle(&mut self, model: UnbanUserFromRoom, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+        self.check_room_manager(session_id, &model.room_id)?;
+
+        if !self.states.unban_user_from_room(&model.room_id, &model.user_id)? {
+            return Err(anyhow::anyhow!(RoomError::UserNotBanned));
+        }
+
+        let mut connection = self.database.get()?;
+        DB::unban_user_from_room(&mut connection, &model.room_id, &model.user_id)?;
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Unban.into())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RoomMetaChanges> for RoomManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="RoomMetaChanges", skip(self, _ctx))]
+    fn handle(&mut self, model: RoomMetaChanges, _ctx: &mut Context<Self>) -> Self::Result {
+        let changes = self.meta_changes.entry(model.room_id).or_default();
+
+        for (key, access_level) in model.metas.into_iter() {
+            let access_level = match changes.get(&key) {
+                Some((previous_access_level, _)) if *previous_access_level < access_level => *previous_access_level,
+                _ => access_level
+            };
+
+            changes.insert(key, (access_level, model.user_id.clone()));
+        }
+    }
+}
+
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<DisconnectFromRoomRequest> for RoomManager<DB> {
     type Result = ();
 
@@ -613,11 +1156,37 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<MessageT
         };
 
         self.logic.message_to_room(&model.room_id, Some(sender_user_id), &model.message)?;
+
+        /* Message already delivered, history is not a reason to fail the request */
+        if let Err(error) = self.add_room_message(&model.room_id, Some(sender_user_id), &model.message) {
+            log::error!("Room message could not added into the history: {:?}", error);
+        }
+
         model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Message.into())).into());
         Ok(())
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RoomMessageHistory> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="RoomMessageHistory", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="room_message_history")]
+    fn handle(&mut self, model: RoomMessageHistory, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+
+        /* Only the room members can read the history */
+        if self.states.get_users_room_type(session_id, &model.room_id)?.is_none() {
+            return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom));
+        }
+
+        let limit = model.limit.unwrap_or(self.config.max_room_message_history_page_size);
+        let messages = self.get_room_messages(&model.room_id, model.before, limit)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomMessageHistoryResponse { room_id: &model.room_id, messages }).into());
+        Ok(())
+    }
+}
+
 
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<Play> for RoomManager<DB> {
     type Result = anyhow::Result<()>;
@@ -625,10 +1194,12 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<Play> fo
     #[tracing::instrument(name="Play", skip(self, _ctx))]
     #[yummy_macros::plugin_api(name="play")]
     fn handle(&mut self, model: Play, _ctx: &mut Context<Self>) -> Self::Result {
-        let sender_user_id = match model.auth.deref() {
-            Some(user) => &user.user,
-            None => return Err(anyhow::anyhow!(AuthError::TokenNotValid))
-        };
+        let (sender_user_id, session_id) = get_user_session_id_from_auth!(model);
+
+        /* Spectators only watch the game */
+        if let Some(RoomUserType::Spectator) = self.states.get_users_room_type(session_id, &model.room_id)? {
+            return Err(anyhow::anyhow!(RoomError::SpectatorCanNotPlay));
+        }
 
         self.logic.play(&model.room_id, Some(sender_user_id), &model.message)?;
         model.socket.send(Answer::success(model.request_id, Cow::Borrowed(RequestRoomTypeVariant::Play.into())).into());
@@ -636,6 +1207,104 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<Play> fo
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<SetReady> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="SetReady", skip(self, ctx))]
+    #[yummy_macros::plugin_api(name="set_ready")]
+    fn handle(&mut self, model: SetReady, ctx: &mut Context<Self>) -> Self::Result {
+        let (user_id, session_id) = get_user_session_id_from_auth!(model);
+
+        match self.states.get_users_room_type(session_id, &model.room_id)? {
+            Some(RoomUserType::Spectator) => return Err(anyhow::anyhow!(RoomError::SpectatorCanNotPlay)),
+            Some(_) => (),
+            None => return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom))
+        };
+
+        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::AutoStart])?;
+        let game_state = room_infos.get_game_state().into_owned();
+
+        if game_state == RoomGameState::Playing {
+            return Err(anyhow::anyhow!(RoomError::GameAlreadyStarted));
+        }
+
+        self.states.set_room_user_ready(&model.room_id, user_id, model.ready)?;
+        self.send_to_room(&model.room_id, RoomResponse::UserReadyChanged { user_id, room_id: &model.room_id, ready: model.ready }.into())?;
+
+        /* First ready user starts the ready check */
+        if model.ready && game_state != RoomGameState::ReadyCheck {
+            self.change_game_state(&model.room_id, RoomGameState::ReadyCheck)?;
+        }
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+
+        if model.ready && room_infos.get_auto_start().into_owned() && self.is_all_players_ready(&model.room_id)? {
+            ctx.notify(StartGame {
+                request_id: None,
+                auth: model.auth.clone(),
+                room_id: model.room_id,
+                socket: model.socket.clone()
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<StartGame> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="StartGame", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="start_game")]
+    fn handle(&mut self, model: StartGame, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+
+        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::AutoStart])?;
+
+        /* Room owner and moderators can start the game. Auto start rooms can be started by the players when everyone is ready */
+        match self.states.get_users_room_type(session_id, &model.room_id)? {
+            Some(RoomUserType::Owner) | Some(RoomUserType::Moderator) => (),
+            Some(RoomUserType::User) if room_infos.get_auto_start().into_owned() && self.is_all_players_ready(&model.room_id)? => (),
+            Some(_) => return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission)),
+            None => return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom))
+        };
+
+        if room_infos.get_game_state().into_owned() == RoomGameState::Playing {
+            return Err(anyhow::anyhow!(RoomError::GameAlreadyStarted));
+        }
+
+        self.change_game_state(&model.room_id, RoomGameState::Playing)?;
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<FinishGame> for RoomManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="FinishGame", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="finish_game")]
+    fn handle(&mut self, model: FinishGame, _ctx: &mut Context<Self>) -> Self::Result {
+        let session_id = get_session_id_from_auth!(model);
+
+        match self.states.get_users_room_type(session_id, &model.room_id)? {
+            Some(RoomUserType::Owner) | Some(RoomUserType::Moderator) => (),
+            Some(_) => return Err(anyhow::anyhow!(RoomError::UserDoesNotHaveEnoughPermission)),
+            None => return Err(anyhow::anyhow!(RoomError::UserNotInTheRoom))
+        };
+
+        let room_infos = self.states.get_room_info(&model.room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState])?;
+
+        if room_infos.get_game_state().into_owned() != RoomGameState::Playing {
+            return Err(anyhow::anyhow!(RoomError::GameNotStarted));
+        }
+
+        self.change_game_state(&model.room_id, RoomGameState::Finished)?;
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RoomListRequest> for RoomManager<DB> {
     type Result = anyhow::Result<()>;
 
@@ -648,8 +1317,8 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RoomList
             &model.members[..]
         };
 
-        let rooms = self.states.get_rooms(&model.tag, members)?;
-        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomList { rooms }).into());
+        let (rooms, cursor) = self.states.get_rooms(&model.filter, members)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), RoomList { rooms, cursor }).into());
         Ok(())
     }
 }
@@ -2,9 +2,9 @@ use std::{fmt::Debug, sync::Arc, collections::HashMap, borrow::Cow};
 
 use actix::prelude::Message;
 use serde_json::Value;
-use yummy_model::state::{RoomInfoTypeVariant, RoomUserInformation, RoomInfoTypeCollection};
+use yummy_model::state::{RoomInfoTypeVariant, RoomUserInformation, RoomInfoTypeCollection, RoomListFilter, RoomMessage, RoomInvite, RoomBan};
 use yummy_general::client::ClientTrait;
-use yummy_model::{auth::UserAuth, CreateRoomAccessType, meta::{RoomMetaAccess, MetaType, MetaAction}, RoomId, RoomUserType, UserId};
+use yummy_model::{auth::UserAuth, CreateRoomAccessType, meta::{RoomMetaAccess, MetaType, MetaDefinitions, MetaAction, RoomMetaType, operation::RoomMetaOperation}, RoomId, RoomUserType, UserId, RoomGameState};
 use serde::Serialize;
 use thiserror::Error;
 use yummy_macros::model;
@@ -22,8 +22,12 @@ pub struct CreateRoomRequest {
     pub access_type: CreateRoomAccessType,
     pub join_request: bool,
     pub max_user: usize,
+    pub max_spectator: usize,
     pub tags: Vec<String>,
-    pub metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>,
+    pub metas: Option<MetaDefinitions<RoomMetaAccess>>,
+    pub password: Option<String>,
+    pub generate_password: bool,
+    pub auto_start: bool,
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
@@ -35,6 +39,8 @@ pub struct JoinToRoomRequest {
     pub auth: Arc<Option<UserAuth>>,
     pub room_id: RoomId,
     pub room_user_type: RoomUserType,
+    pub password: Option<String>,
+    pub history: Option<usize>,
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
@@ -57,6 +63,19 @@ pub struct KickUserFromRoom {
     pub room_id: RoomId,
     pub user_id: UserId,
     pub ban: bool,
+    pub reason: Option<String>,
+    pub ban_duration: Option<u64>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="UnbanUserFromRoom")]
+pub struct UnbanUserFromRoom {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub user_id: UserId,
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
@@ -93,12 +112,90 @@ pub struct MessageToRoomRequest {
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
 
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="RoomMessageHistory")]
+pub struct RoomMessageHistory {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub before: Option<usize>,
+    pub limit: Option<usize>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="InviteToRoom")]
+pub struct InviteToRoom {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub user_id: UserId,
+    pub room_user_type: RoomUserType,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="AcceptRoomInvite")]
+pub struct AcceptRoomInvite {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub history: Option<usize>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="DeclineRoomInvite")]
+pub struct DeclineRoomInvite {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="RoomInvites")]
+pub struct RoomInvites {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="CancelRoomInvite")]
+pub struct CancelRoomInvite {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub user_id: UserId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="RoomMetaOperation")]
+pub struct UpdateRoomMeta {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub key: String,
+    pub operation: RoomMetaOperation,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
 #[derive(Message, Validate, Debug)]
 #[rtype(result = "anyhow::Result<()>")]
 #[model(request_type="RoomList")]
 pub struct RoomListRequest {
     pub request_id: Option<usize>, 
-    pub tag: Option<String>,
+    pub filter: RoomListFilter,
     pub members: Vec<RoomInfoTypeVariant>,
     pub socket: Arc<dyn ClientTrait + Sync + Send>
 }
@@ -136,19 +233,71 @@ pub struct UpdateRoom {
     pub description: Option<String>,
     pub join_request: Option<bool>,
     pub socket: Arc<dyn ClientTrait + Sync + Send>,
-    pub metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>,
+    pub metas: Option<MetaDefinitions<RoomMetaAccess>>,
     pub meta_action: MetaAction,
     pub access_type: Option<CreateRoomAccessType>,
     pub max_user: Option<usize>,
+    pub max_spectator: Option<usize>,
     pub tags: Option<Vec<String>>,
-    pub user_permission: Option<HashMap<UserId, RoomUserType>>
+    pub user_permission: Option<HashMap<UserId, RoomUserType>>,
+    pub password: Option<String>,
+    pub generate_password: bool
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="SetReady")]
+pub struct SetReady {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub ready: bool,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="StartGame")]
+pub struct StartGame {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="FinishGame")]
+pub struct FinishGame {
+    pub request_id: Option<usize>, 
+    pub auth: Arc<Option<UserAuth>>,
+    pub room_id: RoomId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+/* Plugins can change the new owner, users are ordered by join time */
+#[derive(Debug, Clone)]
+pub struct SelectNewOwner {
+    pub room_id: RoomId,
+    pub previous_owner: UserId,
+    pub users: Vec<RoomUserInformation>,
+    pub new_owner: Option<Arc<UserId>>
 }
 
 
 
 #[derive(Serialize, Debug, Clone)]
 pub struct RoomCreated {
-    pub room_id: RoomId
+    pub room_id: RoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomPasswordUpdated<'a> {
+    pub room_id: &'a RoomId,
+    pub password: &'a str
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -157,7 +306,11 @@ pub struct JoinToRoom<'a> {
     pub room_id: &'a RoomId,
     pub room_name: Cow<'a, Option<String>>,
     pub users: Cow<'a, Vec<RoomUserInformation>>,
-    pub metas: Cow<'a, HashMap<String, MetaType<RoomMetaAccess>>>
+    pub spectators: Cow<'a, Vec<RoomUserInformation>>,
+    pub metas: Cow<'a, HashMap<String, MetaType<RoomMetaAccess>>>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<RoomMessage>
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -168,7 +321,40 @@ pub struct UserJoinedToRoom<'a> {
 
 #[derive(Serialize, Debug, Clone)]
 pub struct RoomList {
-    pub rooms: Vec<RoomInfoTypeCollection>
+    pub rooms: Vec<RoomInfoTypeCollection>,
+    pub cursor: Option<String>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomMessageHistoryResponse<'a> {
+    pub room_id: &'a RoomId,
+    pub messages: Vec<RoomMessage>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomInviteReceived<'a> {
+    pub room_id: &'a RoomId,
+    pub room_name: Cow<'a, Option<String>>,
+    pub inviter: &'a UserId,
+    pub room_user_type: RoomUserType,
+    pub expire_date: i32
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomInviteAnswered<'a> {
+    pub room_id: &'a RoomId,
+    pub user_id: &'a UserId
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomInviteCancelled<'a> {
+    pub room_id: &'a RoomId
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomInvitesResponse<'a> {
+    pub room_id: &'a RoomId,
+    pub invites: Vec<RoomInvite>
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -196,6 +382,22 @@ pub struct JoinRequestDeclined<'a> {
     pub room_id: &'a RoomId,
 }
 
+#[derive(Serialize, Debug, Clone)]
+pub struct RoomMetaUpdated<'a> {
+    pub room_id: &'a RoomId,
+    pub key: &'a str,
+    pub value: &'a RoomMetaType
+}
+
+/* Changed keys with the lowest access level of the old and new values. Sender does not need to be informed again */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct RoomMetaChanges {
+    pub room_id: RoomId,
+    pub user_id: Option<Arc<UserId>>,
+    pub metas: HashMap<String, RoomMetaAccess>
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct RoomInfo {
     #[serde(flatten)]
@@ -223,7 +425,34 @@ pub enum RoomError {
     UserNotInTheRoom,
 
     #[error("Banned from room")]
-    BannedFromRoom
+    BannedFromRoom,
+
+    #[error("Only friends of the room owner can join")]
+    OnlyFriendsCanJoin,
+
+    #[error("Room password is not valid")]
+    RoomPasswordNotValid,
+
+    #[error("Spectators can not play")]
+    SpectatorCanNotPlay,
+
+    #[error("Game already started")]
+    GameAlreadyStarted,
+
+    #[error("Game is not started")]
+    GameNotStarted,
+
+    #[error("User already in the room")]
+    UserAlreadyInTheRoom,
+
+    #[error("Room invite not found")]
+    RoomInviteNotFound,
+
+    #[error("User is not banned")]
+    UserNotBanned,
+
+    #[error("Ban duration is not valid")]
+    BanDurationNotValid
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -234,7 +463,13 @@ pub enum RoomResponse<'a> {
         room_id: &'a RoomId
     },
     DisconnectedFromRoom {
-        room_id: &'a RoomId
+        room_id: &'a RoomId,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ban: Option<&'a RoomBan>
     },
     MessageFromRoom {
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -247,6 +482,24 @@ pub enum RoomResponse<'a> {
         user_id: Option<&'a UserId>,
         room_id: &'a RoomId,
         message: &'a Value
+    },
+    UserReadyChanged {
+        user_id: &'a UserId,
+        room_id: &'a RoomId,
+        ready: bool
+    },
+    GameStateChanged {
+        room_id: &'a RoomId,
+        game_state: RoomGameState
+    },
+    OwnerChanged {
+        room_id: &'a RoomId,
+        user_id: &'a UserId,
+        previous_owner: &'a UserId
+    },
+    RoomMetaChanged {
+        room_id: &'a RoomId,
+        metas: HashMap<&'a str, &'a RoomMetaType>
     }
 }
 
@@ -4,7 +4,9 @@ use yummy_model::CreateRoomAccessType;
 #[allow(unused_mut)]
 
 use yummy_model::config::configure_environment;
-use yummy_model::state::RoomUserInformation;
+use yummy_model::state::{RoomUserInformation, RoomListFilter};
+use yummy_model::meta::operation::RoomMetaOperation;
+use yummy_model::meta::MetaDefinitions;
 use uuid::Uuid;
 use yummy_testing::model::*;
 
@@ -15,7 +17,9 @@ use yummy_model::config::get_configuration;
 use yummy_model::web::GenericAnswer;
 use yummy_testing::client::DummyClient;
 
+use std::cell::RefCell;
 use std::env::temp_dir;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use actix::Actor;
@@ -27,7 +31,7 @@ use super::*;
 use crate::auth::AuthManager;
 use crate::auth::model::*;
 use crate::conn::ConnectionManager;
-use crate::plugin::PluginExecuter;
+use crate::plugin::{PluginBuilder, PluginExecuter, YummyPlugin, YummyPluginError, YummyPluginInstaller};
 
 
 macro_rules! email_auth {
@@ -54,7 +58,33 @@ macro_rules! email_auth {
     };
 }
 
+/* Selects a user who is not in the room */
+struct InvalidOwnerPlugin;
+
+impl YummyPlugin for InvalidOwnerPlugin {
+    fn pre_select_new_owner(&self, model: Rc<RefCell<SelectNewOwner>>) -> Result<(), YummyPluginError> {
+        model.borrow_mut().new_owner = Some(Arc::new(UserId::new()));
+        Result::Ok(())
+    }
+}
+
+struct InvalidOwnerInstaller;
+
+impl YummyPluginInstaller for InvalidOwnerInstaller {
+    fn install(&self, executer: &mut PluginExecuter, _config: Arc<YummyConfig>) {
+        executer.add_plugin("invalid_owner".to_string(), Box::new(InvalidOwnerPlugin));
+    }
+}
+
 fn create_actor() -> anyhow::Result<(Addr<RoomManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState, Arc<DummyClient>)> {
+    create_actor_with_config(|_| ())
+}
+
+fn create_actor_with_config(configure: impl FnOnce(&mut YummyConfig)) -> anyhow::Result<(Addr<RoomManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState, Arc<DummyClient>)> {
+    create_actor_with_plugin(PluginBuilder::default(), configure)
+}
+
+fn create_actor_with_plugin(builder: PluginBuilder, configure: impl FnOnce(&mut YummyConfig)) -> anyhow::Result<(Addr<RoomManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState, Arc<DummyClient>)> {
     let mut db_location = temp_dir();
     db_location.push(format!("{}.db", Uuid::new_v4()));
     let connection = create_connection(db_location.to_str().unwrap())?;
@@ -62,6 +92,7 @@ fn create_actor() -> anyhow::Result<(Addr<RoomManager<yummy_database::SqliteStor
     configure_environment();
 
     let mut config = get_configuration().deref().clone();
+    configure(&mut config);
 
     #[cfg(feature = "stateless")] {
         use rand::Rng;     
@@ -76,7 +107,7 @@ fn create_actor() -> anyhow::Result<(Addr<RoomManager<yummy_database::SqliteStor
     let resource_factory = ResourceFactory::<DefaultDatabaseStore>::new(Arc::new(connection.clone()));
     let states = YummyState::new(config.clone(), Box::new(resource_factory), #[cfg(feature = "stateless")] conn.clone());
     let connection = Arc::new(connection);
-    let executer = Arc::new(PluginExecuter::new(config.clone(), states.clone(), connection.clone()));
+    let executer = Arc::new(builder.build(config.clone(), states.clone(), connection.clone()));
 
     ConnectionManager::new(config.clone(), states.clone(), executer.clone(), #[cfg(feature = "stateless")] conn.clone()).start();
 
@@ -97,8 +128,12 @@ async fn create_room_1() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Friend,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:recipient.clone()
     }).await??;
 
@@ -129,8 +164,12 @@ async fn create_room_2() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:recipient.clone()
     }).await??;
 
@@ -167,8 +206,12 @@ async fn create_room_3() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -182,6 +225,8 @@ async fn create_room_3() -> anyhow::Result<()> {
         auth: user_2.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -194,6 +239,8 @@ async fn create_room_3() -> anyhow::Result<()> {
         auth: user_3.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_3_socket.clone()
     }).await??;
 
@@ -223,8 +270,12 @@ async fn create_room_3() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -251,8 +302,12 @@ async fn create_room_4() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -266,6 +321,8 @@ async fn create_room_4() -> anyhow::Result<()> {
         auth: user_2.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -277,6 +334,8 @@ async fn create_room_4() -> anyhow::Result<()> {
         auth: user_3.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_3_socket.clone()
     }).await??;
 
@@ -343,8 +402,12 @@ async fn message_to_room() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -359,6 +422,8 @@ async fn message_to_room() -> anyhow::Result<()> {
         auth: user_2.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -367,6 +432,8 @@ async fn message_to_room() -> anyhow::Result<()> {
         auth: user_3.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_3_socket.clone()
     }).await??;
 
@@ -436,8 +503,12 @@ async fn get_rooms() -> anyhow::Result<()> {
             join_request: false,
             access_type: CreateRoomAccessType::Public,
             max_user: 4,
+            max_spectator: 0,
             metas: None,
             tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+            password: None,
+            generate_password: false,
+            auto_start: false,
             socket:user_socket.clone()
         }).await??;
     }
@@ -446,7 +517,7 @@ async fn get_rooms() -> anyhow::Result<()> {
         request_id: None,
         socket: user_1_socket.clone(),
         members: Vec::new(),
-        tag: None
+        filter: RoomListFilter::default()
     }).await??;
 
     let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
@@ -454,8 +525,11 @@ async fn get_rooms() -> anyhow::Result<()> {
 
     assert!(result.status);
     let items = result.result;
+    let cursor = items.get("cursor").and_then(|cursor| cursor.as_str()).map(|cursor| cursor.to_string());
+    assert!(cursor.is_some());
+
     if let Some(serde_json::Value::Array(items)) = items.get("rooms") {
-        assert_eq!(items.len(), 100);
+        assert_eq!(items.len(), config.max_room_list_page_size);
         let first_item = items.get(0).unwrap();
 
         if let serde_json::Value::Object(obj) = first_item {
@@ -474,6 +548,33 @@ async fn get_rooms() -> anyhow::Result<()> {
         assert!(false, "Return value is not array");
     }
 
+    // Remaining rooms are in the next page
+    room_manager.send(RoomListRequest {
+        request_id: None,
+        socket: user_1_socket.clone(),
+        members: vec![RoomInfoTypeVariant::RoomName],
+        filter: RoomListFilter { cursor, limit: 100, ..Default::default() }
+    }).await??;
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    let result: GenericAnswer<serde_json::Value> = message.into();
+
+    assert!(result.status);
+    assert!(result.result.get("cursor").unwrap().is_null());
+    assert_eq!(result.result.get("rooms").unwrap().as_array().unwrap().len(), 100 - config.max_room_list_page_size);
+
+    // Tag filter
+    room_manager.send(RoomListRequest {
+        request_id: None,
+        socket: user_1_socket.clone(),
+        members: vec![RoomInfoTypeVariant::RoomName],
+        filter: RoomListFilter { tags: vec!["tag 5".to_string()], ..Default::default() }
+    }).await??;
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    let result: GenericAnswer<serde_json::Value> = message.into();
+    assert!(result.result.get("rooms").unwrap().as_array().unwrap().is_empty());
+
     Ok(())
 }
 
@@ -495,12 +596,16 @@ async fn room_meta_check() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
-        metas: Some(HashMap::from([
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([
             ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
             ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
             ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
         ])),
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -512,6 +617,8 @@ async fn room_meta_check() -> anyhow::Result<()> {
         auth: user_2.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -574,8 +681,12 @@ async fn room_meta_update() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -592,10 +703,13 @@ async fn room_meta_update() -> anyhow::Result<()> {
         meta_action: MetaAction::OnlyAddOrUpdate,
         access_type: None,
         max_user: None,
+        max_spectator: None,
         tags: None,
         user_permission: None,
+        password: None,
+        generate_password: false,
         socket: user_1_socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("1".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
             ("2".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
             ("3".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
@@ -636,10 +750,13 @@ async fn room_meta_update() -> anyhow::Result<()> {
         meta_action: MetaAction::OnlyAddOrUpdate,
         access_type: None,
         max_user: None,
+        max_spectator: None,
         tags: None,
         user_permission: None,
+        password: None,
+        generate_password: false,
         socket: user_1_socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("10".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
         ]))
     }).await??;
@@ -672,10 +789,13 @@ async fn room_meta_update() -> anyhow::Result<()> {
         meta_action: MetaAction::RemoveUnusedMetas,
         access_type: None,
         max_user: None,
+        max_spectator: None,
         tags: None,
         user_permission: None,
+        password: None,
+        generate_password: false,
         socket: user_1_socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("10".to_string(), MetaType::Bool(true, RoomMetaAccess::User)),
             ("11".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
         ]))
@@ -709,10 +829,13 @@ async fn room_meta_update() -> anyhow::Result<()> {
         meta_action: MetaAction::RemoveAllMetas,
         access_type: Some(CreateRoomAccessType::Private),
         max_user: Some(512),
+        max_spectator: None,
         tags: None,
         user_permission: Some(HashMap::from([(user_1_id, RoomUserType::User)])),
+        password: None,
+        generate_password: false,
         socket: user_1_socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("12".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
         ]))
     }).await??;
@@ -754,13 +877,17 @@ async fn room_update() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
-        metas: Some(HashMap::from([
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([
             ("gender".to_string(), MetaType::String("Male".to_string(), RoomMetaAccess::User)),
             ("location".to_string(), MetaType::String("Copenhagen".to_string(), RoomMetaAccess::User)),
             ("score".to_string(), MetaType::Number(15.3, RoomMetaAccess::Anonymous)),
             ("other".to_string(), MetaType::Bool(true, RoomMetaAccess::Anonymous)),
         ])),
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -772,6 +899,8 @@ async fn room_update() -> anyhow::Result<()> {
         auth: user_2.clone(),
         room_id: room_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -805,12 +934,15 @@ async fn room_update() -> anyhow::Result<()> {
         access_type: None,
         user_permission: None,
         max_user: None,
-        metas: Some(HashMap::from([
+        max_spectator: None,
+        metas: Some(MetaDefinitions::from([
             ("gender".to_string(), MetaType::String("Female".to_string(), RoomMetaAccess::User)),
             ("location".to_string(), MetaType::String("oslo".to_string(), RoomMetaAccess::User)),
             ("score".to_string(), MetaType::Number(30.0, RoomMetaAccess::Anonymous)),
         ])),
         tags: Some(vec!["new tag".to_string()]),
+        password: None,
+        generate_password: false,
         socket:user_1_socket.clone(),
     }).await??;
 
@@ -913,8 +1045,12 @@ async fn multi_room_support() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -929,8 +1065,12 @@ async fn multi_room_support() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
 
@@ -942,6 +1082,8 @@ async fn multi_room_support() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -954,6 +1096,8 @@ async fn multi_room_support() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_2_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1071,8 +1215,12 @@ async fn room_join_request_approve() -> anyhow::Result<()> {
         join_request: true,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
     
@@ -1084,6 +1232,8 @@ async fn room_join_request_approve() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1196,8 +1346,12 @@ async fn room_join_request_decline() -> anyhow::Result<()> {
         join_request: true,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
     
@@ -1209,6 +1363,8 @@ async fn room_join_request_decline() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1324,8 +1480,12 @@ async fn user_ban_test() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
     
@@ -1337,6 +1497,8 @@ async fn user_ban_test() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1350,7 +1512,9 @@ async fn user_ban_test() -> anyhow::Result<()> {
         room_id: room_1_id,
         user_id: user_1_auth_jwt.id.deref().clone(),
         socket:user_2_socket.clone(),
-        ban: true
+        ban: true,
+        reason: None,
+        ban_duration: None
     }).await?.unwrap_err();
 
     let response: ReceiveError = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
@@ -1363,11 +1527,15 @@ async fn user_ban_test() -> anyhow::Result<()> {
         room_id: room_1_id,
         user_id: user_2_auth_jwt.id.deref().clone(),
         socket:user_1_socket.clone(),
-        ban: true
+        ban: true,
+        reason: Some("Cheating".to_string()),
+        ban_duration: None
     }).await??;
 
     let message: DisconnectedFromRoom = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
     assert_eq!(&message.class_type[..], "DisconnectedFromRoom");
+    assert_eq!(message.reason, Some("Cheating".to_string()));
+    assert_eq!(message.ban.map(|ban| ban.expire_date), Some(None));
     
     
     // User try to connect room again, but it should be failed
@@ -1376,6 +1544,8 @@ async fn user_ban_test() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await?.unwrap_err();
 
@@ -1451,8 +1621,12 @@ async fn kick_ban_test() -> anyhow::Result<()> {
         join_request: false,
         access_type: CreateRoomAccessType::Public,
         max_user: 4,
+        max_spectator: 0,
         metas: None,
         tags: vec!["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string(), "tag 4".to_string()],
+        password: None,
+        generate_password: false,
+        auto_start: false,
         socket:user_1_socket.clone()
     }).await??;
     
@@ -1464,6 +1638,8 @@ async fn kick_ban_test() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1477,7 +1653,9 @@ async fn kick_ban_test() -> anyhow::Result<()> {
         room_id: room_1_id,
         user_id: user_1_auth_jwt.id.deref().clone(),
         socket:user_2_socket.clone(),
-        ban: false
+        ban: false,
+        reason: None,
+        ban_duration: None
     }).await?.unwrap_err();
 
     let response: ReceiveError = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
@@ -1490,7 +1668,9 @@ async fn kick_ban_test() -> anyhow::Result<()> {
         room_id: room_1_id,
         user_id: user_2_auth_jwt.id.deref().clone(),
         socket:user_1_socket.clone(),
-        ban: false
+        ban: false,
+        reason: None,
+        ban_duration: None
     }).await??;
 
     let message: DisconnectedFromRoom = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
@@ -1502,6 +1682,8 @@ async fn kick_ban_test() -> anyhow::Result<()> {
         auth: user_2_auth.clone(),
         room_id: room_1_id,
         room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
         socket:user_2_socket.clone()
     }).await??;
 
@@ -1512,3 +1694,1762 @@ async fn kick_ban_test() -> anyhow::Result<()> {
 
     return Ok(());
 }
+
+#[actix::test]
+async fn friend_only_room() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, states, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Friend,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    // User is not a friend of the room owner
+    assert!(room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    let message = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Only friends of the room owner can join"));
+
+    states.add_friend(&user_1.deref().as_ref().unwrap().user, &user_2.deref().as_ref().unwrap().user)?;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+
+    Ok(())
+}
+
+#[actix::test]
+async fn password_room() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: true,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_created: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_created.room_id;
+    let password = room_created.password.unwrap();
+    assert_eq!(password.len(), config.room_password_length);
+
+    // Password required
+    assert!(room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    let message = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Room password is not valid"));
+
+    // Wrong password
+    assert!(room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: Some(format!("{}-", password)),
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    let message = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Room password is not valid"));
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: Some(password.clone()),
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+
+    // Only room owner can change the password
+    assert!(room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: true,
+        socket: user_2_socket.clone(),
+        metas: None
+    }).await?.is_err());
+
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: true,
+        socket: user_1_socket.clone(),
+        metas: None
+    }).await??;
+
+    let message: serde_json::Value = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    let new_password = message["password"].as_str().unwrap().to_string();
+    assert_eq!(new_password.len(), config.room_password_length);
+
+    // Old password should not work anymore
+    if new_password != password {
+        assert!(room_manager.send(JoinToRoomRequest {
+            request_id: None,
+            auth: user_3.clone(),
+            room_id,
+            room_user_type: RoomUserType::User,
+            password: Some(password),
+            history: None,
+            socket:user_3_socket.clone()
+        }).await?.is_err());
+    }
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: Some(new_password),
+        history: None,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_3_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+
+    // Remove the password
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: Some(String::new()),
+        generate_password: false,
+        socket: user_1_socket.clone(),
+        metas: None
+    }).await??;
+
+    room_manager.send(GetRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        members: vec![RoomInfoTypeVariant::Password],
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let message: serde_json::Value = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert!(message["password"].is_null());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn spectator_room() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+    let user_1_id = user_1.clone().deref().as_ref().unwrap().user.clone();
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+    let user_2_id = user_2.clone().deref().as_ref().unwrap().user.clone();
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 1,
+        max_spectator: 1,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    // Room is full for the players, but spectators have their own slots
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::Spectator,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+    assert_eq!(message.result.users.len(), 1);
+    assert_eq!(message.result.users[0].user_id.deref(), &user_1_id);
+    assert_eq!(message.result.spectators.len(), 1);
+    assert_eq!(message.result.spectators[0].user_id.deref(), &user_2_id);
+
+    assert!(room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        room_user_type: RoomUserType::Spectator,
+        password: None,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await?.is_err());
+
+    // Spectators are receiving the game messages
+    room_manager.send(Play {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        message: serde_json::Value::String("MOVE".to_string()),
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message = serde_json::from_str::<MessageReceivedFromRoom>(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(message.user_id, user_1_id.clone());
+    assert_eq!(&message.message, "MOVE");
+    assert_eq!(&message.class_type[..], "Play");
+
+    // But they could not play
+    assert!(room_manager.send(Play {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        message: serde_json::Value::String("MOVE".to_string()),
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    let message = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Spectators can not play"));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_game_state() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, states, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 2,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: true,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    // Only the room owner and moderators can start the game before everyone is ready
+    assert!(room_manager.send(StartGame {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    // Game is not started yet
+    assert!(room_manager.send(FinishGame {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        socket:user_1_socket.clone()
+    }).await?.is_err());
+
+    // First ready user starts the ready check
+    room_manager.send(SetReady {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        ready: true,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "GameStateChanged");
+    assert_eq!(message["game_state"], 1);
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "UserReadyChanged");
+    assert_eq!(message["ready"], true);
+
+    // All players are ready, game started automatically
+    room_manager.send(SetReady {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        ready: true,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+
+    let message: serde_json::Value = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "GameStateChanged");
+    assert_eq!(message["game_state"], 2);
+
+    let room_infos = states.get_room_info(&room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::ReadyUsers])?;
+    assert_eq!(room_infos.get_game_state().into_owned(), RoomGameState::Playing);
+    assert!(room_infos.get_ready_users().is_empty());
+
+    assert!(room_manager.send(SetReady {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        ready: true,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    // Only the room owner and moderators can finish the game
+    assert!(room_manager.send(FinishGame {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    room_manager.send(FinishGame {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "GameStateChanged");
+    assert_eq!(message["game_state"], 3);
+
+    // Room list can be filtered by the game state
+    room_manager.send(RoomListRequest {
+        request_id: None,
+        socket: user_1_socket.clone(),
+        members: vec![RoomInfoTypeVariant::GameState],
+        filter: RoomListFilter { game_state: Some(RoomGameState::Finished), ..Default::default() }
+    }).await??;
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    let result: GenericAnswer<serde_json::Value> = message.into();
+    assert_eq!(result.result["rooms"].as_array().unwrap().len(), 1);
+    assert_eq!(result.result["rooms"][0]["game-state"], 3);
+
+    room_manager.send(RoomListRequest {
+        request_id: None,
+        socket: user_1_socket.clone(),
+        members: vec![RoomInfoTypeVariant::GameState],
+        filter: RoomListFilter { game_state: Some(RoomGameState::Waiting), ..Default::default() }
+    }).await??;
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    let result: GenericAnswer<serde_json::Value> = message.into();
+    assert!(result.result["rooms"].as_array().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_auto_start_after_leave() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, states, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    let user_4_socket = Arc::new(DummyClient::default());
+    let user_4 = email_auth!(auth_manager, config.clone(), "user4@gmail.com".to_string(), "erhan".into(), true, user_4_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: true,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    for (user, socket) in [(&user_2, &user_2_socket), (&user_3, &user_3_socket), (&user_4, &user_4_socket)] {
+        room_manager.send(JoinToRoomRequest {
+            request_id: None,
+            auth: user.clone(),
+            room_id,
+            room_user_type: RoomUserType::User,
+            password: None,
+            history: None,
+            socket:socket.clone()
+        }).await??;
+    }
+
+    for (user, socket) in [(&user_1, &user_1_socket), (&user_2, &user_2_socket)] {
+        room_manager.send(SetReady {
+            request_id: None,
+            auth: user.clone(),
+            room_id,
+            ready: true,
+            socket:socket.clone()
+        }).await??;
+    }
+
+    // One not ready player is still in the room
+    room_manager.send(DisconnectFromRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        socket:user_3_socket.clone()
+    }).await?;
+
+    let room_infos = states.get_room_info(&room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState])?;
+    assert_eq!(room_infos.get_game_state().into_owned(), RoomGameState::ReadyCheck);
+
+    // Last not ready player is kicked, game started automatically
+    room_manager.send(KickUserFromRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        user_id: user_4.deref().as_ref().unwrap().user.clone(),
+        socket:user_1_socket.clone(),
+        ban: false,
+        reason: None,
+        ban_duration: None
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+
+    let room_infos = states.get_room_info(&room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::GameState, RoomInfoTypeVariant::ReadyUsers])?;
+    assert_eq!(room_infos.get_game_state().into_owned(), RoomGameState::Playing);
+    assert!(room_infos.get_ready_users().is_empty());
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "GameStateChanged");
+    assert_eq!(message["game_state"], 2);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn owner_succession_oldest() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, mut states, user_1_socket) = create_actor_with_config(|config| config.room_owner_succession = RoomOwnerSuccession::Oldest)?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 3,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        room_user_type: RoomUserType::Moderator,
+        password: None,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    // Owner leaves the room
+    room_manager.send(DisconnectFromRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        socket:user_1_socket.clone()
+    }).await?;
+
+    actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+
+    let new_owner_id = user_2.deref().as_ref().unwrap().user.clone();
+    let new_owner_session = &user_2.deref().as_ref().unwrap().session;
+    let other_session = &user_3.deref().as_ref().unwrap().session;
+
+    assert_eq!(states.get_users_room_type(new_owner_session, &room_id)?, Some(RoomUserType::Owner));
+    assert_eq!(states.get_users_room_type(other_session, &room_id)?, Some(RoomUserType::Moderator));
+
+    let room_infos = states.get_room_info(&room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::UserLength])?;
+    assert_eq!(room_infos.get_user_length().into_owned(), 2);
+
+    let message: serde_json::Value = serde_json::from_str(&user_3_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "OwnerChanged");
+    assert_eq!(message["user_id"], new_owner_id.to_string());
+    assert_eq!(message["previous_owner"], user_1.deref().as_ref().unwrap().user.to_string());
+
+    // New owner can manage the room
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        name: Some("New name".to_string()),
+        socket: user_2_socket.clone(),
+        description: None,
+        access_type: None,
+        join_request: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        metas: None,
+        user_permission: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        password: None,
+        generate_password: false
+    }).await??;
+
+    Ok(())
+}
+
+#[actix::test]
+async fn owner_succession_invalid_plugin_selection() -> anyhow::Result<()> {
+    let mut builder = PluginBuilder::default();
+    builder.add_installer(Box::new(InvalidOwnerInstaller));
+
+    let (room_manager, auth_manager, config, mut states, user_1_socket) = create_actor_with_plugin(builder, |config| config.room_owner_succession = RoomOwnerSuccession::Oldest)?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 3,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    // Owner leaves the room, plugin selects a user who is not in the room
+    room_manager.send(DisconnectFromRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        socket:user_1_socket.clone()
+    }).await?;
+
+    actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+
+    let new_owner_id = user_2.deref().as_ref().unwrap().user.clone();
+    let new_owner_session = &user_2.deref().as_ref().unwrap().session;
+    let other_session = &user_3.deref().as_ref().unwrap().session;
+
+    // Default selection is used
+    assert_eq!(states.get_users_room_type(new_owner_session, &room_id)?, Some(RoomUserType::Owner));
+    assert_eq!(states.get_users_room_type(other_session, &room_id)?, Some(RoomUserType::User));
+
+    let message: serde_json::Value = serde_json::from_str(&user_3_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "OwnerChanged");
+    assert_eq!(message["user_id"], new_owner_id.to_string());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn owner_succession_highest_type() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, mut states, user_1_socket) = create_actor_with_config(|config| config.room_owner_succession = RoomOwnerSuccession::HighestType)?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 3,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        room_user_type: RoomUserType::Moderator,
+        password: None,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    // Owner leaves the room
+    room_manager.send(DisconnectFromRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        socket:user_1_socket.clone()
+    }).await?;
+
+    actix::clock::sleep(std::time::Duration::from_millis(100)).await;
+
+    let new_owner_id = user_3.deref().as_ref().unwrap().user.clone();
+    let new_owner_session = &user_3.deref().as_ref().unwrap().session;
+    let other_session = &user_2.deref().as_ref().unwrap().session;
+
+    assert_eq!(states.get_users_room_type(new_owner_session, &room_id)?, Some(RoomUserType::Owner));
+    assert_eq!(states.get_users_room_type(other_session, &room_id)?, Some(RoomUserType::User));
+
+    let room_infos = states.get_room_info(&room_id, RoomMetaAccess::System, &[RoomInfoTypeVariant::UserLength])?;
+    assert_eq!(room_infos.get_user_length().into_owned(), 2);
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "OwnerChanged");
+    assert_eq!(message["user_id"], new_owner_id.to_string());
+    assert_eq!(message["previous_owner"], user_1.deref().as_ref().unwrap().user.to_string());
+
+    // New owner can manage the room
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id,
+        name: Some("New name".to_string()),
+        socket: user_3_socket.clone(),
+        description: None,
+        access_type: None,
+        join_request: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        metas: None,
+        user_permission: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        password: None,
+        generate_password: false
+    }).await??;
+
+    Ok(())
+}
+
+macro_rules! send_room_messages {
+    ($room_manager: expr, $auth: expr, $room_id: expr, $socket: expr, $messages: expr) => {
+        for message in $messages {
+            $room_manager.send(MessageToRoomRequest {
+                request_id: None,
+                auth: $auth.clone(),
+                room_id: $room_id,
+                message: serde_json::Value::String(message.to_string()),
+                socket: $socket.clone()
+            }).await??;
+        }
+    };
+}
+
+#[actix::test]
+async fn room_message_history() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor_with_config(|config| config.room_message_history_size = 2)?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+    let user_1_id = user_1.clone().deref().as_ref().unwrap().user.clone();
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    send_room_messages!(room_manager, user_1, room_id, user_1_socket, ["HELLO", "WORLD", "YUMMY"]);
+
+    // Late joiner receives the last messages
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: Some(10),
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_front().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+    assert_eq!(message.result.messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![2, 3]);
+    assert_eq!(message.result.messages[0].user_id, Some(user_1_id.clone()));
+    assert_eq!(message.result.messages[1].message, serde_json::Value::String("YUMMY".to_string()));
+
+    // Previous messages
+    room_manager.send(RoomMessageHistory {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        before: Some(3),
+        limit: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<yummy_testing::model::RoomMessageHistoryResponse> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "RoomMessageHistory");
+    assert_eq!(message.result.room_id, room_id);
+    assert_eq!(message.result.messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![2]);
+
+    // Only the room members can read the history
+    assert!(room_manager.send(RoomMessageHistory {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id: room_id,
+        before: None,
+        limit: None,
+        socket:user_3_socket.clone()
+    }).await?.is_err());
+
+    // History is not requested
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_3_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert!(message.result.messages.is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_message_history_persist() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor_with_config(|config| {
+        config.room_message_history_size = 1;
+        config.room_message_history_persist = true;
+    })?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    send_room_messages!(room_manager, user_1, room_id, user_1_socket, ["HELLO", "WORLD", "YUMMY"]);
+
+    // Database keeps more messages than the ring
+    room_manager.send(RoomMessageHistory {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        before: None,
+        limit: Some(2),
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<yummy_testing::model::RoomMessageHistoryResponse> = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(message.result.messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![2, 3]);
+
+    room_manager.send(RoomMessageHistory {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        before: Some(2),
+        limit: None,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<yummy_testing::model::RoomMessageHistoryResponse> = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(message.result.messages.iter().map(|message| message.id).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(message.result.messages[0].message, serde_json::Value::String("HELLO".to_string()));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_invite() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+    let user_1_id = user_1.clone().deref().as_ref().unwrap().user.clone();
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+    let user_2_id = user_2.clone().deref().as_ref().unwrap().user.clone();
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    let user_3 = email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket);
+    let user_3_id = user_3.clone().deref().as_ref().unwrap().user.clone();
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: Some("Private room".to_string()),
+        description: None,
+        join_request: true,
+        access_type: CreateRoomAccessType::Private,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    // Only the room members can invite
+    assert!(room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_3_socket.clone()
+    }).await?.is_err());
+    user_3_socket.clone().messages.lock().unwrap().pop_back();
+
+    // Ownership can not be given with invite
+    assert!(room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        room_user_type: RoomUserType::Owner,
+        socket:user_1_socket.clone()
+    }).await?.is_err());
+    user_1_socket.clone().messages.lock().unwrap().pop_back();
+
+    room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: Answer = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert!(message.status);
+
+    let message: yummy_testing::model::RoomInviteReceived = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.class_type[..], "RoomInvite");
+    assert_eq!(message.room_id, room_id);
+    assert_eq!(message.room_name, Some("Private room".to_string()));
+    assert_eq!(message.inviter, user_1_id);
+    assert_eq!(message.room_user_type, RoomUserType::User);
+
+    room_manager.send(RoomInvites {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<yummy_testing::model::RoomInvitesResponse> = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "RoomInvites");
+    assert_eq!(message.result.invites.len(), 1);
+    assert_eq!(message.result.invites[0].user_id, user_2_id);
+    assert_eq!(message.result.invites[0].inviter, user_1_id);
+
+    // Invited user does not need approvement
+    room_manager.send(AcceptRoomInvite {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+    assert_eq!(message.result.users.len(), 2);
+
+    // Invite is used
+    assert!(room_manager.send(AcceptRoomInvite {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+    user_2_socket.clone().messages.lock().unwrap().pop_back();
+
+    // User already in the room
+    assert!(room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_1_socket.clone()
+    }).await?.is_err());
+    user_1_socket.clone().messages.lock().unwrap().pop_back();
+
+    // Normal users can not invite
+    assert!(room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        user_id: user_3_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+    user_2_socket.clone().messages.lock().unwrap().pop_back();
+
+    // Declined invite
+    room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_3_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_1_socket.clone()
+    }).await??;
+    user_1_socket.clone().messages.lock().unwrap().pop_back();
+
+    room_manager.send(DeclineRoomInvite {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id: room_id,
+        socket:user_3_socket.clone()
+    }).await??;
+
+    let message: yummy_testing::model::RoomInviteAnswered = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.class_type[..], "RoomInviteDeclined");
+    assert_eq!(message.room_id, room_id);
+    assert_eq!(message.user_id, user_3_id);
+
+    // Cancelled invite
+    room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_3_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    room_manager.send(CancelRoomInvite {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_3_id.clone(),
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: yummy_testing::model::RoomInviteCancelled = serde_json::from_str(&user_3_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.class_type[..], "RoomInviteCancelled");
+    assert_eq!(message.room_id, room_id);
+
+    assert!(room_manager.send(AcceptRoomInvite {
+        request_id: None,
+        auth: user_3.clone(),
+        room_id: room_id,
+        history: None,
+        socket:user_3_socket.clone()
+    }).await?.is_err());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_invite_expired() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor_with_config(|config| config.room_invite_lifetime = std::time::Duration::from_secs(0))?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+    let user_2_id = user_2.clone().deref().as_ref().unwrap().user.clone();
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: true,
+        access_type: CreateRoomAccessType::Private,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(InviteToRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        room_user_type: RoomUserType::User,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    assert!(room_manager.send(AcceptRoomInvite {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn timed_ban_and_unban() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket);
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket);
+    let user_2_id = user_2.clone().deref().as_ref().unwrap().user.clone();
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    macro_rules! join_and_ban {
+        ($ban_duration: expr) => {
+            room_manager.send(JoinToRoomRequest {
+                request_id: None,
+                auth: user_2.clone(),
+                room_id: room_id,
+                room_user_type: RoomUserType::User,
+                password: None,
+                history: None,
+                socket:user_2_socket.clone()
+            }).await??;
+
+            room_manager.send(KickUserFromRoom {
+                request_id: None,
+                auth: user_1.clone(),
+                room_id: room_id,
+                user_id: user_2_id.clone(),
+                socket:user_1_socket.clone(),
+                ban: true,
+                reason: Some("Spam".to_string()),
+                ban_duration: $ban_duration
+            }).await??;
+        };
+    }
+
+    // Ban is already expired
+    join_and_ban!(Some(0));
+
+    let message: DisconnectedFromRoom = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(message.reason, Some("Spam".to_string()));
+    assert!(message.ban.unwrap().expire_date.is_some());
+
+    // Expired ban can not be removed
+    assert!(room_manager.send(UnbanUserFromRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        socket:user_1_socket.clone()
+    }).await?.is_err());
+    user_1_socket.clone().messages.lock().unwrap().pop_back();
+
+    // Permanent ban
+    join_and_ban!(None);
+
+    assert!(room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    // Only the room owner or moderators can unban
+    assert!(room_manager.send(UnbanUserFromRoom {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        socket:user_2_socket.clone()
+    }).await?.is_err());
+
+    room_manager.send(UnbanUserFromRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id: room_id,
+        user_id: user_2_id.clone(),
+        socket:user_1_socket.clone()
+    }).await??;
+
+    let message: Answer = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert!(message.status);
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id: room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket:user_2_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<Joined> = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap()).unwrap();
+    assert_eq!(&message.response_type[..], "JoinToRoom");
+
+    // Out of range duration is rejected and user stays in the room
+    assert!(room_manager.send(KickUserFromRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        user_id: user_2_id.clone(),
+        socket:user_1_socket.clone(),
+        ban: true,
+        reason: None,
+        ban_duration: Some(u64::MAX)
+    }).await?.is_err());
+
+    room_manager.send(KickUserFromRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        user_id: user_2_id.clone(),
+        socket:user_1_socket.clone(),
+        ban: false,
+        reason: None,
+        ban_duration: None
+    }).await??;
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_meta_operation() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket.clone());
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket.clone());
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([
+            ("round".to_string(), MetaType::Number(1.0, RoomMetaAccess::User)),
+            ("secret".to_string(), MetaType::Number(1.0, RoomMetaAccess::Owner)),
+        ])),
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    /* Increment with max value */
+    room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        key: "round".to_string(),
+        operation: RoomMetaOperation::Increment { value: 5.0, min: None, max: Some(4.0) },
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(&message.response_type[..], "RoomMetaOperation");
+    assert_eq!(message.result["key"].as_str(), Some("round"));
+    assert_eq!(message.result["value"].as_f64(), Some(4.0));
+
+    /* Append to missing list */
+    room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        key: "players".to_string(),
+        operation: RoomMetaOperation::Append { value: MetaType::String("erhan".to_string(), RoomMetaAccess::Anonymous) },
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["value"], serde_json::json!(["erhan"]));
+
+    /* Compare and set fails if the value changed */
+    assert!(room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        key: "round".to_string(),
+        operation: RoomMetaOperation::CompareAndSet { expected: MetaType::Number(1.0, RoomMetaAccess::Anonymous), value: MetaType::Number(10.0, RoomMetaAccess::User) },
+        socket: user_1_socket.clone()
+    }).await?.is_err());
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Meta value is not matched with expected value"));
+
+    /* Users who are not in the room can not change metas */
+    assert!(room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        key: "round".to_string(),
+        operation: RoomMetaOperation::Increment { value: 1.0, min: None, max: None },
+        socket: user_2_socket.clone()
+    }).await?.is_err());
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket: user_2_socket.clone()
+    }).await??;
+
+    /* Room users can not change owner metas */
+    assert!(room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        key: "secret".to_string(),
+        operation: RoomMetaOperation::Increment { value: 1.0, min: None, max: None },
+        socket: user_2_socket.clone()
+    }).await?.is_err());
+
+    let message = user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Meta access level is not enough"));
+
+    /* Check room metas */
+    room_manager.send(GetRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        socket: user_1_socket.clone(),
+        members: Vec::new(),
+        room_id
+    }).await??;
+
+    let room_info: GenericAnswer<serde_json::Value> = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let metas: HashMap<String, serde_json::Value> = serde_json::from_value(room_info.result.get("metas").unwrap().clone())?;
+    assert_eq!(metas.get("round"), Some(&serde_json::json!(4.0)));
+    assert_eq!(metas.get("players"), Some(&serde_json::json!(["erhan"])));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_meta_schema() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor_with_config(|config| {
+        config.meta_schema = Arc::new(serde_json::from_str(r#"{
+            "room": {
+                "keys": {
+                    "round": { "type": "number", "min": 0, "max": 10 },
+                    "winner": { "writable": false },
+                    "map": { "type": "string", "pattern": "^[a-z]+$", "access": 3 }
+                }
+            }
+        }"#).unwrap());
+    })?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket.clone());
+
+    /* Number is out of range */
+    assert!(room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([("round".to_string(), MetaType::Number(-1.0, RoomMetaAccess::Anonymous))])),
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket: user_1_socket.clone()
+    }).await?.is_err());
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("'round' meta should be greater than or equal to 0"));
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: Some(MetaDefinitions::from([
+            ("round".to_string(), MetaType::Number(1.0, RoomMetaAccess::Anonymous)),
+            ("map".to_string(), MetaType::String("desert".to_string(), RoomMetaAccess::Anonymous))
+        ])),
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    /* Read only metas can not be changed by clients */
+    assert!(room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: false,
+        socket: user_1_socket.clone(),
+        metas: Some(MetaDefinitions::from([("winner".to_string(), MetaType::String("erhan".to_string(), RoomMetaAccess::Anonymous))]))
+    }).await?.is_err());
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("'winner' meta is read only"));
+
+    /* Pattern should be matched */
+    assert!(room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: false,
+        socket: user_1_socket.clone(),
+        metas: Some(MetaDefinitions::from([("map".to_string(), MetaType::String("Desert 2".to_string(), RoomMetaAccess::Anonymous))]))
+    }).await?.is_err());
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("'map' meta does not match with the pattern"));
+
+    /* Operations are validated with the final value */
+    assert!(room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        key: "round".to_string(),
+        operation: RoomMetaOperation::Increment { value: 20.0, min: None, max: None },
+        socket: user_1_socket.clone()
+    }).await?.is_err());
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("'round' meta should be less than or equal to 10"));
+
+    /* Schema access level applied to the map meta */
+    room_manager.send(GetRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        socket: user_1_socket.clone(),
+        members: Vec::new(),
+        room_id
+    }).await??;
+
+    let room_info: GenericAnswer<serde_json::Value> = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let metas: HashMap<String, serde_json::Value> = serde_json::from_value(room_info.result.get("metas").unwrap().clone())?;
+    assert_eq!(metas.get("round"), Some(&serde_json::json!(1.0)));
+    assert_eq!(metas.get("map"), Some(&serde_json::json!("desert")));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn room_meta_changed() -> anyhow::Result<()> {
+    let (room_manager, auth_manager, config, _, user_1_socket) = create_actor_with_config(|config| config.meta_change_tick_interval = std::time::Duration::from_millis(1000))?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket.clone());
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket.clone());
+
+    room_manager.send(CreateRoomRequest {
+        request_id: None,
+        auth: user_1.clone(),
+        name: None,
+        description: None,
+        join_request: false,
+        access_type: CreateRoomAccessType::Public,
+        max_user: 4,
+        max_spectator: 0,
+        metas: None,
+        tags: Vec::new(),
+        password: None,
+        generate_password: false,
+        auto_start: false,
+        socket: user_1_socket.clone()
+    }).await??;
+
+    let room_id: yummy_testing::model::RoomCreated = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let room_id = room_id.room_id;
+
+    room_manager.send(JoinToRoomRequest {
+        request_id: None,
+        auth: user_2.clone(),
+        room_id,
+        room_user_type: RoomUserType::User,
+        password: None,
+        history: None,
+        socket: user_2_socket.clone()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(1500)).await;
+    user_1_socket.clone().messages.lock().unwrap().clear();
+    user_2_socket.clone().messages.lock().unwrap().clear();
+
+    /* Changes in the same tick are merged */
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: false,
+        socket: user_1_socket.clone(),
+        metas: Some(MetaDefinitions::from([
+            ("round".to_string(), MetaType::Number(1.0, RoomMetaAccess::User)),
+            ("secret".to_string(), MetaType::Number(1.0, RoomMetaAccess::Owner))
+        ]))
+    }).await??;
+
+    room_manager.send(UpdateRoomMeta {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        key: "round".to_string(),
+        operation: RoomMetaOperation::Increment { value: 1.0, min: None, max: None },
+        socket: user_1_socket.clone()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(1500)).await;
+
+    /* Only visible keys are sent */
+    assert_eq!(user_2_socket.clone().messages.lock().unwrap().len(), 1);
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "RoomMetaChanged");
+    assert_eq!(message["room_id"], serde_json::json!(room_id));
+    assert_eq!(message["metas"], serde_json::json!({ "round": 2.0 }));
+
+    /* Sender already knows the changes */
+    assert_eq!(user_1_socket.clone().messages.lock().unwrap().len(), 2);
+
+    /* Removed metas are sent as null */
+    room_manager.send(UpdateRoom {
+        request_id: None,
+        auth: user_1.clone(),
+        room_id,
+        name: None,
+        description: None,
+        join_request: None,
+        meta_action: MetaAction::OnlyAddOrUpdate,
+        access_type: None,
+        max_user: None,
+        max_spectator: None,
+        tags: None,
+        user_permission: None,
+        password: None,
+        generate_password: false,
+        socket: user_1_socket.clone(),
+        metas: Some(MetaDefinitions::from([("round".to_string(), MetaType::Null)]))
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(1500)).await;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "RoomMetaChanged");
+    assert_eq!(message["metas"], serde_json::json!({ "round": null }));
+
+    Ok(())
+}
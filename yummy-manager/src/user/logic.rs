@@ -3,22 +3,28 @@
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{marker::PhantomData, sync::Arc, ops::Deref};
 
+use actix_broker::{Broker, SystemBroker};
+use serde_json::Value;
 use yummy_cache::state::YummyState;
 use yummy_database::DatabaseTrait;
 use yummy_model::meta::collection::{UserMetaCollection, UserMetaCollectionInformation};
 use yummy_model::request::RequestUserTypeVariant;
-use yummy_model::user::UserUpdate;
-use yummy_model::{UserId, UserType, UserInformationModel, UserMetaId};
+use yummy_model::user::{UserUpdate, UserSuspensionModel, InboxCursor};
+use yummy_model::password::Password;
+use yummy_model::{UserId, UserType, UserInformationModel, UserMetaId, SendMessage};
 use yummy_model::config::YummyConfig;
 use yummy_model::meta::{UserMetaAccess, MetaType, UserMetaType};
+use yummy_model::meta::operation::UserMetaOperation;
 use yummy_model::web::Answer;
 use yummy_general::database::Pool;
 
 use crate::{auth::model::AuthError, get_user_id_from_auth};
 
-use super::model::{GetUserInformation, GetUserInformationEnum, UpdateUser};
+use super::model::{GetUserInformation, GetUserInformationEnum, UpdateUser, AddFriend, ProcessFriendRequest, RemoveFriend, FriendList, FriendInformation, MessageToUser, MessageFromUser, GetInbox, AckInbox, InboxResponse, SuspendUser, UnsuspendUser, SuspendedUsers, UpdateUserMeta, UserMetaChanges, UserMetaChanged};
 use super::model::UserError;
 
 /* **************************************************************************************************************** */
@@ -70,13 +76,23 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
         }
 
         match self.states.get_user_type(current_user_id)? {
-            Some(UserType::User) => Ok(UserMetaAccess::User),
+            Some(UserType::User) => match self.states.is_friend(current_user_id, target_user_id)? {
+                true => Ok(UserMetaAccess::Friend),
+                false => Ok(UserMetaAccess::User)
+            },
             Some(UserType::Mod) => Ok(UserMetaAccess::Mod),
             Some(UserType::Admin) => Ok(UserMetaAccess::Admin),
             None => Err(anyhow::anyhow!(UserError::UserNotFound))
         }
     }
 
+    fn check_admin(&self, user_id: &UserId) -> anyhow::Result<()> {
+        match self.states.get_user_type(user_id)? {
+            Some(UserType::Admin) => Ok(()),
+            _ => Err(anyhow::anyhow!(UserError::UserDoesNotHaveEnoughPermission))
+        }
+    }
+
     pub fn get_user_meta(&self, user_id: UserId, key: String) -> anyhow::Result<Option<UserMetaType>> {
         Ok(self.states.get_user_meta(&user_id, UserMetaAccess::System)?
             .get_with_name(&key)
@@ -89,15 +105,145 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
     }
 
     pub fn set_user_meta(&self, user_id: UserId, key: String, value: UserMetaType) -> anyhow::Result<()> {
-        Ok(self.states.set_user_meta(&user_id, key, value)?)
+        let previous_metas = self.states.get_user_meta(&user_id, UserMetaAccess::System)?;
+        self.states.set_user_meta(&user_id, key, value)?;
+        self.publish_meta_changes(&user_id, None, &previous_metas)
     }
 
     pub fn remove_all_metas(&self, user_id: UserId) -> anyhow::Result<()> {
-        Ok(self.states.remove_all_user_metas(&user_id)?)
+        let previous_metas = self.states.get_user_meta(&user_id, UserMetaAccess::System)?;
+        self.states.remove_all_user_metas(&user_id)?;
+        self.publish_meta_changes(&user_id, None, &previous_metas)
     }
 
     pub fn remove_user_meta(&self, user_id: UserId, key: String) -> anyhow::Result<()> {
-        Ok(self.states.remove_user_meta(&user_id, key)?)
+        let previous_metas = self.states.get_user_meta(&user_id, UserMetaAccess::System)?;
+        self.states.remove_user_meta(&user_id, key)?;
+        self.publish_meta_changes(&user_id, None, &previous_metas)
+    }
+
+    pub fn apply_user_meta_operation(&self, user_id: UserId, key: String, operation: UserMetaOperation) -> anyhow::Result<UserMetaType> {
+        let previous_metas = self.states.get_user_meta(&user_id, UserMetaAccess::System)?;
+        let meta = self.apply_meta_operation(&user_id, key, UserMetaAccess::System, &operation, false)?;
+        self.publish_meta_changes(&user_id, None, &previous_metas)?;
+        Ok(meta)
+    }
+
+    /* User manager sends the changes to the users who share a room with the user, with the next tick */
+    fn publish_meta_changes(&self, user_id: &UserId, changed_by: Option<&UserId>, previous_metas: &UserMetaCollection) -> anyhow::Result<()> {
+        let metas = self.states.get_user_meta(user_id, UserMetaAccess::System)?.get_changes(previous_metas);
+
+        if !metas.is_empty() {
+            Broker::<SystemBroker>::issue_async(UserMetaChanges {
+                user_id: user_id.clone(),
+                changed_by: changed_by.map(|user_id| Arc::new(user_id.clone())),
+                metas
+            });
+        }
+
+        Ok(())
+    }
+
+    /* Every room member only receives the keys that visible to the member */
+    pub fn send_meta_changes(&mut self, user_id: &UserId, changes: &HashMap<String, (UserMetaAccess, Option<Arc<UserId>>)>) -> anyhow::Result<()> {
+        let mut receivers = HashSet::new();
+
+        for session_id in self.states.get_user_sessions(user_id).iter() {
+            for room_id in self.states.get_user_rooms(session_id).unwrap_or_default().iter() {
+                receivers.extend(self.states.get_users_from_room(room_id)?);
+            }
+        }
+
+        if receivers.is_empty() {
+            return Ok(());
+        }
+
+        let metas = self.states.get_user_meta(user_id, UserMetaAccess::System)?;
+
+        for receiver in receivers.into_iter().filter(|receiver| receiver.as_ref() != user_id) {
+            let access_level = self.get_user_access_level(&receiver, user_id)? as u8;
+
+            let changed_metas = changes.iter()
+
+                /* Last writer already knows the value */
+                .filter(|(_, (meta_access_level, changed_by))| meta_access_level.clone() as u8 <= access_level && changed_by.as_ref() != Some(&receiver))
+                .map(|(key, _)| {
+                    let meta = metas.get_with_name(key)
+                        .map(|item| &item.meta)
+                        .filter(|meta| meta.get_access_level() as u8 <= access_level)
+                        .unwrap_or(&MetaType::Null);
+                    (&key[..], meta)
+                })
+                .collect::<HashMap<_, _>>();
+
+            if !changed_metas.is_empty() {
+                Broker::<SystemBroker>::issue_async(SendMessage {
+                    message: SendMessage::build("UserMetaChanged", UserMetaChanged { user_id, metas: changed_metas }),
+                    user_id: receiver
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /* State executes the operation atomically, database record is replaced before the state updated */
+    fn apply_meta_operation(&self, user_id: &UserId, key: String, access_level: UserMetaAccess, operation: &UserMetaOperation, validate: bool) -> anyhow::Result<UserMetaType> {
+        if operation.get_access_level() as u8 > access_level.clone() as u8 {
+            return Err(anyhow::anyhow!(UserError::MetaAccessLevelCannotBeBiggerThanUsersAccessLevel(key)));
+        }
+
+        let max_user_meta = self.config.max_user_meta;
+        let schema = &self.config.meta_schema.user;
+        let mut connection = self.database.get()?;
+
+        Ok(self.states.apply_user_meta_operation(user_id, key.clone(), access_level, operation, |meta| {
+            /* Computed value is validated before the state commits it, rejected values never reach the cache */
+            if validate {
+                schema.validate(&key, meta)?;
+            }
+
+            DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+                let metas = DB::get_user_meta(connection, user_id, UserMetaAccess::System)?;
+                let remove_list = metas.iter().filter(|item| item.name == key).filter_map(|item| item.id.clone()).collect::<Vec<UserMetaId>>();
+
+                if let MetaType::Null = meta {
+                    return DB::remove_user_metas(connection, remove_list);
+                }
+
+                if remove_list.is_empty() && metas.len() >= max_user_meta {
+                    return Err(anyhow::anyhow!(UserError::MetaLimitOverToMaximum));
+                }
+
+                DB::remove_user_metas(connection, remove_list)?;
+                DB::insert_user_metas(connection, user_id, vec![(&key, meta)])
+            })
+        })?)
+    }
+
+    /* Expired metas are already hidden from the clients, database records are removed periodically */
+    pub fn remove_expired_metas(&self) -> anyhow::Result<usize> {
+        DB::remove_expired_user_metas(&mut self.database.get()?)
+    }
+
+    pub fn update_user_meta(&mut self, model: &UpdateUserMeta) -> anyhow::Result<UserMetaType> {
+        let user_id = get_user_id_from_auth!(model);
+
+        let target_user_id = match &model.target_user_id {
+            Some(target_user_id) => target_user_id,
+            None => user_id
+        };
+
+        let access_level = self.get_user_access_level(user_id, target_user_id)?;
+
+        if self.states.get_user_information(target_user_id, UserMetaAccess::Anonymous)?.is_none() {
+            return Err(anyhow::anyhow!(UserError::UserNotFound));
+        }
+
+        let previous_metas = self.states.get_user_meta(target_user_id, UserMetaAccess::System)?;
+        let meta = self.apply_meta_operation(target_user_id, model.key.clone(), access_level, &model.operation, true)?;
+        self.publish_meta_changes(target_user_id, Some(user_id), &previous_metas)?;
+        Ok(meta)
     }
 
     pub fn get_user_information(&mut self, model: &GetUserInformation) -> anyhow::Result<UserInformationModel> {
@@ -125,11 +271,15 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
                 match requester.deref() {
                     Some(requester) => {
                         let user_type = self.states.get_user_type(&requester.user)?;
-                        execute(user, match user_type.unwrap_or_default() {
+                        let access_type = match user_type.unwrap_or_default() {
                             UserType::Admin => UserMetaAccess::Admin,
                             UserType::Mod => UserMetaAccess::Mod,
-                            UserType::User => UserMetaAccess::User
-                        })
+                            UserType::User => match self.states.is_friend(&requester.user, user)? {
+                                true => UserMetaAccess::Friend,
+                                false => UserMetaAccess::User
+                            }
+                        };
+                        execute(user, access_type)
                     },
                     None => execute(user, UserMetaAccess::Anonymous)
                 }
@@ -149,7 +299,7 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
 
         let has_user_update = custom_id.is_some() || device_id.is_some() || email.is_some() || name.is_some() || password.is_some() || user_type.is_some();
 
-        if !has_user_update && metas.as_ref().map(|dict| dict.len()).unwrap_or_default() == 0 {
+        if !has_user_update && metas.as_ref().map(|dict| dict.metas.len()).unwrap_or_default() == 0 {
             return Err(anyhow::anyhow!(UserError::UpdateInformationMissing));
         }
 
@@ -176,7 +326,7 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
             if password.trim().len() < 4 {
                 return Err(anyhow::anyhow!(UserError::PasswordIsTooSmall))
             }
-            updates.password = Some(password.clone());
+            updates.password = Some(Password::from(password.clone()).hash()?);
         }
 
         if let Some(email) = email {
@@ -190,8 +340,12 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
 
         let config = self.config.clone();
 
-        DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+        /* Client metas should be valid before touching the database */
+        let metas = &config.meta_schema.user.validate_metas(metas.clone())?;
+        let previous_metas = self.states.get_user_meta(target_user_id, UserMetaAccess::System)?;
 
+        let (response, user_information) = DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            let mut user_information = user_information;
             let meta_action = meta_action.clone();
             let user_access_level_code = user_access_level.clone() as u8;
 
@@ -342,12 +496,248 @@ impl<DB: DatabaseTrait + ?Sized> UserLogic<DB> {
                 false => Answer::success(model.request_id, Cow::Borrowed(RequestUserTypeVariant::Update.into()))
             };
 
-            // Update user cache
-            self.states.update_user_information(target_user_id, user_information)?;
+            Ok((response, user_information))
+        })?;
+
+        // States are updated after the commit, rollback should not change the cache or revoke the tokens
+        self.states.update_user_information(target_user_id, user_information)?;
+
+        // Password changed, all issued tokens should be revoked
+        if password.is_some() {
+            self.states.revoke_user_tokens(target_user_id)?;
+        }
+
+        self.publish_meta_changes(target_user_id, Some(user_id), &previous_metas)?;
+        Ok(response)
+    }
+
+    /* Returns true if the target user already sent a friend request and both users are friends now */
+    pub fn add_friend(&mut self, model: &AddFriend) -> anyhow::Result<bool> {
+        let user_id = get_user_id_from_auth!(model);
+
+        if user_id == &model.user_id {
+            return Err(anyhow::anyhow!(UserError::CannotAddYourselfAsFriend));
+        }
+
+        if self.states.get_user_information(&model.user_id, UserMetaAccess::Anonymous)?.is_none() {
+            return Err(anyhow::anyhow!(UserError::UserNotFound));
+        }
+
+        if self.states.is_friend(user_id, &model.user_id)? {
+            return Err(anyhow::anyhow!(UserError::AlreadyFriend));
+        }
+
+        let mut connection = self.database.get()?;
+
+        let (friends, added) = DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+
+            // Target user already want to be friend, no need to wait for approvement
+            if DB::remove_friend_request(connection, &model.user_id, user_id)? > 0 {
+                return Ok((true, DB::add_friend(connection, user_id, &model.user_id)?));
+            }
+
+            // Unique constraint catches the requests sent at the same time
+            if DB::is_friend_requested(connection, user_id, &model.user_id)? || !DB::create_friend_request(connection, user_id, &model.user_id)? {
+                return Err(anyhow::anyhow!(UserError::FriendRequestAlreadySent));
+            }
+
+            Ok((false, true))
+        })?;
 
-            Ok(response)
+        // Friendship already created by another request, stale friend request is removed
+        if !added {
+            return Err(anyhow::anyhow!(UserError::AlreadyFriend));
+        }
+
+        // States are updated after the commit, rollback should not leave any friendship in the cache
+        if friends {
+            self.states.add_friend(user_id, &model.user_id)?;
+        }
+
+        Ok(friends)
+    }
+
+    pub fn process_friend_request(&mut self, model: &ProcessFriendRequest) -> anyhow::Result<()> {
+        let user_id = get_user_id_from_auth!(model);
+        let mut connection = self.database.get()?;
+
+        let added = DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            if DB::remove_friend_request(connection, &model.user_id, user_id)? == 0 {
+                return Err(anyhow::anyhow!(UserError::FriendRequestNotFound));
+            }
+
+            match model.status {
+                true => DB::add_friend(connection, user_id, &model.user_id),
+                false => Ok(false)
+            }
+        })?;
+
+        // Friendship already created by another request, stale friend request is removed
+        if model.status && !added {
+            return Err(anyhow::anyhow!(UserError::AlreadyFriend));
+        }
+
+        if added {
+            self.states.add_friend(user_id, &model.user_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_friend(&mut self, model: &RemoveFriend) -> anyhow::Result<()> {
+        let user_id = get_user_id_from_auth!(model);
+        let mut connection = self.database.get()?;
+
+        if DB::remove_friend(&mut connection, user_id, &model.user_id)? == 0 {
+            return Err(anyhow::anyhow!(UserError::NotFriend));
+        }
+
+        self.states.remove_friend(user_id, &model.user_id)?;
+        Ok(())
+    }
+
+    pub fn friend_list(&mut self, model: &FriendList) -> anyhow::Result<Vec<FriendInformation>> {
+        let user_id = get_user_id_from_auth!(model);
+
+        let users = match model.pending {
+            true => DB::get_friend_requests(&mut self.database.get()?, user_id)?,
+            false => self.states.get_user_friends(user_id)?.into_iter().collect()
+        };
+
+        let mut friends = Vec::with_capacity(users.len());
+        for user_id in users.into_iter() {
+            let name = self.states.get_user_information(&user_id, UserMetaAccess::Anonymous)?.and_then(|user| user.name);
+            let online = self.states.is_user_online(&user_id);
+            friends.push(FriendInformation { user_id, name, online });
+        }
+
+        Ok(friends)
+    }
+
+    pub fn message_to_user(&mut self, model: &MessageToUser) -> anyhow::Result<()> {
+        let user_id = get_user_id_from_auth!(model);
+
+        if user_id == &model.user_id {
+            return Err(anyhow::anyhow!(UserError::CannotSendMessageToYourself));
+        }
+
+        /* Messages could be stored into the inbox, so the size is limited with the serialized content */
+        if self.config.max_user_message_size > 0 && serde_json::to_string(&model.message)?.len() > self.config.max_user_message_size {
+            return Err(anyhow::anyhow!(UserError::MessageTooBig));
+        }
+
+        if self.states.get_user_information(&model.user_id, UserMetaAccess::Anonymous)?.is_none() {
+            return Err(anyhow::anyhow!(UserError::UserNotFound));
+        }
+
+        Ok(())
+    }
+
+    pub fn store_inbox_message(&self, user_id: &UserId, sender_user_id: &UserId, message: &Value) -> anyhow::Result<()> {
+        DB::insert_inbox_message(&mut self.database.get()?, user_id, sender_user_id, serde_json::to_string(message)?)
+    }
+
+    /* Read messages from the inbox without removing them, messages stay in the inbox until they are acknowledged */
+    pub fn read_inbox_messages(&self, user_id: &UserId, cursor: Option<&InboxCursor>, limit: Option<usize>) -> anyhow::Result<InboxResponse> {
+        let mut connection = self.database.get()?;
+        let rows = DB::get_inbox_messages(&mut connection, user_id, cursor.map(|cursor| (cursor.insert_date, &cursor.id)), limit)?;
+        let last = rows.last().map(|row| InboxCursor::new(row.insert_date, row.id.clone()));
+
+        let remaining = match (limit, last.as_ref().or(cursor)) {
+            (Some(_), after) => DB::count_inbox_messages(&mut connection, user_id, after.map(|after| (after.insert_date, &after.id)))?,
+            (None, _) => 0
+        };
+
+        let messages = rows.into_iter().map(|row| MessageFromUser {
+            id: Some(row.id),
+            user_id: row.sender_user_id,
+            message: serde_json::from_str(&row.message).unwrap_or(Value::String(row.message)),
+            insert_date: Some(row.insert_date)
+        }).collect();
+
+        Ok(InboxResponse {
+            messages,
+            remaining,
+            cursor: match remaining {
+                0 => None,
+                _ => last.map(|last| last.to_string())
+            }
         })
     }
+
+    pub fn get_inbox(&mut self, model: &GetInbox) -> anyhow::Result<InboxResponse> {
+        let user_id = get_user_id_from_auth!(model);
+
+        let limit = match model.limit {
+            Some(limit) => limit.min(self.config.max_inbox_page_size),
+            None => self.config.max_inbox_page_size
+        };
+
+        /* Invalid cursor starts from the first message, same as the room list */
+        let cursor = model.cursor.as_ref().and_then(|cursor| InboxCursor::parse(cursor));
+        self.read_inbox_messages(user_id, cursor.as_ref(), Some(limit))
+    }
+
+    /* Removes the received messages from the inbox, returns the removed message count */
+    pub fn ack_inbox(&mut self, model: &AckInbox) -> anyhow::Result<usize> {
+        let user_id = get_user_id_from_auth!(model);
+
+        if model.ids.is_empty() {
+            return Ok(0);
+        }
+
+        DB::remove_inbox_messages(&mut self.database.get()?, user_id, model.ids.clone())
+    }
+
+    /* Returns the stored suspension, the live sessions should be closed by the caller */
+    pub fn suspend_user(&mut self, model: &SuspendUser) -> anyhow::Result<UserSuspensionModel> {
+        let user_id = get_user_id_from_auth!(model);
+        self.check_admin(user_id)?;
+
+        if user_id == &model.user_id {
+            return Err(anyhow::anyhow!(UserError::CannotSuspendYourself));
+        }
+
+        if self.states.get_user_information(&model.user_id, UserMetaAccess::Anonymous)?.is_none() {
+            return Err(anyhow::anyhow!(UserError::UserNotFound));
+        }
+
+        let mut connection = self.database.get()?;
+
+        // Suspension without duration is permanent
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default();
+        let expire_date = match model.duration {
+            Some(duration) => Some(i32::try_from(duration).ok().and_then(|duration| now.checked_add(duration)).ok_or(UserError::SuspensionDurationNotValid)?),
+            None => None
+        };
+
+        DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            DB::suspend_user(connection, &model.user_id, user_id, model.reason.as_deref(), expire_date)?;
+            DB::get_user_suspension(connection, &model.user_id)?.ok_or_else(|| anyhow::anyhow!(UserError::UserNotSuspended))
+        })
+    }
+
+    pub fn unsuspend_user(&mut self, model: &UnsuspendUser) -> anyhow::Result<()> {
+        let user_id = get_user_id_from_auth!(model);
+        self.check_admin(user_id)?;
+
+        let mut connection = self.database.get()?;
+
+        DB::transaction::<_, anyhow::Error, _>(&mut connection, |connection| {
+            if DB::get_user_suspension(connection, &model.user_id)?.is_none() {
+                return Err(anyhow::anyhow!(UserError::UserNotSuspended));
+            }
+
+            DB::unsuspend_user(connection, &model.user_id)?;
+            Ok(())
+        })
+    }
+
+    pub fn suspended_users(&mut self, model: &SuspendedUsers) -> anyhow::Result<Vec<UserSuspensionModel>> {
+        let user_id = get_user_id_from_auth!(model);
+        self.check_admin(user_id)?;
+
+        DB::get_suspended_users(&mut self.database.get()?)
+    }
 }
 
 /* **************************************************************************************************************** */
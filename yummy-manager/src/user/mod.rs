@@ -5,40 +5,80 @@ mod test;
 mod logic;
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use actix::{Context, Actor, Handler};
+use actix::{Context, Actor, Handler, AsyncContext};
+use actix_broker::{BrokerIssue, BrokerSubscribe};
 use yummy_database::DatabaseTrait;
 
+use yummy_model::{SendMessage, UserId};
 use yummy_model::config::YummyConfig;
-use yummy_model::web::GenericAnswer;
+use yummy_model::meta::UserMetaAccess;
+use yummy_model::web::{GenericAnswer, Answer};
 use yummy_general::database::Pool;
 use yummy_cache::state::YummyState;
+use std::ops::Deref;
+
 use crate::YummyModel;
+use crate::auth::model::{AuthError, TerminateUserSessions};
+use crate::conn::model::{SendUserMessage, UserConnected, UserMessageUndelivered};
+use crate::get_user_id_from_auth;
 use crate::plugin::PluginExecuter;
 
 pub use self::logic::UserLogic;
 use self::model::*;
 
+/* Changed key with the lowest access level and the last user who changed it */
+type PendingMetaChanges = HashMap<String, (UserMetaAccess, Option<Arc<UserId>>)>;
+
 pub struct UserManager<DB: DatabaseTrait + ?Sized> {
+    config: Arc<YummyConfig>,
     executer: Arc<PluginExecuter>,
     _marker: PhantomData<DB>,
-    logic: UserLogic<DB>
+    logic: UserLogic<DB>,
+    meta_changes: HashMap<UserId, PendingMetaChanges>
 }
 
 impl<DB: DatabaseTrait + ?Sized> UserManager<DB> {
     pub fn new(config: Arc<YummyConfig>, states: YummyState, database: Arc<Pool>, executer: Arc<PluginExecuter>) -> Self {
         Self {
+            config: config.clone(),
             executer,
             _marker: PhantomData,
-            logic: UserLogic::new(config, states, database)
+            logic: UserLogic::new(config, states, database),
+            meta_changes: HashMap::new()
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> UserManager<DB> {
+    fn send_meta_changes(&mut self) {
+        for (user_id, changes) in std::mem::take(&mut self.meta_changes).into_iter() {
+            if let Err(error) = self.logic.send_meta_changes(&user_id, &changes) {
+                log::error!("User meta changes could not sent: {:?}", error);
+            }
+        }
+    }
+
+    fn remove_expired_metas(&mut self) {
+        if let Err(error) = self.logic.remove_expired_metas() {
+            log::error!("Expired user metas could not removed: {:?}", error);
         }
     }
 }
 
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Actor for UserManager<DB> {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        self.subscribe_system_async::<UserConnected>(ctx);
+        self.subscribe_system_async::<UserMessageUndelivered>(ctx);
+        self.subscribe_system_async::<UserMetaChanges>(ctx);
+        ctx.run_interval(self.config.meta_change_tick_interval, |manager, _| manager.send_meta_changes());
+        ctx.run_interval(self.config.meta_sweep_interval, |manager, _| manager.remove_expired_metas());
+    }
 }
 
 impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<GetUserInformation> for UserManager<DB> {
@@ -64,3 +104,243 @@ impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateUs
         Ok(())
     }
 }
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<AddFriend> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="AddFriend", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="add_friend")]
+    fn handle(&mut self, model: AddFriend, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model);
+
+        let message = match self.logic.add_friend(&model)? {
+            true => SendMessage::build("FriendRequestAccepted", FriendRequestProcessed { result: "Accepted", user_id }),
+            false => SendMessage::build("NewFriendRequest", NewFriendRequest { user_id })
+        };
+
+        // Inform target user
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<ProcessFriendRequest> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="ProcessFriendRequest", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="process_friend_request")]
+    fn handle(&mut self, model: ProcessFriendRequest, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model);
+        self.logic.process_friend_request(&model)?;
+
+        // Inform the user who sent the request
+        let message = match model.status {
+            true => SendMessage::build("FriendRequestAccepted", FriendRequestProcessed { result: "Accepted", user_id }),
+            false => SendMessage::build("FriendRequestRejected", FriendRequestProcessed { result: "Rejected", user_id })
+        };
+
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<RemoveFriend> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="RemoveFriend", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="remove_friend")]
+    fn handle(&mut self, model: RemoveFriend, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model);
+        self.logic.remove_friend(&model)?;
+
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message: SendMessage::build("FriendRemoved", FriendRemoved { user_id })
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<FriendList> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="FriendList", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="friend_list")]
+    fn handle(&mut self, model: FriendList, _ctx: &mut Context<Self>) -> Self::Result {
+        let users = self.logic.friend_list(&model)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), FriendListResponse { users }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<MessageToUser> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="MessageToUser", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="message_to_user")]
+    fn handle(&mut self, model: MessageToUser, _ctx: &mut Context<Self>) -> Self::Result {
+        let user_id = get_user_id_from_auth!(model);
+        self.logic.message_to_user(&model)?;
+
+        self.issue_system_async(SendUserMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            sender_user_id: Arc::new(user_id.clone()),
+            message: model.message.clone()
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<GetInbox> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="GetInbox", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="get_inbox")]
+    fn handle(&mut self, model: GetInbox, _ctx: &mut Context<Self>) -> Self::Result {
+        let inbox = self.logic.get_inbox(&model)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), inbox).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<AckInbox> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="AckInbox", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="ack_inbox")]
+    fn handle(&mut self, model: AckInbox, _ctx: &mut Context<Self>) -> Self::Result {
+        let removed = self.logic.ack_inbox(&model)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), InboxAcked { removed }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<SuspendUser> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="SuspendUser", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="suspend_user")]
+    fn handle(&mut self, model: SuspendUser, _ctx: &mut Context<Self>) -> Self::Result {
+        let suspension = self.logic.suspend_user(&model)?;
+
+        // Inform the user before closing the live sessions
+        self.issue_system_async(SendMessage {
+            user_id: Arc::new(model.user_id.clone()),
+            message: SendMessage::build("UserSuspended", UserSuspended { reason: suspension.reason.as_deref(), expire_date: suspension.expire_date })
+        });
+
+        // Sessions on the other servers are closed by the auth manager too
+        self.issue_system_async(TerminateUserSessions {
+            user_id: model.user_id.clone(),
+            socket: model.socket.clone()
+        });
+
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UnsuspendUser> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UnsuspendUser", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="unsuspend_user")]
+    fn handle(&mut self, model: UnsuspendUser, _ctx: &mut Context<Self>) -> Self::Result {
+        self.logic.unsuspend_user(&model)?;
+        model.socket.send(Answer::success(model.request_id, Cow::Borrowed(model.get_request_type())).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<SuspendedUsers> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="SuspendedUsers", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="suspended_users")]
+    fn handle(&mut self, model: SuspendedUsers, _ctx: &mut Context<Self>) -> Self::Result {
+        let users = self.logic.suspended_users(&model)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), SuspendedUsersResponse { users }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UpdateUserMeta> for UserManager<DB> {
+    type Result = anyhow::Result<()>;
+
+    #[tracing::instrument(name="UpdateUserMeta", skip(self, _ctx))]
+    #[yummy_macros::plugin_api(name="update_user_meta")]
+    fn handle(&mut self, model: UpdateUserMeta, _ctx: &mut Context<Self>) -> Self::Result {
+        let meta = self.logic.update_user_meta(&model)?;
+        model.socket.send(GenericAnswer::success(model.request_id, Cow::Borrowed(model.get_request_type()), UserMetaUpdated { key: &model.key, value: &meta }).into());
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UserMetaChanges> for UserManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="UserMetaChanges", skip(self, _ctx))]
+    fn handle(&mut self, model: UserMetaChanges, _ctx: &mut Context<Self>) -> Self::Result {
+        let changes = self.meta_changes.entry(model.user_id).or_default();
+
+        for (key, access_level) in model.metas.into_iter() {
+            let access_level = match changes.get(&key) {
+                Some((previous_access_level, _)) if (previous_access_level.clone() as u8) < (access_level.clone() as u8) => previous_access_level.clone(),
+                _ => access_level
+            };
+
+            changes.insert(key, (access_level, model.changed_by.clone()));
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UserMessageUndelivered> for UserManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="UserMessageUndelivered", skip(self, _ctx))]
+    fn handle(&mut self, model: UserMessageUndelivered, _ctx: &mut Context<Self>) -> Self::Result {
+        let UserMessageUndelivered(message) = model;
+        if let Err(error) = self.logic.store_inbox_message(&message.user_id, &message.sender_user_id, &message.message) {
+            log::error!("Message could not stored into the inbox. Error: {}", error);
+        }
+    }
+}
+
+impl<DB: DatabaseTrait + ?Sized + std::marker::Unpin + 'static> Handler<UserConnected> for UserManager<DB> {
+    type Result = ();
+
+    #[tracing::instrument(name="UserConnected", skip(self, _ctx))]
+    fn handle(&mut self, model: UserConnected, _ctx: &mut Context<Self>) -> Self::Result {
+        if !self.config.deliver_inbox_on_login {
+            return;
+        }
+
+        // Deliver the first page of the messages that received while the user was offline, they are removed after the acknowledge
+        match self.logic.read_inbox_messages(&model.user_id, None, Some(self.config.max_inbox_page_size)) {
+            Ok(inbox) => {
+                for message in inbox.messages.into_iter() {
+                    model.socket.send(SendMessage::build("MessageFromUser", message));
+                }
+
+                if let Some(cursor) = inbox.cursor {
+                    model.socket.send(SendMessage::build("InboxRemaining", InboxRemaining { remaining: inbox.remaining, cursor }));
+                }
+            },
+            Err(error) => log::error!("Inbox could not delivered. Error: {}", error)
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use std::{fmt::Debug, sync::Arc, collections::HashMap};
 use yummy_general::client::ClientTrait;
-use yummy_model::{auth::UserAuth, UserId, UserType, meta::{UserMetaAccess, MetaType, MetaAction}};
+use yummy_model::{auth::UserAuth, UserId, UserInboxId, UserType, meta::{UserMetaAccess, MetaDefinitions, MetaAction, UserMetaType, operation::UserMetaOperation}, user::UserSuspensionModel};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 use actix::prelude::Message;
 use validator::Validate;
@@ -78,7 +79,7 @@ pub struct UpdateUser {
     pub device_id: Option<String>,
     pub custom_id: Option<String>,
     pub user_type: Option<UserType>,
-    pub metas: Option<HashMap<String, MetaType<UserMetaAccess>>>,
+    pub metas: Option<MetaDefinitions<UserMetaAccess>>,
     pub meta_action: MetaAction
 }
 
@@ -103,6 +104,232 @@ impl Default for UpdateUser
     }
 }
 
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="AddFriend")]
+pub struct AddFriend {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ProcessFriendRequest {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub status: bool,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+impl YummyModel for ProcessFriendRequest {
+    fn get_request_type(&self) -> &'static str {
+        match self.status {
+            true => "AcceptFriendRequest",
+            false => "RejectFriendRequest"
+        }
+    }
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="RemoveFriend")]
+pub struct RemoveFriend {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct FriendList {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub pending: bool,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+impl YummyModel for FriendList {
+    fn get_request_type(&self) -> &'static str {
+        match self.pending {
+            true => "FriendRequests",
+            false => "FriendList"
+        }
+    }
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="MessageToUser")]
+pub struct MessageToUser {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub message: Value,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="Inbox")]
+pub struct GetInbox {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="AckInbox")]
+pub struct AckInbox {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub ids: Vec<UserInboxId>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="SuspendUser")]
+pub struct SuspendUser {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub reason: Option<String>,
+    pub duration: Option<u64>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="UnsuspendUser")]
+pub struct UnsuspendUser {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub user_id: UserId,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="SuspendedUsers")]
+pub struct SuspendedUsers {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Message, Validate, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+#[model(request_type="UserMetaOperation")]
+pub struct UpdateUserMeta {
+    pub request_id: Option<usize>,
+    pub auth: Arc<Option<UserAuth>>,
+    pub target_user_id: Option<UserId>,
+    pub key: String,
+    pub operation: UserMetaOperation,
+    pub socket: Arc<dyn ClientTrait + Sync + Send>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FriendInformation {
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub online: bool
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FriendListResponse {
+    pub users: Vec<FriendInformation>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewFriendRequest<'a> {
+    pub user_id: &'a UserId
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FriendRequestProcessed<'a> {
+    pub result: &'a str,
+    pub user_id: &'a UserId
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FriendRemoved<'a> {
+    pub user_id: &'a UserId
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MessageFromUser {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<UserInboxId>,
+
+    pub user_id: UserId,
+    pub message: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert_date: Option<i32>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct InboxResponse {
+    pub messages: Vec<MessageFromUser>,
+    pub remaining: usize,
+    pub cursor: Option<String>
+}
+
+/* Login delivery is limited, rest of the inbox should be fetched with the cursor */
+#[derive(Serialize, Debug, Clone)]
+pub struct InboxRemaining {
+    pub remaining: usize,
+    pub cursor: String
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct InboxAcked {
+    pub removed: usize
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UserSuspended<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expire_date: Option<i32>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SuspendedUsersResponse {
+    pub users: Vec<UserSuspensionModel>
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UserMetaUpdated<'a> {
+    pub key: &'a str,
+    pub value: &'a UserMetaType
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UserMetaChanged<'a> {
+    pub user_id: &'a UserId,
+    pub metas: HashMap<&'a str, &'a UserMetaType>
+}
+
+/* Changed keys with the lowest access level of the old and new values. Sender does not need to be informed again */
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct UserMetaChanges {
+    pub user_id: UserId,
+    pub changed_by: Option<Arc<UserId>>,
+    pub metas: HashMap<String, UserMetaAccess>
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum UpdateUserFieldType {
     #[serde(rename = "name")]
@@ -149,5 +376,38 @@ pub enum UserError {
     UserNotBelongToRoom,
 
     #[error("'{0}' meta access level cannot be bigger than users access level")]
-    MetaAccessLevelCannotBeBiggerThanUsersAccessLevel(String)
+    MetaAccessLevelCannotBeBiggerThanUsersAccessLevel(String),
+
+    #[error("User cannot be added to own friend list")]
+    CannotAddYourselfAsFriend,
+
+    #[error("User already in the friend list")]
+    AlreadyFriend,
+
+    #[error("Friend request already sent")]
+    FriendRequestAlreadySent,
+
+    #[error("Friend request not found")]
+    FriendRequestNotFound,
+
+    #[error("User is not in the friend list")]
+    NotFriend,
+
+    #[error("User cannot send message to own account")]
+    CannotSendMessageToYourself,
+
+    #[error("Message is too big")]
+    MessageTooBig,
+
+    #[error("User does not have enough permission")]
+    UserDoesNotHaveEnoughPermission,
+
+    #[error("User cannot suspend own account")]
+    CannotSuspendYourself,
+
+    #[error("User is not suspended")]
+    UserNotSuspended,
+
+    #[error("Suspension duration is not valid")]
+    SuspensionDurationNotValid
 }
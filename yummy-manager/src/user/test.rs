@@ -9,6 +9,7 @@ use yummy_database::DefaultDatabaseStore;
 use yummy_cache::state_resource::ResourceFactory;
 use yummy_model::UserInformationModel;
 use yummy_model::UserType;
+use yummy_model::{RoomId, RoomUserType, CreateRoomAccessType};
 use std::ops::Deref;
 
 use anyhow::anyhow;
@@ -19,6 +20,7 @@ use yummy_model::config::configure_environment;
 use yummy_model::config::get_configuration;
 use yummy_model::meta::MetaAction;
 use yummy_model::meta::UserMetaAccess;
+use yummy_model::meta::operation::UserMetaOperation;
 use yummy_testing::model::AuthenticatedModel;
 use yummy_model::web::GenericAnswer;
 use yummy_testing::client::DummyClient;
@@ -28,6 +30,7 @@ use std::sync::Arc;
 
 use yummy_database::{create_database, create_connection};
 use yummy_model::meta::MetaType;
+use yummy_model::meta::MetaDefinitions;
 
 use crate::auth::AuthManager;
 use crate::auth::model::*;
@@ -70,12 +73,24 @@ macro_rules! email_auth {
 /* ************************************************** FUNCTIONS *************************************************** */
 /* **************************************************************************************************************** */
 fn create_actor() -> anyhow::Result<(Addr<UserManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, Arc<DummyClient>)> {
+    let (user_manager, auth_manager, config, _, socket) = create_actor_with_states()?;
+    Ok((user_manager, auth_manager, config, socket))
+}
+
+fn create_actor_with_states() -> anyhow::Result<(Addr<UserManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState, Arc<DummyClient>)> {
+    create_actor_with_config(|_| ())
+}
+
+fn create_actor_with_config(configure: impl FnOnce(&mut YummyConfig)) -> anyhow::Result<(Addr<UserManager<yummy_database::SqliteStore>>, Addr<AuthManager<yummy_database::SqliteStore>>, Arc<YummyConfig>, YummyState, Arc<DummyClient>)> {
     let mut db_location = temp_dir();
     db_location.push(format!("{}.db", uuid::Uuid::new_v4()));
     let connection = create_connection(db_location.to_str().unwrap())?;
     
     configure_environment();
-    let config = get_configuration();
+    let mut config = get_configuration().deref().clone();
+    configure(&mut config);
+
+    let config = Arc::new(config);
     #[cfg(feature = "stateless")]
     let conn = r2d2::Pool::new(redis::Client::open(config.redis_url.clone()).unwrap()).unwrap();
 
@@ -87,7 +102,7 @@ fn create_actor() -> anyhow::Result<(Addr<UserManager<yummy_database::SqliteStor
     ConnectionManager::new(config.clone(), states.clone(), executer.clone(), #[cfg(feature = "stateless")] conn.clone()).start();
 
     create_database(&mut connection.clone().get()?)?;
-    Ok((UserManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start(), AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer).start(), config, Arc::new(DummyClient::default())))
+    Ok((UserManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start(), AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer).start(), config, states, Arc::new(DummyClient::default())))
 }
 
 /* **************************************************************************************************************** */
@@ -266,6 +281,48 @@ async fn fail_update_password() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[actix::test]
+async fn update_password() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, socket) = create_actor()?;
+    let user_auth = email_auth!(auth_manager, config.clone(), "erhanbaris@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+
+    auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "erhanbaris@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: false,
+        socket: socket.clone()
+    }).await??;
+    let token: AuthenticatedModel = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+
+    user_manager.send(UpdateUser {
+        auth: user_auth.clone(),
+        password: Some("new password".to_string()),
+        socket: socket.clone(),
+        ..Default::default()
+    }).await??;
+
+    // All issued tokens should be revoked
+    assert!(auth_manager.send(RefreshTokenRequest { request_id: None, auth: Arc::new(None), token: token.refresh_token.unwrap(), socket: socket.clone() }).await?.is_err());
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User token is not valid"));
+
+    // Old password should not be valid anymore
+    let result = auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "erhanbaris@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: false,
+        socket: socket.clone()
+    }).await?;
+    assert_eq!(result.unwrap_err().to_string(), "Email and/or password not valid".to_string());
+
+    email_auth!(auth_manager, config.clone(), "erhanbaris@gmail.com".to_string(), "new password".into(), false, socket.clone());
+    Ok(())
+}
+
 #[actix::test]
 async fn fail_update_email() -> anyhow::Result<()> {
     let (user_manager, auth_manager, config, socket) = create_actor()?;
@@ -394,7 +451,7 @@ async fn update_user_2() -> anyhow::Result<()> {
         target_user_id: Some(user_jwt.id.deref().clone()),
         socket: socket.clone(),
         name: Some("Erhan".to_string()),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("gender".to_string(), MetaType::String("Male".to_string(), UserMetaAccess::Friend)),
             ("location".to_string(), MetaType::String("Copenhagen".to_string(), UserMetaAccess::Friend)),
             ("postcode".to_string(), MetaType::Number(1000.0, UserMetaAccess::Mod)),
@@ -474,7 +531,7 @@ async fn update_user_3() -> anyhow::Result<()> {
         target_user_id: Some(user_jwt.id.deref().clone()),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("1".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
             ("2".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
             ("3".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
@@ -495,7 +552,7 @@ async fn update_user_3() -> anyhow::Result<()> {
         target_user_id: Some(user_jwt.id.deref().clone()),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("1".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
             ("2".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
             ("3".to_string(), MetaType::Bool(true, UserMetaAccess::Admin)),
@@ -548,7 +605,7 @@ async fn meta_manupulation_test_1() -> anyhow::Result<()> {
     user_manager.send(UpdateUser {
         auth: admin.clone(),
         target_user_id: Some(user_id.clone()),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             //("system".to_string(), MetaType::Number(112233.0, UserMetaAccess::System)),
             ("admin".to_string(), MetaType::Number(123456789.0, UserMetaAccess::Admin)),
             ("moderator".to_string(), MetaType::String("Copennhagen".to_string(), UserMetaAccess::Mod)),
@@ -654,7 +711,7 @@ async fn meta_manupulation_test_2() -> anyhow::Result<()> {
         auth: user.clone(),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("1".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
             ("2".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
             ("3".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
@@ -674,7 +731,7 @@ async fn meta_manupulation_test_2() -> anyhow::Result<()> {
         auth: user.clone(),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("1".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
             ("2".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
             ("3".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
@@ -702,7 +759,7 @@ async fn meta_manupulation_test_2() -> anyhow::Result<()> {
         auth: user.clone(),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("6".to_string(), MetaType::Bool(true, UserMetaAccess::Me)),
         ])),
         meta_action: MetaAction::OnlyAddOrUpdate,
@@ -726,7 +783,7 @@ async fn meta_manupulation_test_2() -> anyhow::Result<()> {
         auth: user.clone(),
         name: Some("Erhan".to_string()),
         socket: socket.clone(),
-        metas: Some(HashMap::from([
+        metas: Some(MetaDefinitions::from([
             ("6".to_string(), MetaType::Null),
         ])),
         ..Default::default()
@@ -764,3 +821,514 @@ async fn meta_manupulation_test_2() -> anyhow::Result<()> {
 
     Ok(())  
 }
+
+#[actix::test]
+async fn friend_test() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, socket) = create_actor()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+
+    let user_1_id = user_1.deref().as_ref().unwrap().user.clone();
+    let user_2_id = user_2.deref().as_ref().unwrap().user.clone();
+
+    user_manager.send(UpdateUser {
+        auth: user_2.clone(),
+        socket: socket.clone(),
+        metas: Some(MetaDefinitions::from([
+            ("location".to_string(), MetaType::String("Copenhagen".to_string(), UserMetaAccess::Friend)),
+            ("score".to_string(), MetaType::Number(15.3, UserMetaAccess::Anonymous)),
+        ])),
+        ..Default::default()
+    }).await??;
+
+    // Friend metas are not visible yet
+    user_manager.send(GetUserInformation::user(None, user_2_id.clone(), user_1.clone(), socket.clone())).await??;
+    let user: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert_eq!(user.result.metas.map(|metas| metas.len()).unwrap_or_default(), 1);
+
+    // User cannot add own account
+    assert!(user_manager.send(AddFriend { request_id: None, auth: user_1.clone(), user_id: user_1_id.clone(), socket: socket.clone() }).await?.is_err());
+
+    user_manager.send(AddFriend { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await??;
+
+    // Request already sent
+    assert!(user_manager.send(AddFriend { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await?.is_err());
+
+    user_manager.send(FriendList { request_id: None, auth: user_2.clone(), pending: true, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["users"].as_array().unwrap().len(), 1);
+    assert_eq!(message.result["users"][0]["user_id"].as_str().unwrap(), &user_1_id.to_string());
+
+    // Only the target user can accept the request
+    assert!(user_manager.send(ProcessFriendRequest { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), status: true, socket: socket.clone() }).await?.is_err());
+    user_manager.send(ProcessFriendRequest { request_id: None, auth: user_2.clone(), user_id: user_1_id.clone(), status: true, socket: socket.clone() }).await??;
+
+    user_manager.send(FriendList { request_id: None, auth: user_1.clone(), pending: false, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["users"].as_array().unwrap().len(), 1);
+    assert_eq!(message.result["users"][0]["user_id"].as_str().unwrap(), &user_2_id.to_string());
+
+    assert!(user_manager.send(AddFriend { request_id: None, auth: user_2.clone(), user_id: user_1_id.clone(), socket: socket.clone() }).await?.is_err());
+
+    // Friend metas are visible now
+    user_manager.send(GetUserInformation::user(None, user_2_id.clone(), user_1.clone(), socket.clone())).await??;
+    let user: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert_eq!(user.result.metas.map(|metas| metas.len()).unwrap_or_default(), 2);
+
+    user_manager.send(RemoveFriend { request_id: None, auth: user_2.clone(), user_id: user_1_id.clone(), socket: socket.clone() }).await??;
+    assert!(user_manager.send(RemoveFriend { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await?.is_err());
+
+    user_manager.send(GetUserInformation::user(None, user_2_id.clone(), user_1.clone(), socket.clone())).await??;
+    let user: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert_eq!(user.result.metas.map(|metas| metas.len()).unwrap_or_default(), 1);
+
+    // Reject request
+    user_manager.send(AddFriend { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await??;
+    user_manager.send(ProcessFriendRequest { request_id: None, auth: user_2.clone(), user_id: user_1_id.clone(), status: false, socket: socket.clone() }).await??;
+
+    user_manager.send(FriendList { request_id: None, auth: user_2.clone(), pending: true, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert!(message.result["users"].as_array().unwrap().is_empty());
+
+    user_manager.send(FriendList { request_id: None, auth: user_1.clone(), pending: false, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert!(message.result["users"].as_array().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn inbox_login_delivery_limit_test() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, _, socket) = create_actor_with_config(|config| config.max_inbox_page_size = 2)?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+    let user_2_id = user_2.deref().as_ref().unwrap().user.clone();
+
+    auth_manager.send(LogoutRequest { request_id: None, auth: user_2.clone(), socket: socket.clone() }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    for message in ["WORLD", "YUMMY", "GAME"] {
+        user_manager.send(MessageToUser { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), message: serde_json::Value::String(message.to_string()), socket: socket.clone() }).await??;
+    }
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    // Only the first page is delivered after login
+    let user_2_socket = Arc::new(DummyClient::default());
+    auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "user2@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: false,
+        socket: user_2_socket.clone()
+    }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    let messages = user_2_socket.clone().messages.lock().unwrap().iter().map(|message| serde_json::from_str::<serde_json::Value>(message)).collect::<Result<Vec<_>, _>>()?;
+    let mut delivered = messages.iter().filter(|message| message["type"] == "MessageFromUser").map(|message| message["message"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+    assert_eq!(delivered.len(), 2);
+
+    let remaining = messages.iter().find(|message| message["type"] == "InboxRemaining").unwrap();
+    assert_eq!(remaining["remaining"].as_u64().unwrap(), 1);
+
+    // Rest of the inbox fetched with the cursor
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: None, cursor: Some(remaining["cursor"].as_str().unwrap().to_string()), socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(message.result["remaining"].as_u64().unwrap(), 0);
+
+    delivered.push(message.result["messages"][0]["message"].as_str().unwrap().to_string());
+    delivered.sort();
+    assert_eq!(delivered, vec!["GAME".to_string(), "WORLD".to_string(), "YUMMY".to_string()]);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn message_to_user_test() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, socket) = create_actor()?;
+    let user_1_socket = Arc::new(DummyClient::default());
+    let user_2_socket = Arc::new(DummyClient::default());
+
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket.clone());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket.clone());
+
+    let user_1_id = user_1.deref().as_ref().unwrap().user.clone();
+    let user_2_id = user_2.deref().as_ref().unwrap().user.clone();
+
+    // User cannot send message to own account
+    assert!(user_manager.send(MessageToUser { request_id: None, auth: user_1.clone(), user_id: user_1_id.clone(), message: serde_json::Value::String("HELLO".to_string()), socket: user_1_socket.clone() }).await?.is_err());
+
+    // Online user receives the message immediately
+    user_manager.send(MessageToUser { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), message: serde_json::Value::String("HELLO".to_string()), socket: user_1_socket.clone() }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"].as_str().unwrap(), "MessageFromUser");
+    assert_eq!(message["user_id"].as_str().unwrap(), &user_1_id.to_string());
+    assert_eq!(message["message"].as_str().unwrap(), "HELLO");
+
+    // Message size is limited
+    assert!(user_manager.send(MessageToUser { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), message: serde_json::Value::String("A".repeat(config.max_user_message_size)), socket: user_1_socket.clone() }).await?.is_err());
+
+    // Messages to offline user go into the inbox
+    auth_manager.send(LogoutRequest { request_id: None, auth: user_2.clone(), socket: user_2_socket.clone() }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    for message in ["WORLD", "YUMMY", "GAME"] {
+        user_manager.send(MessageToUser { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), message: serde_json::Value::String(message.to_string()), socket: user_1_socket.clone() }).await??;
+    }
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: Some(1), cursor: None, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(message.result["messages"][0]["user_id"].as_str().unwrap(), &user_1_id.to_string());
+    assert_eq!(message.result["remaining"].as_u64().unwrap(), 2);
+    let first_id = message.result["messages"][0]["id"].as_str().unwrap().to_string();
+    let cursor = message.result["cursor"].as_str().unwrap().to_string();
+
+    // Next page starts after the cursor
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: Some(1), cursor: Some(cursor), socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["messages"].as_array().unwrap().len(), 1);
+    assert_ne!(message.result["messages"][0]["id"].as_str().unwrap(), &first_id);
+    assert_eq!(message.result["remaining"].as_u64().unwrap(), 1);
+    let second_id = message.result["messages"][0]["id"].as_str().unwrap().to_string();
+
+    // Reading does not remove the messages
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: None, cursor: None, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["messages"].as_array().unwrap().len(), 3);
+    assert_eq!(message.result["remaining"].as_u64().unwrap(), 0);
+    assert!(message.result["cursor"].is_null());
+
+    // Only the owner can remove the messages
+    user_manager.send(AckInbox { request_id: None, auth: user_1.clone(), ids: vec![first_id.clone().into(), second_id.clone().into()], socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["removed"].as_u64().unwrap(), 0);
+
+    user_manager.send(AckInbox { request_id: None, auth: user_2.clone(), ids: vec![first_id.into(), second_id.into()], socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["removed"].as_u64().unwrap(), 2);
+
+    // Remaining message delivered after login
+    let user_2_socket = Arc::new(DummyClient::default());
+    auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "user2@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: false,
+        socket: user_2_socket.clone()
+    }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"].as_str().unwrap(), "MessageFromUser");
+    assert_eq!(message["user_id"].as_str().unwrap(), &user_1_id.to_string());
+    assert!(message["insert_date"].is_number());
+    let last_id = message["id"].as_str().unwrap().to_string();
+
+    // Delivered message stays in the inbox until it is acknowledged
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: None, cursor: None, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["messages"].as_array().unwrap().len(), 1);
+
+    user_manager.send(AckInbox { request_id: None, auth: user_2.clone(), ids: vec![last_id.into()], socket: socket.clone() }).await??;
+    user_manager.send(GetInbox { request_id: None, auth: user_2.clone(), limit: None, cursor: None, socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert!(message.result["messages"].as_array().unwrap().is_empty());
+    assert_eq!(message.result["remaining"].as_u64().unwrap(), 0);
+
+    Ok(())
+}
+
+#[actix::test]
+async fn suspend_user_test() -> anyhow::Result<()> {
+    #[allow(unused_mut)]
+    let (user_manager, auth_manager, config, mut states, socket) = create_actor_with_states()?;
+    let admin = email_auth!(auth_manager, config.clone(), "admin@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+
+    user_manager.send(UpdateUser {
+        auth: admin.clone(),
+        user_type : Some(UserType::Admin),
+        socket: socket.clone(),
+        ..Default::default()
+    }).await??;
+
+    let user_socket = Arc::new(DummyClient::default());
+    auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "user2@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: true,
+        socket: user_socket.clone()
+    }).await??;
+
+    let token: AuthenticatedModel = user_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let refresh_token = token.refresh_token.unwrap();
+    let token = token.token;
+    let user_2_jwt = validate_auth(config.clone(), token.clone()).unwrap().user;
+    let user_2_id = user_2_jwt.id.deref().clone();
+    let user_2_session = user_2_jwt.session.clone();
+
+    // Only admins can suspend users
+    assert!(user_manager.send(SuspendUser { request_id: None, auth: user_1.clone(), user_id: user_2_id.clone(), reason: None, duration: None, socket: socket.clone() }).await?.is_err());
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User does not have enough permission"));
+
+    assert!(user_manager.send(SuspendedUsers { request_id: None, auth: user_1.clone(), socket: socket.clone() }).await?.is_err());
+    socket.clone().messages.lock().unwrap().pop_back().unwrap();
+
+    // Admin cannot suspend own account
+    assert!(user_manager.send(SuspendUser { request_id: None, auth: admin.clone(), user_id: admin.deref().as_ref().unwrap().user.clone(), reason: None, duration: None, socket: socket.clone() }).await?.is_err());
+    socket.clone().messages.lock().unwrap().pop_back().unwrap();
+
+    // Live session closed after suspension
+    user_manager.send(SuspendUser { request_id: None, auth: admin.clone(), user_id: user_2_id.clone(), reason: Some("cheating".to_string()), duration: None, socket: socket.clone() }).await??;
+    actix::clock::sleep(std::time::Duration::new(1, 0)).await;
+    assert!(user_socket.disconnected.load(std::sync::atomic::Ordering::Relaxed));
+    assert!(!states.is_session_online(&user_2_session));
+
+    let message: serde_json::Value = serde_json::from_str(&user_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"].as_str().unwrap(), "UserSuspended");
+    assert_eq!(message["reason"].as_str().unwrap(), "cheating");
+    assert!(message["expire_date"].is_null());
+
+    user_manager.send(GetUserInformation::user(None, user_2_id.clone(), admin.clone(), socket.clone())).await??;
+    let user: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    assert!(!user.result.online);
+
+    user_manager.send(SuspendedUsers { request_id: None, auth: admin.clone(), socket: socket.clone() }).await??;
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["users"].as_array().unwrap().len(), 1);
+    assert_eq!(message.result["users"][0]["user_id"].as_str().unwrap(), &user_2_id.to_string());
+
+    // Suspended user cannot login or use the old token
+    assert!(auth_manager.send(EmailAuthRequest {
+        request_id: None,
+        auth: Arc::new(None),
+        email: "user2@gmail.com".to_string(),
+        password: "erhan".into(),
+        if_not_exist_create: false,
+        socket: user_socket.clone()
+    }).await?.is_err());
+    let message = user_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User is suspended"));
+
+    assert!(auth_manager.send(RefreshTokenRequest { request_id: None, auth: Arc::new(None), token: refresh_token.clone(), socket: user_socket.clone() }).await?.is_err());
+    let message = user_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User is suspended"));
+
+    assert!(auth_manager.send(RestoreTokenRequest { request_id: None, auth: Arc::new(None), token: token.clone(), last_sequence: None, socket: user_socket.clone() }).await?.is_err());
+    let message = user_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User is suspended"));
+
+    // Login allowed again after unsuspend
+    user_manager.send(UnsuspendUser { request_id: None, auth: admin.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await??;
+    assert!(user_manager.send(UnsuspendUser { request_id: None, auth: admin.clone(), user_id: user_2_id.clone(), socket: socket.clone() }).await?.is_err());
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User is not suspended"));
+
+    email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), false, user_socket.clone());
+
+    // Custom id logins are checked too
+    auth_manager.send(CustomIdAuthRequest::new(None, Arc::new(None), "custom_user".to_string(), user_socket.clone())).await??;
+    let token: AuthenticatedModel = user_socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let user_3_id = validate_auth(config.clone(), token.token).unwrap().user.id.deref().clone();
+
+    assert!(user_manager.send(SuspendUser { request_id: None, auth: admin.clone(), user_id: user_3_id.clone(), reason: None, duration: Some(u64::MAX), socket: socket.clone() }).await?.is_err());
+    user_manager.send(SuspendUser { request_id: None, auth: admin.clone(), user_id: user_3_id.clone(), reason: None, duration: Some(60), socket: socket.clone() }).await??;
+    assert!(auth_manager.send(CustomIdAuthRequest::new(None, Arc::new(None), "custom_user".to_string(), user_socket.clone())).await?.is_err());
+    let message = user_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("User is suspended"));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn user_meta_operation() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, socket) = create_actor()?;
+
+    let user = email_auth!(auth_manager, config.clone(), "user@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+    let other_user = email_auth!(auth_manager, config, "other_user@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+
+    let user_id = match user.as_ref() {
+        Some(user) => user.user.clone(),
+        None => return Err(anyhow!("User not found"))
+    };
+
+    /* Missing number meta starts from zero */
+    for _ in 0..3 {
+        user_manager.send(UpdateUserMeta {
+            request_id: None,
+            auth: user.clone(),
+            target_user_id: None,
+            key: "gold".to_string(),
+            operation: UserMetaOperation::Increment { value: 10.0, min: None, max: Some(25.0) },
+            socket: socket.clone()
+        }).await??;
+    }
+
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(&message.response_type[..], "UserMetaOperation");
+    assert_eq!(message.result["key"].as_str(), Some("gold"));
+    assert_eq!(message.result["value"].as_f64(), Some(25.0));
+
+    /* List operations */
+    for badge in ["first-win", "first-loss", "first-win"] {
+        user_manager.send(UpdateUserMeta {
+            request_id: None,
+            auth: user.clone(),
+            target_user_id: None,
+            key: "badges".to_string(),
+            operation: UserMetaOperation::Append { value: MetaType::String(badge.to_string(), UserMetaAccess::Anonymous) },
+            socket: socket.clone()
+        }).await??;
+    }
+
+    user_manager.send(UpdateUserMeta {
+        request_id: None,
+        auth: user.clone(),
+        target_user_id: None,
+        key: "badges".to_string(),
+        operation: UserMetaOperation::Remove { value: MetaType::String("first-win".to_string(), UserMetaAccess::Anonymous) },
+        socket: socket.clone()
+    }).await??;
+
+    let message: GenericAnswer<serde_json::Value> = serde_json::from_str(&socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message.result["value"], serde_json::json!(["first-loss"]));
+
+    /* Number operation on list meta is not allowed */
+    assert!(user_manager.send(UpdateUserMeta {
+        request_id: None,
+        auth: user.clone(),
+        target_user_id: None,
+        key: "badges".to_string(),
+        operation: UserMetaOperation::Decrement { value: 1.0, min: None, max: None },
+        socket: socket.clone()
+    }).await?.is_err());
+
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Meta type is not number"));
+
+    /* Compare and set */
+    user_manager.send(UpdateUserMeta {
+        request_id: None,
+        auth: user.clone(),
+        target_user_id: None,
+        key: "status".to_string(),
+        operation: UserMetaOperation::CompareAndSet { expected: MetaType::Null, value: MetaType::String("idle".to_string(), UserMetaAccess::Me) },
+        socket: socket.clone()
+    }).await??;
+
+    assert!(user_manager.send(UpdateUserMeta {
+        request_id: None,
+        auth: user.clone(),
+        target_user_id: None,
+        key: "status".to_string(),
+        operation: UserMetaOperation::CompareAndSet { expected: MetaType::String("playing".to_string(), UserMetaAccess::Anonymous), value: MetaType::String("idle".to_string(), UserMetaAccess::Me) },
+        socket: socket.clone()
+    }).await?.is_err());
+
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Meta value is not matched with expected value"));
+
+    /* Other users can not change private metas */
+    assert!(user_manager.send(UpdateUserMeta {
+        request_id: None,
+        auth: other_user.clone(),
+        target_user_id: Some(user_id.clone()),
+        key: "status".to_string(),
+        operation: UserMetaOperation::CompareAndSet { expected: MetaType::String("idle".to_string(), UserMetaAccess::Anonymous), value: MetaType::String("playing".to_string(), UserMetaAccess::User) },
+        socket: socket.clone()
+    }).await?.is_err());
+
+    let message = socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(message.contains("Meta access level is not enough"));
+
+    /* Check for my informations */
+    user_manager.send(GetUserInformation::me(None, user.clone(), socket.clone())).await??;
+    
+    let information: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let information_meta = information.result.metas.unwrap();
+    assert_eq!(information_meta.len(), 3);
+    assert_eq!(information_meta.iter().find(|item| &item.name == "gold").cloned().map(|item| item.meta), Some(MetaType::Number(25.0, UserMetaAccess::Anonymous)));
+    assert_eq!(information_meta.iter().find(|item| &item.name == "status").cloned().map(|item| item.meta), Some(MetaType::String("idle".to_string(), UserMetaAccess::Anonymous)));
+
+    Ok(())
+}
+
+#[actix::test]
+async fn user_meta_changed_test() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, mut states, user_1_socket) = create_actor_with_states()?;
+    let user_1 = email_auth!(auth_manager, config.clone(), "user1@gmail.com".to_string(), "erhan".into(), true, user_1_socket.clone());
+
+    let user_2_socket = Arc::new(DummyClient::default());
+    let user_2 = email_auth!(auth_manager, config.clone(), "user2@gmail.com".to_string(), "erhan".into(), true, user_2_socket.clone());
+
+    let user_3_socket = Arc::new(DummyClient::default());
+    email_auth!(auth_manager, config.clone(), "user3@gmail.com".to_string(), "erhan".into(), true, user_3_socket.clone());
+
+    /* User 3 does not share a room with user 1 */
+    let room_id = RoomId::new();
+    states.create_room(&room_id, 0, None, None, CreateRoomAccessType::Public, 4, 0, Vec::new(), None, false, None, false);
+
+    for user in [&user_1, &user_2] {
+        let user = user.deref().as_ref().unwrap();
+        states.join_to_room(&room_id, &user.user, &user.session, RoomUserType::User)?;
+    }
+
+    user_manager.send(UpdateUser {
+        auth: user_1.clone(),
+        socket: user_1_socket.clone(),
+        metas: Some(MetaDefinitions::from([
+            ("level".to_string(), MetaType::Number(5.0, UserMetaAccess::User)),
+            ("gold".to_string(), MetaType::Number(100.0, UserMetaAccess::Me))
+        ])),
+        ..Default::default()
+    }).await??;
+
+    actix::clock::sleep(std::time::Duration::from_millis(500)).await;
+
+    let message: serde_json::Value = serde_json::from_str(&user_2_socket.clone().messages.lock().unwrap().pop_back().unwrap())?;
+    assert_eq!(message["type"], "UserMetaChanged");
+    assert_eq!(message["user_id"], serde_json::json!(user_1.deref().as_ref().unwrap().user));
+    assert_eq!(message["metas"], serde_json::json!({ "level": 5.0 }));
+
+    let message = user_1_socket.clone().messages.lock().unwrap().pop_back().unwrap();
+    assert!(!message.contains("UserMetaChanged"));
+
+    assert!(user_3_socket.clone().messages.lock().unwrap().is_empty());
+
+    Ok(())
+}
+
+#[actix::test]
+async fn expiring_meta_test() -> anyhow::Result<()> {
+    let (user_manager, auth_manager, config, socket) = create_actor()?;
+    let user = email_auth!(auth_manager, config, "user@gmail.com".to_string(), "erhan".into(), true, socket.clone());
+
+    user_manager.send(UpdateUser {
+        auth: user.clone(),
+        metas: Some(MetaDefinitions::from([
+            ("boost".to_string(), serde_json::from_str(r#"{"access": 3, "value": 2, "ttl": 300}"#)?),
+            ("cooldown".to_string(), MetaType::Expiring(Box::new(MetaType::Bool(true, UserMetaAccess::Me)), 1)),
+        ])),
+        socket: socket.clone(),
+        ..Default::default()
+    }).await??;
+
+    /* Expired meta is hidden */
+    user_manager.send(GetUserInformation::me(None, user.clone(), socket.clone())).await??;
+
+    let information: GenericAnswer<UserInformationModel> = socket.clone().messages.lock().unwrap().pop_back().unwrap().into();
+    let information_meta = information.result.metas.unwrap_or_default();
+    assert_eq!(information_meta.len(), 1);
+    assert_eq!(information_meta.iter().find(|item| &item.name == "boost").cloned().map(|item| item.meta), Some(MetaType::Number(2.0, UserMetaAccess::Anonymous)));
+
+    Ok(())
+}
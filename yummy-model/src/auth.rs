@@ -9,7 +9,7 @@ use actix_web::web::{Data, self};
 use actix_web::{HttpRequest, FromRequest};
 use actix_web::Error;
 use actix_web::dev::Payload;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Header, Validation};
 use serde::*;
 
 use crate::*;
@@ -24,29 +24,64 @@ pub struct UserJwt {
     pub user_type: UserType
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TokenType {
+    #[default]
+    Access,
+    Refresh
+}
+
+/* Result of the refresh token rotation. Reused means that an already rotated refresh token was presented again */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshTokenRotation {
+    Rotated,
+    Reused,
+    NotFound
+}
+
 #[derive(Debug, Serialize)]
 pub struct ClaimsSerialize<'a> {
     pub exp: usize,
+    pub iat: usize,
+    pub jti: &'a TokenId,
+    pub family: &'a TokenFamilyId,
+    pub token_type: TokenType,
     pub user: &'a UserJwt,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ClaimsDeserialize {
     pub exp: usize,
+    pub iat: usize,
+    pub jti: TokenId,
+    pub family: TokenFamilyId,
+    pub token_type: TokenType,
     pub user: UserJwt,
 }
 
-pub fn generate_auth<T: Borrow<UserJwt>>(config: Arc<YummyConfig>, token: T) -> Option<String> {
+pub fn generate_auth<T: Borrow<UserJwt>>(config: Arc<YummyConfig>, token: T, family: &TokenFamilyId, token_type: TokenType) -> Option<(String, TokenId)> {
     let token = token.borrow();
+    let token_id = TokenId::new();
     let iat = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0));
-    let exp = iat + config.token_lifetime;
+    let exp = iat + match token_type {
+        TokenType::Access => config.token_lifetime,
+        TokenType::Refresh => config.refresh_token_lifetime
+    };
+
     let claims = ClaimsSerialize {
         exp: exp.as_secs() as usize,
+        iat: iat.as_secs() as usize,
+        jti: &token_id,
+        family,
+        token_type,
         user: token,
     };
 
-    match encode(&Header::default(), &claims, &EncodingKey::from_secret(config.salt_key.as_ref())) {
-        Ok(token) => Some(token),
+    let mut header = Header::new(config.jwt_keys.get_algorithm());
+    header.kid = config.jwt_keys.get_key_id().map(|key_id| key_id.to_string());
+
+    match encode(&header, &claims, config.jwt_keys.get_encoding_key()) {
+        Ok(token) => Some((token, token_id)),
         Err(_) => None,
     }
 }
@@ -54,8 +89,14 @@ pub fn generate_auth<T: Borrow<UserJwt>>(config: Arc<YummyConfig>, token: T) ->
 pub fn validate_auth<T: Borrow<str>>(config: Arc<YummyConfig>, token: T) -> Option<ClaimsDeserialize> {
     let token = token.borrow();
     let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as usize).unwrap_or_default();
-    let validation = Validation::default();
-    match decode::<ClaimsDeserialize>(token, &DecodingKey::from_secret(config.salt_key.as_ref()), &validation) {
+    let header = match decode_header(token) {
+        Ok(header) => header,
+        Err(_) => return None
+    };
+
+    let (algorithm, key) = config.jwt_keys.get_decoding_key(header.kid.as_deref())?;
+    let validation = Validation::new(*algorithm);
+    match decode::<ClaimsDeserialize>(token, key, &validation) {
         Ok(c) => match c.claims.exp > now {
             true =>  Some(c.claims),
             false => None
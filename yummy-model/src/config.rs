@@ -7,8 +7,13 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::env;
 
+use jsonwebtoken::Algorithm;
 use rand::{distributions::Alphanumeric, Rng};
 
+use crate::external::{load_external_issuers, ExternalAuthVerifier};
+use crate::meta::schema::{load_meta_schema, MetaSchema};
+use crate::jwt::JwtKeys;
+
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
 /* **************************************************************************************************************** */
@@ -18,18 +23,49 @@ pub const DEFAULT_RUST_LOG: &str = "debug,backend,actix_web=debug";
 
 pub const DEFAULT_HEARTBEAT_TIMEOUT: u64 = 20; // in seconds
 pub const DEFAULT_CONNECTION_RESTORE_WAIT_TIMEOUT: u64 = 10; // in seconds
+pub const DEFAULT_OUTBOUND_BUFFER_SIZE: usize = 100;
 pub const DEFAULT_HEARTBEAT_INTERVAL: u64 = 10; // in seconds
-pub const DEFAULT_TOKEN_LIFETIME: u64 = 24 * 60 * 60; // in seconds
+pub const DEFAULT_TOKEN_LIFETIME: u64 = 15 * 60; // in seconds
+pub const DEFAULT_REFRESH_TOKEN_LIFETIME: u64 = 30 * 24 * 60 * 60; // in seconds
 pub const DEFAULT_MAX_USER_META: usize = 10;
 pub const DEFAULT_MAX_ROOM_META: usize = 10;
+pub const DEFAULT_META_CHANGE_TICK_INTERVAL: u64 = 100; // in milliseconds
+pub const DEFAULT_META_SWEEP_INTERVAL: u64 = 60; // in seconds
 pub const DEFAULT_API_KEY_NAME: &str = "x-yummy-api";
 pub const DEFAULT_SALT_KEY: &str = "YUMMY-SALT";
+pub const DEFAULT_JWT_ALGORITHM: Algorithm = Algorithm::HS256;
+pub const DEFAULT_JWT_KEY_ID: &str = "yummy";
+pub const DEFAULT_EXTERNAL_JWKS_REFRESH_INTERVAL: u64 = 60 * 60; // in seconds
+pub const DEFAULT_ALLOW_UNVERIFIED_LOGIN: bool = true;
+pub const DEFAULT_EMAIL_VERIFICATION_LIFETIME: u64 = 24 * 60 * 60; // in seconds
+pub const DEFAULT_PASSWORD_RESET_LIFETIME: u64 = 60 * 60; // in seconds
+pub const DEFAULT_USER_TOKEN_COOLDOWN: u64 = 60; // in seconds
+pub const DEFAULT_MAIL_FROM: &str = "Yummy <noreply@yummy.local>";
 pub const DEFAULT_DATABASE_PATH: &str = "yummy.db";
 pub const DEFAULT_PYTHON_FILES_PATH: &str = "C:\\codes\\yummy\\examples\\tic-tac-toe";
 pub const DEFAULT_DEFAULT_INTEGRATION_KEY: &str = "YummyYummy";
 pub const DEFAULT_ROOM_PASSWORD_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
 pub const DEFAULT_ROOM_PASSWORD_LENGTH: usize = 4;
 pub const DEFAULT_CACHE_DURATION: u64 = 5 * 60; // in seconds
+pub const DEFAULT_MAX_INBOX_PAGE_SIZE: usize = 50;
+pub const DEFAULT_MAX_ROOM_LIST_PAGE_SIZE: usize = 50;
+pub const DEFAULT_DELIVER_INBOX_ON_LOGIN: bool = true;
+pub const DEFAULT_MAX_USER_MESSAGE_SIZE: usize = 4 * 1024; // in bytes, 0 means unlimited
+pub const DEFAULT_ROOM_MESSAGE_HISTORY_SIZE: usize = 0;
+pub const DEFAULT_ROOM_MESSAGE_HISTORY_PERSIST: bool = false;
+pub const DEFAULT_ROOM_MESSAGE_HISTORY_LIFETIME: u64 = 7 * 24 * 60 * 60; // in seconds
+pub const DEFAULT_ROOM_MESSAGE_SWEEP_INTERVAL: u64 = 60 * 60; // in seconds
+pub const DEFAULT_MAX_ROOM_MESSAGE_HISTORY_PAGE_SIZE: usize = 50;
+pub const DEFAULT_ROOM_INVITE_LIFETIME: u64 = 5 * 60; // in seconds
+pub const DEFAULT_ROOM_BAN_SWEEP_INTERVAL: u64 = 60 * 60; // in seconds
+pub const DEFAULT_MATCHMAKING_TICK_INTERVAL: u64 = 1000; // in milliseconds
+pub const DEFAULT_MATCHMAKING_ROOM_SIZE: usize = 2;
+pub const DEFAULT_MATCHMAKING_INITIAL_TOLERANCE: f64 = 10.0;
+pub const DEFAULT_MATCHMAKING_TOLERANCE_STEP: f64 = 5.0; // per second
+pub const DEFAULT_MATCHMAKING_MAX_TOLERANCE: f64 = 100.0;
+pub const DEFAULT_ROOM_OWNER_SUCCESSION: RoomOwnerSuccession = RoomOwnerSuccession::Oldest;
+pub const DEFAULT_MAX_USER_SESSIONS: usize = 0; // 0 means unlimited
+pub const DEFAULT_SESSION_LIMIT_POLICY: SessionLimitPolicy = SessionLimitPolicy::KickOldest;
 
 #[cfg(feature = "stateless")]
 pub const DEFAULT_REDIS_URL: &str = "redis://127.0.0.1/";
@@ -53,19 +89,64 @@ pub struct YummyConfig {
     pub tls_key_path: Option<String>,
     
     pub token_lifetime: Duration,
+    pub refresh_token_lifetime: Duration,
     pub heartbeat_interval: Duration,
     pub heartbeat_timeout: Duration,
     pub cache_duration: Duration,
     pub connection_restore_wait_timeout: Duration,
+    pub outbound_buffer_size: usize,
 
     pub max_user_meta: usize,
     pub max_room_meta: usize,
+    pub meta_schema_path: Option<String>,
+    pub meta_schema: Arc<MetaSchema>,
+    pub meta_change_tick_interval: Duration,
+    pub meta_sweep_interval: Duration,
     pub room_password_charset: Vec<u8>,
     pub room_password_length: usize,
+    pub max_inbox_page_size: usize,
+    pub max_room_list_page_size: usize,
+    pub deliver_inbox_on_login: bool,
+    pub max_user_message_size: usize,
+
+    pub room_message_history_size: usize,
+    pub room_message_history_persist: bool,
+    pub room_message_history_lifetime: Duration,
+    pub room_message_sweep_interval: Duration,
+    pub max_room_message_history_page_size: usize,
+    pub room_invite_lifetime: Duration,
+    pub room_ban_sweep_interval: Duration,
+
+    pub matchmaking_tick_interval: Duration,
+    pub matchmaking_room_size: usize,
+    pub matchmaking_initial_tolerance: f64,
+    pub matchmaking_tolerance_step: f64,
+    pub matchmaking_max_tolerance: f64,
+
+    pub room_owner_succession: RoomOwnerSuccession,
+
+    pub max_user_sessions: usize,
+    pub session_limit_policy: SessionLimitPolicy,
+    pub trusted_proxies: Vec<String>,
 
     pub integration_key: String,
     pub api_key_name: String,
     pub salt_key: String,
+    pub jwt_algorithm: Algorithm,
+    pub jwt_key_id: String,
+    pub jwt_private_key_path: Option<String>,
+    pub jwt_public_keys_path: Option<String>,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub external_issuers_path: Option<String>,
+    pub external_jwks_refresh_interval: Duration,
+    pub external_auth: ExternalAuthVerifier,
+    pub allow_unverified_login: bool,
+    pub email_verification_lifetime: Duration,
+    pub password_reset_lifetime: Duration,
+    pub user_token_cooldown: Duration,
+    pub smtp_url: Option<String>,
+    pub mail_from: String,
+    pub mail_file_path: Option<String>,
     pub database_path: String,
     pub python_files_path: String,
 
@@ -78,6 +159,22 @@ pub struct YummyConfig {
 
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
+/* **************************************************************************************************************** */
+/* New room owner selection when the owner leaves the room. Python plugins can override the selection */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomOwnerSuccession {
+    Oldest,
+    HighestType
+}
+
+/* Action for the new login when the user reached the concurrent session limit */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionLimitPolicy {
+    KickOldest,
+    Reject
+}
+
+/* **************************************************************************************************************** */
 /* ************************************************** FUNCTIONS *************************************************** */
 /* **************************************************************************************************************** */
 pub fn get_env_var<R: Clone + FromStr>(key: &str, default: R) -> R {
@@ -86,6 +183,19 @@ pub fn get_env_var<R: Clone + FromStr>(key: &str, default: R) -> R {
         .unwrap_or(default)
 }
 
+/* Generated room passwords pick characters from the charset, empty charset or zero length could not produce a password */
+pub fn validate_room_password(charset: &[u8], length: usize) -> anyhow::Result<()> {
+    if charset.is_empty() {
+        return Err(anyhow::anyhow!("ROOM_PASSWORD_CHARSET should not be empty"));
+    }
+
+    if length == 0 {
+        return Err(anyhow::anyhow!("ROOM_PASSWORD_LENGTH should be bigger than 0"));
+    }
+
+    Ok(())
+}
+
 pub fn configure_environment() {
     let profile = get_profile();
     dotenv::from_filename(format!("{}.env", profile)).ok();
@@ -113,6 +223,33 @@ pub fn get_raw_configuration() -> YummyConfig {
         .map(char::from)
         .collect::<String>());
 
+    let salt_key = get_env_var("SALT_KEY", DEFAULT_SALT_KEY.to_string());
+    let jwt_algorithm = get_env_var("JWT_ALGORITHM", DEFAULT_JWT_ALGORITHM);
+    let jwt_key_id = get_env_var("JWT_KEY_ID", DEFAULT_JWT_KEY_ID.to_string());
+    let jwt_private_key_path = env::var("JWT_PRIVATE_KEY_PATH").ok();
+    let jwt_public_keys_path = env::var("JWT_PUBLIC_KEYS_PATH").ok();
+    let jwt_keys = JwtKeys::new(jwt_algorithm, &jwt_key_id, &salt_key, jwt_private_key_path.as_deref(), jwt_public_keys_path.as_deref())
+        .unwrap_or_else(|error| panic!("JWT keys could not be loaded. {}", error));
+
+    let external_issuers_path = env::var("EXTERNAL_ISSUERS_PATH").ok();
+    let external_issuers = match external_issuers_path.as_deref() {
+        Some(path) => load_external_issuers(path).unwrap_or_else(|error| panic!("External issuers could not be loaded. {}", error)),
+        None => Vec::new()
+    };
+    let external_auth = ExternalAuthVerifier::new(external_issuers)
+        .unwrap_or_else(|error| panic!("External issuers could not be loaded. {}", error));
+
+    let meta_schema_path = env::var("META_SCHEMA_PATH").ok();
+    let meta_schema = match meta_schema_path.as_deref() {
+        Some(path) => load_meta_schema(path).unwrap_or_else(|error| panic!("Meta schema could not be loaded. {}", error)),
+        None => MetaSchema::default()
+    };
+
+    let room_password_charset = get_env_var("ROOM_PASSWORD_CHARSET", DEFAULT_ROOM_PASSWORD_CHARSET.to_string()).as_bytes().to_vec();
+    let room_password_length = get_env_var("ROOM_PASSWORD_LENGTH", DEFAULT_ROOM_PASSWORD_LENGTH);
+    validate_room_password(&room_password_charset, room_password_length)
+        .unwrap_or_else(|error| panic!("Room password configuration is not valid. {}", error));
+
     YummyConfig {
         server_name: get_env_var("SERVER_NAME", server_name),
         bind_ip: get_env_var("BIND_IP", DEFAULT_BIND_IP.to_string()),
@@ -125,17 +262,58 @@ pub fn get_raw_configuration() -> YummyConfig {
         heartbeat_timeout: Duration::from_secs(get_env_var("HEARTBEAT_TIMEOUT", DEFAULT_HEARTBEAT_TIMEOUT)),
         cache_duration: Duration::from_secs(get_env_var("CACHE_DURATION", DEFAULT_CACHE_DURATION)),
         connection_restore_wait_timeout: Duration::from_secs(get_env_var("CONNECTION_RESTORE_WAIT_TIMEOUT", DEFAULT_CONNECTION_RESTORE_WAIT_TIMEOUT)),
+        outbound_buffer_size: get_env_var("OUTBOUND_BUFFER_SIZE", DEFAULT_OUTBOUND_BUFFER_SIZE),
         heartbeat_interval: Duration::from_secs(get_env_var("HEARTBEAT_INTERVAL", DEFAULT_HEARTBEAT_INTERVAL)),
         token_lifetime: Duration::from_secs(get_env_var("TOKEN_LIFETIME", DEFAULT_TOKEN_LIFETIME)),
+        refresh_token_lifetime: Duration::from_secs(get_env_var("REFRESH_TOKEN_LIFETIME", DEFAULT_REFRESH_TOKEN_LIFETIME)),
         api_key_name: get_env_var("API_KEY_NAME", DEFAULT_API_KEY_NAME.to_string()),
-        salt_key: get_env_var("SALT_KEY", DEFAULT_SALT_KEY.to_string()),
+        salt_key,
+        jwt_algorithm,
+        jwt_key_id,
+        jwt_private_key_path,
+        jwt_public_keys_path,
+        jwt_keys: Arc::new(jwt_keys),
+        external_issuers_path,
+        external_jwks_refresh_interval: Duration::from_secs(get_env_var("EXTERNAL_JWKS_REFRESH_INTERVAL", DEFAULT_EXTERNAL_JWKS_REFRESH_INTERVAL)),
+        external_auth,
+        allow_unverified_login: get_env_var("ALLOW_UNVERIFIED_LOGIN", DEFAULT_ALLOW_UNVERIFIED_LOGIN),
+        email_verification_lifetime: Duration::from_secs(get_env_var("EMAIL_VERIFICATION_LIFETIME", DEFAULT_EMAIL_VERIFICATION_LIFETIME)),
+        password_reset_lifetime: Duration::from_secs(get_env_var("PASSWORD_RESET_LIFETIME", DEFAULT_PASSWORD_RESET_LIFETIME)),
+        user_token_cooldown: Duration::from_secs(get_env_var("USER_TOKEN_COOLDOWN", DEFAULT_USER_TOKEN_COOLDOWN)),
+        smtp_url: env::var("SMTP_URL").ok(),
+        mail_from: get_env_var("MAIL_FROM", DEFAULT_MAIL_FROM.to_string()),
+        mail_file_path: env::var("MAIL_FILE_PATH").ok(),
         integration_key: get_env_var("INTEGRATION_KEY", DEFAULT_DEFAULT_INTEGRATION_KEY.to_string()),
         database_path: get_env_var("DATABASE_PATH", DEFAULT_DATABASE_PATH.to_string()),
         python_files_path: get_env_var("PYTHON_FILES_PATH", DEFAULT_PYTHON_FILES_PATH.to_string()),
         max_user_meta: get_env_var("MAX_USER_META", DEFAULT_MAX_USER_META),
         max_room_meta: get_env_var("MAX_ROOM_META", DEFAULT_MAX_ROOM_META),
-        room_password_charset: get_env_var("ROOM_PASSWORD_CHARSET", DEFAULT_ROOM_PASSWORD_CHARSET.to_string()).as_bytes().to_vec(),
-        room_password_length: get_env_var("ROOM_PASSWORD_LENGTH", DEFAULT_ROOM_PASSWORD_LENGTH),
+        meta_schema_path,
+        meta_schema: Arc::new(meta_schema),
+        meta_change_tick_interval: Duration::from_millis(get_env_var("META_CHANGE_TICK_INTERVAL", DEFAULT_META_CHANGE_TICK_INTERVAL)),
+        meta_sweep_interval: Duration::from_secs(get_env_var("META_SWEEP_INTERVAL", DEFAULT_META_SWEEP_INTERVAL)),
+        room_password_charset,
+        room_password_length,
+        max_inbox_page_size: get_env_var("MAX_INBOX_PAGE_SIZE", DEFAULT_MAX_INBOX_PAGE_SIZE),
+        max_room_list_page_size: get_env_var("MAX_ROOM_LIST_PAGE_SIZE", DEFAULT_MAX_ROOM_LIST_PAGE_SIZE),
+        deliver_inbox_on_login: get_env_var("DELIVER_INBOX_ON_LOGIN", DEFAULT_DELIVER_INBOX_ON_LOGIN),
+        max_user_message_size: get_env_var("MAX_USER_MESSAGE_SIZE", DEFAULT_MAX_USER_MESSAGE_SIZE),
+        room_message_history_size: get_env_var("ROOM_MESSAGE_HISTORY_SIZE", DEFAULT_ROOM_MESSAGE_HISTORY_SIZE),
+        room_message_history_persist: get_env_var("ROOM_MESSAGE_HISTORY_PERSIST", DEFAULT_ROOM_MESSAGE_HISTORY_PERSIST),
+        room_message_history_lifetime: Duration::from_secs(get_env_var("ROOM_MESSAGE_HISTORY_LIFETIME", DEFAULT_ROOM_MESSAGE_HISTORY_LIFETIME)),
+        room_message_sweep_interval: Duration::from_secs(get_env_var("ROOM_MESSAGE_SWEEP_INTERVAL", DEFAULT_ROOM_MESSAGE_SWEEP_INTERVAL)),
+        max_room_message_history_page_size: get_env_var("MAX_ROOM_MESSAGE_HISTORY_PAGE_SIZE", DEFAULT_MAX_ROOM_MESSAGE_HISTORY_PAGE_SIZE),
+        room_invite_lifetime: Duration::from_secs(get_env_var("ROOM_INVITE_LIFETIME", DEFAULT_ROOM_INVITE_LIFETIME)),
+        room_ban_sweep_interval: Duration::from_secs(get_env_var("ROOM_BAN_SWEEP_INTERVAL", DEFAULT_ROOM_BAN_SWEEP_INTERVAL)),
+        matchmaking_tick_interval: Duration::from_millis(get_env_var("MATCHMAKING_TICK_INTERVAL", DEFAULT_MATCHMAKING_TICK_INTERVAL)),
+        matchmaking_room_size: get_env_var("MATCHMAKING_ROOM_SIZE", DEFAULT_MATCHMAKING_ROOM_SIZE),
+        matchmaking_initial_tolerance: get_env_var("MATCHMAKING_INITIAL_TOLERANCE", DEFAULT_MATCHMAKING_INITIAL_TOLERANCE),
+        matchmaking_tolerance_step: get_env_var("MATCHMAKING_TOLERANCE_STEP", DEFAULT_MATCHMAKING_TOLERANCE_STEP),
+        matchmaking_max_tolerance: get_env_var("MATCHMAKING_MAX_TOLERANCE", DEFAULT_MATCHMAKING_MAX_TOLERANCE),
+        room_owner_succession: get_env_var("ROOM_OWNER_SUCCESSION", DEFAULT_ROOM_OWNER_SUCCESSION),
+        max_user_sessions: get_env_var("MAX_USER_SESSIONS", DEFAULT_MAX_USER_SESSIONS),
+        session_limit_policy: get_env_var("SESSION_LIMIT_POLICY", DEFAULT_SESSION_LIMIT_POLICY),
+        trusted_proxies: env::var("TRUSTED_PROXIES").map(|proxies| proxies.split(',').map(|proxy| proxy.trim().to_string()).filter(|proxy| !proxy.is_empty()).collect()).unwrap_or_default(),
 
         #[cfg(feature = "stateless")] redis_url: get_env_var("REDIS_URL", DEFAULT_REDIS_URL.to_string()),
         #[cfg(feature = "stateless")] redis_prefix: get_env_var("REDIS_PREFIX", DEFAULT_REDIS_PREFIX.to_string()),
@@ -153,7 +331,42 @@ impl Default for YummyConfig {
     }
 }
 
+impl FromStr for RoomOwnerSuccession {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match &value.to_lowercase()[..] {
+            "oldest" => Ok(RoomOwnerSuccession::Oldest),
+            "highest-type" => Ok(RoomOwnerSuccession::HighestType),
+            _ => Err(format!("'{}' is not valid room owner succession", value))
+        }
+    }
+}
+
+impl FromStr for SessionLimitPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match &value.to_lowercase()[..] {
+            "kick-oldest" => Ok(SessionLimitPolicy::KickOldest),
+            "reject" => Ok(SessionLimitPolicy::Reject),
+            _ => Err(format!("'{}' is not valid session limit policy", value))
+        }
+    }
+}
+
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */
 /* ************************************************** UNIT TESTS ************************************************** */
 /* **************************************************************************************************************** */
+#[cfg(test)]
+mod test {
+    use super::validate_room_password;
+
+    #[test]
+    fn room_password_validation() {
+        assert!(validate_room_password(b"ABC", 4).is_ok());
+        assert!(validate_room_password(b"", 4).is_err());
+        assert!(validate_room_password(b"ABC", 0).is_err());
+    }
+}
@@ -5,6 +5,8 @@ extern crate diesel;
 /* **************************************************** MODS ****************************************************** */
 /* **************************************************************************************************************** */
 pub mod auth;
+pub mod jwt;
+pub mod external;
 pub mod config;
 pub mod user;
 pub mod meta;
@@ -13,6 +15,7 @@ pub mod schema;
 pub mod request;
 pub mod password;
 pub mod state;
+pub mod matchmaking;
 
 use std::borrow::Cow;
 /* **************************************************************************************************************** */
@@ -164,6 +167,10 @@ pub struct UserAuthenticated(pub UserJwt);
 #[rtype(result = "()")]
 pub struct WebsocketMessage(pub String);
 
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct ForceDisconnect;
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct UserMetaInformation {
     pub id: UserMetaId,
@@ -213,6 +220,17 @@ pub enum RoomUserType {
     User = 1,
     Moderator = 2,
     Owner = 3,
+    Spectator = 4
+}
+
+#[derive(Default, Copy, Clone, Debug, Serialize_repr, Deserialize_repr, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum RoomGameState {
+    #[default]
+    Waiting = 0,
+    ReadyCheck = 1,
+    Playing = 2,
+    Finished = 3
 }
 
 /* **************************************************************************************************************** */
@@ -294,6 +312,7 @@ impl From<RoomUserType> for i32 {
             RoomUserType::User => 1,
             RoomUserType::Moderator => 2,
             RoomUserType::Owner => 3,
+            RoomUserType::Spectator => 4,
         }
     }
 }
@@ -304,23 +323,56 @@ impl From<i32> for RoomUserType {
             1 => RoomUserType::User,
             2 => RoomUserType::Moderator,
             3 => RoomUserType::Owner,
+            4 => RoomUserType::Spectator,
             _ => RoomUserType::default()
         }
     }
 }
 
+impl From<RoomGameState> for i32 {
+    fn from(item: RoomGameState) -> Self {
+        match item {
+            RoomGameState::Waiting => 0,
+            RoomGameState::ReadyCheck => 1,
+            RoomGameState::Playing => 2,
+            RoomGameState::Finished => 3,
+        }
+    }
+}
+
+impl From<i32> for RoomGameState {
+    fn from(game_state: i32) -> Self {
+        match game_state {
+            0 => RoomGameState::Waiting,
+            1 => RoomGameState::ReadyCheck,
+            2 => RoomGameState::Playing,
+            3 => RoomGameState::Finished,
+            _ => RoomGameState::default()
+        }
+    }
+}
+
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */
 /* **************************************************************************************************************** */
 generate_type!(UserId);
 generate_type!(UserMetaId);
+generate_type!(UserFriendId);
+generate_type!(UserFriendRequestId);
+generate_type!(UserInboxId);
+generate_type!(UserSuspensionId);
+generate_type!(UserIdentityId);
+generate_type!(UserTokenId);
 generate_type!(SessionId);
+generate_type!(TokenId);
+generate_type!(TokenFamilyId);
 generate_type!(RoomId);
 generate_type!(RoomMetaId);
 generate_type!(RoomTagId);
 generate_type!(RoomUserId);
 generate_type!(RoomUserBanId);
 generate_type!(RoomUserRequestId);
+generate_type!(RoomMessageId);
 
 generate_redis_convert!(CreateRoomAccessType);
 generate_redis_convert!(RoomUserType);
@@ -330,7 +382,7 @@ generate_redis_convert!(RoomUserType);
 /* **************************************************************************************************************** */
 #[cfg(test)]
 mod test {
-    use crate::{CreateRoomAccessType, RoomUserType, UserType};
+    use crate::{CreateRoomAccessType, RoomUserType, UserType, RoomGameState};
 
     #[test]
     fn create_room_access_type() {
@@ -351,15 +403,33 @@ mod test {
         assert_eq!(i32::from(RoomUserType::User), 1);
         assert_eq!(i32::from(RoomUserType::Moderator), 2);
         assert_eq!(i32::from(RoomUserType::Owner), 3);
+        assert_eq!(i32::from(RoomUserType::Spectator), 4);
 
         assert_eq!(RoomUserType::from(1), RoomUserType::User);
         assert_eq!(RoomUserType::from(2), RoomUserType::Moderator);
         assert_eq!(RoomUserType::from(3), RoomUserType::Owner);
+        assert_eq!(RoomUserType::from(4), RoomUserType::Spectator);
 
         assert_eq!(RoomUserType::from(-1), RoomUserType::User);
         assert_eq!(RoomUserType::from(100), RoomUserType::User);
     }
 
+    #[test]
+    fn room_game_state() {
+        assert_eq!(i32::from(RoomGameState::Waiting), 0);
+        assert_eq!(i32::from(RoomGameState::ReadyCheck), 1);
+        assert_eq!(i32::from(RoomGameState::Playing), 2);
+        assert_eq!(i32::from(RoomGameState::Finished), 3);
+
+        assert_eq!(RoomGameState::from(0), RoomGameState::Waiting);
+        assert_eq!(RoomGameState::from(1), RoomGameState::ReadyCheck);
+        assert_eq!(RoomGameState::from(2), RoomGameState::Playing);
+        assert_eq!(RoomGameState::from(3), RoomGameState::Finished);
+
+        assert_eq!(RoomGameState::from(-1), RoomGameState::Waiting);
+        assert_eq!(RoomGameState::from(100), RoomGameState::Waiting);
+    }
+
     #[test]
     fn user_type() {
         assert_eq!(i32::from(UserType::User), 1);
@@ -0,0 +1,59 @@
+/* **************************************************************************************************************** */
+/* **************************************************** MODS ****************************************************** */
+/* *************************************************** IMPORTS **************************************************** */
+/* **************************************************************************************************************** */
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::{UserId, SessionId};
+
+/* **************************************************************************************************************** */
+/* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
+/* **************************************************** MACROS **************************************************** */
+/* *************************************************** STRUCTS **************************************************** */
+/* **************************************************************************************************************** */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchmakingTicket {
+    pub user_id: Arc<UserId>,
+    pub session_id: SessionId,
+    pub properties: HashMap<String, Value>,
+    pub insert_date: i32
+}
+
+/* **************************************************************************************************************** */
+/* **************************************************** ENUMS ***************************************************** */
+/* ************************************************** FUNCTIONS *************************************************** */
+/* *************************************************** TRAITS ***************************************************** */
+/* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl MatchmakingTicket {
+    /* Numeric properties (skill, level, etc.) can differ up to tolerance, all other properties need to be same. Missing properties are accepted */
+    pub fn is_compatible(&self, other: &MatchmakingTicket, tolerance: f64) -> bool {
+        for (key, value) in self.properties.iter() {
+            let other_value = match other.properties.get(key) {
+                Some(other_value) => other_value,
+                None => continue
+            };
+
+            let compatible = match (value.as_f64(), other_value.as_f64()) {
+                (Some(value), Some(other_value)) => (value - other_value).abs() <= tolerance,
+                _ => value == other_value
+            };
+
+            if !compatible {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ********************************************** TRAIT IMPLEMENTS ************************************************ */
+/* ************************************************* MACROS CALL ************************************************** */
+/* ************************************************** UNIT TESTS ************************************************** */
+/* **************************************************************************************************************** */
@@ -3,7 +3,7 @@
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
 use serde::{Serialize, de::Visitor, Deserialize, Deserializer, Serializer, ser::SerializeMap};
-use std::{fmt::{self, Debug}, marker::PhantomData, ops::Index};
+use std::{fmt::{self, Debug}, marker::PhantomData, ops::Index, collections::HashMap};
 
 use crate::{UserMetaId, RoomMetaId};
 
@@ -120,6 +120,35 @@ impl<T, I> MetaCollection<T, I> where
         } 
     }
 
+    /* Added, changed and removed keys compared to the previous collection. Lowest access level of the old and new value is used */
+    pub fn get_changes(&self, previous: &Self) -> HashMap<String, T> where T: Into<i32> {
+        let mut changes = HashMap::new();
+
+        for item in self.items.iter() {
+            match previous.get_with_name(&item.name) {
+                Some(previous_item) if previous_item.meta == item.meta => (),
+                Some(previous_item) => {
+                    let access = match previous_item.meta.get_access_level().into() < item.meta.get_access_level().into() {
+                        true => previous_item.meta.get_access_level(),
+                        false => item.meta.get_access_level()
+                    };
+                    changes.insert(item.name.clone(), access);
+                },
+                None => {
+                    changes.insert(item.name.clone(), item.meta.get_access_level());
+                }
+            };
+        }
+
+        for previous_item in previous.items.iter() {
+            if self.get_with_name(&previous_item.name).is_none() {
+                changes.insert(previous_item.name.clone(), previous_item.meta.get_access_level());
+            }
+        }
+
+        changes
+    }
+
     pub fn get_data(self) -> Vec<MetaInformation<T, I>> {
         self
             .items
@@ -220,9 +249,9 @@ impl<T: Default + Debug + PartialEq + Clone + From<i32>, I: Default + ToString +
 /* **************************************************************************************************************** */
 #[cfg(test)]
 mod test {
-    use crate::{meta::{UserMetaType, UserMetaAccess}, UserMetaId};
+    use crate::{meta::{UserMetaType, UserMetaAccess, RoomMetaType, RoomMetaAccess}, UserMetaId};
 
-    use super::{UserMetaCollection, UserMetaCollectionInformation};
+    use super::{UserMetaCollection, UserMetaCollectionInformation, RoomMetaCollection};
 
     #[test]
     fn user_meta_collection_test() {
@@ -276,4 +305,29 @@ mod test {
         serde_json::from_str::<UserMetaCollection>("1024").unwrap_err();
         
     }
+
+    #[test]
+    fn meta_collection_changes_test() {
+        let mut previous = RoomMetaCollection::new();
+        previous.add("same".to_string(), RoomMetaType::Number(1.0, RoomMetaAccess::User));
+        previous.add("changed".to_string(), RoomMetaType::Number(1.0, RoomMetaAccess::Anonymous));
+        previous.add("hidden".to_string(), RoomMetaType::Bool(true, RoomMetaAccess::User));
+        previous.add("removed".to_string(), RoomMetaType::String("erhan".to_string(), RoomMetaAccess::Owner));
+
+        let mut current = RoomMetaCollection::new();
+        current.add("same".to_string(), RoomMetaType::Number(1.0, RoomMetaAccess::User));
+        current.add("changed".to_string(), RoomMetaType::Number(2.0, RoomMetaAccess::Anonymous));
+        current.add("hidden".to_string(), RoomMetaType::Bool(true, RoomMetaAccess::Owner));
+        current.add("added".to_string(), RoomMetaType::Bool(false, RoomMetaAccess::Moderator));
+
+        let changes = current.get_changes(&previous);
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes.get("same"), None);
+        assert_eq!(changes.get("changed"), Some(&RoomMetaAccess::Anonymous));
+        assert_eq!(changes.get("hidden"), Some(&RoomMetaAccess::User));
+        assert_eq!(changes.get("removed"), Some(&RoomMetaAccess::Owner));
+        assert_eq!(changes.get("added"), Some(&RoomMetaAccess::Moderator));
+
+        assert!(current.get_changes(&current).is_empty());
+    }
 }
@@ -2,14 +2,17 @@
 /* **************************************************** MODS ****************************************************** */
 /* **************************************************************************************************************** */
 pub mod collection;
+pub mod operation;
+pub mod schema;
 
 /* **************************************************************************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
 use serde::{Serialize, de::Visitor, de::MapAccess, Deserialize, Deserializer, Serializer};
-use std::{fmt::{self, Debug}, marker::PhantomData};
+use std::{fmt::{self, Debug}, marker::PhantomData, collections::{HashMap, HashSet}};
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::de::{self};
-use serde::ser::SerializeSeq;
+use serde::ser::{SerializeSeq, SerializeMap};
 use serde_repr::{Serialize_repr, Deserialize_repr};
 
 /* **************************************************************************************************************** */
@@ -18,6 +21,9 @@ use serde_repr::{Serialize_repr, Deserialize_repr};
 pub type UserMetaType = MetaType<UserMetaAccess>;
 pub type RoomMetaType = MetaType<RoomMetaAccess>;
 
+/* Objects are wrapped with this key, plain maps are reserved for the access level definition */
+pub const META_OBJECT_KEY: &str = "$object";
+
 /* **************************************************************************************************************** */
 /* **************************************************** MACROS **************************************************** */
 /* *************************************************** STRUCTS **************************************************** */
@@ -27,6 +33,13 @@ struct MetaVisitor<T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32
     _marker: PhantomData<T>
 }
 
+/* Client sent metas, keys with the explicitly defined "access" are not overridden by the schema access level */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetaDefinitions<T: Default + Debug + PartialEq + Clone + From<i32>> {
+    pub metas: HashMap<String, MetaType<T>>,
+    pub defined_access: HashSet<String>
+}
+
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
 /* **************************************************************************************************************** */
@@ -71,7 +84,11 @@ pub enum MetaType<T: Default + Debug + PartialEq + Clone + From<i32>> {
     Number(f64, T),
     String(String, T),
     Bool(bool, T),
-    List(Box<Vec<MetaType<T>>>, T)
+    List(Box<Vec<MetaType<T>>>, T),
+    Object(Box<HashMap<String, MetaType<T>>>, T),
+
+    /* Wrapped meta will be removed after the expire date (unix timestamp) */
+    Expiring(Box<MetaType<T>>, i32)
 }
 
 /* **************************************************************************************************************** */
@@ -156,6 +173,58 @@ impl<T: Default + Debug + PartialEq + Clone + From<i32>> MetaType<T> {
             MetaType::String(_, access_level) => access_level.clone(),
             MetaType::Bool(_, access_level) => access_level.clone(),
             MetaType::List(_, access_level) => access_level.clone(),
+            MetaType::Object(_, access_level) => access_level.clone(),
+            MetaType::Expiring(meta, _) => meta.get_access_level(),
+        }
+    }
+
+    pub fn with_access_level(self, access_level: T) -> Self {
+        match self {
+            MetaType::Null => MetaType::Null,
+            MetaType::Number(value, _) => MetaType::Number(value, access_level),
+            MetaType::String(value, _) => MetaType::String(value, access_level),
+            MetaType::Bool(value, _) => MetaType::Bool(value, access_level),
+            MetaType::List(value, _) => MetaType::List(value, access_level),
+            MetaType::Object(value, _) => MetaType::Object(value, access_level),
+            MetaType::Expiring(meta, expire_date) => MetaType::Expiring(Box::new(meta.with_access_level(access_level)), expire_date),
+        }
+    }
+
+    pub fn get_expire_date(&self) -> Option<i32> {
+        match self {
+            MetaType::Expiring(_, expire_date) => Some(*expire_date),
+            _ => None
+        }
+    }
+
+    /* Null metas are never stored, so there is no need to keep expire date for them */
+    pub fn with_expire_date(self, expire_date: Option<i32>) -> Self {
+        match (self.into_value(), expire_date) {
+            (MetaType::Null, _) => MetaType::Null,
+            (meta, Some(expire_date)) => MetaType::Expiring(Box::new(meta), expire_date),
+            (meta, None) => meta
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        match self {
+            MetaType::Expiring(_, expire_date) => *expire_date <= SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default(),
+            _ => false
+        }
+    }
+
+    /* Return the actual meta without the expire information */
+    pub fn get_value(&self) -> &MetaType<T> {
+        match self {
+            MetaType::Expiring(meta, _) => meta.get_value(),
+            _ => self
+        }
+    }
+
+    pub fn into_value(self) -> MetaType<T> {
+        match self {
+            MetaType::Expiring(meta, _) => meta.into_value(),
+            _ => self
         }
     }
 }
@@ -169,6 +238,48 @@ impl<'de, T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>> Deseri
     }
 }
 
+impl<T: Default + Debug + PartialEq + Clone + From<i32>> From<HashMap<String, MetaType<T>>> for MetaDefinitions<T> {
+    fn from(metas: HashMap<String, MetaType<T>>) -> Self {
+        let defined_access = metas.keys().cloned().collect();
+        Self { metas, defined_access }
+    }
+}
+
+impl<T: Default + Debug + PartialEq + Clone + From<i32>, const N: usize> From<[(String, MetaType<T>); N]> for MetaDefinitions<T> {
+    fn from(metas: [(String, MetaType<T>); N]) -> Self {
+        HashMap::from(metas).into()
+    }
+}
+
+impl<'de, T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>> Deserialize<'de> for MetaDefinitions<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        let mut definitions = MetaDefinitions { metas: HashMap::with_capacity(values.len()), defined_access: HashSet::new() };
+
+        for (key, value) in values.iter() {
+            if value.get("access").is_some() {
+                definitions.defined_access.insert(key.clone());
+            }
+
+            definitions.metas.insert(key.clone(), MetaType::deserialize(value).map_err(de::Error::custom)?);
+        }
+
+        Ok(definitions)
+    }
+}
+
+impl<T: Default + Debug + PartialEq + Clone + From<i32>> Serialize for MetaDefinitions<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.metas.serialize(serializer)
+    }
+}
+
 impl<T: Default + Debug + PartialEq + Clone + From<i32>> Serialize for MetaType<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -186,6 +297,12 @@ impl<T: Default + Debug + PartialEq + Clone + From<i32>> Serialize for MetaType<
                 }
                 seq.end()
             },
+            MetaType::Object(object, _) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(META_OBJECT_KEY, object.as_ref())?;
+                map.end()
+            },
+            MetaType::Expiring(meta, _) => meta.serialize(serializer),
         }
     }
 }
@@ -244,30 +361,45 @@ impl<'de, T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>> Visito
     fn visit_map<E>(self, mut access: E) -> Result<Self::Value, E::Error> where E: MapAccess<'de> {
         let mut visibility: Option<T> = None;
         let mut value: Option<MetaType<T>> = None;
+        let mut object: Option<HashMap<String, MetaType<T>>> = None;
+        let mut expire_date: Option<i32> = None;
         while let Some(key) = access.next_key::<&str>()? {
             match key {
+                META_OBJECT_KEY => object = Some(access.next_value::<HashMap<String, MetaType<T>>>()?),
                 "access" => visibility = Some(match access.next_value::<usize>() {
                     Ok(n) => (n as i32).into(),
                     _ => return Err(de::Error::custom(r#"Invalid "access" type"#))
                 }),
                 "value" => value = Some(access.next_value::<MetaType<T>>()?),
+                "ttl" | "expires_at" if expire_date.is_some() => return Err(de::Error::custom(r#""ttl" can not be used with "expires_at""#)),
+                "ttl" => expire_date = Some(match access.next_value::<i32>() {
+                    Ok(ttl) if ttl > 0 => SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default().saturating_add(ttl),
+                    _ => return Err(de::Error::custom(r#"Invalid "ttl" type"#))
+                }),
+
+                /* Already expired metas would be hidden immediately */
+                "expires_at" => expire_date = Some(match access.next_value::<i32>() {
+                    Ok(expires_at) if expires_at > SystemTime::now().duration_since(UNIX_EPOCH).map(|item| item.as_secs() as i32).unwrap_or_default() => expires_at,
+                    _ => return Err(de::Error::custom(r#"Invalid "expires_at" type"#))
+                }),
                 _ => return Err(de::Error::custom(format!(r#""{}" is not valid"#, key)))
             }
         }
 
+        if let Some(object) = object {
+            return match (visibility, value) {
+                (None, None) => Ok(MetaType::Object(Box::new(object), T::default()).with_expire_date(expire_date)),
+                _ => Err(de::Error::custom(format!(r#""{}" can not be used with "access" and "value""#, META_OBJECT_KEY)))
+            };
+        }
+
         let visibility = match visibility {
             Some(visibility) => visibility,
             None => return Err(de::Error::custom(r#""access" key is missing"#))
         };
         
         match value {
-            Some(value) => Ok(match value {
-                MetaType::Null => MetaType::Null,
-                MetaType::Number(val, _) => MetaType::Number(val, visibility),
-                MetaType::String(val, _) => MetaType::String(val, visibility),
-                MetaType::Bool(val, _) => MetaType::Bool(val, visibility),
-                MetaType::List(val, _) => MetaType::List(val, visibility),
-            }),
+            Some(value) => Ok(value.with_access_level(visibility).with_expire_date(expire_date)),
             None => Err(de::Error::custom(r#""value" key is missing"#))
         }
     }
@@ -279,7 +411,9 @@ impl<'de, T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>> Visito
 /* **************************************************************************************************************** */
 #[cfg(test)]
 mod test {
-    use crate::meta::{MetaAction, UserMetaAccess, RoomMetaAccess};
+    use std::collections::{HashMap, HashSet};
+
+    use crate::meta::{MetaAction, UserMetaAccess, RoomMetaAccess, MetaType, UserMetaType, MetaDefinitions};
 
     #[test]
     fn meta_action() {
@@ -324,4 +458,69 @@ mod test {
         assert_eq!(RoomMetaAccess::from(-1), RoomMetaAccess::Anonymous);
         assert_eq!(RoomMetaAccess::from(100), RoomMetaAccess::Anonymous);
     }
+
+    #[test]
+    fn object_meta() {
+        let meta: UserMetaType = serde_json::from_str(r#"{"access": 3, "value": {"$object": {"position": {"$object": {"x": 1, "y": 2}}, "name": "erhan"}}}"#).unwrap();
+        assert_eq!(meta, MetaType::Object(Box::new(HashMap::from([
+            ("position".to_string(), MetaType::Object(Box::new(HashMap::from([
+                ("x".to_string(), MetaType::Number(1.0, UserMetaAccess::Anonymous)),
+                ("y".to_string(), MetaType::Number(2.0, UserMetaAccess::Anonymous))
+            ])), UserMetaAccess::Anonymous)),
+            ("name".to_string(), MetaType::String("erhan".to_string(), UserMetaAccess::Anonymous))
+        ])), UserMetaAccess::Me));
+
+        /* Serialized value should be parsed back */
+        let serialized = serde_json::to_string(&meta).unwrap();
+        let parsed: UserMetaType = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(parsed, MetaType::Object(match meta { MetaType::Object(object, _) => object, _ => unreachable!() }, UserMetaAccess::Anonymous));
+
+        /* Object keys can be "access" and "value" */
+        let meta: UserMetaType = serde_json::from_str(r#"{"$object": {"access": 1, "value": 2}}"#).unwrap();
+        assert_eq!(meta, MetaType::Object(Box::new(HashMap::from([
+            ("access".to_string(), MetaType::Number(1.0, UserMetaAccess::Anonymous)),
+            ("value".to_string(), MetaType::Number(2.0, UserMetaAccess::Anonymous))
+        ])), UserMetaAccess::Anonymous));
+
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"$object": {}, "access": 1}"#).is_err());
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"x": 1}"#).is_err());
+    }
+
+    #[test]
+    fn meta_definitions() {
+        let definitions: MetaDefinitions<UserMetaAccess> = serde_json::from_str(r#"{"plain": 1, "anonymous": {"access": 0, "value": 2}, "object": {"$object": {}}}"#).unwrap();
+        assert_eq!(definitions.metas.get("plain"), Some(&MetaType::Number(1.0, UserMetaAccess::Anonymous)));
+        assert_eq!(definitions.metas.get("anonymous"), Some(&MetaType::Number(2.0, UserMetaAccess::Anonymous)));
+        assert_eq!(definitions.defined_access, HashSet::from(["anonymous".to_string()]));
+    }
+
+    #[test]
+    fn expiring_meta() {
+        let meta: UserMetaType = serde_json::from_str(r#"{"access": 3, "value": true, "expires_at": 2000000000}"#).unwrap();
+        assert_eq!(meta, MetaType::Expiring(Box::new(MetaType::Bool(true, UserMetaAccess::Me)), 2000000000));
+        assert_eq!(meta.get_access_level(), UserMetaAccess::Me);
+        assert_eq!(meta.get_expire_date(), Some(2000000000));
+        assert_eq!(meta.get_value(), &MetaType::Bool(true, UserMetaAccess::Me));
+        assert!(!meta.is_expired());
+        assert!(MetaType::Expiring(Box::new(MetaType::Bool(true, UserMetaAccess::Me)), 1000).is_expired());
+
+        /* Expire information is not part of the value */
+        assert_eq!(serde_json::to_string(&meta).unwrap(), "true");
+
+        let meta: UserMetaType = serde_json::from_str(r#"{"access": 1, "value": 10, "ttl": 60}"#).unwrap();
+        assert!(matches!(meta, MetaType::Expiring(_, _)));
+        assert!(!meta.is_expired());
+
+        let meta: UserMetaType = serde_json::from_str(r#"{"$object": {"x": 1}, "ttl": 60}"#).unwrap();
+        assert!(matches!(meta.get_value(), MetaType::Object(_, UserMetaAccess::Anonymous)));
+
+        /* Null meta removes the key, expire date has no effect */
+        let meta: UserMetaType = serde_json::from_str(r#"{"access": 1, "value": null, "ttl": 60}"#).unwrap();
+        assert_eq!(meta, MetaType::Null);
+
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"access": 1, "value": 10, "ttl": 60, "expires_at": 1000}"#).is_err());
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"access": 1, "value": 10, "ttl": -1}"#).is_err());
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"access": 1, "value": 10, "ttl": 0}"#).is_err());
+        assert!(serde_json::from_str::<UserMetaType>(r#"{"access": 1, "value": 10, "expires_at": 1000}"#).is_err());
+    }
 }
@@ -0,0 +1,237 @@
+/* **************************************************************************************************************** */
+/* *************************************************** IMPORTS **************************************************** */
+/* **************************************************************************************************************** */
+use std::fmt::Debug;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use super::{MetaType, UserMetaAccess, RoomMetaAccess};
+
+/* **************************************************************************************************************** */
+/* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
+/* **************************************************************************************************************** */
+pub type UserMetaOperation = MetaOperation<UserMetaAccess>;
+pub type RoomMetaOperation = MetaOperation<RoomMetaAccess>;
+
+/* **************************************************************************************************************** */
+/* **************************************************** ENUMS ***************************************************** */
+/* **************************************************************************************************************** */
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation")]
+#[serde(bound(serialize = "T: Default + Debug + PartialEq + Clone + From<i32>", deserialize = "T: Default + Debug + PartialEq + Clone + From<i32> + Into<i32>"))]
+pub enum MetaOperation<T: Default + Debug + PartialEq + Clone + From<i32>> {
+    Increment {
+        value: f64,
+
+        #[serde(default)]
+        min: Option<f64>,
+
+        #[serde(default)]
+        max: Option<f64>
+    },
+    Decrement {
+        value: f64,
+
+        #[serde(default)]
+        min: Option<f64>,
+
+        #[serde(default)]
+        max: Option<f64>
+    },
+    Append {
+        value: MetaType<T>
+    },
+    Remove {
+        value: MetaType<T>
+    },
+    CompareAndSet {
+        #[serde(default = "MetaType::default_null")]
+        expected: MetaType<T>,
+        value: MetaType<T>
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum MetaOperationError {
+    #[error("Meta type is not number")]
+    MetaIsNotNumber,
+
+    #[error("Meta type is not list")]
+    MetaIsNotList,
+
+    #[error("Meta value is not matched with expected value")]
+    CompareFailed
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl<T: Default + Debug + PartialEq + Clone + From<i32>> MetaType<T> {
+    fn default_null() -> Self {
+        MetaType::Null
+    }
+
+    /* Compare only the stored values, access level is not part of the value */
+    pub fn is_same_value(&self, other: &MetaType<T>) -> bool {
+        match (self.get_value(), other.get_value()) {
+            (MetaType::Null, MetaType::Null) => true,
+            (MetaType::Number(left, _), MetaType::Number(right, _)) => left == right,
+            (MetaType::String(left, _), MetaType::String(right, _)) => left == right,
+            (MetaType::Bool(left, _), MetaType::Bool(right, _)) => left == right,
+            (MetaType::List(left, _), MetaType::List(right, _)) => left.len() == right.len() && left.iter().zip(right.iter()).all(|(left, right)| left.is_same_value(right)),
+            (MetaType::Object(left, _), MetaType::Object(right, _)) => left.len() == right.len() && left.iter().all(|(key, left)| right.get(key).map(|right| left.is_same_value(right)).unwrap_or_default()),
+            _ => false
+        }
+    }
+}
+
+impl<T: Default + Debug + PartialEq + Clone + From<i32>> MetaOperation<T> {
+    /* Calculate the new meta value. Missing number metas start from zero and missing list metas start empty. Existing access level and expire date are kept */
+    pub fn apply(&self, current: Option<&MetaType<T>>) -> Result<MetaType<T>, MetaOperationError> {
+        let current = match current {
+            Some(MetaType::Null) | None => None,
+            Some(meta) if meta.is_expired() => None,
+            Some(MetaType::Expiring(meta, expire_date)) => return match self {
+                MetaOperation::CompareAndSet { .. } => self.apply(Some(meta)),
+                _ => self.apply(Some(meta)).map(|meta| meta.with_expire_date(Some(*expire_date)))
+            },
+            Some(meta) => Some(meta)
+        };
+
+        match self {
+            MetaOperation::Increment { value, min, max } => Self::calculate_number(current, *value, *min, *max),
+            MetaOperation::Decrement { value, min, max } => Self::calculate_number(current, -*value, *min, *max),
+            MetaOperation::Append { value } => {
+                let (mut list, access) = Self::get_list(current)?;
+                list.push(value.clone());
+                Ok(MetaType::List(Box::new(list), access))
+            },
+            MetaOperation::Remove { value } => {
+                let (mut list, access) = Self::get_list(current)?;
+                list.retain(|item| !item.is_same_value(value));
+                Ok(MetaType::List(Box::new(list), access))
+            },
+            MetaOperation::CompareAndSet { expected, value } => {
+                let matched = match current {
+                    Some(current) => current.is_same_value(expected),
+                    None => *expected == MetaType::Null
+                };
+
+                match matched {
+                    true => Ok(value.clone()),
+                    false => Err(MetaOperationError::CompareFailed)
+                }
+            }
+        }
+    }
+
+    /* Return the access level that required to execute operation */
+    pub fn get_access_level(&self) -> T {
+        match self {
+            MetaOperation::CompareAndSet { value, .. } => value.get_access_level(),
+            _ => T::default()
+        }
+    }
+
+    fn calculate_number(current: Option<&MetaType<T>>, value: f64, min: Option<f64>, max: Option<f64>) -> Result<MetaType<T>, MetaOperationError> {
+        let (current, access) = match current {
+            Some(MetaType::Number(number, access)) => (*number, access.clone()),
+            Some(_) => return Err(MetaOperationError::MetaIsNotNumber),
+            None => (0.0, T::default())
+        };
+
+        let mut number = current + value;
+
+        if let Some(min) = min {
+            number = number.max(min);
+        }
+
+        if let Some(max) = max {
+            number = number.min(max);
+        }
+
+        Ok(MetaType::Number(number, access))
+    }
+
+    fn get_list(current: Option<&MetaType<T>>) -> Result<(Vec<MetaType<T>>, T), MetaOperationError> {
+        match current {
+            Some(MetaType::List(list, access)) => Ok((list.to_vec(), access.clone())),
+            Some(_) => Err(MetaOperationError::MetaIsNotList),
+            None => Ok((Vec::new(), T::default()))
+        }
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************** UNIT TESTS ************************************************** */
+/* **************************************************************************************************************** */
+#[cfg(test)]
+mod test {
+    use crate::meta::{MetaType, UserMetaAccess, RoomMetaAccess};
+    use super::{UserMetaOperation, RoomMetaOperation, MetaOperationError};
+
+    #[test]
+    fn number_operations() {
+        let current = MetaType::Number(10.0, UserMetaAccess::Friend);
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "Increment", "value": 5}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::Number(15.0, UserMetaAccess::Friend)));
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "Increment", "value": 5, "max": 12}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::Number(12.0, UserMetaAccess::Friend)));
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "Decrement", "value": 15, "min": 0}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::Number(0.0, UserMetaAccess::Friend)));
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "Decrement", "value": 3}"#).unwrap();
+        assert_eq!(operation.apply(None), Ok(MetaType::Number(-3.0, UserMetaAccess::Anonymous)));
+
+        assert_eq!(operation.apply(Some(&MetaType::String("10".to_string(), UserMetaAccess::Anonymous))), Err(MetaOperationError::MetaIsNotNumber));
+    }
+
+    #[test]
+    fn list_operations() {
+        let current = MetaType::List(Box::new(vec![MetaType::Number(1.0, RoomMetaAccess::Anonymous), MetaType::String("a".to_string(), RoomMetaAccess::Anonymous)]), RoomMetaAccess::User);
+
+        let operation: RoomMetaOperation = serde_json::from_str(r#"{"operation": "Append", "value": true}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::List(Box::new(vec![MetaType::Number(1.0, RoomMetaAccess::Anonymous), MetaType::String("a".to_string(), RoomMetaAccess::Anonymous), MetaType::Bool(true, RoomMetaAccess::Anonymous)]), RoomMetaAccess::User)));
+        assert_eq!(operation.apply(None), Ok(MetaType::List(Box::new(vec![MetaType::Bool(true, RoomMetaAccess::Anonymous)]), RoomMetaAccess::Anonymous)));
+
+        let operation: RoomMetaOperation = serde_json::from_str(r#"{"operation": "Remove", "value": "a"}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::List(Box::new(vec![MetaType::Number(1.0, RoomMetaAccess::Anonymous)]), RoomMetaAccess::User)));
+
+        assert_eq!(operation.apply(Some(&MetaType::Bool(true, RoomMetaAccess::Anonymous))), Err(MetaOperationError::MetaIsNotList));
+    }
+
+    #[test]
+    fn compare_and_set_operations() {
+        let current = MetaType::String("waiting".to_string(), UserMetaAccess::Anonymous);
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "CompareAndSet", "expected": "waiting", "value": {"access": 3, "value": "playing"}}"#).unwrap();
+        assert_eq!(operation.get_access_level(), UserMetaAccess::Me);
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::String("playing".to_string(), UserMetaAccess::Me)));
+        assert_eq!(operation.apply(Some(&MetaType::String("playing".to_string(), UserMetaAccess::Anonymous))), Err(MetaOperationError::CompareFailed));
+        assert_eq!(operation.apply(None), Err(MetaOperationError::CompareFailed));
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "CompareAndSet", "value": 1}"#).unwrap();
+        assert_eq!(operation.apply(None), Ok(MetaType::Number(1.0, UserMetaAccess::Anonymous)));
+        assert_eq!(operation.apply(Some(&current)), Err(MetaOperationError::CompareFailed));
+    }
+
+    #[test]
+    fn expiring_operations() {
+        let current = MetaType::Expiring(Box::new(MetaType::Number(10.0, UserMetaAccess::Friend)), i32::MAX);
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "Increment", "value": 5}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::Expiring(Box::new(MetaType::Number(15.0, UserMetaAccess::Friend)), i32::MAX)));
+
+        /* Expired metas are handled like missing metas */
+        let expired = MetaType::Expiring(Box::new(MetaType::Number(10.0, UserMetaAccess::Friend)), 1);
+        assert_eq!(operation.apply(Some(&expired)), Ok(MetaType::Number(5.0, UserMetaAccess::Anonymous)));
+
+        let operation: UserMetaOperation = serde_json::from_str(r#"{"operation": "CompareAndSet", "expected": 10, "value": 20}"#).unwrap();
+        assert_eq!(operation.apply(Some(&current)), Ok(MetaType::Number(20.0, UserMetaAccess::Anonymous)));
+        assert_eq!(operation.apply(Some(&expired)), Err(MetaOperationError::CompareFailed));
+    }
+}
@@ -0,0 +1,334 @@
+/* **************************************************************************************************************** */
+/* *************************************************** IMPORTS **************************************************** */
+/* **************************************************************************************************************** */
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::fs;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use serde::de;
+use thiserror::Error;
+
+use super::{MetaType, MetaDefinitions};
+
+/* **************************************************************************************************************** */
+/* *************************************************** STRUCTS **************************************************** */
+/* **************************************************************************************************************** */
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetaSchema {
+    #[serde(default)]
+    pub user: MetaSchemaSection,
+
+    #[serde(default)]
+    pub room: MetaSchemaSection
+}
+
+/* Keys that are not defined in the section are allowed only if the section is not strict */
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetaSchemaSection {
+    #[serde(default)]
+    pub strict: bool,
+
+    #[serde(default)]
+    pub keys: HashMap<String, MetaKeySchema>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetaKeySchema {
+    #[serde(rename = "type")]
+    pub meta_type: Option<MetaSchemaType>,
+
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+
+    #[serde(default, deserialize_with = "deserialize_pattern")]
+    pub pattern: Option<Regex>,
+
+    pub access: Option<i32>,
+
+    #[serde(default = "default_writable")]
+    pub writable: bool
+}
+
+/* **************************************************************************************************************** */
+/* **************************************************** ENUMS ***************************************************** */
+/* **************************************************************************************************************** */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetaSchemaType {
+    Number,
+    String,
+    Bool,
+    List,
+    Object
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MetaSchemaError {
+    #[error("'{0}' could not be read")]
+    FileCouldNotRead(String),
+
+    #[error("'{0}' is not valid. {1}")]
+    FileNotValid(String, String),
+
+    #[error("'{0}' meta is not allowed")]
+    KeyNotAllowed(String),
+
+    #[error("'{0}' meta is read only")]
+    KeyNotWritable(String),
+
+    #[error("'{0}' meta should be {1}")]
+    TypeNotValid(String, MetaSchemaType),
+
+    #[error("'{0}' meta should be greater than or equal to {1}")]
+    NumberTooSmall(String, f64),
+
+    #[error("'{0}' meta should be less than or equal to {1}")]
+    NumberTooBig(String, f64),
+
+    #[error("'{0}' meta length should be at least {1}")]
+    LengthTooShort(String, usize),
+
+    #[error("'{0}' meta length should be at most {1}")]
+    LengthTooLong(String, usize),
+
+    #[error("'{0}' meta does not match with the pattern")]
+    PatternNotMatched(String)
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************** FUNCTIONS *************************************************** */
+/* **************************************************************************************************************** */
+pub fn load_meta_schema(path: &str) -> Result<MetaSchema, MetaSchemaError> {
+    let content = fs::read_to_string(path).map_err(|_| MetaSchemaError::FileCouldNotRead(path.to_string()))?;
+    serde_json::from_str(&content).map_err(|error| MetaSchemaError::FileNotValid(path.to_string(), error.to_string()))
+}
+
+fn default_writable() -> bool {
+    true
+}
+
+fn deserialize_pattern<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error> where D: Deserializer<'de> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(pattern) => Regex::new(&pattern).map(Some).map_err(de::Error::custom),
+        None => Ok(None)
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl MetaSchemaSection {
+    /* Validate client metas and set the schema access level to the metas that defined without access level */
+    pub fn validate_metas<T: Default + Debug + PartialEq + Clone + From<i32>>(&self, metas: Option<MetaDefinitions<T>>) -> Result<Option<HashMap<String, MetaType<T>>>, MetaSchemaError> {
+        let metas = match metas {
+            Some(metas) => metas,
+            None => return Ok(None)
+        };
+
+        let mut validated_metas = HashMap::with_capacity(metas.metas.len());
+
+        for (key, meta) in metas.metas.into_iter() {
+            self.validate(&key, &meta)?;
+
+            let meta = match self.keys.get(&key).and_then(|schema| schema.access) {
+                Some(access) if !metas.defined_access.contains(&key) => meta.with_access_level(T::from(access)),
+                _ => meta
+            };
+
+            validated_metas.insert(key, meta);
+        }
+
+        Ok(Some(validated_metas))
+    }
+
+    pub fn validate<T: Default + Debug + PartialEq + Clone + From<i32>>(&self, key: &str, meta: &MetaType<T>) -> Result<(), MetaSchemaError> {
+        let schema = match self.keys.get(key) {
+            Some(schema) => schema,
+            None => return match self.strict {
+                true => Err(MetaSchemaError::KeyNotAllowed(key.to_string())),
+                false => Ok(())
+            }
+        };
+
+        if !schema.writable {
+            return Err(MetaSchemaError::KeyNotWritable(key.to_string()));
+        }
+
+        schema.validate(key, meta)
+    }
+}
+
+impl MetaKeySchema {
+    /* Null means the meta will be removed, so there is nothing to validate */
+    pub fn validate<T: Default + Debug + PartialEq + Clone + From<i32>>(&self, key: &str, meta: &MetaType<T>) -> Result<(), MetaSchemaError> {
+        let meta_type = match meta {
+            MetaType::Null => return Ok(()),
+            MetaType::Expiring(meta, _) => return self.validate(key, meta),
+            MetaType::Number(_, _) => MetaSchemaType::Number,
+            MetaType::String(_, _) => MetaSchemaType::String,
+            MetaType::Bool(_, _) => MetaSchemaType::Bool,
+            MetaType::List(_, _) => MetaSchemaType::List,
+            MetaType::Object(_, _) => MetaSchemaType::Object
+        };
+
+        if let Some(expected_type) = self.meta_type {
+            if expected_type != meta_type {
+                return Err(MetaSchemaError::TypeNotValid(key.to_string(), expected_type));
+            }
+        }
+
+        let length = match meta {
+            MetaType::Number(number, _) => {
+                if let Some(min) = self.min {
+                    if *number < min {
+                        return Err(MetaSchemaError::NumberTooSmall(key.to_string(), min));
+                    }
+                }
+
+                if let Some(max) = self.max {
+                    if *number > max {
+                        return Err(MetaSchemaError::NumberTooBig(key.to_string(), max));
+                    }
+                }
+
+                None
+            },
+            MetaType::String(string, _) => {
+                if let Some(pattern) = &self.pattern {
+                    if !pattern.is_match(string) {
+                        return Err(MetaSchemaError::PatternNotMatched(key.to_string()));
+                    }
+                }
+
+                Some(string.chars().count())
+            },
+            MetaType::List(list, _) => Some(list.len()),
+            MetaType::Object(object, _) => Some(object.len()),
+            _ => None
+        };
+
+        if let Some(length) = length {
+            if let Some(min_length) = self.min_length {
+                if length < min_length {
+                    return Err(MetaSchemaError::LengthTooShort(key.to_string(), min_length));
+                }
+            }
+
+            if let Some(max_length) = self.max_length {
+                if length > max_length {
+                    return Err(MetaSchemaError::LengthTooLong(key.to_string(), max_length));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ********************************************** TRAIT IMPLEMENTS ************************************************ */
+/* **************************************************************************************************************** */
+impl fmt::Display for MetaSchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetaSchemaType::Number => write!(f, "number"),
+            MetaSchemaType::String => write!(f, "string"),
+            MetaSchemaType::Bool => write!(f, "bool"),
+            MetaSchemaType::List => write!(f, "list"),
+            MetaSchemaType::Object => write!(f, "object")
+        }
+    }
+}
+
+/* **************************************************************************************************************** */
+/* ************************************************** UNIT TESTS ************************************************** */
+/* **************************************************************************************************************** */
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::env::temp_dir;
+    use std::fs;
+
+    use crate::UserId;
+    use crate::meta::{MetaType, UserMetaAccess, RoomMetaAccess};
+    use super::{MetaSchema, MetaSchemaError, MetaSchemaType, load_meta_schema};
+
+    fn create_schema() -> MetaSchema {
+        serde_json::from_str(r#"{
+            "user": {
+                "strict": true,
+                "keys": {
+                    "level": {"type": "number", "min": 1, "max": 100, "writable": false},
+                    "nickname": {"type": "string", "min_length": 3, "max_length": 8, "pattern": "^[a-z]+$", "access": 3},
+                    "badges": {"type": "list", "max_length": 2},
+                    "position": {"type": "object"}
+                }
+            },
+            "room": {
+                "keys": {
+                    "round": {"type": "number", "min": 0}
+                }
+            }
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn validate_user_metas() {
+        let schema = create_schema();
+
+        assert_eq!(schema.user.validate("nickname", &MetaType::String("erhan".to_string(), UserMetaAccess::Anonymous)), Ok(()));
+        assert_eq!(schema.user.validate("nickname", &MetaType::Number(1.0, UserMetaAccess::Anonymous)), Err(MetaSchemaError::TypeNotValid("nickname".to_string(), MetaSchemaType::String)));
+        assert_eq!(schema.user.validate("nickname", &MetaType::String("er".to_string(), UserMetaAccess::Anonymous)), Err(MetaSchemaError::LengthTooShort("nickname".to_string(), 3)));
+        assert_eq!(schema.user.validate("nickname", &MetaType::String("erhanbaris".to_string(), UserMetaAccess::Anonymous)), Err(MetaSchemaError::LengthTooLong("nickname".to_string(), 8)));
+        assert_eq!(schema.user.validate("nickname", &MetaType::String("Erhan".to_string(), UserMetaAccess::Anonymous)), Err(MetaSchemaError::PatternNotMatched("nickname".to_string())));
+        assert_eq!(schema.user.validate("nickname", &MetaType::<UserMetaAccess>::Null), Ok(()));
+
+        assert_eq!(schema.user.validate("level", &MetaType::Number(10.0, UserMetaAccess::Anonymous)), Err(MetaSchemaError::KeyNotWritable("level".to_string())));
+        assert_eq!(schema.user.validate("unknown", &MetaType::Number(10.0, UserMetaAccess::Anonymous)), Err(MetaSchemaError::KeyNotAllowed("unknown".to_string())));
+
+        let badges = (0..3).map(|index| MetaType::Number(index as f64, UserMetaAccess::Anonymous)).collect::<Vec<_>>();
+        assert_eq!(schema.user.validate("badges", &MetaType::List(Box::new(badges), UserMetaAccess::Anonymous)), Err(MetaSchemaError::LengthTooLong("badges".to_string(), 2)));
+        assert_eq!(schema.user.validate("position", &MetaType::Object(Box::default(), UserMetaAccess::Anonymous)), Ok(()));
+
+        /* Schema access level used only if the meta defined without access level */
+        let metas = schema.user.validate_metas(Some(serde_json::from_str(r#"{"nickname": "erhan", "badges": {"access": 2, "value": []}}"#).unwrap())).unwrap().unwrap();
+
+        assert_eq!(metas.get("nickname"), Some(&MetaType::String("erhan".to_string(), UserMetaAccess::Me)));
+        assert_eq!(metas.get("badges"), Some(&MetaType::List(Box::default(), UserMetaAccess::Friend)));
+
+        /* Explicit anonymous access is not overridden */
+        let metas = schema.user.validate_metas(Some(serde_json::from_str(r#"{"nickname": {"access": 0, "value": "erhan"}}"#).unwrap())).unwrap().unwrap();
+        assert_eq!(metas.get("nickname"), Some(&MetaType::String("erhan".to_string(), UserMetaAccess::Anonymous)));
+    }
+
+    #[test]
+    fn validate_room_metas() {
+        let schema = create_schema();
+
+        assert_eq!(schema.room.validate("round", &MetaType::Number(-1.0, RoomMetaAccess::Anonymous)), Err(MetaSchemaError::NumberTooSmall("round".to_string(), 0.0)));
+        assert_eq!(schema.room.validate("round", &MetaType::Number(1.0, RoomMetaAccess::Anonymous)), Ok(()));
+        assert_eq!(schema.room.validate("unknown", &MetaType::Number(1.0, RoomMetaAccess::Anonymous)), Ok(()));
+        assert_eq!(MetaSchema::default().room.validate("round", &MetaType::Number(-1.0, RoomMetaAccess::Anonymous)), Ok(()));
+    }
+
+    #[test]
+    fn load_schema() -> anyhow::Result<()> {
+        let path = temp_dir().join(format!("{}.json", UserId::new().to_string()));
+        fs::write(&path, r#"{"room": {"strict": true, "keys": {"round": {"type": "number"}}}}"#)?;
+
+        let schema = load_meta_schema(path.to_str().unwrap())?;
+        assert!(schema.room.strict);
+        assert!(!schema.user.strict);
+        assert_eq!(schema.room.keys.get("round").unwrap().meta_type, Some(MetaSchemaType::Number));
+
+        fs::write(&path, r#"{"room": {"keys": {"name": {"pattern": "["}}}}"#)?;
+        assert!(matches!(load_meta_schema(path.to_str().unwrap()), Err(MetaSchemaError::FileNotValid(_, _))));
+        assert!(matches!(load_meta_schema("not-exist.json"), Err(MetaSchemaError::FileCouldNotRead(_))));
+        Ok(())
+    }
+}
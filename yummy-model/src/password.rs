@@ -1,3 +1,6 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use secrecy::*;
 use serde::{
     de::{self, Visitor},
@@ -7,6 +10,15 @@ use serde::{
 #[derive(Debug)]
 pub struct Password(Secret<String>);
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum PasswordVerification {
+    Valid,
+
+    // Password matched with the plain text record, it should be hashed and saved again
+    ValidButLegacy,
+    Invalid
+}
+
 impl Password {
     pub fn get(&self) -> &String {
         self.0.expose_secret()
@@ -15,6 +27,30 @@ impl Password {
     pub fn from(str: String) -> Self {
         Self(Secret::<String>::new(str))
     }
+
+    /* Argon2id hash with per-user salt, serialized as PHC string */
+    pub fn hash(&self) -> anyhow::Result<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(self.get().as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|error| anyhow::anyhow!("Password could not hashed. {}", error))
+    }
+
+    pub fn verify(&self, stored_password: &str) -> PasswordVerification {
+        match PasswordHash::new(stored_password) {
+            Ok(hash) => match Argon2::default().verify_password(self.get().as_bytes(), &hash) {
+                Ok(_) => PasswordVerification::Valid,
+                Err(_) => PasswordVerification::Invalid
+            },
+
+            /* Old records are not in PHC format, they are keeping the plain text password */
+            Err(_) => match !stored_password.is_empty() && stored_password == self.get() {
+                true => PasswordVerification::ValidButLegacy,
+                false => PasswordVerification::Invalid
+            }
+        }
+    }
 }
 
 impl From<&str> for Password {
@@ -2,10 +2,11 @@ use std::collections::HashMap;
 
 use serde_json::Value;
 use strum_macros::EnumDiscriminants;
-use crate::state::RoomInfoTypeVariant;
+use crate::state::{RoomInfoTypeVariant, RoomListFilter};
 
 use crate::password::Password;
-use crate::{UserId, UserType, CreateRoomAccessType, RoomId, RoomUserType, meta::{MetaType, RoomMetaAccess, UserMetaAccess, MetaAction}};
+use crate::{UserId, UserInboxId, SessionId, UserType, CreateRoomAccessType, RoomId, RoomUserType, meta::{MetaDefinitions, RoomMetaAccess, UserMetaAccess, MetaAction}};
+use crate::meta::operation::{UserMetaOperation, RoomMetaOperation};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, EnumDiscriminants, Debug)]
@@ -34,6 +35,12 @@ pub enum RequestAuthType {
         id: String
     },
     
+    #[strum_discriminants(serde(rename = "AuthExternal"))]
+    #[serde(rename = "AuthExternal")]
+    External {
+        token: String
+    },
+    
     #[strum_discriminants(serde(rename = "RefreshToken"))]
     #[serde(rename = "RefreshToken")]
     Refresh {
@@ -43,12 +50,97 @@ pub enum RequestAuthType {
     #[strum_discriminants(serde(rename = "RestoreToken"))]
     #[serde(rename = "RestoreToken")]
     Restore {
-        token: String
+        token: String,
+
+        #[serde(default)]
+        last_sequence: Option<usize>
     },
     
     #[strum_discriminants(serde(rename = "Logout"))]
     #[serde(rename = "Logout")]
-    Logout
+    Logout,
+    
+    #[strum_discriminants(serde(rename = "LinkIdentity"))]
+    #[serde(rename = "LinkIdentity")]
+    LinkIdentity {
+        #[serde(flatten)]
+        identity: RequestIdentity,
+
+        #[serde(default)]
+        merge: bool
+    },
+    
+    #[strum_discriminants(serde(rename = "UnlinkIdentity"))]
+    #[serde(rename = "UnlinkIdentity")]
+    UnlinkIdentity {
+        #[serde(flatten)]
+        identity: RequestUnlinkIdentity
+    },
+    
+    #[strum_discriminants(serde(rename = "VerifyEmail"))]
+    #[serde(rename = "VerifyEmail")]
+    VerifyEmail {
+        token: String
+    },
+    
+    #[strum_discriminants(serde(rename = "SendVerificationEmail"))]
+    #[serde(rename = "SendVerificationEmail")]
+    SendVerificationEmail {
+        email: String
+    },
+    
+    #[strum_discriminants(serde(rename = "ForgotPassword"))]
+    #[serde(rename = "ForgotPassword")]
+    ForgotPassword {
+        email: String
+    },
+    
+    #[strum_discriminants(serde(rename = "ResetPassword"))]
+    #[serde(rename = "ResetPassword")]
+    ResetPassword {
+        token: String,
+        password: Password
+    },
+    
+    #[strum_discriminants(serde(rename = "ListSessions"))]
+    #[serde(rename = "ListSessions")]
+    ListSessions,
+    
+    #[strum_discriminants(serde(rename = "TerminateSession"))]
+    #[serde(rename = "TerminateSession")]
+    TerminateSession {
+        session_id: SessionId
+    }
+}
+
+/* Login methods that could be linked to an authenticated user, credentials are required to prove the ownership */
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "identity")]
+pub enum RequestIdentity {
+    Email {
+        email: String,
+        password: Password
+    },
+    DeviceId {
+        id: String
+    },
+    CustomId {
+        id: String
+    },
+    External {
+        token: String
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "identity")]
+pub enum RequestUnlinkIdentity {
+    Email,
+    DeviceId,
+    CustomId,
+    External {
+        provider: String
+    }
 }
 
 #[derive(Deserialize, Serialize, EnumDiscriminants, Debug)]
@@ -77,10 +169,96 @@ pub enum RequestUserType {
         #[serde(rename = "user_type")]
         user_type: Option<UserType>,
 
-        metas: Option<HashMap<String, MetaType<UserMetaAccess>>>,
+        metas: Option<MetaDefinitions<UserMetaAccess>>,
 
         #[serde(default)]
         meta_action: MetaAction
+    },
+
+    #[strum_discriminants(serde(rename = "AddFriend"))]
+    #[serde(rename = "AddFriend")]
+    AddFriend {
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "AcceptFriendRequest"))]
+    #[serde(rename = "AcceptFriendRequest")]
+    AcceptFriendRequest {
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "RejectFriendRequest"))]
+    #[serde(rename = "RejectFriendRequest")]
+    RejectFriendRequest {
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "RemoveFriend"))]
+    #[serde(rename = "RemoveFriend")]
+    RemoveFriend {
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "FriendList"))]
+    #[serde(rename = "FriendList")]
+    FriendList,
+
+    #[strum_discriminants(serde(rename = "FriendRequests"))]
+    #[serde(rename = "FriendRequests")]
+    FriendRequests,
+
+    #[strum_discriminants(serde(rename = "MessageToUser"))]
+    #[serde(rename = "MessageToUser")]
+    Message {
+        user_id: UserId,
+        message: Value,
+    },
+
+    #[strum_discriminants(serde(rename = "Inbox"))]
+    #[serde(rename = "Inbox")]
+    Inbox {
+        #[serde(default)]
+        limit: Option<usize>,
+
+        #[serde(default)]
+        cursor: Option<String>
+    },
+
+    #[strum_discriminants(serde(rename = "AckInbox"))]
+    #[serde(rename = "AckInbox")]
+    AckInbox {
+        ids: Vec<UserInboxId>
+    },
+
+    #[strum_discriminants(serde(rename = "SuspendUser"))]
+    #[serde(rename = "SuspendUser")]
+    Suspend {
+        user_id: UserId,
+
+        #[serde(default)]
+        reason: Option<String>,
+
+        #[serde(default)]
+        duration: Option<u64>
+    },
+
+    #[strum_discriminants(serde(rename = "UnsuspendUser"))]
+    #[serde(rename = "UnsuspendUser")]
+    Unsuspend {
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "SuspendedUsers"))]
+    #[serde(rename = "SuspendedUsers")]
+    SuspendedUsers,
+
+    #[strum_discriminants(serde(rename = "UserMetaOperation"))]
+    #[serde(rename = "UserMetaOperation")]
+    MetaOperation {
+        key: String,
+
+        #[serde(flatten)]
+        operation: UserMetaOperation
     }
 }
 
@@ -106,11 +284,23 @@ pub enum RequestRoomType {
         #[serde(default)]
         max_user: usize,
 
+        #[serde(default)]
+        max_spectator: usize,
+
         #[serde(default)]
         tags: Vec<String>,
 
         #[serde(default)]
-        metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>
+        metas: Option<MetaDefinitions<RoomMetaAccess>>,
+
+        #[serde(default)]
+        password: Option<String>,
+
+        #[serde(default)]
+        generate_password: bool,
+
+        #[serde(default)]
+        auto_start: bool
     },
 
     #[strum_discriminants(serde(rename = "GetRoom"))]
@@ -129,6 +319,12 @@ pub enum RequestRoomType {
 
         #[serde(default)]
         room_user_type: RoomUserType,
+
+        #[serde(default)]
+        password: Option<String>,
+
+        #[serde(default)]
+        history: Option<usize>,
     },
     
     #[strum_discriminants(serde(rename = "RoomDisconnect"))]
@@ -156,6 +352,9 @@ pub enum RequestRoomType {
     Kick {
         room_id: RoomId,
         user_id: UserId,
+
+        #[serde(default)]
+        reason: Option<String>,
     },
     
     #[strum_discriminants(serde(rename = "BanUserFromRoom"))]
@@ -163,6 +362,19 @@ pub enum RequestRoomType {
     Ban {
         room_id: RoomId,
         user_id: UserId,
+
+        #[serde(default)]
+        reason: Option<String>,
+
+        #[serde(default)]
+        duration: Option<u64>,
+    },
+    
+    #[strum_discriminants(serde(rename = "UnbanUserFromRoom"))]
+    #[serde(rename = "UnbanUserFromRoom")]
+    Unban {
+        room_id: RoomId,
+        user_id: UserId,
     },
 
     #[strum_discriminants(serde(rename = "RoomList"))]
@@ -171,6 +383,9 @@ pub enum RequestRoomType {
         #[serde(default)]
         tag: Option<String>,
 
+        #[serde(flatten)]
+        filter: RoomListFilter,
+
         #[serde(default)]
         members: Vec<RoomInfoTypeVariant>,
     },
@@ -187,7 +402,7 @@ pub enum RequestRoomType {
         description: Option<String>,
 
         #[serde(default)]
-        metas: Option<HashMap<String, MetaType<RoomMetaAccess>>>,
+        metas: Option<MetaDefinitions<RoomMetaAccess>>,
         
         #[serde(default)]
         meta_action: MetaAction,
@@ -201,11 +416,20 @@ pub enum RequestRoomType {
         #[serde(default)]
         max_user: Option<usize>,
         
+        #[serde(default)]
+        max_spectator: Option<usize>,
+        
         #[serde(default)]
         tags: Option<Vec<String>>,
         
         #[serde(default)]
-        user_permission: Option<HashMap<UserId, RoomUserType>>
+        user_permission: Option<HashMap<UserId, RoomUserType>>,
+
+        #[serde(default)]
+        password: Option<String>,
+
+        #[serde(default)]
+        generate_password: bool
     },
 
     #[strum_discriminants(serde(rename = "WaitingRoomJoins"))]
@@ -220,6 +444,105 @@ pub enum RequestRoomType {
         room_id: RoomId,
         user_id: UserId,
         status: bool
+    },
+
+    #[strum_discriminants(serde(rename = "SetReady"))]
+    #[serde(rename = "SetReady")]
+    SetReady {
+        room_id: RoomId,
+        ready: bool
+    },
+
+    #[strum_discriminants(serde(rename = "StartGame"))]
+    #[serde(rename = "StartGame")]
+    StartGame {
+        room_id: RoomId
+    },
+
+    #[strum_discriminants(serde(rename = "FinishGame"))]
+    #[serde(rename = "FinishGame")]
+    FinishGame {
+        room_id: RoomId
+    },
+
+    #[strum_discriminants(serde(rename = "RoomMessageHistory"))]
+    #[serde(rename = "RoomMessageHistory")]
+    MessageHistory {
+        room_id: RoomId,
+
+        #[serde(default)]
+        before: Option<usize>,
+
+        #[serde(default)]
+        limit: Option<usize>
+    },
+
+    #[strum_discriminants(serde(rename = "InviteToRoom"))]
+    #[serde(rename = "InviteToRoom")]
+    Invite {
+        room_id: RoomId,
+        user_id: UserId,
+
+        #[serde(default)]
+        room_user_type: RoomUserType
+    },
+
+    #[strum_discriminants(serde(rename = "AcceptRoomInvite"))]
+    #[serde(rename = "AcceptRoomInvite")]
+    AcceptInvite {
+        room_id: RoomId,
+
+        #[serde(default)]
+        history: Option<usize>
+    },
+
+    #[strum_discriminants(serde(rename = "DeclineRoomInvite"))]
+    #[serde(rename = "DeclineRoomInvite")]
+    DeclineInvite {
+        room_id: RoomId
+    },
+
+    #[strum_discriminants(serde(rename = "RoomInvites"))]
+    #[serde(rename = "RoomInvites")]
+    Invites {
+        room_id: RoomId
+    },
+
+    #[strum_discriminants(serde(rename = "CancelRoomInvite"))]
+    #[serde(rename = "CancelRoomInvite")]
+    CancelInvite {
+        room_id: RoomId,
+        user_id: UserId
+    },
+
+    #[strum_discriminants(serde(rename = "RoomMetaOperation"))]
+    #[serde(rename = "RoomMetaOperation")]
+    MetaOperation {
+        room_id: RoomId,
+        key: String,
+
+        #[serde(flatten)]
+        operation: RoomMetaOperation
+    }
+}
+
+#[derive(Deserialize, Serialize, EnumDiscriminants, Debug)]
+#[strum_discriminants(name(RequestMatchmakingTypeVariant), derive(Deserialize, Serialize))]
+#[serde(tag = "type")]
+pub enum RequestMatchmakingType {
+    #[strum_discriminants(serde(rename = "JoinQueue"))]
+    #[serde(rename = "JoinQueue")]
+    Join {
+        queue: String,
+
+        #[serde(default)]
+        properties: HashMap<String, Value>
+    },
+
+    #[strum_discriminants(serde(rename = "LeaveQueue"))]
+    #[serde(rename = "LeaveQueue")]
+    Leave {
+        queue: String
     }
 }
 
@@ -248,6 +571,14 @@ pub enum Request {
 
         #[serde(flatten)]
         room_type: RequestRoomType
+    },
+    
+    Matchmaking {
+        #[serde(default)]
+        request_id: Option<usize>,
+
+        #[serde(flatten)]
+        matchmaking_type: RequestMatchmakingType
     }
 }
 
@@ -257,6 +588,19 @@ impl From<RequestUserTypeVariant> for &'static str {
             RequestUserTypeVariant::Me => "Me",
             RequestUserTypeVariant::Get => "GetUser",
             RequestUserTypeVariant::Update => "UpdateUser",
+            RequestUserTypeVariant::AddFriend => "AddFriend",
+            RequestUserTypeVariant::AcceptFriendRequest => "AcceptFriendRequest",
+            RequestUserTypeVariant::RejectFriendRequest => "RejectFriendRequest",
+            RequestUserTypeVariant::RemoveFriend => "RemoveFriend",
+            RequestUserTypeVariant::FriendList => "FriendList",
+            RequestUserTypeVariant::FriendRequests => "FriendRequests",
+            RequestUserTypeVariant::Message => "MessageToUser",
+            RequestUserTypeVariant::Inbox => "Inbox",
+            RequestUserTypeVariant::AckInbox => "AckInbox",
+            RequestUserTypeVariant::Suspend => "SuspendUser",
+            RequestUserTypeVariant::Unsuspend => "UnsuspendUser",
+            RequestUserTypeVariant::SuspendedUsers => "SuspendedUsers",
+            RequestUserTypeVariant::MetaOperation => "UserMetaOperation",
         }
     }
 }
@@ -268,9 +612,18 @@ impl From<RequestAuthTypeVariant> for &'static str {
             RequestAuthTypeVariant::Email => "AuthEmail",
             RequestAuthTypeVariant::DeviceId => "AuthDeviceId",
             RequestAuthTypeVariant::CustomId => "AuthCustomId",
+            RequestAuthTypeVariant::External => "AuthExternal",
             RequestAuthTypeVariant::Refresh => "RefreshToken",
             RequestAuthTypeVariant::Restore => "RestoreToken",
             RequestAuthTypeVariant::Logout => "Logout",
+            RequestAuthTypeVariant::LinkIdentity => "LinkIdentity",
+            RequestAuthTypeVariant::UnlinkIdentity => "UnlinkIdentity",
+            RequestAuthTypeVariant::VerifyEmail => "VerifyEmail",
+            RequestAuthTypeVariant::SendVerificationEmail => "SendVerificationEmail",
+            RequestAuthTypeVariant::ForgotPassword => "ForgotPassword",
+            RequestAuthTypeVariant::ResetPassword => "ResetPassword",
+            RequestAuthTypeVariant::ListSessions => "ListSessions",
+            RequestAuthTypeVariant::TerminateSession => "TerminateSession",
         }
     }
 }
@@ -287,10 +640,31 @@ impl From<RequestRoomTypeVariant> for &'static str {
             RequestRoomTypeVariant::Play => "Play",
             RequestRoomTypeVariant::Kick => "KickUserFromRoom",
             RequestRoomTypeVariant::Ban => "BanUserFromRoom",
+            RequestRoomTypeVariant::Unban => "UnbanUserFromRoom",
             RequestRoomTypeVariant::List => "RoomList",
             RequestRoomTypeVariant::Update => "UpdateUser",
             RequestRoomTypeVariant::WaitingRoomJoins => "WaitingRoomJoins",
             RequestRoomTypeVariant::ProcessWaitingUser => "ProcessWaitingUser",
+            RequestRoomTypeVariant::SetReady => "SetReady",
+            RequestRoomTypeVariant::StartGame => "StartGame",
+            RequestRoomTypeVariant::FinishGame => "FinishGame",
+            RequestRoomTypeVariant::MessageHistory => "RoomMessageHistory",
+            RequestRoomTypeVariant::Invite => "InviteToRoom",
+            RequestRoomTypeVariant::AcceptInvite => "AcceptRoomInvite",
+            RequestRoomTypeVariant::DeclineInvite => "DeclineRoomInvite",
+            RequestRoomTypeVariant::Invites => "RoomInvites",
+            RequestRoomTypeVariant::CancelInvite => "CancelRoomInvite",
+            RequestRoomTypeVariant::MetaOperation => "RoomMetaOperation",
         }
     }
-}
\ No newline at end of file
+}
+
+
+impl From<RequestMatchmakingTypeVariant> for &'static str {
+    fn from(value: RequestMatchmakingTypeVariant) -> Self {
+        match value {
+            RequestMatchmakingTypeVariant::Join => "JoinQueue",
+            RequestMatchmakingTypeVariant::Leave => "LeaveQueue",
+        }
+    }
+}
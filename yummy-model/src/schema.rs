@@ -8,6 +8,7 @@ table! {
         custom_id -> Nullable<Text>,
         password -> Nullable<Text>,
         user_type -> Integer,
+        email_verified -> Integer,
         insert_date -> Integer,
         last_login_date -> Integer,
     }
@@ -22,6 +23,7 @@ table! {
         meta_type -> Integer,
         access -> Integer,
         insert_date -> Integer,
+        expire_date -> Nullable<Integer>,
     }
 }
 
@@ -31,6 +33,7 @@ table! {
         name ->  Nullable<Text>,
         description ->  Nullable<Text>,
         max_user -> Integer,
+        max_spectator -> Integer,
         password -> Nullable<Text>,
         access_type -> Integer,
         join_request -> Integer,
@@ -75,7 +78,9 @@ table! {
         room_id -> Text,
         user_id -> Text,
         blocker_user_id -> Text,
+        reason -> Nullable<Text>,
         insert_date -> Integer,
+        expire_date -> Nullable<Integer>,
     }
 }
 
@@ -88,7 +93,79 @@ table! {
         meta_type -> Integer,
         access -> Integer,
         insert_date -> Integer,
+        expire_date -> Nullable<Integer>,
     }
 }
 
-allow_tables_to_appear_in_same_query!(user, user_meta, room, room_tag, room_user, room_meta, room_user_request, room_user_ban);
+table! {
+    user_friend_request {
+        id -> Text,
+        user_id -> Text,
+        target_user_id -> Text,
+        insert_date -> Integer,
+    }
+}
+
+table! {
+    user_friend {
+        id -> Text,
+        user_id -> Text,
+        friend_user_id -> Text,
+        insert_date -> Integer,
+    }
+}
+
+table! {
+    user_inbox {
+        id -> Text,
+        user_id -> Text,
+        sender_user_id -> Text,
+        message -> Text,
+        insert_date -> Integer,
+    }
+}
+
+table! {
+    user_suspension {
+        id -> Text,
+        user_id -> Text,
+        suspender_user_id -> Text,
+        reason -> Nullable<Text>,
+        insert_date -> Integer,
+        expire_date -> Nullable<Integer>,
+    }
+}
+
+table! {
+    user_identity {
+        id -> Text,
+        user_id -> Text,
+        provider -> Text,
+        subject -> Text,
+        insert_date -> Integer,
+    }
+}
+
+table! {
+    user_token {
+        id -> Text,
+        user_id -> Text,
+        token_type -> Integer,
+        token -> Text,
+        insert_date -> Integer,
+        expire_date -> Integer,
+    }
+}
+
+table! {
+    room_message {
+        id -> Text,
+        room_id -> Text,
+        sequence -> Integer,
+        user_id -> Nullable<Text>,
+        message -> Text,
+        insert_date -> Integer,
+    }
+}
+
+allow_tables_to_appear_in_same_query!(user, user_meta, user_friend, user_friend_request, user_inbox, user_suspension, user_identity, user_token, room, room_tag, room_user, room_meta, room_user_request, room_user_ban, room_message);
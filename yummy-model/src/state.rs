@@ -5,12 +5,14 @@
 use std::collections::{HashMap, HashSet};
 use std::borrow::Cow;
 use std::sync::Arc;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
 use serde::de::Visitor;
 use crate::meta::{RoomMetaAccess, MetaType};
-use crate::{UserId, RoomUserType, CreateRoomAccessType, RoomId};
+use crate::meta::operation::MetaOperationError;
+use crate::{UserId, SessionId, RoomUserType, CreateRoomAccessType, RoomId, RoomGameState};
 use serde::ser::SerializeMap;
+use serde_repr::{Serialize_repr, Deserialize_repr};
 use strum_macros::EnumDiscriminants;
 use serde::{Serialize, Deserialize, Serializer, Deserializer};
 use thiserror::Error;
@@ -52,9 +54,118 @@ pub struct RoomUserInformation {
     pub user_type: RoomUserType
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoomListFilter {
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    #[serde(default)]
+    pub match_all_tags: bool,
+
+    #[serde(default)]
+    pub access_type: Option<CreateRoomAccessType>,
+
+    #[serde(default)]
+    pub game_state: Option<RoomGameState>,
+
+    #[serde(default)]
+    pub joinable: bool,
+
+    #[serde(default)]
+    pub has_free_slot: bool,
+
+    #[serde(default)]
+    pub metas: Vec<RoomMetaFilter>,
+
+    #[serde(default)]
+    pub sort: RoomListSort,
+
+    #[serde(default)]
+    pub descending: bool,
+
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    #[serde(default)]
+    pub limit: usize
+}
+
+/* Only public (anonymous) room metas could be used for filtering */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoomMetaFilter {
+    pub key: String,
+
+    #[serde(default)]
+    pub value: Option<MetaType<RoomMetaAccess>>,
+
+    #[serde(default)]
+    pub min: Option<f64>,
+
+    #[serde(default)]
+    pub max: Option<f64>
+}
+
+/* Cursor points to the last room of the previous page. Rooms are ordered by sort value, then room id */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomListCursor {
+    pub score: i64,
+    pub room_id: String
+}
+
+/* Message kept in the room history. Id is the order of the message in the room */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomMessage {
+    pub id: usize,
+    pub user_id: Option<UserId>,
+    pub message: serde_json::Value,
+    pub insert_date: i32
+}
+
+/* Ban without expire date is permanent */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomBan {
+    pub reason: Option<String>,
+    pub insert_date: i32,
+    pub expire_date: Option<i32>
+}
+
+/* Pending invitation for a user, invitation is ignored after the expire date */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomInvite {
+    pub user_id: UserId,
+    pub inviter: UserId,
+    pub room_user_type: RoomUserType,
+    pub insert_date: i32,
+    pub expire_date: i32
+}
+
+/* Connection details of the client, ip and client informations are provided by the transport layer */
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub client: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserSession {
+    pub session_id: SessionId,
+    pub insert_date: i32,
+    pub last_activity: i32,
+    pub ip: Option<String>,
+    pub client: Option<String>
+}
+
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
 /* **************************************************************************************************************** */
+#[derive(Default, Copy, Clone, Debug, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RoomListSort {
+    #[default]
+    InsertDate = 0,
+    UserCount = 1
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, PartialEq, Deserialize)]
 #[strum_discriminants(name(RoomInfoTypeVariant))]
 pub enum RoomInfoType {
@@ -68,7 +179,14 @@ pub enum RoomInfoType {
     Metas(HashMap<String, MetaType<RoomMetaAccess>>),
     InsertDate(i32),
     JoinRequest(bool),
-    BannedUsers(HashSet<UserId>)
+    BannedUsers(HashMap<UserId, RoomBan>),
+    Password(Option<String>),
+    Spectators(Vec<RoomUserInformation>),
+    MaxSpectator(usize),
+    SpectatorLength(usize),
+    GameState(RoomGameState),
+    ReadyUsers(HashSet<UserId>),
+    AutoStart(bool)
 }
 
 #[derive(Error, Debug)]
@@ -91,9 +209,21 @@ pub enum YummyStateError {
     #[error("Room has max users")]
     RoomHasMaxUsers,
     
+    #[error("Room has max spectators")]
+    RoomHasMaxSpectators,
+    
+    #[error("User already in queue")]
+    UserAlreadyInQueue,
+    
     #[error("Cache could not readed")]
     CacheCouldNotReaded,
     
+    #[error("Meta access level is not enough")]
+    MetaAccessLevelNotEnough,
+    
+    #[error("{0}")]
+    MetaOperationFailed(#[from] MetaOperationError),
+    
     #[error("Cache error {0}")]
     CacheError(#[from] anyhow::Error)
 }
@@ -102,8 +232,110 @@ pub enum YummyStateError {
 /* ************************************************** FUNCTIONS *************************************************** */
 /* *************************************************** TRAITS ***************************************************** */
 /* ************************************************* IMPLEMENTS *************************************************** */
+/* **************************************************************************************************************** */
+impl RoomBan {
+    pub fn is_active(&self, now: i32) -> bool {
+        self.expire_date.map(|expire_date| expire_date > now).unwrap_or(true)
+    }
+}
+
+impl RoomListFilter {
+    pub fn is_tags_match(&self, tags: &[String]) -> bool {
+        match self.match_all_tags {
+            true => self.tags.iter().all(|tag| tags.contains(tag)),
+            false => self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag))
+        }
+    }
+
+    /* Joinable rooms are the rooms that game is not started yet */
+    pub fn is_game_state_match(&self, game_state: RoomGameState) -> bool {
+        if let Some(filter_game_state) = self.game_state {
+            if filter_game_state != game_state {
+                return false;
+            }
+        }
+
+        !self.joinable || Self::is_joinable_state(game_state)
+    }
+
+    pub fn is_joinable_state(game_state: RoomGameState) -> bool {
+        matches!(game_state, RoomGameState::Waiting | RoomGameState::ReadyCheck)
+    }
+
+    pub fn get_cursor(&self) -> Option<RoomListCursor> {
+        self.cursor.as_ref().and_then(|cursor| RoomListCursor::parse(cursor))
+    }
+
+    /* Zero means server default. Client could not ask more than configured page size */
+    pub fn get_limit(&self, max_limit: usize) -> usize {
+        match self.limit {
+            0 => max_limit,
+            limit => limit.min(max_limit)
+        }
+    }
+}
+
+impl RoomMetaFilter {
+    pub fn is_match(&self, meta: Option<&MetaType<RoomMetaAccess>>) -> bool {
+        let meta = match meta {
+            Some(meta) if meta.get_access_level() == RoomMetaAccess::Anonymous && !meta.is_expired() => meta.get_value(),
+            _ => return false
+        };
+
+        if let Some(value) = &self.value {
+            // Access levels are not part of the value
+            if serde_json::to_value(value).ok() != serde_json::to_value(meta).ok() {
+                return false;
+            }
+        }
+
+        if self.min.is_some() || self.max.is_some() {
+            let number = match meta {
+                MetaType::Number(number, _) => *number,
+                _ => return false
+            };
+
+            if self.min.map(|min| number < min).unwrap_or_default() || self.max.map(|max| number > max).unwrap_or_default() {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl RoomListCursor {
+    pub fn new(score: i64, room_id: String) -> Self {
+        Self { score, room_id }
+    }
+
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let (score, room_id) = cursor.split_once(':')?;
+        Some(Self {
+            score: score.parse().ok()?,
+            room_id: room_id.to_string()
+        })
+    }
+
+    /* Returns true if the room comes after the cursor with the requested order */
+    pub fn is_after(&self, score: i64, room_id: &str, descending: bool) -> bool {
+        let ordering = (score, room_id).cmp(&(self.score, &self.room_id[..]));
+        match descending {
+            true => ordering == std::cmp::Ordering::Less,
+            false => ordering == std::cmp::Ordering::Greater
+        }
+    }
+}
+
+/* **************************************************************************************************************** */
 /* ********************************************** TRAIT IMPLEMENTS ************************************************ */
 /* **************************************************************************************************************** */
+impl Display for RoomListCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.score, self.room_id)
+    }
+}
+
 impl From<RoomInfoTypeVariant> for u32 {
     fn from(value: RoomInfoTypeVariant) -> Self {
         match value {
@@ -118,6 +350,13 @@ impl From<RoomInfoTypeVariant> for u32 {
             RoomInfoTypeVariant::InsertDate => 8,
             RoomInfoTypeVariant::JoinRequest => 9,
             RoomInfoTypeVariant::BannedUsers => 10,
+            RoomInfoTypeVariant::Password => 11,
+            RoomInfoTypeVariant::Spectators => 12,
+            RoomInfoTypeVariant::MaxSpectator => 13,
+            RoomInfoTypeVariant::SpectatorLength => 14,
+            RoomInfoTypeVariant::GameState => 15,
+            RoomInfoTypeVariant::ReadyUsers => 16,
+            RoomInfoTypeVariant::AutoStart => 17,
         }
     }
 }
@@ -136,6 +375,13 @@ impl From<u32> for RoomInfoTypeVariant {
             8 => RoomInfoTypeVariant::InsertDate,
             9 => RoomInfoTypeVariant::JoinRequest,
             10 => RoomInfoTypeVariant::BannedUsers,
+            11 => RoomInfoTypeVariant::Password,
+            12 => RoomInfoTypeVariant::Spectators,
+            13 => RoomInfoTypeVariant::MaxSpectator,
+            14 => RoomInfoTypeVariant::SpectatorLength,
+            15 => RoomInfoTypeVariant::GameState,
+            16 => RoomInfoTypeVariant::ReadyUsers,
+            17 => RoomInfoTypeVariant::AutoStart,
             _ => RoomInfoTypeVariant::RoomName
         }
     }
@@ -205,6 +451,13 @@ impl Serialize for RoomInfoTypeCollection {
                 RoomInfoType::BannedUsers(banned_users) => items.serialize_entry("banned-users", banned_users),
                 RoomInfoType::InsertDate(insert_date) => items.serialize_entry("insert-date", insert_date),
                 RoomInfoType::JoinRequest(join_request) => items.serialize_entry("join-request", join_request),
+                RoomInfoType::Password(password) => items.serialize_entry("password", password),
+                RoomInfoType::Spectators(spectators) => items.serialize_entry("spectators", spectators),
+                RoomInfoType::MaxSpectator(max_spectator) => items.serialize_entry("max-spectator", max_spectator),
+                RoomInfoType::SpectatorLength(spectator_length) => items.serialize_entry("spectator-length", spectator_length),
+                RoomInfoType::GameState(game_state) => items.serialize_entry("game-state", game_state),
+                RoomInfoType::ReadyUsers(ready_users) => items.serialize_entry("ready-users", ready_users),
+                RoomInfoType::AutoStart(auto_start) => items.serialize_entry("auto-start", auto_start),
             }?;
         }
         
@@ -226,10 +479,94 @@ impl RoomInfoTypeCollection {
     generate_room_type_getter!(get_metas, RoomInfoType::Metas, HashMap<String, MetaType<RoomMetaAccess>>);
     generate_room_type_getter!(get_insert_date, RoomInfoType::InsertDate, i32);
     generate_room_type_getter!(get_join_request, RoomInfoType::JoinRequest, bool);
-    generate_room_type_getter!(get_banned_users, RoomInfoType::BannedUsers, HashSet<UserId>);
+    generate_room_type_getter!(get_banned_users, RoomInfoType::BannedUsers, HashMap<UserId, RoomBan>);
+    generate_room_type_getter!(get_password, RoomInfoType::Password, Option<String>);
+    generate_room_type_getter!(get_spectators, RoomInfoType::Spectators, Vec<RoomUserInformation>);
+    generate_room_type_getter!(get_max_spectator, RoomInfoType::MaxSpectator, usize);
+    generate_room_type_getter!(get_spectator_length, RoomInfoType::SpectatorLength, usize);
+    generate_room_type_getter!(get_game_state, RoomInfoType::GameState, RoomGameState);
+    generate_room_type_getter!(get_ready_users, RoomInfoType::ReadyUsers, HashSet<UserId>);
+    generate_room_type_getter!(get_auto_start, RoomInfoType::AutoStart, bool);
 
 }
 
 /* **************************************************************************************************************** */
 /* ************************************************** UNIT TESTS ************************************************** */
-/* **************************************************************************************************************** */
\ No newline at end of file
+/* **************************************************************************************************************** */
+#[cfg(test)]
+mod test {
+    use crate::RoomGameState;
+    use crate::meta::{MetaType, RoomMetaAccess};
+    use crate::state::{RoomListCursor, RoomListFilter, RoomMetaFilter};
+
+    #[test]
+    fn room_list_cursor() {
+        let cursor = RoomListCursor::parse("10:room").unwrap();
+        assert_eq!(cursor, RoomListCursor::new(10, "room".to_string()));
+        assert_eq!(cursor.to_string(), "10:room".to_string());
+
+        assert!(RoomListCursor::parse("room").is_none());
+        assert!(RoomListCursor::parse("abc:room").is_none());
+
+        assert!(cursor.is_after(11, "a", false));
+        assert!(cursor.is_after(10, "roon", false));
+        assert!(!cursor.is_after(10, "room", false));
+        assert!(!cursor.is_after(9, "z", false));
+
+        assert!(cursor.is_after(9, "z", true));
+        assert!(!cursor.is_after(10, "room", true));
+        assert!(!cursor.is_after(11, "a", true));
+    }
+
+    #[test]
+    fn room_list_filter() {
+        let filter = RoomListFilter {
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+            joinable: true,
+            ..Default::default()
+        };
+
+        assert!(filter.is_tags_match(&["tag 2".to_string()]));
+        assert!(!filter.is_tags_match(&["tag 3".to_string()]));
+        assert!(filter.is_game_state_match(RoomGameState::ReadyCheck));
+        assert!(!filter.is_game_state_match(RoomGameState::Playing));
+
+        let filter = RoomListFilter {
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+            match_all_tags: true,
+            limit: 100,
+            ..Default::default()
+        };
+
+        assert!(!filter.is_tags_match(&["tag 2".to_string()]));
+        assert!(filter.is_tags_match(&["tag 1".to_string(), "tag 2".to_string(), "tag 3".to_string()]));
+        assert!(filter.is_game_state_match(RoomGameState::Playing));
+        assert_eq!(filter.get_limit(50), 50);
+        assert_eq!(RoomListFilter::default().get_limit(50), 50);
+    }
+
+    #[test]
+    fn room_meta_filter() {
+        let filter = RoomMetaFilter {
+            key: "level".to_string(),
+            min: Some(10.0),
+            max: Some(20.0),
+            ..Default::default()
+        };
+
+        assert!(filter.is_match(Some(&MetaType::Number(15.0, RoomMetaAccess::Anonymous))));
+        assert!(!filter.is_match(Some(&MetaType::Number(25.0, RoomMetaAccess::Anonymous))));
+        assert!(!filter.is_match(Some(&MetaType::Number(15.0, RoomMetaAccess::User))));
+        assert!(!filter.is_match(Some(&MetaType::String("15".to_string(), RoomMetaAccess::Anonymous))));
+        assert!(!filter.is_match(None));
+
+        let filter = RoomMetaFilter {
+            key: "map".to_string(),
+            value: Some(MetaType::String("desert".to_string(), RoomMetaAccess::Anonymous)),
+            ..Default::default()
+        };
+
+        assert!(filter.is_match(Some(&MetaType::String("desert".to_string(), RoomMetaAccess::Anonymous))));
+        assert!(!filter.is_match(Some(&MetaType::String("forest".to_string(), RoomMetaAccess::Anonymous))));
+    }
+}
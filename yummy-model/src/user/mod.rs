@@ -1,13 +1,20 @@
 use std::borrow::Cow;
 
 use crate::RoomId;
+use crate::RoomMessageId;
 use crate::RoomMetaId;
 use crate::RoomTagId;
 use crate::RoomUserBanId;
 use crate::RoomUserId;
 use crate::RoomUserRequestId;
+use crate::UserFriendId;
+use crate::UserFriendRequestId;
+use crate::UserInboxId;
 use crate::UserId;
 use crate::UserMetaId;
+use crate::UserSuspensionId;
+use crate::UserIdentityId;
+use crate::UserTokenId;
 use crate::UserType;
 use crate::schema::*;
 use diesel::*;
@@ -39,6 +46,86 @@ pub struct UserMetaInsert<'a> {
     pub meta_type: i32,
     pub access: i32,
     pub insert_date: i32,
+    pub expire_date: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_friend_request)]
+pub struct UserFriendRequestInsert<'a> {
+    pub id: UserFriendRequestId,
+    pub user_id: &'a UserId,
+    pub target_user_id: &'a UserId,
+    pub insert_date: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_friend)]
+pub struct UserFriendInsert<'a> {
+    pub id: UserFriendId,
+    pub user_id: &'a UserId,
+    pub friend_user_id: &'a UserId,
+    pub insert_date: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_inbox)]
+pub struct UserInboxInsert<'a> {
+    pub id: UserInboxId,
+    pub user_id: &'a UserId,
+    pub sender_user_id: &'a UserId,
+    pub message: String,
+    pub insert_date: i32,
+}
+
+#[derive(Clone, Debug, Queryable)]
+#[diesel(table_name = user_inbox)]
+pub struct UserInboxModel {
+    pub id: UserInboxId,
+    pub sender_user_id: UserId,
+    pub message: String,
+    pub insert_date: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_suspension)]
+pub struct UserSuspensionInsert<'a> {
+    pub id: UserSuspensionId,
+    pub user_id: &'a UserId,
+    pub suspender_user_id: &'a UserId,
+    pub reason: Option<&'a str>,
+    pub insert_date: i32,
+    pub expire_date: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_identity)]
+pub struct UserIdentityInsert<'a> {
+    pub id: UserIdentityId,
+    pub user_id: &'a UserId,
+    pub provider: &'a str,
+    pub subject: &'a str,
+    pub insert_date: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = user_token)]
+pub struct UserTokenInsert<'a> {
+    pub id: UserTokenId,
+    pub user_id: &'a UserId,
+    pub token_type: i32,
+    pub token: &'a str,
+    pub insert_date: i32,
+    pub expire_date: i32,
+}
+
+#[derive(Clone, Debug, Queryable, Serialize, Deserialize, PartialEq, Eq)]
+#[diesel(table_name = user_suspension)]
+pub struct UserSuspensionModel {
+    pub user_id: UserId,
+    pub suspender_user_id: UserId,
+    pub reason: Option<String>,
+    pub insert_date: i32,
+    pub expire_date: Option<i32>,
 }
 
 #[derive(Default, Debug, Insertable)]
@@ -46,8 +133,10 @@ pub struct UserMetaInsert<'a> {
 pub struct RoomInsert {
     pub id: RoomId,
     pub name: Option<String>,
+    pub password: Option<String>,
     pub access_type: i32,
     pub max_user: i32,
+    pub max_spectator: i32,
     pub join_request: i32,
     pub insert_date: i32,
 }
@@ -58,8 +147,10 @@ pub struct RoomUpdate<'a> {
     pub name: Option<Option<&'a str>>,
     pub description: Option<Option<&'a str>>,
     pub max_user: Option<i32>,
+    pub max_spectator: Option<i32>,
     pub join_request: Option<i32>,
     pub access_type: Option<i32>,
+    pub password: Option<Option<&'a str>>,
 }
 
 #[derive(Debug, Insertable)]
@@ -100,6 +191,28 @@ pub struct RoomUserBanInsert<'a> {
     pub room_id: &'a RoomId,
     pub user_id: &'a UserId,
     pub blocker_user_id: &'a UserId,
+    pub reason: Option<&'a str>,
+    pub insert_date: i32,
+    pub expire_date: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = room_message)]
+pub struct RoomMessageInsert<'a> {
+    pub id: RoomMessageId,
+    pub room_id: &'a RoomId,
+    pub sequence: i32,
+    pub user_id: Option<&'a UserId>,
+    pub message: String,
+    pub insert_date: i32,
+}
+
+#[derive(Clone, Debug, Queryable)]
+#[diesel(table_name = room_message)]
+pub struct RoomMessageModel {
+    pub sequence: i32,
+    pub user_id: Option<UserId>,
+    pub message: String,
     pub insert_date: i32,
 }
 
@@ -113,6 +226,7 @@ pub struct RoomMetaInsert<'a> {
     pub meta_type: i32,
     pub access: i32,
     pub insert_date: i32,
+    pub expire_date: Option<i32>,
 }
 
 #[derive(Default, Clone, Debug, Queryable, Serialize, Deserialize, PartialEq, Eq)]
@@ -123,6 +237,7 @@ pub struct RoomMetaModel {
     pub value: String,
     pub meta_type: i32,
     pub access: i32,
+    pub expire_date: Option<i32>,
 }
 
 #[derive(Default, Debug, AsChangeset)]
@@ -134,6 +249,7 @@ pub struct UserUpdate {
     pub user_type: Option<i32>,
     pub device_id: Option<Option<String>>,
     pub custom_id: Option<Option<String>>,
+    pub email_verified: Option<i32>,
 }
 
 #[derive(Default, Clone, Debug, Queryable, Serialize, Deserialize, PartialEq, Eq)]
@@ -144,6 +260,7 @@ pub struct UserMetaModel {
     pub value: String,
     pub meta_type: i32,
     pub access: i32,
+    pub expire_date: Option<i32>,
 }
 
 pub struct LoginInfo {
@@ -151,5 +268,58 @@ pub struct LoginInfo {
     pub name: Option<String>,
     pub email: Option<String>,
     pub password: Option<String>,
-    pub user_type: UserType
+    pub user_type: UserType,
+    pub email_verified: bool
+}
+
+/* One-time tokens sent to the user's email address */
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum UserTokenType {
+    EmailVerification = 1,
+    PasswordReset = 2
+}
+
+/* Inbox pages are ordered by the insert date and message id, cursor keeps the last returned message */
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InboxCursor {
+    pub insert_date: i32,
+    pub id: UserInboxId
+}
+
+impl InboxCursor {
+    pub fn new(insert_date: i32, id: UserInboxId) -> Self {
+        Self { insert_date, id }
+    }
+
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let (insert_date, id) = cursor.split_once(':')?;
+        Some(Self {
+            insert_date: insert_date.parse().ok()?,
+            id: UserInboxId(uuid::Uuid::parse_str(id).ok()?)
+        })
+    }
+}
+
+impl std::fmt::Display for InboxCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.insert_date, self.id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::InboxCursor;
+    use crate::UserInboxId;
+
+    #[test]
+    fn inbox_cursor() {
+        let id = UserInboxId::new();
+        let cursor = InboxCursor::parse(&format!("10:{}", id.to_string())).unwrap();
+        assert_eq!(cursor, InboxCursor::new(10, id.clone()));
+        assert_eq!(cursor.to_string(), format!("10:{}", id.to_string()));
+
+        assert!(InboxCursor::parse("10").is_none());
+        assert!(InboxCursor::parse("date:room").is_none());
+        assert!(InboxCursor::parse("10:room").is_none());
+    }
 }
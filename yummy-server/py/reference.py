@@ -25,6 +25,62 @@ def post_customid_auth(model: model.CustomIdAuth, success: bool):
     pass
 
 
+def pre_external_auth(model: model.ExternalAuth):
+    pass
+
+
+def post_external_auth(model: model.ExternalAuth, success: bool):
+    pass
+
+
+def pre_link_identity(model: model.LinkIdentity):
+    pass
+
+
+def post_link_identity(model: model.LinkIdentity, success: bool):
+    pass
+
+
+def pre_unlink_identity(model: model.UnlinkIdentity):
+    pass
+
+
+def post_unlink_identity(model: model.UnlinkIdentity, success: bool):
+    pass
+
+
+def pre_verify_email(model: model.VerifyEmail):
+    pass
+
+
+def post_verify_email(model: model.VerifyEmail, success: bool):
+    pass
+
+
+def pre_send_verification_email(model: model.SendVerificationEmail):
+    pass
+
+
+def post_send_verification_email(model: model.SendVerificationEmail, success: bool):
+    pass
+
+
+def pre_forgot_password(model: model.ForgotPassword):
+    pass
+
+
+def post_forgot_password(model: model.ForgotPassword, success: bool):
+    pass
+
+
+def pre_reset_password(model: model.ResetPassword):
+    pass
+
+
+def post_reset_password(model: model.ResetPassword, success: bool):
+    pass
+
+
 def pre_logout(model: model.Logout):
     pass
 
@@ -57,6 +113,22 @@ def post_refresh_token(model: model.RefreshToken, success: bool):
     pass
 
 
+def pre_list_sessions(model: model.ListSessions):
+    pass
+
+
+def post_list_sessions(model: model.ListSessions, success: bool):
+    pass
+
+
+def pre_terminate_session(model: model.TerminateSession):
+    pass
+
+
+def post_terminate_session(model: model.TerminateSession, success: bool):
+    pass
+
+
 def pre_get_user_information(model: model.GetUserInformation):
     pass
 
@@ -73,6 +145,94 @@ def post_update_user(model: model.UpdateUser, success: bool):
     pass
 
 
+def pre_update_user_meta(model: model.UpdateUserMeta):
+    pass
+
+
+def post_update_user_meta(model: model.UpdateUserMeta, success: bool):
+    pass
+
+
+def pre_add_friend(model: model.AddFriend):
+    pass
+
+
+def post_add_friend(model: model.AddFriend, success: bool):
+    pass
+
+
+def pre_process_friend_request(model: model.ProcessFriendRequest):
+    pass
+
+
+def post_process_friend_request(model: model.ProcessFriendRequest, success: bool):
+    pass
+
+
+def pre_remove_friend(model: model.RemoveFriend):
+    pass
+
+
+def post_remove_friend(model: model.RemoveFriend, success: bool):
+    pass
+
+
+def pre_friend_list(model: model.FriendList):
+    pass
+
+
+def post_friend_list(model: model.FriendList, success: bool):
+    pass
+
+
+def pre_message_to_user(model: model.MessageToUser):
+    pass
+
+
+def post_message_to_user(model: model.MessageToUser, success: bool):
+    pass
+
+
+def pre_get_inbox(model: model.GetInbox):
+    pass
+
+
+def post_get_inbox(model: model.GetInbox, success: bool):
+    pass
+
+
+def pre_ack_inbox(model: model.AckInbox):
+    pass
+
+
+def post_ack_inbox(model: model.AckInbox, success: bool):
+    pass
+
+
+def pre_suspend_user(model: model.SuspendUser):
+    pass
+
+
+def post_suspend_user(model: model.SuspendUser, success: bool):
+    pass
+
+
+def pre_unsuspend_user(model: model.UnsuspendUser):
+    pass
+
+
+def post_unsuspend_user(model: model.UnsuspendUser, success: bool):
+    pass
+
+
+def pre_suspended_users(model: model.SuspendedUsers):
+    pass
+
+
+def post_suspended_users(model: model.SuspendedUsers, success: bool):
+    pass
+
+
 def pre_create_room(model: model.CreateRoom):
     pass
 
@@ -89,6 +249,14 @@ def post_update_room(model: model.UpdateRoom, success: bool):
     pass
 
 
+def pre_update_room_meta(model: model.UpdateRoomMeta):
+    pass
+
+
+def post_update_room_meta(model: model.UpdateRoomMeta, success: bool):
+    pass
+
+
 def pre_join_to_room(model: model.UpdateRoom):
     pass
 
@@ -113,6 +281,14 @@ def post_kick_user_from_room(model: model.KickUserFromRoom, success: bool):
     pass
 
 
+def pre_unban_user_from_room(model: model.UnbanUserFromRoom):
+    pass
+
+
+def post_unban_user_from_room(model: model.UnbanUserFromRoom, success: bool):
+    pass
+
+
 def pre_disconnect_from_room(model: model.DisconnectFromRoom):
     pass
 
@@ -159,3 +335,115 @@ def pre_play(model: model.Play):
 
 def post_play(model: model.Play, success: bool):
     pass
+
+
+def pre_set_ready(model: model.SetReady):
+    pass
+
+
+def post_set_ready(model: model.SetReady, success: bool):
+    pass
+
+
+def pre_start_game(model: model.StartGame):
+    pass
+
+
+def post_start_game(model: model.StartGame, success: bool):
+    pass
+
+
+def pre_finish_game(model: model.FinishGame):
+    pass
+
+
+def post_finish_game(model: model.FinishGame, success: bool):
+    pass
+
+
+def pre_select_new_owner(model: model.SelectNewOwner):
+    pass
+
+
+def post_select_new_owner(model: model.SelectNewOwner, success: bool):
+    pass
+
+
+def pre_room_message_history(model: model.RoomMessageHistory):
+    pass
+
+
+def post_room_message_history(model: model.RoomMessageHistory, success: bool):
+    pass
+
+
+def pre_invite_to_room(model: model.InviteToRoom):
+    pass
+
+
+def post_invite_to_room(model: model.InviteToRoom, success: bool):
+    pass
+
+
+def pre_accept_room_invite(model: model.AcceptRoomInvite):
+    pass
+
+
+def post_accept_room_invite(model: model.AcceptRoomInvite, success: bool):
+    pass
+
+
+def pre_decline_room_invite(model: model.DeclineRoomInvite):
+    pass
+
+
+def post_decline_room_invite(model: model.DeclineRoomInvite, success: bool):
+    pass
+
+
+def pre_room_invites(model: model.RoomInvites):
+    pass
+
+
+def post_room_invites(model: model.RoomInvites, success: bool):
+    pass
+
+
+def pre_cancel_room_invite(model: model.CancelRoomInvite):
+    pass
+
+
+def post_cancel_room_invite(model: model.CancelRoomInvite, success: bool):
+    pass
+
+
+def pre_join_queue(model: model.JoinQueue):
+    pass
+
+
+def post_join_queue(model: model.JoinQueue, success: bool):
+    pass
+
+
+def pre_leave_queue(model: model.LeaveQueue):
+    pass
+
+
+def post_leave_queue(model: model.LeaveQueue, success: bool):
+    pass
+
+
+def pre_match_found(model: model.MatchFound):
+    pass
+
+
+def post_match_found(model: model.MatchFound, success: bool):
+    pass
+
+
+def pre_check_match_compatibility(model: model.CheckMatchCompatibility):
+    pass
+
+
+def post_check_match_compatibility(model: model.CheckMatchCompatibility, success: bool):
+    pass
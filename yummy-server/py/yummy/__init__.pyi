@@ -6,7 +6,7 @@ import room
 from typing import NoReturn
 
 
-MetaType = int | str | bool | list['MetaType']
+MetaType = int | str | bool | list['MetaType'] | dict[str, 'MetaType']
 
 def fail(message: str) -> NoReturn:
     """ Throw exception with error message. Message will be sent to client. """
@@ -19,9 +19,10 @@ META_ACTION_REMOVE_UNUSED_METAS: int
 META_ACTION_REMOVE_ALL_METAS: int
 
 # RoomUserType
-ROOM_TYPE_TYPE_USER: int
-ROOM_TYPE_TYPE_MODERATOR: int
-ROOM_TYPE_TYPE_OWNER: int
+ROOM_USER_TYPE_USER: int
+ROOM_USER_TYPE_MODERATOR: int
+ROOM_USER_TYPE_OWNER: int
+ROOM_USER_TYPE_SPECTATOR: int
 
 # RoomInfoType
 ROOM_INFO_TYPE_ROOM_NAME: int
@@ -35,3 +36,16 @@ ROOM_INFO_TYPE_INSERT_DATE: int
 ROOM_INFO_TYPE_TAGS: int
 ROOM_INFO_TYPE_BANNED_USERS: int
 ROOM_INFO_TYPE_METAS: int
+ROOM_INFO_TYPE_PASSWORD: int
+ROOM_INFO_TYPE_SPECTATORS: int
+ROOM_INFO_TYPE_MAX_SPECTATOR: int
+ROOM_INFO_TYPE_SPECTATOR_LENGTH: int
+ROOM_INFO_TYPE_GAME_STATE: int
+ROOM_INFO_TYPE_READY_USERS: int
+ROOM_INFO_TYPE_AUTO_START: int
+
+# RoomGameState
+ROOM_GAME_STATE_WAITING: int
+ROOM_GAME_STATE_READY_CHECK: int
+ROOM_GAME_STATE_PLAYING: int
+ROOM_GAME_STATE_FINISHED: int
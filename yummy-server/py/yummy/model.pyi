@@ -39,6 +39,39 @@ class CustomIdAuth(BaseModel):
     def set_custom_id(self, custom_id: str): ...
 
 
+class ExternalAuth(BaseModel):
+    def get_token(self) -> str: ...
+
+
+class LinkIdentity(BaseModel):
+    def get_identity(self) -> str: ...
+
+    def get_merge(self) -> bool: ...
+    def set_merge(self, merge: bool): ...
+
+
+class UnlinkIdentity(BaseModel):
+    def get_identity(self) -> str: ...
+    def get_provider(self) -> Optional[str]: ...
+
+
+class VerifyEmail(BaseModel):
+    def get_token(self) -> str: ...
+
+
+class SendVerificationEmail(BaseModel):
+    def get_email(self) -> str: ...
+
+
+class ForgotPassword(BaseModel):
+    def get_email(self) -> str: ...
+
+
+class ResetPassword(BaseModel):
+    def get_token(self) -> str: ...
+    def get_password(self) -> str: ...
+
+
 class Logout(BaseModel):
     ...
 
@@ -56,6 +89,14 @@ class RefreshToken(BaseModel):
     ...
 
 
+class ListSessions(BaseModel):
+    ...
+
+
+class TerminateSession(BaseModel):
+    def get_target_session_id(self) -> str: ...
+
+
 class GetUserInformation(BaseModel):
     def get_query_type(self) -> str: ...
     def get_requester_user_id(self) -> Optional[str]: ...
@@ -90,6 +131,72 @@ class UpdateUser(BaseModel):
     def set_metas(self, value: Optional[dict[str, MetaType]]): ...
 
 
+class UpdateUserMeta(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+    def get_key(self) -> str: ...
+    def set_key(self, value: str): ...
+
+    def get_operation(self) -> dict[str, any]: ...
+    def set_operation(self, value: dict[str, any]): ...
+
+
+class AddFriend(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+
+class ProcessFriendRequest(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+    def get_status(self) -> bool: ...
+    def set_status(self, value: bool): ...
+
+
+class RemoveFriend(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+
+class FriendList(BaseModel):
+    def get_pending(self) -> bool: ...
+
+
+class MessageToUser(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+    def get_message(self) -> any: ...
+    def set_message(self, value: any): ...
+
+
+class GetInbox(BaseModel):
+    def get_limit(self) -> Optional[int]: ...
+    def set_limit(self, value: Optional[int]): ...
+
+    def get_cursor(self) -> Optional[str]: ...
+    def set_cursor(self, value: Optional[str]): ...
+
+
+class AckInbox(BaseModel):
+    def get_ids(self) -> list[str]: ...
+
+
+class SuspendUser(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+    def get_reason(self) -> Optional[str]: ...
+    def set_reason(self, value: Optional[str]): ...
+
+    def get_duration(self) -> Optional[int]: ...
+    def set_duration(self, value: Optional[int]): ...
+
+
+class UnsuspendUser(BaseModel):
+    def get_target_user_id(self) -> Optional[str]: ...
+
+
+class SuspendedUsers(BaseModel):
+    ...
+
+
 class CreateRoom(BaseModel):
     def get_name(self) -> Optional[str]: ...
     def set_name(self, value: Optional[str]): ...
@@ -106,12 +213,24 @@ class CreateRoom(BaseModel):
     def get_max_user(self) -> Optional[int]: ...
     def set_max_user(self, value: Optional[int]): ...
 
+    def get_max_spectator(self) -> Optional[int]: ...
+    def set_max_spectator(self, value: Optional[int]): ...
+
     def get_metas(self) -> Optional[dict[str, MetaType]]: ...
     def set_metas(self, value: Optional[dict[str, MetaType]]): ...
 
     def get_tags(self) -> Optional[list[str]]: ...
     def set_tags(self, value: Optional[list[str]]): ...
 
+    def get_password(self) -> Optional[str]: ...
+    def set_password(self, value: Optional[str]): ...
+
+    def get_generate_password(self) -> bool: ...
+    def set_generate_password(self, value: bool): ...
+
+    def get_auto_start(self) -> bool: ...
+    def set_auto_start(self, value: bool): ...
+
 
 class UpdateRoom(BaseModel):
     def get_room_id(self) -> Optional[str]: ...
@@ -131,6 +250,9 @@ class UpdateRoom(BaseModel):
     def get_max_user(self) -> Optional[int]: ...
     def set_max_user(self, value: Optional[int]): ...
 
+    def get_max_spectator(self) -> Optional[int]: ...
+    def set_max_spectator(self, value: Optional[int]): ...
+
     def get_meta_action(self) -> Optional[int]: ...
     def set_meta_action(self, value: Optional[int]): ...
 
@@ -143,6 +265,22 @@ class UpdateRoom(BaseModel):
     def get_tags(self) -> Optional[list[str]]: ...
     def set_tags(self, value: Optional[list[str]]): ...
 
+    def get_password(self) -> Optional[str]: ...
+    def set_password(self, value: Optional[str]): ...
+
+    def get_generate_password(self) -> bool: ...
+    def set_generate_password(self, value: bool): ...
+
+
+class UpdateRoomMeta(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+    def get_key(self) -> str: ...
+    def set_key(self, value: str): ...
+
+    def get_operation(self) -> dict[str, any]: ...
+    def set_operation(self, value: dict[str, any]): ...
+
 
 class JoinToRoom(BaseModel):
     def get_room_id(self) -> Optional[str]: ...
@@ -150,6 +288,12 @@ class JoinToRoom(BaseModel):
     def get_room_user_type(self) -> int: ...
     def set_room_user_type(self, value: int): ...
 
+    def get_password(self) -> Optional[str]: ...
+    def set_password(self, value: Optional[str]): ...
+
+    def get_history(self) -> Optional[int]: ...
+    def set_history(self, value: Optional[int]): ...
+
 
 class ProcessWaitingUser(BaseModel):
     def get_room_id(self) -> Optional[str]: ...
@@ -166,6 +310,17 @@ class KickUserFromRoom(BaseModel):
     def get_ban(self) -> bool: ...
     def set_ban(self, value: bool): ...
 
+    def get_reason(self) -> Optional[str]: ...
+    def set_reason(self, value: Optional[str]): ...
+
+    def get_ban_duration(self) -> Optional[int]: ...
+    def set_ban_duration(self, value: Optional[int]): ...
+
+
+class UnbanUserFromRoom(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+    def get_target_user_id(self) -> Optional[str]: ...
+
 
 class DisconnectFromRoom(BaseModel):
     def get_room_id(self) -> Optional[str]: ...
@@ -182,6 +337,18 @@ class RoomListRequest(RequestIdModel):
     def get_tag(self) -> Optional[str]: ...
     def set_tag(self, value: Optional[str]): ...
 
+    def get_tags(self) -> list[str]: ...
+    def set_tags(self, value: list[str]): ...
+
+    def get_game_state(self) -> Optional[int]: ...
+    def set_game_state(self, value: Optional[int]): ...
+
+    def get_cursor(self) -> Optional[str]: ...
+    def set_cursor(self, value: Optional[str]): ...
+
+    def get_limit(self) -> int: ...
+    def set_limit(self, value: int): ...
+
     def get_members(self) -> list[int]: ...
     def set_members(self, value: list[int]): ...
 
@@ -202,3 +369,101 @@ class Play(BaseModel):
 
     def get_message(self) -> any: ...
     def set_message(self, value: any): ...
+
+
+class SetReady(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+    def get_ready(self) -> bool: ...
+    def set_ready(self, value: bool): ...
+
+
+class StartGame(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+
+class FinishGame(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+
+class SelectNewOwner:
+    def get_room_id(self) -> str: ...
+    def get_previous_owner(self) -> str: ...
+    def get_users(self) -> list[dict[str, any]]: ...
+
+    def get_new_owner(self) -> Optional[str]: ...
+    def set_new_owner(self, value: Optional[str]): ...
+
+
+class RoomMessageHistory(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+    def get_before(self) -> Optional[int]: ...
+    def set_before(self, value: Optional[int]): ...
+
+    def get_limit(self) -> Optional[int]: ...
+    def set_limit(self, value: Optional[int]): ...
+
+
+class InviteToRoom(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+    def get_target_user_id(self) -> Optional[str]: ...
+
+    def get_room_user_type(self) -> int: ...
+    def set_room_user_type(self, value: int): ...
+
+
+class AcceptRoomInvite(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+    def get_history(self) -> Optional[int]: ...
+    def set_history(self, value: Optional[int]): ...
+
+
+class DeclineRoomInvite(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+
+class RoomInvites(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+
+
+class CancelRoomInvite(BaseModel):
+    def get_room_id(self) -> Optional[str]: ...
+    def get_target_user_id(self) -> Optional[str]: ...
+
+
+class JoinQueue(BaseModel):
+    def get_queue(self) -> str: ...
+    def set_queue(self, value: str): ...
+
+    def get_properties(self) -> dict[str, any]: ...
+    def set_properties(self, value: dict[str, any]): ...
+
+
+class LeaveQueue(BaseModel):
+    def get_queue(self) -> str: ...
+
+
+class MatchFound:
+    def get_queue(self) -> str: ...
+    def get_user_ids(self) -> list[str]: ...
+
+    def get_room_name(self) -> Optional[str]: ...
+    def set_room_name(self, value: Optional[str]): ...
+
+    def get_max_user(self) -> int: ...
+    def set_max_user(self, value: int): ...
+
+    def get_tags(self) -> list[str]: ...
+    def set_tags(self, value: list[str]): ...
+
+
+class CheckMatchCompatibility:
+    def get_queue(self) -> str: ...
+    def get_left(self) -> dict[str, any]: ...
+    def get_right(self) -> dict[str, any]: ...
+    def get_tolerance(self) -> float: ...
+
+    def get_compatible(self) -> bool: ...
+    def set_compatible(self, value: bool): ...
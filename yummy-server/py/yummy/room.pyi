@@ -38,6 +38,14 @@ def remove_room_metas(room_id: str) -> bool:
     ...
 
 
+def get_room_user_type(room_id: str, user_id: str) -> Optional[int]:
+    """
+    Get user's type in the room. Returns None if the user is not in the room.
+    Compare the result with ROOM_USER_TYPE_* constants.
+    """
+    ...
+
+
 def message_to_room(room_id: str, message: any) -> bool:
     """
     Send message to room users
@@ -57,3 +65,38 @@ def raw_message_to_room_user(room_id: str, message: any) -> bool:
     Send raw message to room user
     """
     ...
+
+
+def increment_room_meta(room_id: str, key: str, value: float, min: Optional[float] = None, max: Optional[float] = None) -> Optional[float]:
+    """
+    Atomically increment room's number meta and return the new value. Missing meta starts from zero.
+    """
+    ...
+
+
+def decrement_room_meta(room_id: str, key: str, value: float, min: Optional[float] = None, max: Optional[float] = None) -> Optional[float]:
+    """
+    Atomically decrement room's number meta and return the new value. Missing meta starts from zero.
+    """
+    ...
+
+
+def append_room_meta(room_id: str, key: str, value: MetaType) -> Optional[list[MetaType]]:
+    """
+    Atomically append value to room's list meta and return the new list.
+    """
+    ...
+
+
+def remove_from_room_meta(room_id: str, key: str, value: MetaType) -> Optional[list[MetaType]]:
+    """
+    Atomically remove all matched values from room's list meta and return the new list.
+    """
+    ...
+
+
+def compare_and_set_room_meta(room_id: str, key: str, expected: Optional[MetaType], value: MetaType, access_level: Optional[int]) -> bool:
+    """
+    Set room's meta only if the current value is equal to expected value. Use None as expected value for missing meta.
+    """
+    ...
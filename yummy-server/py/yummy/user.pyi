@@ -42,3 +42,38 @@ def remove_user_metas(user_id: str) -> bool:
     Remove all user's metas.
     """
     ...
+
+
+def increment_user_meta(user_id: str, key: str, value: float, min: Optional[float] = None, max: Optional[float] = None) -> Optional[float]:
+    """
+    Atomically increment user's number meta and return the new value. Missing meta starts from zero.
+    """
+    ...
+
+
+def decrement_user_meta(user_id: str, key: str, value: float, min: Optional[float] = None, max: Optional[float] = None) -> Optional[float]:
+    """
+    Atomically decrement user's number meta and return the new value. Missing meta starts from zero.
+    """
+    ...
+
+
+def append_user_meta(user_id: str, key: str, value: MetaType) -> Optional[list[MetaType]]:
+    """
+    Atomically append value to user's list meta and return the new list.
+    """
+    ...
+
+
+def remove_from_user_meta(user_id: str, key: str, value: MetaType) -> Optional[list[MetaType]]:
+    """
+    Atomically remove all matched values from user's list meta and return the new list.
+    """
+    ...
+
+
+def compare_and_set_user_meta(user_id: str, key: str, expected: Optional[MetaType], value: MetaType, access_level: Optional[int]) -> bool:
+    """
+    Set user's meta only if the current value is equal to expected value. Use None as expected value for missing meta.
+    """
+    ...
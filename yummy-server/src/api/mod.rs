@@ -18,10 +18,12 @@ use yummy_model::{auth::UserAuth, request::RequestUserType};
 use yummy_model::request::*;
 use yummy_manager::auth::AuthManager;
 use yummy_manager::room::RoomManager;
+use yummy_manager::matchmaking::MatchmakingManager;
 use yummy_manager::user::UserManager;
 use yummy_manager::auth::model::*;
 use yummy_manager::user::model::*;
 use yummy_manager::room::model::*;
+use yummy_manager::matchmaking::model::*;
 
 use validator::{Validate, ValidationErrors};
 
@@ -57,9 +59,18 @@ pub(crate) fn process_auth<DB: DatabaseTrait + Unpin + 'static>(request_id: Opti
         RequestAuthType::Email { email, password, if_not_exist_create } => as_response!(request_id, RequestAuthTypeVariant::Email, auth_manager, EmailAuthRequest { request_id, auth, email, password, if_not_exist_create, socket }),
         RequestAuthType::DeviceId { id } => as_response!(request_id, RequestAuthTypeVariant::DeviceId, auth_manager, DeviceIdAuthRequest::new(request_id, auth, id, socket)),
         RequestAuthType::CustomId { id } => as_response!(request_id, RequestAuthTypeVariant::CustomId, auth_manager, CustomIdAuthRequest::new(request_id, auth, id, socket)),
+        RequestAuthType::External { token } => as_response!(request_id, RequestAuthTypeVariant::External, auth_manager, ExternalAuthRequest { request_id, auth, token, socket }),
+        RequestAuthType::LinkIdentity { identity, merge } => as_response!(request_id, RequestAuthTypeVariant::LinkIdentity, auth_manager, LinkIdentityRequest { request_id, auth, identity, merge, socket }),
+        RequestAuthType::UnlinkIdentity { identity } => as_response!(request_id, RequestAuthTypeVariant::UnlinkIdentity, auth_manager, UnlinkIdentityRequest { request_id, auth, identity, socket }),
+        RequestAuthType::VerifyEmail { token } => as_response!(request_id, RequestAuthTypeVariant::VerifyEmail, auth_manager, VerifyEmailRequest { request_id, auth, token, socket }),
+        RequestAuthType::SendVerificationEmail { email } => as_response!(request_id, RequestAuthTypeVariant::SendVerificationEmail, auth_manager, SendVerificationEmailRequest { request_id, auth, email, socket }),
+        RequestAuthType::ForgotPassword { email } => as_response!(request_id, RequestAuthTypeVariant::ForgotPassword, auth_manager, ForgotPasswordRequest { request_id, auth, email, socket }),
+        RequestAuthType::ResetPassword { token, password } => as_response!(request_id, RequestAuthTypeVariant::ResetPassword, auth_manager, ResetPasswordRequest { request_id, auth, token, password, socket }),
         RequestAuthType::Refresh { token } => as_response!(request_id, RequestAuthTypeVariant::Refresh, auth_manager, RefreshTokenRequest { request_id, auth, token, socket }),
-        RequestAuthType::Restore { token } => as_response!(request_id, RequestAuthTypeVariant::Restore, auth_manager, RestoreTokenRequest { request_id, auth, token, socket }),
+        RequestAuthType::Restore { token, last_sequence } => as_response!(request_id, RequestAuthTypeVariant::Restore, auth_manager, RestoreTokenRequest { request_id, auth, token, last_sequence, socket }),
         RequestAuthType::Logout => as_response!(request_id, RequestAuthTypeVariant::Logout, auth_manager, LogoutRequest { request_id, auth, socket }),
+        RequestAuthType::ListSessions => as_response!(request_id, RequestAuthTypeVariant::ListSessions, auth_manager, ListSessionsRequest { request_id, auth, socket }),
+        RequestAuthType::TerminateSession { session_id } => as_response!(request_id, RequestAuthTypeVariant::TerminateSession, auth_manager, TerminateSessionRequest { request_id, auth, session_id, socket }),
     };
     Ok(())
 }
@@ -70,6 +81,19 @@ pub(crate) fn process_user<DB: DatabaseTrait + Unpin + 'static>(request_id: Opti
         RequestUserType::Me => as_response!(request_id, RequestUserTypeVariant::Me, user_manager, GetUserInformation::me(request_id, auth, socket)),
         RequestUserType::Get { user_id } => as_response!(request_id, RequestUserTypeVariant::Get, user_manager, GetUserInformation::user(request_id, user_id, auth, socket)),
         RequestUserType::Update { name, email, password, device_id, custom_id, user_type, metas, meta_action } => as_response!(request_id, RequestUserTypeVariant::Update, user_manager, UpdateUser { request_id, auth, name, email, password, device_id, custom_id, metas, meta_action, user_type, socket, target_user_id: None }),
+        RequestUserType::AddFriend { user_id } => as_response!(request_id, RequestUserTypeVariant::AddFriend, user_manager, AddFriend { request_id, auth, user_id, socket }),
+        RequestUserType::AcceptFriendRequest { user_id } => as_response!(request_id, RequestUserTypeVariant::AcceptFriendRequest, user_manager, ProcessFriendRequest { request_id, auth, user_id, status: true, socket }),
+        RequestUserType::RejectFriendRequest { user_id } => as_response!(request_id, RequestUserTypeVariant::RejectFriendRequest, user_manager, ProcessFriendRequest { request_id, auth, user_id, status: false, socket }),
+        RequestUserType::RemoveFriend { user_id } => as_response!(request_id, RequestUserTypeVariant::RemoveFriend, user_manager, RemoveFriend { request_id, auth, user_id, socket }),
+        RequestUserType::FriendList => as_response!(request_id, RequestUserTypeVariant::FriendList, user_manager, FriendList { request_id, auth, pending: false, socket }),
+        RequestUserType::FriendRequests => as_response!(request_id, RequestUserTypeVariant::FriendRequests, user_manager, FriendList { request_id, auth, pending: true, socket }),
+        RequestUserType::Message { user_id, message } => as_response!(request_id, RequestUserTypeVariant::Message, user_manager, MessageToUser { request_id, auth, user_id, message, socket }),
+        RequestUserType::Inbox { limit, cursor } => as_response!(request_id, RequestUserTypeVariant::Inbox, user_manager, GetInbox { request_id, auth, limit, cursor, socket }),
+        RequestUserType::AckInbox { ids } => as_response!(request_id, RequestUserTypeVariant::AckInbox, user_manager, AckInbox { request_id, auth, ids, socket }),
+        RequestUserType::Suspend { user_id, reason, duration } => as_response!(request_id, RequestUserTypeVariant::Suspend, user_manager, SuspendUser { request_id, auth, user_id, reason, duration, socket }),
+        RequestUserType::Unsuspend { user_id } => as_response!(request_id, RequestUserTypeVariant::Unsuspend, user_manager, UnsuspendUser { request_id, auth, user_id, socket }),
+        RequestUserType::SuspendedUsers => as_response!(request_id, RequestUserTypeVariant::SuspendedUsers, user_manager, SuspendedUsers { request_id, auth, socket }),
+        RequestUserType::MetaOperation { key, operation } => as_response!(request_id, RequestUserTypeVariant::MetaOperation, user_manager, UpdateUserMeta { request_id, auth, key, operation, socket, target_user_id: None }),
     };
     Ok(())
 }
@@ -77,18 +101,42 @@ pub(crate) fn process_user<DB: DatabaseTrait + Unpin + 'static>(request_id: Opti
 #[tracing::instrument(name="process_room", skip(room_manager))]
 pub(crate) fn process_room<DB: DatabaseTrait + Unpin + 'static>(request_id: Option<usize>, room_type: RequestRoomType, room_manager: Addr<RoomManager<DB>>, auth: Arc<Option<UserAuth>>, socket: Arc<dyn ClientTrait + Sync + Send>) -> ProcessResult {
     match room_type {
-        RequestRoomType::Create { name, description, access_type, max_user, tags, metas, join_request } => as_response!(request_id, RequestRoomTypeVariant::Create, room_manager, CreateRoomRequest { request_id, auth, socket, name, description, access_type, max_user, tags, metas, join_request }),
+        RequestRoomType::Create { name, description, access_type, max_user, max_spectator, tags, metas, join_request, password, generate_password, auto_start } => as_response!(request_id, RequestRoomTypeVariant::Create, room_manager, CreateRoomRequest { request_id, auth, socket, name, description, access_type, max_user, max_spectator, tags, metas, join_request, password, generate_password, auto_start }),
         RequestRoomType::GetRoom { room_id, members } => as_response!(request_id, RequestRoomTypeVariant::GetRoom, room_manager, GetRoomRequest  { request_id, auth, socket, room_id, members }),
-        RequestRoomType::Join { room_id, room_user_type } => as_response!(request_id, RequestRoomTypeVariant::Join, room_manager, JoinToRoomRequest { request_id, auth, socket, room_id, room_user_type }),
+        RequestRoomType::Join { room_id, room_user_type, password, history } => as_response!(request_id, RequestRoomTypeVariant::Join, room_manager, JoinToRoomRequest { request_id, auth, socket, room_id, room_user_type, password, history }),
         RequestRoomType::Disconnect { room_id } => as_response!(request_id, RequestRoomTypeVariant::Disconnect, room_manager, DisconnectFromRoomRequest { request_id, auth, socket, room_id }),
         RequestRoomType::Message { room_id, message } => as_response!(request_id, RequestRoomTypeVariant::Message, room_manager, MessageToRoomRequest { request_id, auth, socket, room_id, message }),
         RequestRoomType::Play { room_id, message } => as_response!(request_id, RequestRoomTypeVariant::Play, room_manager, Play { request_id, auth, socket, room_id, message }),
-        RequestRoomType::Update { room_id, user_permission, name, description, max_user, join_request, metas, meta_action, access_type, tags } => as_response!(request_id, RequestRoomTypeVariant::Update, room_manager, UpdateRoom { request_id, auth, socket, room_id , user_permission, name, description, max_user, metas, meta_action, access_type, join_request, tags }),
-        RequestRoomType::Kick { room_id, user_id } => as_response!(request_id, RequestRoomTypeVariant::Kick, room_manager, KickUserFromRoom { request_id, auth, socket, room_id, user_id, ban: false }),
-        RequestRoomType::Ban { room_id, user_id } => as_response!(request_id, RequestRoomTypeVariant::Ban, room_manager, KickUserFromRoom { request_id, auth, socket, room_id, user_id, ban: true }),
-        RequestRoomType::List { tag, members } => as_response!(request_id, RequestRoomTypeVariant::List, room_manager, RoomListRequest { request_id, socket, tag, members }),
+        RequestRoomType::Update { room_id, user_permission, name, description, max_user, max_spectator, join_request, metas, meta_action, access_type, tags, password, generate_password } => as_response!(request_id, RequestRoomTypeVariant::Update, room_manager, UpdateRoom { request_id, auth, socket, room_id , user_permission, name, description, max_user, max_spectator, metas, meta_action, access_type, join_request, tags, password, generate_password }),
+        RequestRoomType::Kick { room_id, user_id, reason } => as_response!(request_id, RequestRoomTypeVariant::Kick, room_manager, KickUserFromRoom { request_id, auth, socket, room_id, user_id, ban: false, reason, ban_duration: None }),
+        RequestRoomType::Ban { room_id, user_id, reason, duration } => as_response!(request_id, RequestRoomTypeVariant::Ban, room_manager, KickUserFromRoom { request_id, auth, socket, room_id, user_id, ban: true, reason, ban_duration: duration }),
+        RequestRoomType::Unban { room_id, user_id } => as_response!(request_id, RequestRoomTypeVariant::Unban, room_manager, UnbanUserFromRoom { request_id, auth, socket, room_id, user_id }),
+        RequestRoomType::List { tag, mut filter, members } => {
+            // Single tag is still supported for the old clients
+            filter.tags.extend(tag);
+            as_response!(request_id, RequestRoomTypeVariant::List, room_manager, RoomListRequest { request_id, socket, filter, members })
+        },
         RequestRoomType::ProcessWaitingUser { room_id, user_id, status } => as_response!(request_id, RequestRoomTypeVariant::ProcessWaitingUser, room_manager, ProcessWaitingUser { request_id, auth, socket, room_id, user_id, status }),
-        RequestRoomType::WaitingRoomJoins { room_id } => as_response!(request_id, RequestRoomTypeVariant::WaitingRoomJoins, room_manager, WaitingRoomJoins { request_id, auth, socket, room_id })
+        RequestRoomType::WaitingRoomJoins { room_id } => as_response!(request_id, RequestRoomTypeVariant::WaitingRoomJoins, room_manager, WaitingRoomJoins { request_id, auth, socket, room_id }),
+        RequestRoomType::SetReady { room_id, ready } => as_response!(request_id, RequestRoomTypeVariant::SetReady, room_manager, SetReady { request_id, auth, socket, room_id, ready }),
+        RequestRoomType::StartGame { room_id } => as_response!(request_id, RequestRoomTypeVariant::StartGame, room_manager, StartGame { request_id, auth, socket, room_id }),
+        RequestRoomType::FinishGame { room_id } => as_response!(request_id, RequestRoomTypeVariant::FinishGame, room_manager, FinishGame { request_id, auth, socket, room_id }),
+        RequestRoomType::MessageHistory { room_id, before, limit } => as_response!(request_id, RequestRoomTypeVariant::MessageHistory, room_manager, RoomMessageHistory { request_id, auth, socket, room_id, before, limit }),
+        RequestRoomType::Invite { room_id, user_id, room_user_type } => as_response!(request_id, RequestRoomTypeVariant::Invite, room_manager, InviteToRoom { request_id, auth, socket, room_id, user_id, room_user_type }),
+        RequestRoomType::AcceptInvite { room_id, history } => as_response!(request_id, RequestRoomTypeVariant::AcceptInvite, room_manager, AcceptRoomInvite { request_id, auth, socket, room_id, history }),
+        RequestRoomType::DeclineInvite { room_id } => as_response!(request_id, RequestRoomTypeVariant::DeclineInvite, room_manager, DeclineRoomInvite { request_id, auth, socket, room_id }),
+        RequestRoomType::Invites { room_id } => as_response!(request_id, RequestRoomTypeVariant::Invites, room_manager, RoomInvites { request_id, auth, socket, room_id }),
+        RequestRoomType::CancelInvite { room_id, user_id } => as_response!(request_id, RequestRoomTypeVariant::CancelInvite, room_manager, CancelRoomInvite { request_id, auth, socket, room_id, user_id }),
+        RequestRoomType::MetaOperation { room_id, key, operation } => as_response!(request_id, RequestRoomTypeVariant::MetaOperation, room_manager, UpdateRoomMeta { request_id, auth, socket, room_id, key, operation })
+    };
+    Ok(())
+}
+
+#[tracing::instrument(name="process_matchmaking", skip(matchmaking_manager))]
+pub(crate) fn process_matchmaking<DB: DatabaseTrait + Unpin + 'static>(request_id: Option<usize>, matchmaking_type: RequestMatchmakingType, matchmaking_manager: Addr<MatchmakingManager<DB>>, auth: Arc<Option<UserAuth>>, socket: Arc<dyn ClientTrait + Sync + Send>) -> ProcessResult {
+    match matchmaking_type {
+        RequestMatchmakingType::Join { queue, properties } => as_response!(request_id, RequestMatchmakingTypeVariant::Join, matchmaking_manager, JoinMatchmakingQueue { request_id, auth, socket, queue, properties }),
+        RequestMatchmakingType::Leave { queue } => as_response!(request_id, RequestMatchmakingTypeVariant::Leave, matchmaking_manager, LeaveMatchmakingQueue { request_id, auth, socket, queue })
     };
     Ok(())
 }
@@ -27,6 +27,7 @@ use crate::api::websocket::websocket_endpoint;
 use yummy_manager::auth::AuthManager;
 use yummy_manager::user::UserManager;
 use yummy_manager::room::RoomManager;
+use yummy_manager::matchmaking::MatchmakingManager;
 use yummy_manager::plugin::PluginExecuter;
 
 use async_trait::async_trait;
@@ -109,6 +110,7 @@ pub fn create_websocket_server_with_config(config: Arc<YummyConfig>, test_server
 
         let auth_manager = Data::new(AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
         let user_manager = Data::new(UserManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
+        let matchmaking_manager = Data::new(MatchmakingManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
         let room_manager = Data::new(RoomManager::<yummy_database::SqliteStore>::new(config.clone(), states, connection, executer.clone()).start());
 
         let query_cfg = QueryConfig::default()
@@ -121,6 +123,7 @@ pub fn create_websocket_server_with_config(config: Arc<YummyConfig>, test_server
             .app_data(auth_manager)
             .app_data(user_manager)
             .app_data(room_manager)
+            .app_data(matchmaking_manager)
             .app_data(query_cfg)
             .app_data(JsonConfig::default().error_handler(json_error_handler))
             .app_data(Data::new(config.clone()))
@@ -149,6 +152,7 @@ pub fn config(cfg: &mut ServiceConfig) {
 
     let auth_manager = Data::new(AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
     let user_manager = Data::new(UserManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
+    let matchmaking_manager = Data::new(MatchmakingManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
     let room_manager = Data::new(RoomManager::<yummy_database::SqliteStore>::new(config.clone(), states, connection, executer.clone()).start());
 
     let query_cfg = QueryConfig::default()
@@ -161,6 +165,7 @@ pub fn config(cfg: &mut ServiceConfig) {
         .app_data(auth_manager)
         .app_data(user_manager)
         .app_data(room_manager)
+        .app_data(matchmaking_manager)
         .app_data(query_cfg)
         .app_data(JsonConfig::default().error_handler(json_error_handler))
         .app_data(Data::new(config.clone()))
@@ -13,6 +13,8 @@ use actix_web::web::Data;
 use actix_web::web::Payload;
 use yummy_database::DatabaseTrait;
 use yummy_model::WebsocketMessage;
+use yummy_model::ForceDisconnect;
+use yummy_model::state::ClientInfo;
 use yummy_model::auth::ApiIntegration;
 use yummy_model::auth::UserAuth;
 use yummy_general::client::ClientTrait;
@@ -21,7 +23,9 @@ use yummy_model::UserAuthenticated;
 use yummy_model::request::Request;
 use yummy_model::web::GenericAnswer;
 use yummy_manager::auth::model::StartUserTimeout;
+use yummy_manager::auth::model::SessionActivity;
 use yummy_manager::room::RoomManager;
+use yummy_manager::matchmaking::MatchmakingManager;
 use yummy_manager::user::UserManager;
 use std::borrow::Cow;
 use std::ops::Deref;
@@ -43,6 +47,7 @@ use crate::api::process_user;
 
 use super::ProcessResult;
 use super::process_room;
+use super::process_matchmaking;
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -53,7 +58,10 @@ pub struct GameWebsocket<DB: DatabaseTrait + ?Sized + Unpin + 'static> {
     auth_manager: Addr<AuthManager<DB>>,
     user_manager: Addr<UserManager<DB>>,
     room_manager: Addr<RoomManager<DB>>,
+    matchmaking_manager: Addr<MatchmakingManager<DB>>,
     hb: Instant,
+    last_activity: Instant,
+    client_info: ClientInfo,
     user_auth: Arc<Option<UserAuth>>,
     config: Arc<YummyConfig>,
     client: Arc<dyn ClientTrait + Sync + Send>
@@ -62,20 +70,35 @@ pub struct GameWebsocket<DB: DatabaseTrait + ?Sized + Unpin + 'static> {
 #[derive(Debug)]
 struct GameWebsocketClient {
     sender: Recipient<WebsocketMessage>,
-    auth: Recipient<UserAuthenticated>
+    auth: Recipient<UserAuthenticated>,
+    disconnect: Recipient<ForceDisconnect>,
+    info: ClientInfo
 }
 
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
 /* ************************************************** FUNCTIONS *************************************************** */
 /* **************************************************************************************************************** */
-pub async fn websocket_endpoint<DB: DatabaseTrait + Unpin + 'static>(req: HttpRequest, stream: Payload, config: Data<Arc<YummyConfig>>, auth_manager: Data<Addr<AuthManager<DB>>>, user_manager: Data<Addr<UserManager<DB>>>, room_manager: Data<Addr<RoomManager<DB>>>, _: ApiIntegration) -> Result<actix_web::HttpResponse, YummyError> {
+pub async fn websocket_endpoint<DB: DatabaseTrait + Unpin + 'static>(req: HttpRequest, stream: Payload, config: Data<Arc<YummyConfig>>, auth_manager: Data<Addr<AuthManager<DB>>>, user_manager: Data<Addr<UserManager<DB>>>, room_manager: Data<Addr<RoomManager<DB>>>, matchmaking_manager: Data<Addr<MatchmakingManager<DB>>>, _: ApiIntegration) -> Result<actix_web::HttpResponse, YummyError> {
     let config = config.get_ref();
 
+    /* Forwarded headers could be sent by the client itself, they are used only behind a trusted proxy */
+    let ip = match req.peer_addr().map(|addr| addr.ip().to_string()) {
+        Some(peer) if config.trusted_proxies.contains(&peer) => req.connection_info().realip_remote_addr().map(|ip| ip.to_string()),
+        peer => peer
+    };
+
+    let client_info = ClientInfo {
+        ip,
+        client: req.headers().get(actix_web::http::header::USER_AGENT).and_then(|agent| agent.to_str().ok()).map(|agent| agent.to_string())
+    };
+
     ws::start(GameWebsocket::new(config.clone(),
+        client_info,
         auth_manager.get_ref().clone(),
         user_manager.get_ref().clone(),
-        room_manager.get_ref().clone()),
+        room_manager.get_ref().clone(),
+        matchmaking_manager.get_ref().clone()),
         &req, stream)
         .map_err(YummyError::from)
 }
@@ -87,15 +110,20 @@ pub async fn websocket_endpoint<DB: DatabaseTrait + Unpin + 'static>(req: HttpRe
 impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> GameWebsocket<DB> {
     pub fn new(
         config: Arc<YummyConfig>,
+        client_info: ClientInfo,
         auth: Addr<AuthManager<DB>>,
         user: Addr<UserManager<DB>>,
         room: Addr<RoomManager<DB>>,
+        matchmaking: Addr<MatchmakingManager<DB>>,
     ) -> Self {
         Self {
             hb: Instant::now(),
+            last_activity: Instant::now(),
+            client_info,
             auth_manager: auth,
             user_manager: user,
             room_manager: room,
+            matchmaking_manager: matchmaking,
             config,
             user_auth: Arc::new(None),
             client: Arc::new(EmptyClient::default())
@@ -116,10 +144,19 @@ impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> GameWebsocket<DB> {
         let user_info = self.user_auth.clone();
         let socket = self.client.clone();
 
+        /* Session activity is not updated for each message, heartbeat interval is enough */
+        if user_info.is_some() && Instant::now().duration_since(self.last_activity) > self.config.heartbeat_interval {
+            self.last_activity = Instant::now();
+            self.auth_manager.do_send(SessionActivity {
+                auth: user_info.clone()
+            });
+        }
+
         let validation = match message {
             Request::Auth { request_id, auth_type } => process_auth(request_id, auth_type, self.auth_manager.clone(), user_info, socket),
             Request::User { request_id, user_type } => process_user(request_id, user_type, self.user_manager.clone(), user_info, socket),
             Request::Room { request_id, room_type } => process_room(request_id, room_type, self.room_manager.clone(), user_info, socket),
+            Request::Matchmaking { request_id, matchmaking_type } => process_matchmaking(request_id, matchmaking_type, self.matchmaking_manager.clone(), user_info, socket),
         };
 
         if let Err((request_id, request_type, error)) = validation {
@@ -143,10 +180,12 @@ impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> GameWebsocket<DB> {
 }
 
 impl GameWebsocketClient {
-    pub fn new<DB: DatabaseTrait + ?Sized + Unpin + 'static>(address: Addr<GameWebsocket<DB>>) -> Self {
+    pub fn new<DB: DatabaseTrait + ?Sized + Unpin + 'static>(address: Addr<GameWebsocket<DB>>, info: ClientInfo) -> Self {
         Self {
             sender: address.clone().recipient(),
-            auth: address.recipient()
+            auth: address.clone().recipient(),
+            disconnect: address.recipient(),
+            info
         }
     }
 }
@@ -159,7 +198,7 @@ impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> Actor for GameWebsocket<DB> {
 
     fn started(&mut self, ctx: &mut Self::Context) {
         log::debug!("New socket started");
-        self.client = Arc::new(GameWebsocketClient::new(ctx.address()));
+        self.client = Arc::new(GameWebsocketClient::new(ctx.address(), self.client_info.clone()));
         self.hb(ctx);
     }
 
@@ -227,6 +266,17 @@ impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> Handler<UserAuthenticated> fo
     }
 }
 
+impl<DB: DatabaseTrait + ?Sized + Unpin + 'static> Handler<ForceDisconnect> for GameWebsocket<DB> {
+    type Result = ();
+    
+    #[tracing::instrument(name="ForceDisconnect", skip(self, ctx))]
+    fn handle(&mut self, _: ForceDisconnect, ctx: &mut Self::Context) {
+        /* Session already closed, timeout should not be started for it */
+        self.user_auth = Arc::new(None);
+        ctx.stop();
+    }
+}
+
 impl ClientTrait for GameWebsocketClient {
     fn send(&self, message: String) {
         self.sender.do_send(WebsocketMessage(message));
@@ -235,6 +285,14 @@ impl ClientTrait for GameWebsocketClient {
     fn authenticated(&self, user: yummy_model::auth::UserJwt) {
         self.auth.do_send(UserAuthenticated(user));
     }
+
+    fn client_info(&self) -> ClientInfo {
+        self.info.clone()
+    }
+
+    fn disconnect(&self) {
+        self.disconnect.do_send(ForceDisconnect);
+    }
 }
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */
@@ -130,6 +130,7 @@ pub fn create_websocket_server_with_config(config: Arc<YummyConfig>, test_server
 
         let auth_manager = Data::new(AuthManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
         let user_manager = Data::new(UserManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
+        let matchmaking_manager = Data::new(MatchmakingManager::<yummy_database::SqliteStore>::new(config.clone(), states.clone(), connection.clone(), executer.clone()).start());
         let room_manager = Data::new(RoomManager::<yummy_database::SqliteStore>::new(config.clone(), states, connection.clone(), executer.clone()).start());
 
         let query_cfg = QueryConfig::default()
@@ -142,6 +143,7 @@ pub fn create_websocket_server_with_config(config: Arc<YummyConfig>, test_server
             .app_data(auth_manager)
             .app_data(user_manager)
             .app_data(room_manager)
+            .app_data(matchmaking_manager)
             .app_data(query_cfg)
             .app_data(JsonConfig::default().error_handler(json_error_handler))
             .app_data(Data::new(config.clone()))
@@ -695,12 +697,11 @@ async fn token_refresh_1() -> anyhow::Result<()> {
     let receive = client.get_text().await;
     assert!(receive.is_some());
 
-    let token = serde_json::from_str::<AuthenticatedModel>(&receive.unwrap())?.token;
+    let refresh_token = serde_json::from_str::<AuthenticatedModel>(&receive.unwrap())?.refresh_token;
 
-    // Not valid
     let request = json!({
         "type": "RefreshToken",
-        "token": token
+        "token": refresh_token
     });
     client.send(request).await;
     let receive = client.get_text().await;
@@ -1039,6 +1040,47 @@ async fn join_room() -> anyhow::Result<()> {
     Ok(())
 }
 
+// Matchmaking test cases
+#[actix_web::test]
+async fn join_and_leave_queue() -> anyhow::Result<()> {
+    let server = create_websocket_server(yummy_model::config::get_configuration());
+
+    let mut client = yummy_general::websocket::WebsocketTestClient::<String, String>::new(server.url("/v1/socket") , yummy_model::config::DEFAULT_API_KEY_NAME.to_string(), yummy_model::config::DEFAULT_DEFAULT_INTEGRATION_KEY.to_string()).await;
+    custom_id_auth!(client, "1234567890");
+
+    client.send(json!({
+        "type": "JoinQueue",
+        "queue": "ranked",
+        "properties": {
+            "skill": 1250,
+            "region": "eu"
+        }
+    })).await;
+
+    let response = serde_json::from_str::<Answer>(&client.get_text().await.unwrap())?;
+    assert!(response.status);
+    assert_eq!(&response.response_type, "JoinQueue");
+
+    client.send(json!({
+        "type": "LeaveQueue",
+        "queue": "ranked"
+    })).await;
+
+    let response = serde_json::from_str::<Answer>(&client.get_text().await.unwrap())?;
+    assert!(response.status);
+
+    // User is not in the queue anymore
+    client.send(json!({
+        "type": "LeaveQueue",
+        "queue": "ranked"
+    })).await;
+
+    let response = serde_json::from_str::<ReceiveError>(&client.get_text().await.unwrap())?;
+    assert!(!response.status);
+
+    Ok(())
+}
+
 #[actix_web::test]
 async fn ping_pong() -> anyhow::Result<()> {
     let server = create_websocket_server(yummy_model::config::get_configuration());
@@ -6,20 +6,22 @@ use std::sync::Arc;
 use prometheus::{IntCounterVec, opts};
 use yummy_database::DefaultDatabaseStore;
 use yummy_manager::plugin::python::PythonPluginInstaller;
-use yummy_model::config::{get_configuration, configure_environment};
+use yummy_model::config::{get_configuration, configure_environment, YummyConfig};
 use yummy_general::tls::load_rustls_config;
 use yummy_model::web::json_error_handler;
 
 use yummy_manager::conn::ConnectionManager;
 use yummy_manager::user::UserManager;
 use yummy_manager::auth::AuthManager;
+use yummy_manager::auth::model::UpdateExternalJwks;
 
 use yummy_manager::plugin::PluginBuilder;
 
 use yummy_cache::state::YummyState;
 use yummy_cache::state_resource::ResourceFactory;
 
-use actix::Actor;
+use actix::{Actor, Addr};
+use jsonwebtoken::jwk::JwkSet;
 use actix_web::error::InternalError;
 use actix_web::web::{JsonConfig, QueryConfig};
 use actix_web::{web, HttpResponse, Responder};
@@ -36,6 +38,36 @@ async fn health(counter: web::Data<IntCounterVec>) -> impl Responder {
     HttpResponse::Ok().finish()
 }
 
+/* Public keys for verifying the tokens without the shared secret */
+#[get("/.well-known/jwks.json")]
+async fn jwks(config: web::Data<Arc<YummyConfig>>) -> impl Responder {
+    HttpResponse::Ok().json(config.jwt_keys.get_jwks())
+}
+
+/* Remote JWKS documents are downloaded periodically, providers are rotating their keys */
+async fn refresh_external_jwks(config: Arc<YummyConfig>, auth_manager: Addr<AuthManager<DefaultDatabaseStore>>) {
+    let client = awc::Client::default();
+    let issuers = config.external_auth.get_issuers().iter().filter(|issuer| issuer.is_remote_jwks()).cloned().collect::<Vec<_>>();
+
+    if issuers.is_empty() {
+        return;
+    }
+
+    loop {
+        for issuer in issuers.iter() {
+            match client.get(&issuer.jwks).send().await {
+                Ok(mut response) => match response.json::<JwkSet>().limit(1024 * 1024).await {
+                    Ok(jwks) => auth_manager.do_send(UpdateExternalJwks { issuer: issuer.name.clone(), jwks }),
+                    Err(error) => log::error!("'{}' JWKS is not valid. {}", issuer.name, error)
+                },
+                Err(error) => log::error!("'{}' JWKS could not be downloaded. {}", issuer.name, error)
+            }
+        }
+
+        actix::clock::sleep(config.external_jwks_refresh_interval).await;
+    }
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
 
@@ -43,6 +75,7 @@ async fn main() -> std::io::Result<()> {
     let counter = IntCounterVec::new(counter_opts, &["method", "path"]).unwrap();
 
     use yummy_manager::room::RoomManager;
+    use yummy_manager::matchmaking::MatchmakingManager;
 
     configure_environment();
     let config = get_configuration();
@@ -57,7 +90,9 @@ async fn main() -> std::io::Result<()> {
 
     let database = Arc::new(yummy_database::create_connection(&config.database_path).unwrap());
     let mut connection = database.clone().get().unwrap();
-    yummy_database::create_database(&mut connection).unwrap_or_default();
+    if let Err(error) = yummy_database::create_database(&mut connection) {
+        log::error!("Database could not be migrated: {:?}", error);
+    }
 
     let prometheus = PrometheusMetricsBuilder::new("api")
         .endpoint("/metrics")
@@ -82,8 +117,10 @@ async fn main() -> std::io::Result<()> {
 
     let user_manager = Data::new(UserManager::<DefaultDatabaseStore>::new(config.clone(), states.clone(), database.clone(), executer.clone()).start());
     let room_manager = Data::new(RoomManager::<DefaultDatabaseStore>::new(config.clone(), states.clone(), database.clone(), executer.clone()).start());
+    let matchmaking_manager = Data::new(MatchmakingManager::<DefaultDatabaseStore>::new(config.clone(), states.clone(), database.clone(), executer.clone()).start());
     let conn_manager = Data::new(ConnectionManager::new(config.clone(), states.clone(), executer.clone(), #[cfg(feature = "stateless")] redis_client).start());
     let auth_manager = Data::new(AuthManager::<DefaultDatabaseStore>::new(config.clone(), states.clone(), database.clone(), executer.clone()).start());
+    actix::spawn(refresh_external_jwks(config.clone(), auth_manager.get_ref().clone()));
     
     let data_config = Data::new(config.clone());
 
@@ -101,12 +138,14 @@ async fn main() -> std::io::Result<()> {
             .app_data(auth_manager.clone())
             .app_data(user_manager.clone())
             .app_data(room_manager.clone())
+            .app_data(matchmaking_manager.clone())
             .app_data(conn_manager.clone())
             .app_data(web::Data::new(counter.clone()))
             .wrap(middleware::Compress::default())
             .wrap(middleware::Logger::default())
             .wrap(prometheus.clone())
             .service(health)
+            .service(jwks)
             .route("/v1/socket", web::get().to(websocket_endpoint::<DefaultDatabaseStore>))
     });
 
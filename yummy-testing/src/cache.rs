@@ -2,6 +2,8 @@
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
+use std::collections::HashSet;
+
 use yummy_cache::{state::resource::YummyCacheResourceFactory, cache::YummyCacheResource};
 use yummy_model::{UserId, UserType, meta::collection::{UserMetaCollection, RoomMetaCollection}, UserInformationModel, RoomId};
 
@@ -15,6 +17,7 @@ pub struct DummyUserInformationResource;
 pub struct DummyUserMetaResource;
 pub struct DummyUserTypeResource;
 pub struct DummyRoomMetaResource;
+pub struct DummyUserFriendResource;
 
 /* **************************************************************************************************************** */
 /* **************************************************** ENUMS ***************************************************** */
@@ -38,6 +41,10 @@ impl YummyCacheResourceFactory for DummyResourceFactory {
     fn room_metas(&self) -> Box<dyn YummyCacheResource<K=yummy_model::RoomId, V=yummy_model::meta::collection::RoomMetaCollection>> {
         Box::new(DummyRoomMetaResource {})
     }
+
+    fn user_friends(&self) -> Box<dyn YummyCacheResource<K=UserId, V=HashSet<UserId>>> {
+        Box::new(DummyUserFriendResource {})
+    }
 }
 
 impl YummyCacheResource for DummyUserInformationResource {
@@ -68,6 +75,13 @@ impl YummyCacheResource for DummyRoomMetaResource {
     fn get(&self, _: &Self::K) -> anyhow::Result<Option<Self::V>> { Ok(None) }
 }
 
+impl YummyCacheResource for DummyUserFriendResource {
+    type K=UserId;
+    type V=HashSet<UserId>;
+
+    fn get(&self, _: &Self::K) -> anyhow::Result<Option<Self::V>> { Ok(None) }
+}
+
 /* **************************************************************************************************************** */
 /* ********************************************** TRAIT IMPLEMENTS ************************************************ */
 /* ************************************************* MACROS CALL ************************************************** */
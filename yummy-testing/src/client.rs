@@ -2,7 +2,7 @@
 /* **************************************************** MODS ****************************************************** */
 /* *************************************************** IMPORTS **************************************************** */
 /* **************************************************************************************************************** */
-use std::{sync::Mutex, collections::VecDeque};
+use std::{sync::Mutex, sync::atomic::{AtomicBool, Ordering}, collections::VecDeque};
 
 use yummy_general::client::ClientTrait;
 use yummy_model::auth::UserJwt;
@@ -15,7 +15,8 @@ use yummy_model::auth::UserJwt;
 #[derive(Debug)]
 pub struct DummyClient {
     pub messages: Mutex<VecDeque<String>>,
-    pub auth: Mutex<UserJwt>
+    pub auth: Mutex<UserJwt>,
+    pub disconnected: AtomicBool
 }
 
 /* **************************************************************************************************************** */
@@ -37,13 +38,18 @@ impl ClientTrait for DummyClient {
         self_auth.name = auth.name;
         self_auth.session = auth.session;
     }
+
+    fn disconnect(&self) {
+        self.disconnected.store(true, Ordering::Relaxed);
+    }
 }
 
 impl Default for DummyClient {
     fn default() -> Self {
         Self {
             messages: Mutex::default(),
-            auth: Mutex::new(UserJwt::default())
+            auth: Mutex::new(UserJwt::default()),
+            disconnected: AtomicBool::default()
         }
     }
 }
@@ -12,6 +12,10 @@ use yummy_model::RoomId;
 use yummy_model::RoomUserType;
 
 use yummy_model::state::RoomUserInformation;
+use yummy_model::state::RoomMessage;
+use yummy_model::state::RoomInvite;
+use yummy_model::state::RoomBan;
+use yummy_model::state::UserSession;
 
 /* **************************************************************************************************************** */
 /* ******************************************** STATICS/CONSTS/TYPES ********************************************** */
@@ -48,14 +52,23 @@ pub struct AuthenticatedModel {
     pub status: bool,
     #[serde(rename = "type")]
     pub class_type: String,
-    pub token: String
+    pub token: String,
+
+    #[serde(default)]
+    pub refresh_token: Option<String>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Joined {
     pub room_name: Option<String>,
     pub users: Vec<RoomUserInformation>,
-    pub metas: serde_json::Value
+
+    #[serde(default)]
+    pub spectators: Vec<RoomUserInformation>,
+    pub metas: serde_json::Value,
+
+    #[serde(default)]
+    pub messages: Vec<RoomMessage>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -87,7 +100,10 @@ pub struct WaitingRoomJoinsResponse {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RoomCreated {
-    pub room_id: RoomId
+    pub room_id: RoomId,
+
+    #[serde(default)]
+    pub password: Option<String>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -100,7 +116,13 @@ pub struct UserDisconnectedFromRoom {
 pub struct DisconnectedFromRoom {
     #[serde(rename = "type")]
     pub class_type: String,
-    pub room_id: RoomId
+    pub room_id: RoomId,
+
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    #[serde(default)]
+    pub ban: Option<RoomBan>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -112,6 +134,58 @@ pub struct MessageReceivedFromRoom {
     pub message: String
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomMessageHistoryResponse {
+    pub room_id: RoomId,
+    pub messages: Vec<RoomMessage>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomInviteReceived {
+    #[serde(rename = "type")]
+    pub class_type: String,
+    pub room_id: RoomId,
+    pub room_name: Option<String>,
+    pub inviter: UserId,
+    pub room_user_type: RoomUserType,
+    pub expire_date: i32
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomInviteAnswered {
+    #[serde(rename = "type")]
+    pub class_type: String,
+    pub room_id: RoomId,
+    pub user_id: UserId
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomInviteCancelled {
+    #[serde(rename = "type")]
+    pub class_type: String,
+    pub room_id: RoomId
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoomInvitesResponse {
+    pub room_id: RoomId,
+    pub invites: Vec<RoomInvite>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<UserSession>
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchFound {
+    #[serde(rename = "type")]
+    pub class_type: String,
+    pub queue: String,
+    pub room_id: RoomId,
+    pub users: Vec<UserId>
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JoinRequested {
     pub room_id: RoomId,
@@ -147,6 +221,7 @@ into_impl!(UserJoinedToRoom);
 into_impl!(RoomCreated);
 into_impl!(UserDisconnectedFromRoom);
 into_impl!(MessageReceivedFromRoom);
+into_impl!(MatchFound);
 
 /* **************************************************************************************************************** */
 /* ************************************************* MACROS CALL ************************************************** */